@@ -1,6 +1,7 @@
 use crate::time;
+use serde::Deserialize;
 use crate::MacroMotion;
-use rand::rngs::ThreadRng;
+use crate::seed::ThreadRng;
 use rand::seq::SliceRandom;
 
 use crate::analysis::{in_range, in_range_usize};
@@ -8,7 +9,8 @@ use crate::analysis::{in_range, in_range_usize};
 /// Represents the stereo position in a mix, indicating the panning of the audio signal.
 /// Variants allow for mono or varying degrees of stereo positioning, either left, right,
 /// or with distinct levels for both left and right channels.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum StereoField {
   /// Mono signal with equal amplitude in a single channel.
   Mono,
@@ -20,6 +22,30 @@ pub enum StereoField {
   LeftRight(f32, f32),
 }
 
+impl StereoField {
+  /// Gain of a delay replica in the (left, right) channels.
+  ///
+  /// Replica 0 is the dry signal and is never panned.
+  /// `LeftRight` alternates the echoes between channels (ping-pong) with the given amplitudes.
+  pub fn gains(&self, replica: usize) -> (f32, f32) {
+    if replica == 0 {
+      return (1f32, 1f32);
+    }
+    match *self {
+      StereoField::Mono => (1f32, 1f32),
+      StereoField::Left(amp) => (amp, 0f32),
+      StereoField::Right(amp) => (0f32, amp),
+      StereoField::LeftRight(l, r) => {
+        if replica % 2 == 1 {
+          (l, 0f32)
+        } else {
+          (0f32, r)
+        }
+      }
+    }
+  }
+}
+
 /// Parameters for configuring a delay effect, including duration, echo count, and
 /// gain and mix levels, along with stereo panning options.
 ///
@@ -29,6 +55,8 @@ pub enum StereoField {
 pub struct DelayParams {
   /// Total length of the delay in seconds.
   pub len_seconds: f32,
+  /// Length of the delay in cycles when it is synced to the tempo. See `DelayParams::at_tempo`.
+  pub len_cycles: Option<f32>,
   /// Number of echo artifacts to create within the delay.
   pub n_echoes: usize,
   /// Gain applied to each echo, controlling the decay of echo intensity.
@@ -42,6 +70,7 @@ pub struct DelayParams {
 /// Macro struct for generating `DelayParams` instances with specified ranges, motions,
 /// and a list of delay rates as cycle ratios. This allows the selection of different delay times
 /// based on the cycles-per-second (`cps`) input.
+#[derive(Debug, Deserialize)]
 pub struct DelayParamsMacro {
   /// Range for gain values to be applied to each echo [min, max].
   pub gain: [f32; 2],
@@ -68,6 +97,7 @@ pub struct DelayParamsMacro {
 impl DelayParamsMacro {
   /// Generates a new `DelayParams` instance by selecting a delay ratio from `dtimes_cycles`,
   /// and calculating the delay length in seconds based on `cps`.
+  /// The ratio is kept so notes rendered at another tempo of a tempo map scale the delay with it.
   ///
   /// # Arguments
   /// - `rng`: A random number generator to sample values within ranges.
@@ -92,6 +122,7 @@ impl DelayParamsMacro {
 
     DelayParams {
       len_seconds,
+      len_cycles: Some(delay_len_cycles),
       n_echoes,
       gain,
       mix,
@@ -100,6 +131,20 @@ impl DelayParamsMacro {
   }
 }
 
+impl DelayParams {
+  /// The delay of a note playing at `cps`.
+  /// Delays synced to the tempo follow it when the tempo changes, the others keep their length in seconds.
+  pub fn at_tempo(&self, cps: f32) -> DelayParams {
+    match self.len_cycles {
+      Some(len_cycles) => DelayParams {
+        len_seconds: len_cycles / cps,
+        ..*self
+      },
+      None => *self,
+    }
+  }
+}
+
 pub fn is_passthrough(params: &DelayParams) -> bool {
   params.mix == 0f32 || params.len_seconds == 0f32 || params.gain == 0f32 || params.n_echoes == 0
 }
@@ -107,6 +152,7 @@ pub fn is_passthrough(params: &DelayParams) -> bool {
 pub static passthrough: DelayParams = DelayParams {
   mix: 0f32,
   len_seconds: 0f32,
+  len_cycles: None,
   gain: 0f32,
   n_echoes: 0,
   pan: StereoField::Mono,
@@ -127,6 +173,32 @@ pub fn gain(j: usize, replica: usize, params: &DelayParams) -> f32 {
   params.mix * params.gain.powi(replica as i32)
 }
 
+/// The echo of sample `v` at replica gain `gain`. See `apply_mono`.
+#[inline]
+pub fn echo(v: f32, gain: f32, clip: Option<f32>) -> f32 {
+  match clip {
+    Some(thresh) if gain * v.abs() > thresh => gain * v.signum() * thresh,
+    _ => gain * v,
+  }
+}
+
+/// Apply a delay without a stereo field to a mono signal, as the renderer always has.
+///
+/// The dry signal is replica 0 and is added again. Each replica is added in place in order,
+/// so it echoes the signal with the replicas before it, and its own earlier echoes, already added.
+/// Nothing is written past the end of `sig`.
+/// With `clip`, an echo louder than `clip` is added at `gain * clip` with its sign.
+pub fn apply_mono(sig: &mut [f32], params: &DelayParams, clip: Option<f32>) {
+  let samples_per_echo: usize = time::samples_from_dur(1f32, params.len_seconds);
+  for replica_n in 0..=params.n_echoes.max(1) {
+    let offset = samples_per_echo * replica_n;
+    for j in 0..sig.len().saturating_sub(offset) {
+      let v = sig[j];
+      sig[j + offset] += echo(v, gain(j, replica_n, params), clip);
+    }
+  }
+}
+
 /// Given a delay params, identify the new duration of the sample for a given context.
 pub fn length(cps: f32, dur: f32, params: &DelayParams) -> usize {
   let samples_per_echo: usize = time::samples_from_dur(1f32, params.len_seconds);
@@ -137,6 +209,15 @@ pub fn length(cps: f32, dur: f32, params: &DelayParams) -> usize {
 mod test {
   use super::*;
 
+  #[test]
+  fn test_stereo_field_gains() {
+    assert_eq!((1f32, 1f32), StereoField::LeftRight(0.9, 0.1).gains(0), "Dry signal must stay centered");
+    assert_eq!((1f32, 1f32), StereoField::Mono.gains(3));
+    assert_eq!((0.9, 0f32), StereoField::LeftRight(0.9, 0.1).gains(1));
+    assert_eq!((0f32, 0.1), StereoField::LeftRight(0.9, 0.1).gains(2));
+    assert_eq!((0f32, 0.5), StereoField::Right(0.5).gains(1));
+  }
+
   fn test_with_outlived_final_note() {
     let durs: Vec<f32> = vec![2f32, 3f32, 10f32, 2f32];
     let durs: Vec<f32> = vec![2f32, 3f32, 10f32, 10f32];
@@ -146,10 +227,67 @@ mod test {
       mix: 0.5f32,
       gain: 0.99,
       len_seconds: 1f32,
+      len_cycles: None,
       n_echoes: 5,
       pan: StereoField::Mono,
     };
 
     let total_dur: f32 = durs.iter().sum();
   }
+
+  #[test]
+  fn test_apply_mono_matches_the_original_delay() {
+    let params = DelayParams {
+      len_seconds: 0.001,
+      len_cycles: None,
+      n_echoes: 3,
+      gain: 0.8,
+      mix: 0.5,
+      pan: StereoField::Mono,
+    };
+    let signal: Vec<f32> = (0..400).map(|i| 1.5f32 * (i as f32 * 0.1).sin()).collect();
+    // the delay loop of `finalize_signal` and `channel_with_reso` before stereo rendering
+    let original = |clip: bool| {
+      let mut sig = signal.clone();
+      let samples_per_echo: usize = time::samples_from_dur(1.0, params.len_seconds);
+      for replica_n in 0..=params.n_echoes.max(1) {
+        for j in 0..sig.len() {
+          let v = sig[j];
+          let offset_j = j + samples_per_echo * replica_n;
+          if offset_j < sig.len() {
+            let gain = gain(j, replica_n, &params);
+            if clip && gain * v.abs() > 1f32 {
+              sig[offset_j] += gain * v.signum();
+            } else {
+              sig[offset_j] += gain * v;
+            }
+          }
+        }
+      }
+      sig
+    };
+    for (clip, thresh) in [(false, None), (true, Some(1f32))] {
+      let mut sig = signal.clone();
+      apply_mono(&mut sig, &params, thresh);
+      assert_eq!(original(clip), sig);
+    }
+    let mut sig = signal.clone();
+    apply_mono(&mut sig, &passthrough, Some(1f32));
+    assert_eq!(original(true).len(), sig.len());
+  }
+
+  #[test]
+  fn test_synced_delays_follow_the_tempo() {
+    let synced = DelayParams {
+      len_seconds: 0.25f32,
+      len_cycles: Some(0.5f32),
+      ..passthrough
+    };
+    assert_eq!(0.125f32, synced.at_tempo(4f32).len_seconds);
+    let fixed = DelayParams {
+      len_cycles: None,
+      ..synced
+    };
+    assert_eq!(0.25f32, fixed.at_tempo(4f32).len_seconds, "Must keep delays given in seconds");
+  }
 }
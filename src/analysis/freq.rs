@@ -264,54 +264,66 @@ pub fn apply_resonance(curr_freq: f32, resonance_f: f32, resonance_distance: f32
 /// println!("{:?}", filtered_samples);
 /// ```
 pub fn butterworth_lowpass_filter(samples: &[f32], sample_rate: u32, cutoff_freq_max: f32) -> Vec<f32> {
-  // Prevent unexpected behavior at the boundary of sample rate
-  let cutoff_freq = cutoff_freq_max.min(NFf - 100f32);
-  // Precompute constants for the filter
-  let omega = 2.0 * std::f32::consts::PI * cutoff_freq / sample_rate as f32;
-  let cos_omega = omega.cos();
-  let sin_omega = omega.sin();
-  let alpha = sin_omega / (2.0 * (1.0 as f32).sqrt());
-
-  // Calculate filter coefficients
-  let b0 = (1.0 - cos_omega) / 2.0;
-  let b1 = 1.0 - cos_omega;
-  let b2 = (1.0 - cos_omega) / 2.0;
-  let a0 = 1.0 + alpha;
-  let a1 = -2.0 * cos_omega;
-  let a2 = 1.0 - alpha;
-
-  // Normalize coefficients
-  let b0 = b0 / a0;
-  let b1 = b1 / a0;
-  let b2 = b2 / a0;
-  let a1 = a1 / a0;
-  let a2 = a2 / a0;
-
-  // Initialize the filtered output buffer
-  let mut filtered_samples = Vec::with_capacity(samples.len());
-
-  // Initialize delay elements
-  let mut x_n1 = 0.0;
-  let mut x_n2 = 0.0;
-  let mut y_n1 = 0.0;
-  let mut y_n2 = 0.0;
-
-  // Process each sample
-  for &sample in samples {
-    // Compute the current output sample
-    let y_n = b0 * sample + b1 * x_n1 + b2 * x_n2 - a1 * y_n1 - a2 * y_n2;
-
-    // Store the computed sample in the output buffer
-    filtered_samples.push(y_n);
+  let mut filter = Butterworth::lowpass(sample_rate, cutoff_freq_max);
+  samples.iter().map(|&sample| filter.process(sample)).collect()
+}
 
-    // Update the delay elements
-    x_n2 = x_n1;
-    x_n1 = sample;
-    y_n2 = y_n1;
-    y_n1 = y_n;
+/// The filter of `butterworth_lowpass_filter`, applied one sample at a time.
+pub struct Butterworth {
+  b0: f32,
+  b1: f32,
+  b2: f32,
+  a1: f32,
+  a2: f32,
+  // delay elements
+  x_n1: f32,
+  x_n2: f32,
+  y_n1: f32,
+  y_n2: f32,
+}
+
+impl Butterworth {
+  pub fn lowpass(sample_rate: u32, cutoff_freq_max: f32) -> Self {
+    // Prevent unexpected behavior at the boundary of sample rate
+    let cutoff_freq = cutoff_freq_max.min(NFf() - 100f32);
+    // Precompute constants for the filter
+    let omega = 2.0 * std::f32::consts::PI * cutoff_freq / sample_rate as f32;
+    let cos_omega = omega.cos();
+    let sin_omega = omega.sin();
+    let alpha = sin_omega / (2.0 * (1.0 as f32).sqrt());
+
+    // Calculate filter coefficients
+    let b0 = (1.0 - cos_omega) / 2.0;
+    let b1 = 1.0 - cos_omega;
+    let b2 = (1.0 - cos_omega) / 2.0;
+    let a0 = 1.0 + alpha;
+    let a1 = -2.0 * cos_omega;
+    let a2 = 1.0 - alpha;
+
+    // Normalize coefficients
+    Butterworth {
+      b0: b0 / a0,
+      b1: b1 / a0,
+      b2: b2 / a0,
+      a1: a1 / a0,
+      a2: a2 / a0,
+      x_n1: 0.0,
+      x_n2: 0.0,
+      y_n1: 0.0,
+      y_n2: 0.0,
+    }
   }
 
-  filtered_samples
+  pub fn process(&mut self, sample: f32) -> f32 {
+    let y_n = self.b0 * sample + self.b1 * self.x_n1 + self.b2 * self.x_n2 - self.a1 * self.y_n1 - self.a2 * self.y_n2;
+
+    // Update the delay elements
+    self.x_n2 = self.x_n1;
+    self.x_n1 = sample;
+    self.y_n2 = self.y_n1;
+    self.y_n1 = y_n;
+    y_n
+  }
 }
 
 #[cfg(test)]
@@ -484,7 +496,7 @@ mod functional {
   #[test]
   /// Generates 5 checkpoints at random. v is randomized in [0,1] and contour is randomized in [-1,1]
   fn test_random_checkpoints() {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
 
     // Generate 5 random checkpoints with increasing `p` values in [0, 1]
     let mut checkpoints: Vec<(f32, f32, f32)> = (0..5)
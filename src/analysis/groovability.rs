@@ -17,7 +17,7 @@ use crate::synth::SR;
 pub fn groove_optimizer(
   input_signal: AudioFormat, transient_emphasis: f32, dynamic_range: f32, rhythmic_threshold: f32, time_constant: f32,
 ) -> AudioFormat {
-  let sr_factor = (time_constant / 1000.0) * SR as f32;
+  let sr_factor = (time_constant / 1000.0) * SR() as f32;
   let attack_time = sr_factor;
   let release_time = sr_factor;
 
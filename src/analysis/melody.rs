@@ -7,7 +7,8 @@ use crate::types::synthesis::{
   Ampl, Bandpass, Direction, Duration, Ely, FilterPoint, Freq, Frex, GlideLen, MacroMotion, Monae, Mote, Note,
   Register, Soids, Tone,
 };
-use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand::Rng;
+use crate::seed::{ThreadRng, thread_rng};
 
 /// Given a list of Lines,
 /// identify the line that has the highest and lowest notes.
@@ -74,7 +75,7 @@ impl LevelMacro {
   /// This uses a random number generator to select values within each
   /// specified range in `LevelMacro`.  
   pub fn gen(&self) -> Levels {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     Levels {
       stable: in_range(&mut rng, self.stable[0], self.stable[1]),
       peak: in_range(&mut rng, self.peak[0], self.peak[1]),
@@ -136,7 +137,7 @@ impl ODRMacro {
   /// Generates a new `ODR` instance with values sampled randomly within
   /// the ranges specified in `ODRMacro` for onset, decay, and release.
   pub fn gen(&self) -> ODR {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     ODR {
       onset: in_range(&mut rng, self.onset[0], self.onset[1]),
       decay: in_range(&mut rng, self.decay[0], self.decay[1]),
@@ -5,13 +5,16 @@ pub mod melody;
 pub mod monic_theory;
 pub mod sampler;
 pub mod sine_cache;
+pub mod tempo;
 pub mod time;
 pub mod tools;
 pub mod transient;
 pub mod trig;
+pub mod tuning;
 pub mod volume;
 pub mod xform_freq;
-use rand::{prelude::SliceRandom, rngs::ThreadRng, Rng};
+use rand::{prelude::SliceRandom, Rng};
+use crate::seed::ThreadRng;
 
 use crate::synth::{pi, pi2};
 
@@ -95,7 +98,7 @@ pub fn is_fmod_range(f: f32, v: f32) -> bool {
   if !f.is_finite() || !v.is_finite() || f <= 0f32 || v <= 0f32 {
     return false;
   }
-  let max_mul = crate::synth::NFf / f;
+  let max_mul = crate::synth::NFf() / f;
   v <= max_mul
 }
 
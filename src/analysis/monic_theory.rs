@@ -1,3 +1,4 @@
+use crate::types::render::Melody;
 use crate::types::synthesis::*;
 
 pub type Chrom = i8;
@@ -14,76 +15,123 @@ fn fit(a: f32, b: f32) -> f32 {
   }
 }
 
-/// Convert from generic Monae to 12-ET representation of a tone
-pub fn monae_to_chrom(Monae: Monae, chroma_mod: i8) -> Chrom {
-  let (rot, q, monic) = Monae;
-
-  if rot < 0 {
-    // 12-ET uses 7 as modular basis of harmony.
-    // Cast this monae into the space
-    return monae_to_chrom((0, q, monic), chroma_mod - 7 * rot.abs());
-  }
-
-  if rot > 0 {
-    // 12-ET uses 7 as modular basis of harmony.
-    return monae_to_chrom((0, q, monic), chroma_mod + 7 * rot.abs());
-  }
-
-  let d_mod = chroma_mod % 12;
-  let chroma;
-
-  if q == 0 {
-    chroma = match monic {
-      1 => 0,
-      3 => 7,
-      5 => 4,
-      7 => 10,
-      9 => 2,
-      _ => {
-        println!("Unhandled case for monic {}", 0);
-        0
-      }
-    };
-  } else {
-    chroma = match monic {
-      1 => 0,
-      3 => 5,
-      5 => 8,
-      7 => 2,
-      9 => 10,
-      _ => {
-        println!("Unhandled case for monic {}", 0);
-        0
-      }
-    };
+/// The ratio each rotation of a monae moves by.
+/// The default of 3/2 rotates around the circle of fifths, the modular basis of 12-ET harmony.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lattice {
+  pub basis: f32,
+}
+
+impl Default for Lattice {
+  fn default() -> Self {
+    Lattice::FIFTHS
+  }
+}
+
+impl Lattice {
+  pub const FIFTHS: Lattice = Lattice { basis: 1.5f32 };
+
+  /// A lattice rotating by `basis`, which must be above 1/1.
+  pub fn new(basis: f32) -> Result<Self, String> {
+    match basis.is_finite() && basis > 1f32 {
+      true => Ok(Lattice { basis }),
+      false => Err(format!("Rotation basis must be above 1, got {}", basis)),
+    }
+  }
+
+  /// 12-ET steps moved by one rotation: 7 for 3/2, 5 for 4/3, 4 for 5/4.
+  pub fn rotation_chrom(&self) -> Chrom {
+    monic_cents(self.basis).0
+  }
+
+  pub fn monae_to_freq(&self, (rotation, q, monic): &Monae) -> f32 {
+    let qq = if *q == 0 { 1 } else { -1 };
+    let m = (*monic as f32).powi(qq);
+    self.basis.powi(*rotation as i32) * m
+  }
+
+  /// Frequency of `tone` as a multiple of the root, in just intonation.
+  pub fn tone_to_freq(&self, tone: &Tone) -> f32 {
+    let (register, m) = tone;
+    fit(2f32.powi(*register as i32), self.monae_to_freq(m))
+  }
+
+  /// Convert from generic Monae to 12-ET representation of a tone.
+  /// Monics below 1 have no spelling and are read as the tonic.
+  pub fn monae_to_chrom(&self, (rotation, q, monic): Monae, chroma_mod: i8) -> Chrom {
+    let (chroma, _) = monic_to_chrom(monic, q).unwrap_or((0, 0f32));
+    let rotated = rotation as i32 * self.rotation_chrom() as i32;
+    (chroma as i32 + rotated + chroma_mod as i32).rem_euclid(12) as Chrom
+  }
+
+  pub fn tone_to_chrom(&self, (register, monae): Tone) -> Chrom {
+    const MIDI_OFFSET: i8 = 3; // the register in tone mapped to the minimum applied MIDI value
+
+    let octaveMod = 12 * (register - MIDI_OFFSET);
+    octaveMod + self.monae_to_chrom(monae, 0)
+  }
+
+  /// Cents from the 12-ET spelling of `tone` to its just intonation, positive when the tone is sharp of 12-ET.
+  pub fn cents_from_12et(&self, (_, monae): &Tone) -> f32 {
+    let cents = 1200f32 * fit(1f32, self.monae_to_freq(monae)).log2();
+    let step = 100f32 * self.monae_to_chrom(*monae, 0) as f32;
+    (cents - step + 600f32).rem_euclid(1200f32) - 600f32
   }
 
-  let mut chroma: Chrom = (chroma + d_mod) % 12;
+  /// The 12-ET spelling of each note in `melody` with its cents from 12-ET.
+  pub fn intonation(&self, melody: &Melody<Note>) -> Vec<Vec<(Chrom, f32)>> {
+    melody
+      .iter()
+      .map(|line| {
+        line.iter().map(|(_, tone, _)| (self.tone_to_chrom(*tone), self.cents_from_12et(tone))).collect()
+      })
+      .collect()
+  }
+}
+
+/// Nearest 12-ET step above the tonic to `ratio`, and the cents from that step to the ratio.
+fn monic_cents(ratio: f32) -> (Chrom, f32) {
+  let cents = 1200f32 * fit(1f32, ratio).log2();
+  let step = (cents / 100f32).round();
+  ((step as i32 % 12) as Chrom, cents - 100f32 * step)
+}
 
-  while chroma < 0 {
-    chroma += 12;
+/// 12-ET spelling of a monic in overtones (q = 0) or undertones, with the cents from that step to the monic.
+/// Every monic from 1 has a spelling: 11 is a sharp tritone (6, -49c) and 13 a flat minor sixth (8, +41c).
+pub fn monic_to_chrom(monic: Monic, q: Q) -> Option<(Chrom, f32)> {
+  if monic < 1 {
+    return None;
   }
+  let ratio = if q == 0 { monic as f32 } else { 1f32 / monic as f32 };
+  Some(monic_cents(ratio))
+}
 
-  chroma
+/// Convert from generic Monae to 12-ET representation of a tone
+pub fn monae_to_chrom(monae: Monae, chroma_mod: i8) -> Chrom {
+  Lattice::FIFTHS.monae_to_chrom(monae, chroma_mod)
 }
 
-pub fn tone_to_chrom((register, monae): Tone) -> Chrom {
-  const MIDI_OFFSET: i8 = 3; // the register in tone mapped to the minimum applied MIDI value
+pub fn tone_to_chrom(tone: Tone) -> Chrom {
+  Lattice::FIFTHS.tone_to_chrom(tone)
+}
+
+pub fn monae_to_freq(monae: &Monae) -> f32 {
+  Lattice::FIFTHS.monae_to_freq(monae)
+}
 
-  let octaveMod = 12 * (register - MIDI_OFFSET);
-  octaveMod + monae_to_chrom(monae, 0)
+/// Frequency of `tone` as a multiple of the root, in just intonation.
+pub fn just_tone_to_freq(tone: &Tone) -> f32 {
+  Lattice::FIFTHS.tone_to_freq(tone)
 }
 
-pub fn monae_to_freq((rotation, q, monic): &Monae) -> f32 {
-  let qq = if *q == 0 { 1 } else { -1 };
-  let harmonic_basis = 1.5f32;
-  let m = (*monic as f32).powi(qq);
-  harmonic_basis.powi(*rotation as i32) * m
+/// Cents from the 12-ET spelling of `tone` to its just intonation. See `Lattice::cents_from_12et`.
+pub fn cents_from_12et(tone: &Tone) -> f32 {
+  Lattice::FIFTHS.cents_from_12et(tone)
 }
 
+/// Frequency of `tone` as a multiple of the root, in the tuning of the current render. See `tuning`.
 pub fn tone_to_freq(tone: &Tone) -> f32 {
-  let (register, m) = tone;
-  fit(2f32.powi(*register as i32), monae_to_freq(m))
+  super::tuning::tone_to_freq(tone)
 }
 
 pub fn note_to_freq((_, tone, _): &Note) -> f32 {
@@ -106,6 +154,63 @@ mod test {
     assert_eq!(expected, chroms);
   }
 
+  #[test]
+  fn test_monics_spell_as_before() {
+    let overtones: Vec<Chrom> = [1, 3, 5, 7, 9].map(|m| monae_to_chrom((0, 0, m), 0)).to_vec();
+    assert_eq!(vec![0, 7, 4, 10, 2], overtones);
+    let undertones: Vec<Chrom> = [1, 3, 5, 7, 9].map(|m| monae_to_chrom((0, 1, m), 0)).to_vec();
+    assert_eq!(vec![0, 5, 8, 2, 10], undertones);
+    assert_eq!(11, monae_to_chrom((1, 0, 5), 0));
+    assert_eq!(5, monae_to_chrom((-1, 0, 1), 0));
+    assert_eq!(4, monae_to_chrom((-2, 0, 3), -1));
+  }
+
+  #[test]
+  fn test_higher_monics() {
+    let (chrom, cents) = monic_to_chrom(11, 0).unwrap();
+    assert_eq!(6, chrom);
+    assert!((cents + 48.68f32).abs() < 0.01);
+    let (chrom, cents) = monic_to_chrom(13, 0).unwrap();
+    assert_eq!(8, chrom);
+    assert!((cents - 40.53f32).abs() < 0.01);
+    assert_eq!(6, monic_to_chrom(11, 1).unwrap().0);
+    assert_eq!(Some((0, 0f32)), monic_to_chrom(1, 1));
+    assert_eq!(None, monic_to_chrom(0, 0));
+    for monic in (1..=31).step_by(2) {
+      let (_, cents) = monic_to_chrom(monic, 0).unwrap();
+      assert!(cents.abs() <= 50f32, "Monic {} is {}c from its spelling", monic, cents);
+    }
+  }
+
+  #[test]
+  fn test_rotation_basis() {
+    let fourths = Lattice::new(4f32 / 3f32).unwrap();
+    assert_eq!(7, Lattice::default().rotation_chrom());
+    assert_eq!(5, fourths.rotation_chrom());
+    assert_eq!(4, Lattice::new(1.25f32).unwrap().rotation_chrom());
+    assert_eq!(5 + 4, fourths.monae_to_chrom((1, 0, 5), 0));
+    assert!((fourths.tone_to_freq(&(0, (1, 0, 3))) - 1f32).abs() < 1e-6);
+    assert_eq!(1.5f32, just_tone_to_freq(&(0, (1, 0, 1))));
+    assert!(Lattice::new(1f32).is_err());
+    assert!(Lattice::new(f32::NAN).is_err());
+  }
+
+  #[test]
+  fn test_cents_from_12et() {
+    assert_eq!(0f32, cents_from_12et(&(5, (0, 0, 1))));
+    assert!((cents_from_12et(&(5, (0, 0, 3))) - 1.96f32).abs() < 0.01);
+    assert!((cents_from_12et(&(5, (0, 0, 5))) + 13.69f32).abs() < 0.01);
+    assert!((cents_from_12et(&(5, (0, 0, 7))) + 31.17f32).abs() < 0.01);
+    assert!((cents_from_12et(&(5, (0, 1, 11))) - 48.68f32).abs() < 0.01);
+    // rotations add up the error of each fifth
+    assert!((cents_from_12et(&(5, (3, 0, 1))) - 5.87f32).abs() < 0.01);
+    let melody: Melody<Note> = vec![vec![((1, 1), (5, (0, 0, 13)), 1f32), ((1, 1), (5, (0, 0, 1)), 1f32)]];
+    let report = Lattice::default().intonation(&melody);
+    assert_eq!(8 + 24, report[0][0].0);
+    assert!((report[0][0].1 - 40.53f32).abs() < 0.01);
+    assert_eq!((24, 0f32), report[0][1]);
+  }
+
   #[test]
   fn view_early_monics() {
     let monics: [usize; 7] = [1, 3, 5, 7, 11, 13, 17];
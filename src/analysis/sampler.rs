@@ -155,13 +155,13 @@ pub fn read_audio_file(path: &str) -> Result<(Vec<Vec<f32>>, u32), Box<dyn std::
     hound::SampleFormat::Int => match spec.bits_per_sample {
       8 => {
         for (i, sample) in reader.samples::<i8>().enumerate() {
-          let sample = (sample.unwrap() as f32 - 128.0) / 128.0; // Normalize [-1, 1]
+          let sample = (sample? as f32 - 128.0) / 128.0; // Normalize [-1, 1]
           channel_samples[i % num_channels].push(sample);
         }
       }
       16 => {
         for (i, sample) in reader.samples::<i16>().enumerate() {
-          let sample = sample.unwrap() as f32 / i16::MAX as f32;
+          let sample = sample? as f32 / i16::MAX as f32;
           channel_samples[i % num_channels].push(sample);
         }
       }
@@ -173,7 +173,7 @@ pub fn read_audio_file(path: &str) -> Result<(Vec<Vec<f32>>, u32), Box<dyn std::
       }
       32 => {
         for (i, sample) in reader.samples::<i32>().enumerate() {
-          let sample = sample.unwrap() as f32 / i32::MAX as f32;
+          let sample = sample? as f32 / i32::MAX as f32;
           channel_samples[i % num_channels].push(sample);
         }
       }
@@ -181,7 +181,7 @@ pub fn read_audio_file(path: &str) -> Result<(Vec<Vec<f32>>, u32), Box<dyn std::
     },
     hound::SampleFormat::Float => {
       for (i, sample) in reader.samples::<f32>().enumerate() {
-        let sample = sample.unwrap(); // Floating-point samples are already normalized
+        let sample = sample?; // Floating-point samples are already normalized
         channel_samples[i % num_channels].push(sample);
       }
     }
@@ -256,23 +256,40 @@ impl ResourceAllowance {
 /// A vector of resampled channels as `Vec<Vec<f32>>`.
 ///
 /// # Errors
-/// Returns an error if resampling fails.
-
+/// Returns an error if the rates are not supported by the resampler or resampling fails.
 pub fn resample_audio(
   samples: &[f32], from_rate: u32, to_rate: u32, resource_allowance: Option<ResourceAllowance>, channels: usize,
-) -> Vec<Vec<f32>> {
+) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
   let resource_profile = resource_allowance.unwrap_or_else(ResourceAllowance::default);
   let (chunk_size, overlap_chunks) = resource_profile.fft_params();
 
-  let mut resampler = FftFixedInOut::<f32>::new(from_rate as usize, to_rate as usize, chunk_size, overlap_chunks)
-    .expect("Failed to create resampler");
+  let mut resampler = FftFixedInOut::<f32>::new(from_rate as usize, to_rate as usize, chunk_size, overlap_chunks)?;
 
   // Process each channel separately
   let input: Vec<Arc<[f32]>> =
     samples.chunks_exact(channels).map(|chunk| Arc::from(chunk.to_vec().into_boxed_slice())).collect();
-  let output = resampler.process(&input, None).expect("Resampling failed");
+  let output = resampler.process(&input, None)?;
+
+  Ok(output.into_iter().map(|chunk| chunk.to_vec()).collect())
+}
 
-  output.into_iter().map(|chunk| chunk.to_vec()).collect()
+/// Resample a single channel by linear interpolation.
+/// Cheap enough to run on every loaded sample; prefer `resample_audio` for mastering.
+pub fn resample_linear(samples: &[f32], from_rate: usize, to_rate: usize) -> Vec<f32> {
+  if samples.is_empty() || from_rate == to_rate {
+    return samples.to_vec();
+  }
+  let step = from_rate as f64 / to_rate as f64;
+  let n_out = (samples.len() as f64 / step).ceil() as usize;
+  (0..n_out)
+    .map(|i| {
+      let pos = i as f64 * step;
+      let index = pos.floor() as usize;
+      let next = samples.get(index + 1).unwrap_or(&samples[index]);
+      let mix = (pos - index as f64) as f32;
+      samples[index] * (1f32 - mix) + next * mix
+    })
+    .collect()
 }
 
 /// Sequences and combines multiple audio buffers into a single buffer.
@@ -1,4 +1,4 @@
-use crate::synth::{pi2, SR};
+use crate::synth::{pi2, DEFAULT_SR, SR};
 
 struct SineCache {
   samples: Vec<f32>,
@@ -9,7 +9,7 @@ struct SineCache {
 impl SineCache {
   /// Create a sample bank at 1Hertz for cached lookups
   pub fn new(resolution: usize) -> Self {
-    let n_samples = SR * resolution;
+    let n_samples = SR() * resolution;
     let sr = n_samples as f32;
     let period_samples = (0..n_samples).map(|t| (pi2 * t as f32 / sr).sin()).collect();
 
@@ -23,7 +23,7 @@ impl SineCache {
     let period_samples = self.samples.len() as f32;
 
     // Compute how many samples correspond to a single cycle at the given frequency
-    let samples_per_cycle = SR as f32 / frequency;
+    let samples_per_cycle = SR() as f32 / frequency;
 
     // Calculate an effective phase based on the input phase and frequency.
     // This converts the phase to a sample index in the buffer.
@@ -52,25 +52,25 @@ mod test_sine_cache {
 
   static TEST_FREQS: [f32; 6] = [60f32, 245f32, 555f32, 1288f32, 4001f32, 9999f32];
 
-  static TEST_DURS: [usize; 5] = [SR / 4, SR, SR * 3, SR * 12, SR * 60];
+  static TEST_DURS: [usize; 5] = [DEFAULT_SR / 4, DEFAULT_SR, DEFAULT_SR * 3, DEFAULT_SR * 12, DEFAULT_SR * 60];
 
   #[test]
   fn test_get_sine() {
     let resolution = 4usize;
     let cache = SineCache::new(resolution);
 
-    let sr = SR as f32;
+    let sr = SR() as f32;
 
     for &n_samples in TEST_DURS.iter() {
       let n = n_samples as f32;
       for &freq in TEST_FREQS.iter() {
         fn get_bad(freq: f32, n_samples: usize) -> Vec<f32> {
-          let sr = SR as f32;
+          let sr = SR() as f32;
           (0..n_samples).map(|t| (pi2 * freq * (t as f32 / sr)).sin()).collect()
         }
 
         fn get_cached(cache: &SineCache, freq: f32, n_samples: usize) -> Vec<f32> {
-          let sr = SR as f32;
+          let sr = SR() as f32;
           (0..n_samples).map(|t| cache.get(freq, freq * (t as f32 / sr))).collect()
         }
 
@@ -0,0 +1,200 @@
+/// Tempo maps, for scores whose tempo changes.
+///
+/// A score plays at `conf.cps` from its start. Each point of its tempo map reaches a new tempo at a cycle of the
+/// score: at once (`step`), or over the cycles since the previous point (`linear` or `exponential`).
+/// The tempo holds after the last point. Cycles are placed in time by integrating the map, see `analysis::time`,
+/// and each note plays at the mean tempo over its length.
+///
+/// Like the tuning, the tempo map is chosen per render and carried into every thread that renders notes
+/// by `render::context::RenderContext`.
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use crate::types::render::{TempoCurve, TempoPoint};
+
+/// The tempo at every cycle of a score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoMap {
+  /// The points of the map, starting with the tempo at cycle 0
+  points: Vec<TempoPoint>,
+  /// Seconds from the start of the score to each point
+  seconds: Vec<f64>,
+}
+
+/// Seconds from the start of a segment reaching tempo `b` from tempo `a` over `len` cycles, to `x` of its length.
+fn segment_seconds(curve: TempoCurve, a: f64, b: f64, len: f64, x: f64) -> f64 {
+  match curve {
+    _ if a == b => len * x / a,
+    TempoCurve::Step => len * x / a,
+    TempoCurve::Linear => len / (b - a) * ((a + (b - a) * x) / a).ln(),
+    TempoCurve::Exponential => {
+      let ln_r = (b / a).ln();
+      len / a * (1f64 - (-x * ln_r).exp()) / ln_r
+    }
+  }
+}
+
+/// The fraction of a segment passed `seconds` after its start. Inverse of `segment_seconds`.
+fn segment_fraction(curve: TempoCurve, a: f64, b: f64, len: f64, seconds: f64) -> f64 {
+  match curve {
+    _ if a == b => seconds * a / len,
+    TempoCurve::Step => seconds * a / len,
+    TempoCurve::Linear => (a * (seconds * (b - a) / len).exp() - a) / (b - a),
+    TempoCurve::Exponential => {
+      let ln_r = (b / a).ln();
+      -(1f64 - seconds * a * ln_r / len).ln() / ln_r
+    }
+  }
+}
+
+impl TempoMap {
+  /// A map starting at `cps` and changing at `points`, which must be after cycle 0 and in order.
+  /// See `inp::validate` for the checks made on playbooks.
+  pub fn new(cps: f32, points: &[TempoPoint]) -> Self {
+    let mut all = vec![TempoPoint {
+      cycle: 0f32,
+      cps,
+      curve: TempoCurve::Step,
+    }];
+    all.extend_from_slice(points);
+    let mut seconds = vec![0f64];
+    for k in 1..all.len() {
+      let (a, b) = (all[k - 1], all[k]);
+      let len = (b.cycle - a.cycle) as f64;
+      seconds.push(seconds[k - 1] + segment_seconds(b.curve, a.cps as f64, b.cps as f64, len, 1f64));
+    }
+    TempoMap { points: all, seconds }
+  }
+
+  /// The map of a score, or `None` when its tempo never changes.
+  pub fn of_score(cps: f32, points: &[TempoPoint]) -> Option<Self> {
+    match points.is_empty() {
+      true => None,
+      false => Some(TempoMap::new(cps, points)),
+    }
+  }
+
+  /// Index of the segment containing `cycle`, ending at that point. 0 before the start of the score.
+  fn segment(&self, cycle: f32) -> usize {
+    self.points.partition_point(|point| point.cycle <= cycle)
+  }
+
+  /// Cycles per second at `cycle`.
+  pub fn cps_at(&self, cycle: f32) -> f32 {
+    let k = self.segment(cycle);
+    if k == 0 || k == self.points.len() {
+      return self.points[k.saturating_sub(1)].cps;
+    }
+    let (a, b) = (self.points[k - 1], self.points[k]);
+    let x = (cycle - a.cycle) / (b.cycle - a.cycle);
+    match b.curve {
+      TempoCurve::Step => a.cps,
+      TempoCurve::Linear => a.cps + (b.cps - a.cps) * x,
+      TempoCurve::Exponential => a.cps * (b.cps / a.cps).powf(x),
+    }
+  }
+
+  /// Seconds from the start of the score to `cycle`.
+  /// Cycles before the start play at the first tempo.
+  pub fn seconds_at(&self, cycle: f32) -> f64 {
+    let k = self.segment(cycle);
+    if k == 0 {
+      return cycle as f64 / self.points[0].cps as f64;
+    }
+    let a = self.points[k - 1];
+    let passed = (cycle - a.cycle) as f64;
+    match self.points.get(k) {
+      None => self.seconds[k - 1] + passed / a.cps as f64,
+      Some(b) => {
+        let len = (b.cycle - a.cycle) as f64;
+        self.seconds[k - 1] + segment_seconds(b.curve, a.cps as f64, b.cps as f64, len, passed / len)
+      }
+    }
+  }
+
+  /// The cycle of the score `seconds` after its start. Inverse of `seconds_at`.
+  pub fn cycle_at(&self, seconds: f64) -> f32 {
+    let k = self.seconds.partition_point(|&s| s <= seconds);
+    if k == 0 {
+      return (seconds * self.points[0].cps as f64) as f32;
+    }
+    let a = self.points[k - 1];
+    let passed = seconds - self.seconds[k - 1];
+    match self.points.get(k) {
+      None => a.cycle + (passed * a.cps as f64) as f32,
+      Some(b) => {
+        let len = (b.cycle - a.cycle) as f64;
+        a.cycle + (len * segment_fraction(b.curve, a.cps as f64, b.cps as f64, len, passed)) as f32
+      }
+    }
+  }
+}
+
+thread_local! {
+  static CURRENT: RefCell<Option<Arc<TempoMap>>> = const { RefCell::new(None) };
+}
+
+/// Place the cycles rendered on the current thread with `map`. `None` plays every cycle at the tempo of the score.
+pub fn set_current(map: Option<Arc<TempoMap>>) {
+  CURRENT.with(|cell| *cell.borrow_mut() = map)
+}
+
+/// The tempo map of the current thread, if the tempo changes.
+pub fn current() -> Option<Arc<TempoMap>> {
+  CURRENT.with(|cell| cell.borrow().clone())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn point(cycle: f32, cps: f32, curve: TempoCurve) -> TempoPoint {
+    TempoPoint { cycle, cps, curve }
+  }
+
+  #[test]
+  fn test_step_changes_tempo_at_the_point() {
+    let map = TempoMap::new(2f32, &[point(4f32, 4f32, TempoCurve::Step)]);
+    assert_eq!(2f32, map.cps_at(3.9f32));
+    assert_eq!(4f32, map.cps_at(4f32));
+    assert!((map.seconds_at(4f32) - 2f64).abs() < 1e-9);
+    assert!((map.seconds_at(8f32) - 3f64).abs() < 1e-9);
+    assert!(
+      (map.seconds_at(-2f32) + 1f64).abs() < 1e-9,
+      "Must play before the start at the first tempo"
+    );
+  }
+
+  #[test]
+  fn test_ramps_integrate_the_tempo() {
+    // from 1 to 3 cycles per second over 4 cycles
+    let linear = TempoMap::new(1f32, &[point(4f32, 3f32, TempoCurve::Linear)]);
+    assert_eq!(2f32, linear.cps_at(2f32));
+    assert!((linear.seconds_at(4f32) - 2f64 * 3f64.ln()).abs() < 1e-6);
+    let exponential = TempoMap::new(1f32, &[point(4f32, 4f32, TempoCurve::Exponential)]);
+    assert!((exponential.cps_at(2f32) - 2f32).abs() < 1e-6);
+    // the integral of 4^(-c/4) from 0 to 4
+    let expected = 4f64 * (1f64 - 0.25f64) / 4f64.ln();
+    assert!((exponential.seconds_at(4f32) - expected).abs() < 1e-6);
+    // a ritardando takes longer than the tempo it starts at
+    let slowing = TempoMap::new(2f32, &[point(8f32, 1f32, TempoCurve::Linear)]);
+    assert!(slowing.seconds_at(8f32) > 4f64 && slowing.seconds_at(8f32) < 8f64);
+  }
+
+  #[test]
+  fn test_cycle_at_inverts_seconds_at() {
+    let map = TempoMap::new(
+      1.5f32,
+      &[
+        point(2f32, 3f32, TempoCurve::Linear),
+        point(6f32, 1f32, TempoCurve::Exponential),
+        point(7f32, 2f32, TempoCurve::Step),
+      ],
+    );
+    for cycle in [-1f32, 0f32, 0.5f32, 2f32, 3.3f32, 6f32, 6.5f32, 7f32, 12f32] {
+      let back = map.cycle_at(map.seconds_at(cycle));
+      assert!((back - cycle).abs() < 1e-4, "Cycle {} came back as {}", cycle, back);
+    }
+    assert!(TempoMap::of_score(2f32, &[]).is_none());
+  }
+}
@@ -1,3 +1,4 @@
+use crate::analysis::tempo;
 use crate::synth::{SRf, SR};
 use crate::types::synthesis::{Note, Ratio};
 use std::collections::HashMap;
@@ -7,7 +8,7 @@ use std::time::Instant;
 /// determines the number of samples required to recreate
 /// one second of audio signal.
 pub fn samples_per_cycle(cps: f32) -> usize {
-  (SRf / cps) as usize
+  (SRf() / cps) as usize
 }
 
 pub fn cycles_from_n(cps: f32, n: usize) -> f32 {
@@ -17,7 +18,7 @@ pub fn cycles_from_n(cps: f32, n: usize) -> f32 {
 
 /// Given a number of samples, returns the absolute time in seconds it represents using the application wide sample rate.
 pub fn samples_to_seconds(n_samples: usize) -> f32 {
-  n_samples as f32 / SRf
+  n_samples as f32 / SRf()
 }
 
 /// Given a duration in seconds, return the number of samples representing this length
@@ -38,7 +39,7 @@ pub fn samples_of_milliseconds(cps: f32, dur_ms: f32) -> usize {
 /// Given a duration in seconds, return the number of samples representing this length
 #[inline]
 pub fn samples_from_dur(cps: f32, dur: f32) -> usize {
-  (SRf * dur.abs() / cps) as usize
+  (SRf() * dur.abs() / cps) as usize
 }
 
 /// Provides the number of samples required to span k cycles at cps.
@@ -48,7 +49,7 @@ pub fn samples_of_cycles(cps: f32, k: f32) -> usize {
 }
 
 pub fn seconds_of_cycles(cps: f32, n_cycles: f32) -> f32 {
-  return samples_of_cycles(cps, n_cycles) as f32 / SRf;
+  return samples_of_cycles(cps, n_cycles) as f32 / SRf();
 }
 
 /// Provides the time in seconds for a given duration ratio.
@@ -66,6 +67,68 @@ pub fn step_to_seconds(cps: f32, ratio: &Ratio) -> f32 {
   duration_to_cycles((ratio.0.abs(), ratio.1.abs())) / cps
 }
 
+/// Seconds from the start of the score to `cycle`.
+/// Follows the tempo map of the current thread, else the constant `cps`. See `analysis::tempo`.
+pub fn seconds_at(cps: f32, cycle: f32) -> f32 {
+  match tempo::current() {
+    Some(map) => map.seconds_at(cycle) as f32,
+    None => cycle / cps,
+  }
+}
+
+/// The sample at which `cycle` of the score begins, following the tempo map of the current thread.
+pub fn samples_at(cps: f32, cycle: f32) -> usize {
+  match tempo::current() {
+    Some(map) => (map.seconds_at(cycle.max(0f32)) * SRf() as f64) as usize,
+    None => samples_of_cycles(cps, cycle),
+  }
+}
+
+/// The cycle of the score `seconds` before `cycle`, following the tempo map of the current thread.
+pub fn cycles_before(cps: f32, cycle: f32, seconds: f32) -> f32 {
+  match tempo::current() {
+    Some(map) => map.cycle_at(map.seconds_at(cycle) - seconds as f64),
+    None => cycle - seconds * cps,
+  }
+}
+
+/// Cycles per second at `cycle`, following the tempo map of the current thread.
+pub fn cps_at(cps: f32, cycle: f32) -> f32 {
+  match tempo::current() {
+    Some(map) => map.cps_at(cycle),
+    None => cps,
+  }
+}
+
+/// The mean tempo from cycle `start` to `end`: the cycles per second of a constant tempo taking as long.
+pub fn cps_between(cps: f32, start: f32, end: f32) -> f32 {
+  match tempo::current() {
+    Some(map) if end > start => ((end - start) as f64 / (map.seconds_at(end) - map.seconds_at(start))) as f32,
+    Some(map) => map.cps_at(start),
+    None => cps,
+  }
+}
+
+/// Sample offset of consecutive notes lasting `durs` cycles, the first starting at the start of the score.
+/// Follows the tempo map of the current thread, else places each note after the length of the one before it.
+pub fn onsets(cps: f32, durs: &[f32]) -> Vec<usize> {
+  let map = tempo::current();
+  let mut cue = 0usize;
+  let mut cycle = 0f32;
+  durs
+    .iter()
+    .map(|dur| {
+      let at = cue;
+      cycle += dur;
+      cue = match &map {
+        Some(map) => (map.seconds_at(cycle) * SRf() as f64) as usize,
+        None => cue + samples_of_dur(cps, *dur),
+      };
+      at
+    })
+    .collect()
+}
+
 pub fn duration_to_cycles((numerator, denominator): Ratio) -> f32 {
   (numerator as f32 / denominator as f32).abs()
 }
@@ -147,6 +210,30 @@ pub fn get_approx_size(seconds: f64) -> usize {
 #[cfg(test)]
 mod test {
   use super::*;
+  use crate::analysis::tempo::TempoMap;
+  use crate::types::render::{TempoCurve, TempoPoint};
+  use std::sync::Arc;
+
+  #[test]
+  fn test_onsets_follow_the_tempo_map() {
+    let durs = [1f32, 1f32, 1f32];
+    let steady = samples_of_dur(2f32, 1f32);
+    assert_eq!(vec![0, steady, 2 * steady], onsets(2f32, &durs));
+    let doubled = TempoPoint {
+      cycle: 1f32,
+      cps: 4f32,
+      curve: TempoCurve::Step,
+    };
+    tempo::set_current(Some(Arc::new(TempoMap::new(2f32, &[doubled]))));
+    let second = SRf() as usize / 2;
+    assert_eq!(vec![0, second, second * 3 / 2], onsets(2f32, &durs));
+    assert_eq!(4f32, cps_between(2f32, 1f32, 2f32));
+    let mean = cps_between(2f32, 0.5f32, 1.5f32);
+    assert!((mean - 1f32 / 0.375f32).abs() < 1e-5, "Must play a note over a change for as long as it lasts");
+    assert_eq!(1f32, cycles_before(2f32, 2f32, 0.25f32));
+    tempo::set_current(None);
+    assert_eq!(samples_of_cycles(2f32, 3f32), samples_at(2f32, 3f32));
+  }
 
   #[test]
   fn test_get_timing() {
@@ -38,7 +38,7 @@ fn apply_lowpass(samples: &[f32], cutoff_hz: f32) -> Result<Vec<f32>, String> {
   // Define filter coefficients for a low-pass filter
   let coeffs = Coefficients::<f32>::from_params(
     FilterType::LowPass,
-    Hertz::from_hz(SRf).unwrap(),
+    Hertz::from_hz(SRf()).unwrap(),
     Hertz::from_hz(cutoff_hz).unwrap(),
     0.707, // Q-factor (1/sqrt(2) for Butterworth)
   )
@@ -74,7 +74,7 @@ fn time_to_coefficient(time_sec: f32) -> f32 {
   if time_sec <= 0.0 {
     0.0
   } else {
-    let coeff = (-1.0 / (time_sec * SRf)).exp();
+    let coeff = (-1.0 / (time_sec * SRf())).exp();
     coeff.min(1.0 - f32::EPSILON).max(f32::EPSILON) // Prevent exactly 1.0 and underflow to 0
   }
 }
@@ -95,7 +95,7 @@ fn time_to_coefficient(time_sec: f32) -> f32 {
 /// # Returns
 /// - `Result<Vec<f32>, String>`: High-pass filtered samples or an error message if filter creation fails.
 fn apply_highpass(samples: &[f32], cutoff_hz: f32) -> Result<Vec<f32>, String> {
-  let sample_rate = SRf;
+  let sample_rate = SRf();
   if cutoff_hz <= 0.0 || cutoff_hz >= sample_rate / 2.0 {
     return Err(format!(
       "Invalid cutoff frequency: {} Hz. Must be between 0 and Nyquist ({} Hz).",
@@ -140,7 +140,7 @@ fn apply_highpass(samples: &[f32], cutoff_hz: f32) -> Result<Vec<f32>, String> {
 /// # Returns
 /// - `Result<Vec<f32>, String>`: High-shelf filtered samples or an error message if filter creation fails.
 fn apply_highshelf(samples: &[f32], cutoff_hz: f32, gain_db: f32) -> Result<Vec<f32>, String> {
-  let sample_rate = SRf;
+  let sample_rate = SRf();
   if cutoff_hz <= 0.0 || cutoff_hz >= sample_rate / 2.0 {
     return Err(format!(
       "Invalid cutoff frequency for high-shelf: {} Hz. Must be between 0 and Nyquist ({} Hz).",
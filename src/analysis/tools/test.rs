@@ -717,7 +717,7 @@ mod unit_test_apply_highpass {
   fn debug_q_factor_sensitivity() {
     let cutoff_hz = 200.0; // High-pass cutoff frequency
     let q_values = vec![0.5, 0.707, 1.0, 2.0]; // Test different Q-factors
-    let sample_rate = SRf;
+    let sample_rate = SRf();
 
     for q in q_values {
       let coeffs = Coefficients::<f32>::from_params(FilterType::HighPass, sample_rate.hz(), cutoff_hz.hz(), q)
@@ -736,7 +736,7 @@ mod unit_test_apply_highpass {
     let mut freq_response = Vec::new();
 
     for freq in test_freqs {
-      let samples: Vec<f32> = (0..1000).map(|i| (PI2 * freq * i as f32 / SRf).sin()).collect();
+      let samples: Vec<f32> = (0..1000).map(|i| (PI2 * freq * i as f32 / SRf()).sin()).collect();
       let filtered = apply_highpass(&samples, cutoff_hz).expect("Filter failed");
       let rms_pre = compute_rms(&samples, samples.len()).iter().sum::<f32>() / samples.len() as f32;
       let rms_post = compute_rms(&filtered, filtered.len()).iter().sum::<f32>() / filtered.len() as f32;
@@ -754,13 +754,13 @@ mod unit_test_apply_highpass {
     const PI2: f32 = 2.0 * std::f32::consts::PI;
 
     let samples: Vec<f32> = (0..10000) // Increase sample size for better analysis
-      .map(|i| (PI2 * freq * i as f32 / SRf).sin())
+      .map(|i| (PI2 * freq * i as f32 / SRf()).sin())
       .collect();
 
     let filtered = apply_highpass(&samples, cutoff_hz).expect("Filter failed");
 
     let rms_over_time: Vec<f32> = filtered
-            .chunks(SRf as usize / 10) // Analyze in 0.1 second chunks
+            .chunks(SRf() as usize / 10) // Analyze in 0.1 second chunks
             .map(|chunk| compute_rms(chunk, chunk.len()).iter().sum::<f32>() / chunk.len() as f32)
             .collect();
 
@@ -773,7 +773,7 @@ mod unit_test_apply_highpass {
     let cutoff_hz = 200.0; // High-pass cutoff
     const PI2: f32 = 2.0 * std::f32::consts::PI;
 
-    let samples: Vec<f32> = (0..1000).map(|i| (PI2 * freq * i as f32 / SRf).sin()).collect();
+    let samples: Vec<f32> = (0..1000).map(|i| (PI2 * freq * i as f32 / SRf()).sin()).collect();
 
     let filtered = apply_highpass(&samples, cutoff_hz).expect("High-pass filter failed");
 
@@ -813,11 +813,11 @@ mod unit_test_apply_highpass {
     let start_freq: f32 = 20.0; // Low frequency
     let end_freq: f32 = 1000.0; // High frequency
     let duration = 1.0; // 1 second
-    let sample_count = (SRf * duration) as usize;
+    let sample_count = (SRf() * duration) as usize;
 
     let chirp: Vec<f32> = (0..sample_count)
       .map(|i| {
-        let t = i as f32 / SRf;
+        let t = i as f32 / SRf();
         let freq = start_freq * (end_freq / start_freq).powf(t / duration);
         (2.0 * PI * freq * t).sin()
       })
@@ -827,7 +827,7 @@ mod unit_test_apply_highpass {
     let filtered = apply_highpass(&chirp, cutoff_hz).expect("Filter failed");
 
     // Analyze RMS in segments
-    let segment_size = SRf as usize / 10; // Analyze 0.1 second segments
+    let segment_size = SRf() as usize / 10; // Analyze 0.1 second segments
     let mut results = Vec::new();
     for i in (0..chirp.len()).step_by(segment_size) {
       let segment = &chirp[i..(i + segment_size).min(chirp.len())];
@@ -855,7 +855,7 @@ mod unit_test_apply_highpass {
   #[test]
   fn test_cutoff_above_nyquist() {
     let samples = vec![1.0, 0.5, 0.0, -0.5, -1.0];
-    let result = apply_highpass(&samples, SRf / 2.0 + 1.0);
+    let result = apply_highpass(&samples, SRf() / 2.0 + 1.0);
     assert!(
       result.is_err(),
       "High-pass filter should fail with cutoff frequency above Nyquist"
@@ -866,7 +866,7 @@ mod unit_test_apply_highpass {
   fn test_high_frequency_signal_preservation() {
     let freq = 1000.0; // Above cutoff
     let cutoff_hz = 200.0;
-    let samples: Vec<f32> = (0..100).map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / SRf).sin()).collect();
+    let samples: Vec<f32> = (0..100).map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / SRf()).sin()).collect();
 
     let filtered = apply_highpass(&samples, cutoff_hz).expect("High-pass filter failed");
     let correlation: f32 = samples.iter().zip(filtered.iter()).map(|(&a, &b)| a * b).sum();
@@ -1411,7 +1411,7 @@ mod test_unit_compressor {
 
   #[test]
   fn test_compressor_output_stability() {
-    let samples: Vec<f32> = (0..1000).map(|i| ((2.0 * PI * 440.0 * i as f32 / SRf).sin())).collect();
+    let samples: Vec<f32> = (0..1000).map(|i| ((2.0 * PI * 440.0 * i as f32 / SRf()).sin())).collect();
     let params = CompressorParams {
       threshold: -12.0,
       ratio: 2.0,
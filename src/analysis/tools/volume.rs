@@ -164,7 +164,7 @@ pub fn envelope_follower(
   }
 
   let envelope_method = method.unwrap_or(EnvelopeMethod::Peak);
-  let hold_samps = (hold_time.unwrap_or(0.0) * SRf).round() as usize;
+  let hold_samps = (hold_time.unwrap_or(0.0) * SRf()).round() as usize;
   let attack_coeff = time_to_coefficient(attack_time);
   let release_coeff = time_to_coefficient(release_time);
   let mix_ratio = mix.unwrap_or(1.0).clamp(0.0, 1.0);
@@ -208,7 +208,7 @@ pub fn envelope_follower(
       }
     }
     EnvelopeMethod::Rms(window_time) | EnvelopeMethod::Hybrid(window_time) => {
-      let window_size = (window_time * SRf).round() as usize;
+      let window_size = (window_time * SRf()).round() as usize;
       let rms_values = compute_rms(&processed_samples, window_size);
 
       for (i, &sample) in processed_samples.iter().enumerate() {
@@ -311,7 +311,7 @@ fn apply_k_weighting(samples: &[f32]) -> Result<Vec<f32>, String> {
 #[cfg(test)]
 mod test_unit_keith_factor {
   use super::*;
-  use rand::thread_rng;
+  use crate::seed::thread_rng;
 
   #[test]
   fn test_punchy_signal() {
@@ -378,8 +378,8 @@ mod test_unit_keith_factor {
 
     let label_short = format!("unit-keith-factor_short.wav");
     let label_long = format!("unit-keith-factor_long.wav");
-    crate::render::engrave::samples(SR, &signal_short, &dev_audio_asset(&label_short));
-    crate::render::engrave::samples(SR, &signal_long, &dev_audio_asset(&label_long));
+    crate::render::engrave::samples(SR(), &signal_short, &dev_audio_asset(&label_short)).unwrap();
+    crate::render::engrave::samples(SR(), &signal_long, &dev_audio_asset(&label_long)).unwrap();
     let keith_factor_short = keith_factor(&signal_short, thresh_fall_db, window_size_sec);
     let keith_factor_long = keith_factor(&signal_long, thresh_fall_db, window_size_sec);
     assert!(
@@ -392,7 +392,7 @@ mod test_unit_keith_factor {
 #[cfg(test)]
 mod test_unit_rescale_signal {
   use super::*;
-  use rand::thread_rng;
+  use crate::seed::thread_rng;
 
   #[test]
   fn test_rms_rescaling() {
@@ -462,7 +462,7 @@ mod test_unit_rescale_signal {
     let label_short_rescaled = format!("unit-rescale_short_rescaled.wav");
     let label_short_rescaled_softer = format!("unit-rescale_short_rescaled_softer.wav");
     let label_long_rescaled = format!("unit-rescale_long_rescaled.wav");
-    crate::render::engrave::samples(SR, &signal_short, &dev_audio_asset(&label_short));
+    crate::render::engrave::samples(SR(), &signal_short, &dev_audio_asset(&label_short)).unwrap();
 
     let curr_energy_short = count_energy(&signal_short);
     let curr_energy_long = count_energy(&signal_long);
@@ -471,7 +471,7 @@ mod test_unit_rescale_signal {
     let expected_short_rms = curr_energy_short * test_scale_factor_louder;
 
     let short_rescaled_louder = rescale_amplitude(expected_short_rms, &signal_short);
-    crate::render::engrave::samples(SR, &short_rescaled_louder, &dev_audio_asset(&label_short_rescaled));
+    crate::render::engrave::samples(SR(), &short_rescaled_louder, &dev_audio_asset(&label_short_rescaled)).unwrap();
 
     assert!(
       count_energy(&signal_short) < count_energy(&short_rescaled_louder),
@@ -483,10 +483,10 @@ mod test_unit_rescale_signal {
 
     let short_rescaled_softer = rescale_amplitude(expected_short_rms_softer, &signal_short);
     crate::render::engrave::samples(
-      SR,
+      SR(),
       &short_rescaled_softer,
       &dev_audio_asset(&label_short_rescaled_softer),
-    );
+    ).unwrap();
     let softer_rms = count_energy(&short_rescaled_softer);
 
     assert!(
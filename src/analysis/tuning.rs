@@ -0,0 +1,293 @@
+/// Tunings map the tones of a score to frequencies.
+///
+/// Scores are written in Monic theory, where each tone is a ratio above the root (see `monic_theory`).
+/// Just intonation, the default, plays those ratios as written. Every other tuning first finds the 12-ET step nearest
+/// to the ratio and plays that step of its scale: 12-ET, quarter-comma meantone, or a Scala `.scl` scale whose
+/// degrees are laid out on the steps by a `.kbm` keyboard mapping (see `inp::scala`).
+/// Step 0 stays at the root of the score, so the reference key and frequency of a keyboard mapping are not used.
+///
+/// Like the sample rate, the tuning and the rotation basis of the lattice are chosen per render and carried into every
+/// thread that renders notes by `render::context::RenderContext`.
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::analysis::monic_theory::{just_tone_to_freq, Lattice};
+use crate::error::RaudioError;
+use crate::inp::scala;
+use crate::render::cache::fnv1a;
+use crate::types::synthesis::Tone;
+
+/// A list of ratios above 1/1, repeating at its last ratio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+  pub description: String,
+  /// Ratio of degrees 1, 2, .. above degree 0, ending with the period (usually 2/1)
+  pub ratios: Vec<f64>,
+}
+
+impl Scale {
+  /// `n` equal divisions of the octave.
+  pub fn equal(n: usize) -> Self {
+    Scale {
+      description: format!("{} tone equal temperament", n),
+      ratios: (1..=n).map(|i| 2f64.powf(i as f64 / n as f64)).collect(),
+    }
+  }
+
+  /// Quarter-comma meantone, from Eb to G#.
+  pub fn meantone() -> Self {
+    let fifth = 5f64.powf(0.25);
+    let mut ratios: Vec<f64> = (-3..=8)
+      .map(|i| fifth.powi(i))
+      .map(|ratio| ratio / 2f64.powi(ratio.log2().floor() as i32))
+      .filter(|&ratio| ratio > 1f64)
+      .collect();
+    ratios.sort_by(f64::total_cmp);
+    ratios.push(2f64);
+    Scale {
+      description: "Quarter-comma meantone".to_string(),
+      ratios,
+    }
+  }
+
+  /// Ratio of `degree` above degree 0. Degrees past the end of the scale repeat at its period.
+  pub fn ratio(&self, degree: i32) -> f64 {
+    let n = self.ratios.len() as i32;
+    let period = self.ratios[self.ratios.len() - 1];
+    let base = match degree.rem_euclid(n) {
+      0 => 1f64,
+      k => self.ratios[k as usize - 1],
+    };
+    period.powi(degree.div_euclid(n)) * base
+  }
+}
+
+/// Which scale degree each 12-ET step plays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyboard {
+  /// Degree of each step in one repetition of the map, starting from the root. `None` for unmapped steps.
+  /// An empty map plays degree `n` on step `n`.
+  pub mapping: Vec<Option<i32>>,
+  /// Degree reached by one repetition of the map
+  pub octave_degree: i32,
+}
+
+impl Keyboard {
+  /// Each step plays the next degree of a scale of `size` degrees.
+  pub fn linear(size: usize) -> Self {
+    Keyboard {
+      mapping: Vec::new(),
+      octave_degree: size as i32,
+    }
+  }
+
+  /// The degree played by `step`, if it is mapped.
+  pub fn degree(&self, step: i32) -> Option<i32> {
+    if self.mapping.is_empty() {
+      return Some(step);
+    }
+    let n = self.mapping.len() as i32;
+    self.mapping[step.rem_euclid(n) as usize].map(|degree| degree + step.div_euclid(n) * self.octave_degree)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Tuning {
+  /// The ratios of Monic theory
+  #[default]
+  Just,
+  /// A scale laid out on the 12-ET steps above the root
+  Scale {
+    name: String,
+    scale: Scale,
+    keyboard: Keyboard,
+  },
+}
+
+impl fmt::Display for Tuning {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Tuning::Just => write!(f, "just"),
+      Tuning::Scale { name, .. } => write!(f, "{}", name),
+    }
+  }
+}
+
+/// Tunings available by name.
+pub const NAMES: [&str; 3] = ["just", "12-et", "meantone"];
+
+impl Tuning {
+  fn with_scale(name: &str, scale: Scale) -> Self {
+    let keyboard = Keyboard::linear(scale.ratios.len());
+    Tuning::Scale {
+      name: name.to_string(),
+      scale,
+      keyboard,
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name.to_lowercase().as_str() {
+      "just" => Some(Tuning::Just),
+      "12-et" | "12et" | "equal" => Some(Tuning::with_scale("12-et", Scale::equal(12))),
+      "meantone" => Some(Tuning::with_scale("meantone", Scale::meantone())),
+      _ => None,
+    }
+  }
+
+  /// Load a Scala scale, laid out by a keyboard mapping or else one degree per step.
+  pub fn load(scl_path: &str, kbm_path: Option<&str>) -> Result<Self, RaudioError> {
+    let scale = scala::load_scale(scl_path)?;
+    let keyboard = match kbm_path {
+      Some(path) => scala::load_keyboard(path)?,
+      None => Keyboard::linear(scale.ratios.len()),
+    };
+    let name = std::path::Path::new(scl_path).file_stem().map(|s| s.to_string_lossy().to_string());
+    Ok(Tuning::Scale {
+      name: name.unwrap_or(scl_path.to_string()),
+      scale,
+      keyboard,
+    })
+  }
+
+  /// Identifies the tuning in cache keys. Scales differing in any ratio or mapping have different keys.
+  pub fn key(&self) -> String {
+    match self {
+      Tuning::Just => "just".to_string(),
+      Tuning::Scale { name, scale, keyboard } => {
+        let layout = format!("{:?}{:?}", scale.ratios, keyboard);
+        format!("{}@{:016x}", name, fnv1a(layout.as_bytes()))
+      }
+    }
+  }
+
+  /// Frequency of `tone` as a multiple of the root.
+  pub fn tone_to_freq(&self, tone: &Tone) -> f32 {
+    self.tune(just_tone_to_freq(tone))
+  }
+
+  /// Frequency played for the just ratio `just` above the root.
+  /// Steps that the keyboard leaves unmapped sound at their 12-ET pitch.
+  pub fn tune(&self, just: f32) -> f32 {
+    match self {
+      Tuning::Just => just,
+      Tuning::Scale { scale, keyboard, .. } => {
+        let step = (12f32 * just.log2()).round() as i32;
+        match keyboard.degree(step) {
+          Some(degree) => scale.ratio(degree) as f32,
+          None => 2f32.powf(step as f32 / 12f32),
+        }
+      }
+    }
+  }
+}
+
+/// The lattice and tuning of a render.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Intonation {
+  pub lattice: Lattice,
+  pub tuning: Tuning,
+}
+
+impl Intonation {
+  /// Identifies the intonation in cache keys. The default lattice adds nothing to the key of the tuning.
+  pub fn key(&self) -> String {
+    match self.lattice == Lattice::default() {
+      true => self.tuning.key(),
+      false => format!("{}/basis={}", self.tuning.key(), self.lattice.basis),
+    }
+  }
+
+  pub fn tone_to_freq(&self, tone: &Tone) -> f32 {
+    self.tuning.tune(self.lattice.tone_to_freq(tone))
+  }
+}
+
+thread_local! {
+  static CURRENT: RefCell<Arc<Intonation>> = RefCell::new(Arc::new(Intonation::default()));
+}
+
+/// Tune every note rendered on the current thread with `intonation`.
+pub fn set_current(intonation: Arc<Intonation>) {
+  CURRENT.with(|cell| *cell.borrow_mut() = intonation)
+}
+
+/// The intonation of the current thread.
+pub fn current() -> Arc<Intonation> {
+  CURRENT.with(|cell| cell.borrow().clone())
+}
+
+/// Frequency of `tone` as a multiple of the root, in the tuning of the current thread.
+pub fn tone_to_freq(tone: &Tone) -> f32 {
+  CURRENT.with(|cell| cell.borrow().tone_to_freq(tone))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn cents(ratio: f32) -> f32 {
+    1200f32 * ratio.log2()
+  }
+
+  #[test]
+  fn test_tunings_of_a_fifth() {
+    let fifth: Tone = (0, (0, 0, 3));
+    assert_eq!(1.5f32, Tuning::Just.tone_to_freq(&fifth));
+    let equal = Tuning::from_name("12-et").unwrap().tone_to_freq(&fifth);
+    assert!((cents(equal) - 700f32).abs() < 0.01);
+    let meantone = Tuning::from_name("meantone").unwrap().tone_to_freq(&fifth);
+    assert!((cents(meantone) - 696.58f32).abs() < 0.01);
+    let octave_up: Tone = (1, (0, 0, 3));
+    assert!((cents(Tuning::from_name("meantone").unwrap().tone_to_freq(&octave_up)) - 1896.58f32).abs() < 0.01);
+  }
+
+  #[test]
+  fn test_meantone_major_third_is_pure() {
+    let third: Tone = (0, (0, 0, 5));
+    let meantone = Tuning::from_name("meantone").unwrap();
+    assert!((meantone.tone_to_freq(&third) - 1.25f32).abs() < 1e-5);
+    assert_eq!(13, Scale::meantone().ratios.len() + 1);
+  }
+
+  #[test]
+  fn test_keyboard_mapping_repeats() {
+    // a pentatonic scale on the white keys, leaving the others unmapped
+    let keyboard = Keyboard {
+      mapping: vec![
+        Some(0),
+        None,
+        Some(1),
+        None,
+        Some(2),
+        None,
+        None,
+        Some(3),
+        None,
+        Some(4),
+        None,
+        None,
+      ],
+      octave_degree: 5,
+    };
+    assert_eq!(Some(0), keyboard.degree(0));
+    assert_eq!(Some(5 + 3), keyboard.degree(12 + 7));
+    assert_eq!(Some(-5 + 4), keyboard.degree(-3));
+    assert_eq!(None, keyboard.degree(1));
+  }
+
+  #[test]
+  fn test_current_tuning_is_per_thread() {
+    let fifth: Tone = (0, (0, 0, 3));
+    set_current(Arc::new(Intonation {
+      lattice: Lattice::default(),
+      tuning: Tuning::from_name("12-et").unwrap(),
+    }));
+    assert!((cents(tone_to_freq(&fifth)) - 700f32).abs() < 0.01);
+    let other = std::thread::spawn(move || tone_to_freq(&fifth)).join().unwrap();
+    assert_eq!(1.5f32, other, "Other threads must keep their own tuning");
+    set_current(Arc::new(Intonation::default()));
+    assert_ne!(Tuning::Just.key(), Tuning::from_name("meantone").unwrap().key());
+  }
+}
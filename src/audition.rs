@@ -0,0 +1,258 @@
+/// Rendering the full range of a preset for one role, for sound design.
+///
+/// An audition plays one melody through every Visibility × Energy × Presence combination of a role
+/// (see `demo::prism`) and writes each to its own file, named after the combination:
+///
+/// ```text
+/// <out-dir>/hop_bass_0_v=visible_e=medium_p=staccatto.wav
+/// ...
+/// <out-dir>/hop_bass_35_v=hidden_e=high_p=tenuto.wav
+/// <out-dir>/index.json
+/// ```
+///
+/// The melody is a built-in test line for the role, or a part of a playbook. `index.json` lists every
+/// variant with its VEP parameters and levels, so the files can be sorted and compared without a listening pass.
+use serde::Serialize;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::analysis::tools::amp_to_db;
+use crate::analysis::volume::rms;
+use crate::cli;
+use crate::demo::prism::{self, LabelledArf};
+use crate::error::RaudioError;
+use crate::presets::Preset;
+use crate::render::context::RenderContext;
+use crate::render::{self, engrave, Renderable2};
+use crate::seed;
+use crate::synth::{self, SR};
+use crate::types::render::{Conf, Melody};
+use crate::types::synthesis::Note;
+use crate::types::timbre::{Arf, Energy, Mode, Presence, Role, Visibility};
+use crate::{files, inp};
+
+#[derive(Debug, Clone)]
+pub struct AuditionOptions {
+  /// Take the melody from a part of this playbook instead of the built-in test melody
+  pub playbook: Option<String>,
+  /// Index of the part to take from `playbook`
+  pub part: usize,
+  /// Mode of the variants. Defaults to the mode of the playbook part, or to the usual mode of the role.
+  pub mode: Option<Mode>,
+  /// Use the same random choices on every audition
+  pub seed: Option<u64>,
+  pub sample_rate: Option<usize>,
+  /// Cycles per second. Defaults to the playbook's, or 2.
+  pub cps: Option<f32>,
+  /// Root frequency. Defaults to the playbook's, or 1.12.
+  pub root: Option<f32>,
+  /// Variants rendered at the same time
+  pub jobs: usize,
+}
+
+impl Default for AuditionOptions {
+  fn default() -> Self {
+    AuditionOptions {
+      playbook: None,
+      part: 0,
+      mode: None,
+      seed: None,
+      sample_rate: None,
+      cps: None,
+      root: None,
+      jobs: 4,
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variant {
+  pub file: String,
+  pub visibility: Visibility,
+  pub energy: Energy,
+  pub presence: Presence,
+  /// Peak level in dBFS, unless the render failed
+  pub peak_db: Option<f32>,
+  /// RMS level in dBFS, unless the render failed
+  pub rms_db: Option<f32>,
+  pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Index {
+  pub preset: String,
+  pub role: Role,
+  pub mode: Mode,
+  /// The playbook and part of the melody, or "built-in"
+  pub melody: String,
+  pub cps: f32,
+  pub root: f32,
+  pub sample_rate: usize,
+  pub seed: Option<u64>,
+  pub variants: Vec<Variant>,
+}
+
+/// The mode the demos audition each role in.
+fn default_mode(role: Role) -> Mode {
+  match role {
+    Role::Kick | Role::Perc | Role::Hats => Mode::Enharmonic,
+    _ => Mode::Melodic,
+  }
+}
+
+/// Render one variant to `filename`, returning its peak and RMS levels.
+fn render_variant(
+  conf: &Conf, melody: &Melody<Note>, arf: &Arf, preset: Preset, options: &AuditionOptions, i: usize, filename: &str,
+) -> Result<(f32, f32), RaudioError> {
+  let _context = RenderContext {
+    seed: options.seed,
+    sample_rate: options.sample_rate.unwrap_or(synth::DEFAULT_SR),
+    ..RenderContext::current()
+  }
+  .for_task(&[i as u64])
+  .enter();
+  let stems: Vec<(Arf, Renderable2)> = vec![(*arf, Preset::create_stem(conf, melody, arf, preset)?)];
+  let samples = render::combiner_with_reso2(conf, &stems, &vec![], &vec![], None)?;
+  let peak = samples.iter().fold(0f32, |max, s| max.max(s.abs()));
+  let level = (amp_to_db(peak), amp_to_db(rms(&samples)));
+  engrave::try_write_audio(SR(), vec![samples], filename)?;
+  Ok(level)
+}
+
+/// Render every VEP variant of `role` with `preset` into `out_dir` and write its `index.json`.
+/// Failed variants are listed in the index without stopping the others.
+pub fn run(preset: Preset, role: Role, out_dir: &str, options: &AuditionOptions) -> Result<Index, String> {
+  let (melody, mode, conf, source) = match &options.playbook {
+    Some(path) => {
+      let mut score = inp::arg_parse::load_score_from_file(path).map_err(|e| e.to_string())?;
+      let n_parts = score.parts.len();
+      if options.part >= n_parts {
+        return Err(format!(
+          "{} has {} parts, there is no part {}",
+          path, n_parts, options.part
+        ));
+      }
+      let (_, arf, melody) = score.parts.swap_remove(options.part);
+      let conf = Conf {
+        cps: options.cps.unwrap_or(score.conf.cps),
+        root: options.root.unwrap_or(score.conf.root),
+      };
+      (
+        melody,
+        options.mode.unwrap_or(arf.mode),
+        conf,
+        format!("{} part {}", path, options.part),
+      )
+    }
+    None => {
+      let conf = Conf {
+        cps: options.cps.unwrap_or(2f32),
+        root: options.root.unwrap_or(1.12f32),
+      };
+      let mode = options.mode.unwrap_or(default_mode(role));
+      (prism::test_melody(role), mode, conf, "built-in".to_string())
+    }
+  };
+  if melody.is_empty() || melody.iter().all(|line| line.is_empty()) {
+    return Err(format!("The melody of {} has no notes", source));
+  }
+
+  let index_path = format!("{}/index.json", out_dir);
+  files::try_with_dir(&index_path).map_err(|e| format!("Failed to create {}: {}", out_dir, e))?;
+  let label = preset.to_string();
+  let arfs: Vec<LabelledArf> = prism::iter_all_vep(&label, role, mode, &melody);
+
+  let next = AtomicUsize::new(0);
+  let variants: Mutex<Vec<Option<Variant>>> = Mutex::new(arfs.iter().map(|_| None).collect());
+  thread::scope(|scope| {
+    for _ in 0..options.jobs.max(1).min(arfs.len()) {
+      scope.spawn(|| loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        let Some((name, arf)) = arfs.get(i) else {
+          break;
+        };
+        let file = format!("{}.wav", name);
+        let filename = format!("{}/{}", out_dir, file);
+        let rendered = panic::catch_unwind(AssertUnwindSafe(|| {
+          render_variant(&conf, &melody, arf, preset, options, i, &filename).map_err(|e| e.to_string())
+        }))
+        .unwrap_or_else(|panic| Err(cli::panic_message(panic)));
+        let (level, error) = match rendered {
+          Ok(level) => (Some(level), None),
+          Err(error) => {
+            eprintln!("{}: failed: {}", file, error);
+            (None, Some(error))
+          }
+        };
+        let variant = Variant {
+          file,
+          visibility: arf.visibility,
+          energy: arf.energy,
+          presence: arf.presence,
+          peak_db: level.map(|(peak, _)| peak),
+          rms_db: level.map(|(_, rms)| rms),
+          error,
+        };
+        variants.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(variant);
+      });
+    }
+  });
+
+  let index = Index {
+    preset: label,
+    role,
+    mode,
+    melody: source,
+    cps: conf.cps,
+    root: conf.root,
+    sample_rate: options.sample_rate.unwrap_or(synth::DEFAULT_SR),
+    seed: options.seed,
+    variants: variants.into_inner().unwrap_or_else(|e| e.into_inner()).into_iter().flatten().collect(),
+  };
+  let json = serde_json::to_string_pretty(&index).map_err(|e| e.to_string())?;
+  fs::write(&index_path, json).map_err(|e| format!("Failed to write {}: {}", index_path, e))?;
+  Ok(index)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::path::Path;
+
+  #[test]
+  fn test_audition_renders_every_variant() {
+    let dir = "dev-audio/audition";
+    let _ = fs::remove_dir_all(dir);
+    let options = AuditionOptions {
+      seed: Some(3),
+      cps: Some(4f32),
+      ..AuditionOptions::default()
+    };
+    let index = run(Preset::Hop, Role::Hats, dir, &options).unwrap();
+    assert_eq!(36, index.variants.len());
+    for variant in &index.variants {
+      assert_eq!(None, variant.error, "{} must render", variant.file);
+      assert!(Path::new(&format!("{}/{}", dir, variant.file)).exists());
+    }
+    assert!(index.variants[0].file.starts_with("hop_hats_0_v=visible_e=medium_p="));
+    assert!(Path::new(&format!("{}/index.json", dir)).exists());
+  }
+
+  #[test]
+  fn test_audition_checks_the_part() {
+    let dir = "dev-audio/audition_part";
+    fs::create_dir_all(dir).unwrap();
+    let playbook = format!("{}/short.json", dir);
+    fs::write(&playbook, serde_json::to_string(&crate::short_score()).unwrap()).unwrap();
+    let options = AuditionOptions {
+      playbook: Some(playbook),
+      part: 99,
+      ..AuditionOptions::default()
+    };
+    let error = run(Preset::Hop, Role::Bass, dir, &options).unwrap_err();
+    assert!(error.contains("no part 99"), "{}", error);
+  }
+}
@@ -0,0 +1,275 @@
+/// Rendering many playbooks from a manifest, for release builds.
+///
+/// A manifest lists each render with its playbook, preset, asset name and the options of `raudio render`:
+///
+/// ```text
+/// {
+///   "out_dir": "out/release",
+///   "renders": [
+///     {"playbook": "playbooks/evocative-1.json", "preset": "hop", "asset_name": "evocative-1", "seed": 7},
+///     {"playbook": "playbooks/groovy-1.json", "preset": "fum", "asset_name": "groovy-1", "seed": 3, "bits": "24"}
+///   ]
+/// }
+/// ```
+///
+/// Playbook, preset pack and tuning file paths are relative to the manifest. A render may set its own `out_dir`.
+/// Renders run a few at a time, sharing the rayon pool. After a render succeeds its inputs are stamped next to the
+/// mixdown in `<asset_name>.batch.json`, and later batches skip it until the playbook, its entry, its preset pack
+/// or the renderer changes. Every render is listed in a JSON report with its outcome and timing, and writes its
+/// progress events, including warnings like empty stems, to `<asset_name>.progress.jsonl` next to its mixdown.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use crate::analysis::tuning::Tuning;
+use crate::cli::{self, RenderFields};
+use crate::inp;
+use crate::presets::{self, Preset};
+use crate::render::cache::fnv1a;
+use crate::render::progress::ProgressSink;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOptions {
+  /// Renders run at the same time
+  pub jobs: usize,
+  /// Render every entry, even those that are up to date
+  pub force: bool,
+  /// Where to write the report. Defaults to `batch-report.json` in the manifest's `out_dir`.
+  pub report: Option<String>,
+  /// Render cache shared by every seeded render. See `render::cache`.
+  pub cache_dir: Option<String>,
+}
+
+impl Default for BatchOptions {
+  fn default() -> Self {
+    BatchOptions {
+      jobs: 2,
+      force: false,
+      report: None,
+      cache_dir: None,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+  out_dir: String,
+  renders: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+  playbook: String,
+  preset: String,
+  asset_name: String,
+  out_dir: Option<String>,
+  #[serde(flatten)]
+  options: RenderFields,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+  Rendered,
+  /// The output was already rendered from the same inputs
+  Skipped,
+  Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderReport {
+  pub asset_name: String,
+  pub playbook: String,
+  pub preset: String,
+  pub outcome: Outcome,
+  /// Path of the mixdown, unless the render failed
+  pub output: Option<String>,
+  pub error: Option<String>,
+  pub seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchReport {
+  pub rendered: usize,
+  pub skipped: usize,
+  pub failed: usize,
+  pub seconds: f64,
+  pub renders: Vec<RenderReport>,
+}
+
+/// Contents of the stamp written after a successful render.
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Stamp {
+  key: String,
+}
+
+/// Hash of everything the output of an entry depends on.
+fn stamp_key(entry: &Entry, preset: Preset, tuning: &Tuning, playbook: &[u8]) -> String {
+  let mut inputs = env!("CARGO_PKG_VERSION").as_bytes().to_vec();
+  inputs.extend(preset.key().as_bytes());
+  inputs.extend(tuning.key().as_bytes());
+  inputs.extend_from_slice(playbook);
+  // plain data always serializes
+  inputs.extend(serde_json::to_vec(entry).unwrap_or_default());
+  format!("{:016x}", fnv1a(&inputs))
+}
+
+/// Render `entry` unless its output is up to date, returning the outcome and the mixdown path.
+fn render_entry(
+  entry: &Entry, base: &Path, out_dir: &str, options: &BatchOptions,
+) -> Result<(Outcome, String), String> {
+  cli::check_asset_name(&entry.asset_name)?;
+  let preset = match presets::pack::is_pack_path(&entry.preset) {
+    true => cli::parse_preset(&base.join(&entry.preset).to_string_lossy())?,
+    false => cli::parse_preset(&entry.preset)?,
+  };
+  let mut fields = entry.options.clone();
+  let relative = |path: &Option<String>| path.as_ref().map(|p| base.join(p).to_string_lossy().to_string());
+  if fields.tuning.as_ref().is_some_and(|t| t.to_lowercase().ends_with(".scl")) {
+    fields.tuning = relative(&fields.tuning);
+  }
+  fields.keyboard = relative(&fields.keyboard);
+  let mut render_options = fields.render_options()?;
+  render_options.cache_dir = options.cache_dir.clone();
+  render_options.progress = Some(ProgressSink::File(format!("{}/{}.progress.jsonl", out_dir, entry.asset_name)));
+  let playbook = base.join(&entry.playbook).to_string_lossy().to_string();
+  let contents = fs::read(&playbook).map_err(|e| format!("Failed to read {}: {}", playbook, e))?;
+
+  let output = format!("{}/{}.wav", out_dir, entry.asset_name);
+  let stamp_path = format!("{}/{}.batch.json", out_dir, entry.asset_name);
+  let stamp = Stamp {
+    key: stamp_key(entry, preset, &render_options.tuning, &contents),
+  };
+  let stamped = fs::read(&stamp_path).ok().and_then(|json| serde_json::from_slice::<Stamp>(&json).ok());
+  if !options.force && stamped.as_ref() == Some(&stamp) && Path::new(&output).exists() {
+    return Ok((Outcome::Skipped, output));
+  }
+  let score = inp::arg_parse::load_score_from_file(&playbook).map_err(|e| e.to_string())?;
+  let output = cli::render_caught(score, preset, out_dir, &entry.asset_name, &render_options)?;
+  // plain data always serializes
+  let json = serde_json::to_vec(&stamp).unwrap_or_default();
+  fs::write(&stamp_path, json).map_err(|e| format!("Failed to write {}: {}", stamp_path, e))?;
+  Ok((Outcome::Rendered, output))
+}
+
+/// Render every entry of the manifest at `manifest_path` and write the report.
+/// Failed renders are reported without stopping the others.
+pub fn run(manifest_path: &str, options: &BatchOptions) -> Result<BatchReport, String> {
+  let start = Instant::now();
+  let json = fs::read_to_string(manifest_path).map_err(|e| format!("Failed to read {}: {}", manifest_path, e))?;
+  let manifest: Manifest =
+    serde_json::from_str(&json).map_err(|e| format!("Invalid manifest {}: {}", manifest_path, e))?;
+  let base = Path::new(manifest_path).parent().unwrap_or(Path::new("."));
+
+  let next = AtomicUsize::new(0);
+  let reports: Mutex<Vec<Option<RenderReport>>> = Mutex::new(manifest.renders.iter().map(|_| None).collect());
+  thread::scope(|scope| {
+    for _ in 0..options.jobs.max(1).min(manifest.renders.len()) {
+      scope.spawn(|| loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        let Some(entry) = manifest.renders.get(i) else {
+          break;
+        };
+        let started = Instant::now();
+        let out_dir = entry.out_dir.as_deref().unwrap_or(&manifest.out_dir);
+        let rendered = render_entry(entry, base, out_dir, options);
+        let seconds = started.elapsed().as_secs_f64();
+        let (outcome, output, error) = match rendered {
+          Ok((outcome, output)) => (outcome, Some(output), None),
+          Err(error) => (Outcome::Failed, None, Some(error)),
+        };
+        match &error {
+          Some(error) => eprintln!("{}: failed: {}", entry.asset_name, error),
+          None => println!("{}: {:?} in {:.1}s", entry.asset_name, outcome, seconds),
+        }
+        let report = RenderReport {
+          asset_name: entry.asset_name.clone(),
+          playbook: entry.playbook.clone(),
+          preset: entry.preset.clone(),
+          outcome,
+          output,
+          error,
+          seconds,
+        };
+        reports.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(report);
+      });
+    }
+  });
+
+  let renders: Vec<RenderReport> =
+    reports.into_inner().unwrap_or_else(|e| e.into_inner()).into_iter().flatten().collect();
+  let count = |outcome: Outcome| renders.iter().filter(|r| r.outcome == outcome).count();
+  let report = BatchReport {
+    rendered: count(Outcome::Rendered),
+    skipped: count(Outcome::Skipped),
+    failed: count(Outcome::Failed),
+    seconds: start.elapsed().as_secs_f64(),
+    renders,
+  };
+  let report_path = options.report.clone().unwrap_or(format!("{}/batch-report.json", manifest.out_dir));
+  crate::files::try_with_dir(&report_path).map_err(|e| format!("Failed to write {}: {}", report_path, e))?;
+  let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+  fs::write(&report_path, json).map_err(|e| format!("Failed to write {}: {}", report_path, e))?;
+  Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_batch_skips_up_to_date_renders() {
+    let dir = "dev-audio/batch";
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+      format!("{}/short.json", dir),
+      serde_json::to_string(&crate::short_score()).unwrap(),
+    )
+    .unwrap();
+    let manifest_path = format!("{}/manifest.json", dir);
+    let write_manifest = |seed: u64| {
+      let manifest = serde_json::json!({
+        "out_dir": format!("{}/out", dir),
+        "renders": [
+          {"playbook": "short.json", "preset": "fum", "asset_name": "short", "seed": seed},
+          {"playbook": "short.json", "preset": "loud", "asset_name": "broken"},
+          {"playbook": "missing.json", "preset": "fum", "asset_name": "missing"}
+        ]
+      });
+      fs::write(&manifest_path, manifest.to_string()).unwrap();
+    };
+    let outcomes =
+      |report: &BatchReport| -> Vec<Outcome> { report.renders.iter().map(|r| r.outcome).collect::<Vec<_>>() };
+
+    write_manifest(1);
+    let first = run(&manifest_path, &BatchOptions::default()).unwrap();
+    assert_eq!(
+      vec![Outcome::Rendered, Outcome::Failed, Outcome::Failed],
+      outcomes(&first)
+    );
+    assert!(first.renders[1].error.as_ref().unwrap().contains("Unknown preset"));
+    assert!(Path::new(&format!("{}/out/short.wav", dir)).exists());
+    assert!(Path::new(&format!("{}/out/batch-report.json", dir)).exists());
+    assert!(Path::new(&format!("{}/out/short.progress.jsonl", dir)).exists());
+
+    let second = run(&manifest_path, &BatchOptions::default()).unwrap();
+    assert_eq!(
+      Outcome::Skipped,
+      second.renders[0].outcome,
+      "Must skip an up to date render"
+    );
+    write_manifest(2);
+    let third = run(&manifest_path, &BatchOptions::default()).unwrap();
+    assert_eq!(
+      Outcome::Rendered,
+      third.renders[0].outcome,
+      "Must render again when its entry changes"
+    );
+    assert_eq!((1, 0, 2), (third.rendered, third.skipped, third.failed));
+  }
+}
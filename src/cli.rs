@@ -0,0 +1,810 @@
+/// Command line interface for the `raudio` binary.
+///
+/// Each subcommand returns `Ok` on success or an error message for `main` to print,
+/// so a bad argument or input file exits with a non-zero code instead of a panic.
+use crate::analysis::monic_theory::Lattice;
+use crate::analysis::sampler::read_audio_file;
+use crate::analysis::tuning::{self, Intonation, Tuning};
+use crate::analysis::tools::{amp_to_db, compute_lufs};
+use crate::analysis::volume::rms;
+use crate::audition::{self, AuditionOptions};
+use crate::batch::{self, BatchOptions};
+use crate::inp::validate;
+use crate::presets::{self, Preset};
+use crate::render::context::RenderContext;
+use crate::render::engrave::{BitDepth, Dither};
+use crate::render::markers::SidecarFormat;
+use crate::render::progress::ProgressSink;
+use crate::serve::{self, ServeOptions};
+use crate::types::render::DruidicScore;
+use crate::types::timbre::{Arf, Mode, Role};
+use crate::{fastmast, files, inp, render, render_score, RenderOptions};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+pub const USAGE: &str = r#"Usage:
+  raudio render <playbook.json|score.mid> <preset|pack.toml> <out-dir> <asset-name> [options]
+      --seed N           Use the same random choices on every render
+      --stems            Also write each part and a <asset-name>.stems.json manifest to <out-dir>
+      --format wav|mid   Render audio (default) or write the score as MIDI
+      --bend             With --format mid, bend notes to their just intonation pitch
+      --arfs FILE        With a .mid playbook, a JSON list of the Arf of each track, in order
+      --range A:B        Render only cycles A to B, with the tails of earlier notes. Other notes are skipped only
+                         with --seed and --cache, once a render of the unchanged playbook has recorded its levels
+      --bits N           WAV sample format: 16, 24, 32 or 32f (default, 32 bit float)
+      --dither MODE      Dither for 16 and 24 bit files: tpdf (default), shaped or none
+      --sample-rate N    Render at N samples per second (default 48000)
+      --markers json|csv Also write the score markers to <asset-name>.markers.json or .csv
+      --stream           Render in blocks through temporary files, for scores too long to fit in memory
+      --cache DIR        With --seed, reuse the parts that did not change since an earlier render
+      --progress DEST    Write progress and timing events as JSON lines to "stderr" or a file
+      --tuning NAME      just (default, or the tuning the playbook names), 12-et, meantone, or a Scala .scl file
+      --keyboard FILE    Lay out the degrees of a .scl tuning with a Scala .kbm keyboard mapping
+      --basis RATIO      Ratio each rotation of a tone moves by, like 4/3 or 1.25 (default 3/2)
+  raudio serve [--addr HOST:PORT] [--workers N] [--out DIR] [--cache DIR] [--pack FILE]...
+      Render jobs posted over HTTP, see src/serve.rs (default 127.0.0.1:7878, 1 worker, ./raudio-jobs)
+      --pack FILE        Load a preset pack that jobs may name, repeatable. Jobs cannot name preset files.
+  raudio batch <manifest.json> [--jobs N] [--force] [--report FILE] [--cache DIR]
+      Render every playbook listed in a manifest, see src/batch.rs (default 2 at a time)
+  raudio audition <preset|pack.toml> <role> <out-dir> [options]
+      Render a melody with all 36 Visibility, Energy and Presence variants of a role, see src/audition.rs
+      --playbook FILE    Audition part N of a playbook instead of the built-in test melody
+      --part N           The part to audition (default 0)
+      --mode MODE        melodic, enharmonic, vagrant, bell or noise (default: the part's or the role's usual mode)
+      --seed N, --sample-rate N, --cps X, --root X, --jobs N
+  raudio presets list
+  raudio validate <playbook.json|score.mid>
+  raudio analyze <file.wav>
+  raudio master <input.wav> <output.wav>
+  raudio export-midi <playbook.json> <output.mid> [--bend]
+  raudio help"#;
+
+/// Exit code for a command that was understood but failed
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for a command that could not be parsed
+pub const EXIT_USAGE: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+  Wav,
+  Mid,
+}
+
+#[derive(Debug)]
+pub struct RenderArgs {
+  pub playbook: String,
+  pub preset: Preset,
+  pub out_dir: String,
+  pub asset_name: String,
+  pub format: OutputFormat,
+  pub pitch_bend: bool,
+  /// A JSON file with the Arf of each part of a MIDI playbook, in order
+  pub arfs: Option<String>,
+  pub options: RenderOptions,
+}
+
+#[derive(Debug)]
+pub enum Command {
+  Render(RenderArgs),
+  Serve(ServeOptions),
+  Batch(String, BatchOptions),
+  Audition(Preset, Role, String, AuditionOptions),
+  ListPresets,
+  Validate(String),
+  Analyze(String),
+  Master(String, String),
+  ExportMidi(String, String, bool),
+  Help,
+}
+
+/// (positional arguments, (flag name, value))
+type SplitArgs = (Vec<String>, Vec<(String, Option<String>)>);
+
+/// Split arguments into positionals and `--flag [value]` pairs.
+/// `valued` lists the flags that take a value.
+fn split_flags(args: &[String], valued: &[&str]) -> Result<SplitArgs, String> {
+  let mut positionals = Vec::new();
+  let mut flags = Vec::new();
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    if let Some(name) = arg.strip_prefix("--") {
+      if valued.contains(&name) {
+        let value = iter.next().ok_or(format!("Missing value for --{}", name))?;
+        flags.push((name.to_string(), Some(value.clone())));
+      } else {
+        flags.push((name.to_string(), None));
+      }
+    } else {
+      positionals.push(arg.clone());
+    }
+  }
+  Ok((positionals, flags))
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+  value
+    .parse::<u64>()
+    .map_err(|_| format!("Invalid --seed '{}', expected an unsigned integer", value))
+}
+
+/// Lowest and highest sample rates accepted by `--sample-rate`
+const SAMPLE_RATES: (usize, usize) = (8000, 192000);
+
+pub(crate) fn parse_sample_rate(value: &str) -> Result<usize, String> {
+  let (min, max) = SAMPLE_RATES;
+  match value.parse::<usize>() {
+    Ok(rate) if (min..=max).contains(&rate) => Ok(rate),
+    _ => Err(format!(
+      "Invalid --sample-rate '{}', expected an integer from {} to {}",
+      value, min, max
+    )),
+  }
+}
+
+/// Parse `A:B` as a window of cycles.
+pub(crate) fn parse_range(value: &str) -> Result<(f32, f32), String> {
+  let invalid = || format!("Invalid --range '{}', expected START:END in cycles", value);
+  let (a, b) = value.split_once(':').ok_or_else(invalid)?;
+  let start = a.parse::<f32>().map_err(|_| invalid())?;
+  let end = b.parse::<f32>().map_err(|_| invalid())?;
+  if start < 0f32 || end <= start {
+    return Err(format!("Invalid --range '{}', END must be greater than START", value));
+  }
+  Ok((start, end))
+}
+
+/// A tuning by name, or from a Scala `.scl` file with an optional `.kbm` keyboard mapping.
+pub(crate) fn parse_tuning(name: &str, keyboard: Option<&str>) -> Result<Tuning, String> {
+  if name.to_lowercase().ends_with(".scl") {
+    return Tuning::load(name, keyboard).map_err(|e| e.to_string());
+  }
+  if keyboard.is_some() {
+    return Err("A keyboard mapping needs a .scl tuning".to_string());
+  }
+  find_tuning(name).map_err(|e| format!("{}, or a Scala .scl file", e))
+}
+
+/// A built-in tuning, by name only. Never reads a file.
+pub(crate) fn find_tuning(name: &str) -> Result<Tuning, String> {
+  Tuning::from_name(name)
+    .ok_or_else(|| format!("Unknown tuning '{}'. Choose one of: {}", name, tuning::NAMES.join(", ")))
+}
+
+/// A rotation basis written as a ratio `N/D` or a decimal.
+pub(crate) fn parse_basis(value: &str) -> Result<Lattice, String> {
+  let invalid = || format!("Invalid basis '{}', expected a ratio like 3/2 or a decimal like 1.5", value);
+  let basis = match value.split_once('/') {
+    Some((n, d)) => {
+      let (n, d) = (n.parse::<f32>().map_err(|_| invalid())?, d.parse::<f32>().map_err(|_| invalid())?);
+      n / d
+    }
+    None => value.parse::<f32>().map_err(|_| invalid())?,
+  };
+  Lattice::new(basis)
+}
+
+/// A preset by name, or a preset pack loaded from the file `name`.
+pub(crate) fn parse_preset(name: &str) -> Result<Preset, String> {
+  if presets::pack::is_pack_path(name) {
+    return presets::pack::load(name).map_err(|e| e.to_string());
+  }
+  find_preset(name).map_err(|e| format!("{}, or a preset pack .toml or .json file", e))
+}
+
+/// A preset or a pack this process already loaded, by name only. Never reads a file.
+pub(crate) fn find_preset(name: &str) -> Result<Preset, String> {
+  if let Some(preset) = Preset::from_name(name) {
+    return Ok(preset);
+  }
+  let names: Vec<String> = Preset::ALL.iter().map(|p| p.to_string()).chain(presets::pack::names()).collect();
+  Err(format!("Unknown preset '{}'. Choose one of: {}", name, names.join(", ")))
+}
+
+/// Options of `raudio render` as JSON fields, for jobs of `serve` and `batch`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub(crate) struct RenderFields {
+  pub seed: Option<u64>,
+  #[serde(default)]
+  pub stems: bool,
+  /// `A:B` in cycles, like `--range`
+  pub range: Option<String>,
+  pub bits: Option<String>,
+  pub dither: Option<String>,
+  pub sample_rate: Option<usize>,
+  pub markers: Option<String>,
+  #[serde(default)]
+  pub stream: bool,
+  /// A tuning name or `.scl` file, like `--tuning`
+  pub tuning: Option<String>,
+  /// A `.kbm` file, like `--keyboard`
+  pub keyboard: Option<String>,
+  /// A ratio like `4/3`, like `--basis`
+  pub basis: Option<String>,
+}
+
+impl RenderFields {
+  pub fn render_options(&self) -> Result<RenderOptions, String> {
+    let mut options = RenderOptions {
+      keep_stems: self.stems,
+      seed: self.seed,
+      streaming: self.stream,
+      ..Default::default()
+    };
+    if let Some(range) = &self.range {
+      options.window = Some(parse_range(range)?);
+    }
+    if let Some(rate) = self.sample_rate {
+      options.sample_rate = Some(parse_sample_rate(&rate.to_string())?);
+    }
+    if let Some(bits) = &self.bits {
+      options.wav_format.bit_depth =
+        BitDepth::from_name(bits).ok_or_else(|| format!("Unknown bits '{}', expected 16, 24, 32 or 32f", bits))?;
+    }
+    if let Some(dither) = &self.dither {
+      options.wav_format.dither =
+        Dither::from_name(dither).ok_or_else(|| format!("Unknown dither '{}', expected tpdf, shaped or none", dither))?;
+    }
+    if let Some(markers) = &self.markers {
+      options.marker_sidecar = Some(
+        SidecarFormat::from_name(markers)
+          .ok_or_else(|| format!("Unknown markers '{}', expected json or csv", markers))?,
+      );
+    }
+    match (&self.tuning, &self.keyboard) {
+      (Some(tuning), keyboard) => options.tuning = parse_tuning(tuning, keyboard.as_deref())?,
+      (None, Some(_)) => return Err("A keyboard mapping needs a .scl tuning".to_string()),
+      (None, None) => {}
+    }
+    if let Some(basis) = &self.basis {
+      options.lattice = parse_basis(basis)?;
+    }
+    Ok(options)
+  }
+}
+
+/// Asset names of jobs become file names in their output directory.
+pub(crate) fn check_asset_name(name: &str) -> Result<(), String> {
+  if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+    return Err(format!("Invalid asset_name '{}', expected a file name", name));
+  }
+  Ok(())
+}
+
+/// `render_score` for long running commands, where a panicking render fails only its own job.
+pub(crate) fn render_caught(
+  score: DruidicScore, preset: Preset, out_dir: &str, asset_name: &str, options: &RenderOptions,
+) -> Result<String, String> {
+  let rendered = panic::catch_unwind(AssertUnwindSafe(|| {
+    render_score(score, preset, out_dir, asset_name, options).map_err(|e| e.to_string())
+  }));
+  rendered.unwrap_or_else(|panic| Err(panic_message(panic)))
+}
+
+/// The message of a caught panic.
+pub(crate) fn panic_message(panic: Box<dyn Any + Send>) -> String {
+  panic
+    .downcast_ref::<&str>()
+    .map(|s| s.to_string())
+    .or_else(|| panic.downcast_ref::<String>().cloned())
+    .unwrap_or("Render panicked".to_string())
+}
+
+fn parse_render(args: &[String]) -> Result<Command, String> {
+  let (positionals, flags) = split_flags(args, &["seed", "format", "range", "bits", "dither", "sample-rate", "markers", "cache", "progress", "tuning", "keyboard", "basis", "arfs"])?;
+  if positionals.len() != 4 {
+    return Err("render expects <playbook> <preset> <out-dir> <asset-name>".to_string());
+  }
+  let mut render_args = RenderArgs {
+    playbook: positionals[0].clone(),
+    preset: parse_preset(&positionals[1])?,
+    out_dir: positionals[2].clone(),
+    asset_name: positionals[3].clone(),
+    format: OutputFormat::Wav,
+    pitch_bend: false,
+    arfs: None,
+    options: RenderOptions::default(),
+  };
+  let (mut tuning, mut keyboard) = (None, None);
+  for (name, value) in flags {
+    let value = value.unwrap_or_default();
+    match name.as_str() {
+      "seed" => render_args.options.seed = Some(parse_seed(&value)?),
+      "stems" => render_args.options.keep_stems = true,
+      "stream" => render_args.options.streaming = true,
+      "cache" => render_args.options.cache_dir = Some(value),
+      "progress" => render_args.options.progress = Some(ProgressSink::from_name(&value)),
+      "bend" => render_args.pitch_bend = true,
+      "arfs" => render_args.arfs = Some(value),
+      "tuning" => tuning = Some(value),
+      "keyboard" => keyboard = Some(value),
+      "basis" => render_args.options.lattice = parse_basis(&value)?,
+      "range" => render_args.options.window = Some(parse_range(&value)?),
+      "sample-rate" => render_args.options.sample_rate = Some(parse_sample_rate(&value)?),
+      "markers" => {
+        render_args.options.marker_sidecar = Some(
+          SidecarFormat::from_name(&value)
+            .ok_or_else(|| format!("Unknown --markers '{}', expected json or csv", value))?,
+        )
+      }
+      "bits" => {
+        render_args.options.wav_format.bit_depth = BitDepth::from_name(&value)
+          .ok_or_else(|| format!("Unknown --bits '{}', expected 16, 24, 32 or 32f", value))?
+      }
+      "dither" => {
+        render_args.options.wav_format.dither = Dither::from_name(&value)
+          .ok_or_else(|| format!("Unknown --dither '{}', expected tpdf, shaped or none", value))?
+      }
+      "format" => {
+        render_args.format = match value.as_str() {
+          "wav" => OutputFormat::Wav,
+          "mid" | "midi" => OutputFormat::Mid,
+          _ => return Err(format!("Unknown --format '{}', expected wav or mid", value)),
+        }
+      }
+      _ => return Err(format!("Unknown option --{} for render", name)),
+    }
+  }
+  match (tuning, keyboard) {
+    (Some(tuning), keyboard) => render_args.options.tuning = parse_tuning(&tuning, keyboard.as_deref())?,
+    (None, Some(_)) => return Err("--keyboard needs a .scl --tuning".to_string()),
+    (None, None) => {}
+  }
+  if render_args.arfs.is_some() && !inp::arg_parse::is_midi_file(&render_args.playbook) {
+    return Err("--arfs needs a .mid playbook, JSON playbooks name their own Arfs".to_string());
+  }
+  Ok(Command::Render(render_args))
+}
+
+fn parse_serve(args: &[String]) -> Result<Command, String> {
+  let (positionals, flags) = split_flags(args, &["addr", "workers", "out", "cache", "pack"])?;
+  if !positionals.is_empty() {
+    return Err(format!("Unexpected argument '{}' for serve", positionals[0]));
+  }
+  let mut options = ServeOptions::default();
+  for (name, value) in flags {
+    let value = value.unwrap_or_default();
+    match name.as_str() {
+      "addr" => options.addr = value,
+      "workers" => {
+        options.workers = match value.parse::<usize>() {
+          Ok(n) if n > 0 => n,
+          _ => return Err(format!("Invalid --workers '{}', expected a positive integer", value)),
+        }
+      }
+      "out" => options.out_dir = value,
+      "cache" => options.cache_dir = Some(value),
+      "pack" => options.packs.push(value),
+      _ => return Err(format!("Unknown option --{} for serve", name)),
+    }
+  }
+  Ok(Command::Serve(options))
+}
+
+fn parse_batch(args: &[String]) -> Result<Command, String> {
+  let (positionals, flags) = split_flags(args, &["jobs", "report", "cache"])?;
+  let [manifest] = positionals.as_slice() else {
+    return Err("batch expects <manifest.json>".to_string());
+  };
+  let mut options = BatchOptions::default();
+  for (name, value) in flags {
+    let value = value.unwrap_or_default();
+    match name.as_str() {
+      "jobs" => {
+        options.jobs = match value.parse::<usize>() {
+          Ok(n) if n > 0 => n,
+          _ => return Err(format!("Invalid --jobs '{}', expected a positive integer", value)),
+        }
+      }
+      "force" => options.force = true,
+      "report" => options.report = Some(value),
+      "cache" => options.cache_dir = Some(value),
+      _ => return Err(format!("Unknown option --{} for batch", name)),
+    }
+  }
+  Ok(Command::Batch(manifest.clone(), options))
+}
+
+/// Parse a kebab-case name of a playbook enum, such as a role or mode.
+fn parse_name<T: DeserializeOwned>(kind: &str, name: &str) -> Result<T, String> {
+  serde_json::from_value(serde_json::Value::String(name.to_lowercase()))
+    .map_err(|_| format!("Unknown {} '{}'", kind, name))
+}
+
+fn parse_audition(args: &[String]) -> Result<Command, String> {
+  let (positionals, flags) =
+    split_flags(args, &["playbook", "part", "mode", "seed", "sample-rate", "cps", "root", "jobs"])?;
+  let [preset, role, out_dir] = positionals.as_slice() else {
+    return Err("audition expects <preset> <role> <out-dir>".to_string());
+  };
+  let positive = |name: &str, value: &str| match value.parse::<f32>() {
+    Ok(x) if x > 0f32 && x.is_finite() => Ok(x),
+    _ => Err(format!("Invalid --{} '{}', expected a positive number", name, value)),
+  };
+  let mut options = AuditionOptions::default();
+  for (name, value) in flags {
+    let value = value.unwrap_or_default();
+    match name.as_str() {
+      "playbook" => options.playbook = Some(value),
+      "part" => {
+        options.part = value.parse::<usize>().map_err(|_| format!("Invalid --part '{}', expected an index", value))?
+      }
+      "mode" => options.mode = Some(parse_name::<Mode>("mode", &value)?),
+      "seed" => options.seed = Some(parse_seed(&value)?),
+      "sample-rate" => options.sample_rate = Some(parse_sample_rate(&value)?),
+      "cps" => options.cps = Some(positive("cps", &value)?),
+      "root" => options.root = Some(positive("root", &value)?),
+      "jobs" => {
+        options.jobs = match value.parse::<usize>() {
+          Ok(n) if n > 0 => n,
+          _ => return Err(format!("Invalid --jobs '{}', expected a positive integer", value)),
+        }
+      }
+      _ => return Err(format!("Unknown option --{} for audition", name)),
+    }
+  }
+  if options.part > 0 && options.playbook.is_none() {
+    return Err("--part needs a --playbook".to_string());
+  }
+  Ok(Command::Audition(
+    parse_preset(preset)?,
+    parse_name::<Role>("role", role)?,
+    out_dir.clone(),
+    options,
+  ))
+}
+
+/// Parse the arguments following the program name.
+pub fn parse(args: &[String]) -> Result<Command, String> {
+  let arg = |i: usize| args.get(i).map(|s| s.as_str());
+  match arg(0) {
+    None | Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+    Some("render") => parse_render(&args[1..]),
+    Some("serve") => parse_serve(&args[1..]),
+    Some("batch") => parse_batch(&args[1..]),
+    Some("audition") => parse_audition(&args[1..]),
+    Some("presets") => match arg(1) {
+      Some("list") | None => Ok(Command::ListPresets),
+      Some(other) => Err(format!("Unknown presets command '{}'", other)),
+    },
+    Some("validate") if args.len() == 2 => Ok(Command::Validate(args[1].clone())),
+    Some("analyze") if args.len() == 2 => Ok(Command::Analyze(args[1].clone())),
+    Some("master") | Some("apply-danceability") if args.len() == 3 => {
+      Ok(Command::Master(args[1].clone(), args[2].clone()))
+    }
+    Some("export-midi") => {
+      let (positionals, flags) = split_flags(&args[1..], &[])?;
+      match (positionals.as_slice(), flags.as_slice()) {
+        ([playbook, output], []) => Ok(Command::ExportMidi(playbook.clone(), output.clone(), false)),
+        ([playbook, output], [(bend, None)]) if bend == "bend" => {
+          Ok(Command::ExportMidi(playbook.clone(), output.clone(), true))
+        }
+        _ => Err("export-midi expects <playbook.json> <output.mid> [--bend]".to_string()),
+      }
+    }
+    Some(cmd @ ("validate" | "analyze" | "master" | "apply-danceability")) => {
+      Err(format!("Wrong number of arguments for {}", cmd))
+    }
+    // the original form: raudio playbook.json preset-name asset-dir asset-name
+    Some(_) => parse_render(args),
+  }
+}
+
+fn load_score(path: &str) -> Result<DruidicScore, String> {
+  inp::arg_parse::load_score_from_file(path).map_err(|e| e.to_string())
+}
+
+/// The Arfs of `--arfs`, a JSON list like the Arfs of a playbook's parts.
+fn load_arfs(path: &str) -> Result<Vec<Arf>, String> {
+  let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+  serde_json::from_str(&json).map_err(|e| format!("Invalid Arf list in {}: {}", path, e))
+}
+
+fn run_render(args: RenderArgs) -> Result<(), String> {
+  let score = match &args.arfs {
+    Some(path) => inp::arg_parse::load_score_with_arfs(&args.playbook, &load_arfs(path)?).map_err(|e| e.to_string())?,
+    None => load_score(&args.playbook)?,
+  };
+  match args.format {
+    OutputFormat::Mid => {
+      let path = format!("{}/{}.mid", args.out_dir, args.asset_name);
+      let _context = RenderContext {
+        intonation: Arc::new(Intonation {
+          lattice: args.options.lattice,
+          tuning: args.options.tuning.clone(),
+        }),
+        ..RenderContext::current()
+      }
+      .enter();
+      let problems = render::midi::score(&score, args.pitch_bend, &path).map_err(|e| e.to_string())?;
+      report_export_problems(&args.playbook, &problems);
+      println!("{}", path);
+    }
+    OutputFormat::Wav => {
+      let path =
+        render_score(score, args.preset, &args.out_dir, &args.asset_name, &args.options).map_err(|e| e.to_string())?;
+      println!("{}", path);
+    }
+  }
+  Ok(())
+}
+
+fn list_presets() {
+  for preset in Preset::ALL {
+    println!("{}", preset);
+  }
+}
+
+/// Print the warnings of a MIDI export to stderr, keeping stdout for the path written.
+fn report_export_problems(playbook: &str, problems: &[validate::Problem]) {
+  for problem in problems {
+    eprintln!("{}: {}", playbook, problem);
+  }
+}
+
+fn validate(path: &str) -> Result<(), String> {
+  let problems = if inp::arg_parse::is_midi_file(path) {
+    validate::validate_score(&inp::midi::load_score(path, &[])?)
+  } else {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    validate::validate_playbook(&json)
+  };
+  for problem in &problems {
+    println!("{}: {}", path, problem);
+  }
+  let n_errors = problems.iter().filter(|p| p.is_error()).count();
+  if n_errors > 0 {
+    return Err(format!(
+      "{}: {} errors, {} warnings",
+      path,
+      n_errors,
+      problems.len() - n_errors
+    ));
+  }
+  println!("{}: ok ({} warnings)", path, problems.len());
+  Ok(())
+}
+
+fn analyze(path: &str) -> Result<(), String> {
+  let (channels, sample_rate) = read_audio_file(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+  let n_samples = channels.iter().map(|c| c.len()).max().unwrap_or(0);
+  println!("file: {}", path);
+  println!("sample rate: {}", sample_rate);
+  println!("channels: {}", channels.len());
+  println!("duration: {:.3}s", n_samples as f32 / sample_rate as f32);
+  for (i, channel) in channels.iter().enumerate() {
+    let peak = channel.iter().fold(0f32, |max, s| max.max(s.abs()));
+    println!(
+      "channel {}: peak {:.2} dBFS, rms {:.2} dBFS",
+      i,
+      amp_to_db(peak),
+      amp_to_db(rms(channel))
+    );
+  }
+  let mono: Vec<f32> = (0..n_samples)
+    .map(|i| channels.iter().map(|c| c.get(i).unwrap_or(&0f32)).sum::<f32>() / channels.len() as f32)
+    .collect();
+  if let Ok(lufs) = compute_lufs(&mono, 0.4f32) {
+    println!("loudness: {:.2} LUFS", lufs);
+  }
+  Ok(())
+}
+
+fn master(input: &str, output: &str) -> Result<(), String> {
+  // check the input first, fastmast panics on unreadable files
+  read_audio_file(input).map_err(|e| format!("Failed to read {}: {}", input, e))?;
+  files::with_dir(output);
+  fastmast::apply_danceability(input, output);
+  println!("{}", output);
+  Ok(())
+}
+
+/// Run a parsed command.
+pub fn run(command: Command) -> Result<(), String> {
+  match command {
+    Command::Help => {
+      println!("{}", USAGE);
+      Ok(())
+    }
+    Command::Render(args) => run_render(args),
+    Command::Serve(options) => {
+      let server = serve::Server::bind(&options).map_err(|e| e.to_string())?;
+      println!("Listening on http://{}", server.local_addr().map_err(|e| e.to_string())?);
+      server.run();
+      Ok(())
+    }
+    Command::Batch(manifest, options) => {
+      let report = batch::run(&manifest, &options)?;
+      println!(
+        "{} rendered, {} skipped, {} failed in {:.1}s",
+        report.rendered, report.skipped, report.failed, report.seconds
+      );
+      if report.failed > 0 {
+        return Err(format!("{} of {} renders failed", report.failed, report.renders.len()));
+      }
+      Ok(())
+    }
+    Command::Audition(preset, role, out_dir, options) => {
+      let index = audition::run(preset, role, &out_dir, &options)?;
+      let failed = index.variants.iter().filter(|v| v.error.is_some()).count();
+      println!("{}/index.json", out_dir);
+      if failed > 0 {
+        return Err(format!("{} of {} variants failed", failed, index.variants.len()));
+      }
+      Ok(())
+    }
+    Command::ListPresets => {
+      list_presets();
+      Ok(())
+    }
+    Command::Validate(path) => validate(&path),
+    Command::Analyze(path) => analyze(&path),
+    Command::Master(input, output) => master(&input, &output),
+    Command::ExportMidi(playbook, output, pitch_bend) => {
+      let score = load_score(&playbook)?;
+      let problems = render::midi::score(&score, pitch_bend, &output).map_err(|e| e.to_string())?;
+      report_export_problems(&playbook, &problems);
+      println!("{}", output);
+      Ok(())
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn args(s: &str) -> Vec<String> {
+    s.split_whitespace().map(|a| a.to_string()).collect()
+  }
+
+  #[test]
+  fn test_parse_render() {
+    let cmd = parse(&args(
+      "render a.json Hop out name --seed 17 --stems --range 4:8.5 --bits 24 --dither shaped --sample-rate 44100 --stream --cache tmp --progress stderr --tuning meantone --basis 4/3",
+    ))
+    .unwrap();
+    match cmd {
+      Command::Render(r) => {
+        assert_eq!("a.json", r.playbook);
+        assert_eq!("hop", r.preset.to_string());
+        assert_eq!(("out", "name"), (r.out_dir.as_str(), r.asset_name.as_str()));
+        assert_eq!(Some(17), r.options.seed);
+        assert!(r.options.keep_stems);
+        assert_eq!(Some((4f32, 8.5f32)), r.options.window);
+        assert_eq!(BitDepth::Int24, r.options.wav_format.bit_depth);
+        assert_eq!(Dither::Shaped, r.options.wav_format.dither);
+        assert_eq!(Some(44100), r.options.sample_rate);
+        assert!(r.options.streaming);
+        assert_eq!(Some("tmp"), r.options.cache_dir.as_deref());
+        assert_eq!(Some(ProgressSink::Stderr), r.options.progress);
+        assert_eq!("meantone", r.options.tuning.to_string());
+        assert_eq!(4f32 / 3f32, r.options.lattice.basis);
+        assert_eq!(OutputFormat::Wav, r.format);
+      }
+      other => panic!("Expected a render command, got {:?}", other),
+    }
+    for preset in ["ambien", "kuwuku", "urbuntu", "hill", "misc"] {
+      assert!(parse(&args(&format!("render a.json {} out name", preset))).is_ok());
+    }
+  }
+
+  #[test]
+  fn test_parse_serve() {
+    match parse(&args("serve --addr 127.0.0.1:9000 --workers 3 --cache tmp --pack a.toml --pack b.json")).unwrap() {
+      Command::Serve(options) => {
+        assert_eq!("127.0.0.1:9000", options.addr);
+        assert_eq!(3, options.workers);
+        assert_eq!(Some("tmp"), options.cache_dir.as_deref());
+        assert_eq!(vec!["a.toml", "b.json"], options.packs);
+        assert_eq!(ServeOptions::default().out_dir, options.out_dir);
+      }
+      other => panic!("Expected a serve command, got {:?}", other),
+    }
+    assert!(parse(&args("serve --workers 0")).is_err());
+    assert!(parse(&args("serve extra")).is_err());
+  }
+
+  #[test]
+  fn test_parse_batch() {
+    match parse(&args("batch release.json --jobs 4 --force --report r.json")).unwrap() {
+      Command::Batch(manifest, options) => {
+        assert_eq!("release.json", manifest);
+        assert_eq!(4, options.jobs);
+        assert!(options.force);
+        assert_eq!(Some("r.json"), options.report.as_deref());
+      }
+      other => panic!("Expected a batch command, got {:?}", other),
+    }
+    assert!(parse(&args("batch")).is_err());
+    assert!(parse(&args("batch a.json --jobs none")).is_err());
+  }
+
+  #[test]
+  fn test_parse_audition() {
+    match parse(&args("audition hop Bass out --playbook a.json --part 2 --mode bell --seed 5 --cps 3")).unwrap() {
+      Command::Audition(preset, role, out_dir, options) => {
+        assert_eq!(("hop", "bass", "out"), (preset.to_string().as_str(), role.to_string().as_str(), out_dir.as_str()));
+        assert_eq!((Some("a.json"), 2), (options.playbook.as_deref(), options.part));
+        assert!(matches!(options.mode, Some(Mode::Bell)));
+        assert_eq!((Some(5), Some(3f32)), (options.seed, options.cps));
+      }
+      other => panic!("Expected an audition command, got {:?}", other),
+    }
+    assert!(parse(&args("audition hop bass")).is_err());
+    assert!(parse(&args("audition hop drums out")).unwrap_err().contains("Unknown role"));
+    assert!(parse(&args("audition hop bass out --mode loud")).is_err());
+    assert!(parse(&args("audition hop bass out --part 1")).is_err());
+    assert!(parse(&args("audition hop bass out --cps 0")).is_err());
+  }
+
+  #[test]
+  fn test_parse_legacy_forms() {
+    assert!(matches!(
+      parse(&args("a.json fum out name --seed 3")),
+      Ok(Command::Render(_))
+    ));
+    assert!(matches!(
+      parse(&args("apply-danceability in.wav out.wav")),
+      Ok(Command::Master(_, _))
+    ));
+    assert!(matches!(parse(&args("")), Ok(Command::Help)));
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    assert!(parse(&args("render a.json hop out")).is_err());
+    assert!(parse(&args("render a.json hop out name --seed x")).is_err());
+    assert!(parse(&args("render a.json hop out name --seed")).is_err());
+    assert!(parse(&args("render a.json hop out name --range 8:4")).is_err());
+    assert!(parse(&args("render a.json hop out name --format mp3")).is_err());
+    assert!(parse(&args("render a.json hop out name --bits 8")).is_err());
+    assert!(parse(&args("render a.json hop out name --dither loud")).is_err());
+    assert!(parse(&args("render a.json hop out name --sample-rate 1000000")).is_err());
+    assert!(parse(&args("render a.json hop out name --markers xml")).is_err());
+    assert!(parse(&args("render a.json hop out name --loud")).is_err());
+    assert!(parse(&args("validate")).is_err());
+    assert!(parse(&args("render a.json missing-pack.toml out name")).is_err());
+    assert!(parse(&args("render a.json hop out name --tuning pythagorean")).unwrap_err().contains("Unknown tuning"));
+    assert!(parse(&args("render a.json hop out name --tuning meantone --keyboard a.kbm")).is_err());
+    assert!(parse(&args("render a.json hop out name --keyboard src/demo/tunings/19-edo.kbm")).is_err());
+    assert!(parse(&args("render a.json hop out name --basis 2/3")).unwrap_err().contains("above 1"));
+    assert!(parse(&args("render a.json hop out name --basis fifth")).unwrap_err().contains("Invalid basis"));
+    assert!(parse(&args("render a.json hop out name --arfs arfs.json")).unwrap_err().contains(".mid playbook"));
+  }
+
+  #[test]
+  fn test_render_arfs_choose_midi_timbre() {
+    let playbook = load_score("src/demo/playbooks/house_short_1.json").unwrap();
+    let dir = std::env::temp_dir().join(format!("raudio-cli-arfs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mid = dir.join("score.mid").to_string_lossy().into_owned();
+    let arfs = dir.join("arfs.json").to_string_lossy().into_owned();
+    std::fs::write(&mid, render::midi::encode_score(&playbook, false).unwrap().0).unwrap();
+    let mut chosen: Vec<Arf> = playbook.parts.iter().map(|(_, arf, _)| *arf).collect();
+    for arf in chosen.iter_mut() {
+      arf.role = Role::Chords;
+      arf.mode = Mode::Enharmonic;
+    }
+    std::fs::write(&arfs, serde_json::to_string(&chosen).unwrap()).unwrap();
+
+    match parse(&args(&format!("render {} hop out name --arfs {}", mid, arfs))).unwrap() {
+      Command::Render(r) => assert_eq!(Some(arfs.as_str()), r.arfs.as_deref()),
+      other => panic!("Expected a render command, got {:?}", other),
+    }
+    let score = inp::arg_parse::load_score_with_arfs(&mid, &load_arfs(&arfs).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(!score.parts.is_empty());
+    for (_, arf, _) in &score.parts {
+      assert!(matches!((arf.role, arf.mode), (Role::Chords, Mode::Enharmonic)), "Must use the Arf from --arfs");
+    }
+  }
+
+  #[test]
+  fn test_validate_reports_missing_file() {
+    assert!(run(Command::Validate("does/not/exist.json".to_string())).is_err());
+    assert!(run(Command::Validate("src/demo/playbooks/house_short_1.json".to_string())).is_ok());
+  }
+}
@@ -191,7 +191,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let delays: Vec<DelayParams> = vec![delay::passthrough];
 
@@ -229,7 +229,7 @@ fn demonstrate() {
             rate: *rate,
             amp: *amp,
           }];
-          let samples = render::combiner(cps, root, &renderables, &reverbs, keep_stems);
+          let samples = render::combiner(cps, root, &renderables, &reverbs, keep_stems).unwrap();
           let filename = format!(
             "{}/reverb_amp_{}_mix_{}_rate_{}_dur_{}.wav",
             location(demo_name),
@@ -238,7 +238,7 @@ fn demonstrate() {
             rate,
             dur
           );
-          render::engrave::samples(SR, &samples, &filename);
+          render::engrave::samples(SR(), &samples, &filename).unwrap();
         }
       }
     }
@@ -247,7 +247,7 @@ fn demonstrate() {
 
 fn samp(c: f32, r: f32) -> SampleBuffer {
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let delays: Vec<DelayParams> = vec![delay::passthrough];
 
@@ -268,7 +268,7 @@ fn samp(c: f32, r: f32) -> SampleBuffer {
   let complexity: f32 = rng.gen::<f32>();
   let group_reverbs = crate::inp::arg_xform::gen_reverbs(&mut rng, cps, &Distance::Near, &Enclosure::Vast, complexity);
 
-  render::combiner(c, r, &renderables, &group_reverbs, None)
+  render::combiner(c, r, &renderables, &group_reverbs, None).unwrap()
 }
 
 #[test]
@@ -287,7 +287,7 @@ fn test_hypnosis() {
   let n_loops = 4;
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let mut roott: f32 = rng.gen::<f32>();
   let base_cps: f32 = 1.2f32 + rng.gen::<f32>();
@@ -311,7 +311,7 @@ fn test_hypnosis() {
   }
 
   let filename = format!("{}/hypnoloop_{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &track, &filename);
+  render::engrave::samples(SR(), &track, &filename).unwrap();
 }
 
 fn render_group(n_versions: usize, n_loops: usize, label: &str) {
@@ -325,7 +325,7 @@ fn render_group(n_versions: usize, n_loops: usize, label: &str) {
   }
 
   let filename = format!("{}/hypnoloop_{}_{}.wav", location(demo_name), demo_name, label);
-  render::engrave::samples(SR, &track, &filename);
+  render::engrave::samples(SR(), &track, &filename).unwrap();
 }
 
 #[test]
@@ -348,5 +348,6 @@ fn test_render_playbook() {
     "mountain",
     "src/demo/playbook-demo-ambien.json",
     "test-preset-ambien",
-  )
+    None,
+  ).unwrap()
 }
@@ -207,7 +207,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let delays: Vec<DelayParams> = vec![delay::passthrough];
 
@@ -216,9 +216,9 @@ fn demonstrate() {
   let kick_mel = kick_melody();
   let conf = Conf { cps, root };
 
-  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf());
-  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf());
-  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf());
+  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf()).unwrap();
+  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf()).unwrap();
+  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf()).unwrap();
 
   let renderables: Vec<(Arf,Renderable2)> = vec![
     (kick_arf(), stem_kick), 
@@ -238,14 +238,14 @@ fn demonstrate() {
 
   let keep_stems = Some(path.as_str());
 
-  let mix = render::combiner_with_reso2(&conf, &renderables, &vec![], &group_reverbs, keep_stems);
+  let mix = render::combiner_with_reso2(&conf, &renderables, &vec![], &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 fn samp(c: f32, r: f32) -> SampleBuffer {
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let delays: Vec<DelayParams> = vec![delay::passthrough];
 
@@ -254,9 +254,9 @@ fn samp(c: f32, r: f32) -> SampleBuffer {
   let kick_mel = kick_melody();
   let conf = Conf { cps, root };
 
-  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf());
-  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf());
-  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf());
+  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf()).unwrap();
+  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf()).unwrap();
+  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf()).unwrap();
 
   use Renderable2::{Group, Instance};
   let renderables: Vec<(Arf,Renderable2)> = vec![
@@ -276,7 +276,7 @@ fn samp(c: f32, r: f32) -> SampleBuffer {
     crate::inp::arg_xform::reverb_params(&mut rng, cps, 32f32/c, &Distance::Near, &Enclosure::Vast, complexity)
   ];
 
-  render::combiner_with_reso2(&conf, &renderables, &vec![], &group_reverbs, None)
+  render::combiner_with_reso2(&conf, &renderables, &vec![], &group_reverbs, None).unwrap()
 }
 
 #[test]
@@ -296,7 +296,7 @@ fn test_hypnosis() {
   let n_loops = 2;
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let mut roott: f32 = rng.gen::<f32>();
   let base_cps: f32 = 1.2f32 + rng.gen::<f32>();
@@ -320,7 +320,7 @@ fn test_hypnosis() {
   }
 
   let filename = format!("{}/hypnoloop_{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &track, &filename);
+  render::engrave::samples(SR(), &track, &filename).unwrap();
 }
 
 fn render_group(n_versions: usize, n_loops: usize, label: &str) {
@@ -334,7 +334,7 @@ fn render_group(n_versions: usize, n_loops: usize, label: &str) {
   }
 
   let filename = format!("{}/hypnoloop_{}_{}.wav", location(demo_name), demo_name, label);
-  render::engrave::samples(SR, &track, &filename);
+  render::engrave::samples(SR(), &track, &filename).unwrap();
 }
 
 #[test]
@@ -352,5 +352,5 @@ fn test_demo() {
 fn test_render_playbook() {
   let filepath: &str = &format!("{}/demo/amen/test_amen_playbook", crate::demo::out_dir);
 
-  crate::render_playbook(filepath, "hop", "src/demo/playbook-demo-ambien.json", "test-preset-fum")
+  crate::render_playbook(filepath, "hop", "src/demo/playbook-demo-ambien.json", "test-preset-fum", None).unwrap()
 }
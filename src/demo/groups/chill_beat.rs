@@ -16,7 +16,7 @@ use crate::types::synthesis::{
 };
 
 use presets::mountain::{hats, kick, perc};
-use rand::thread_rng;
+use crate::seed::thread_rng;
 
 fn kick_melody() -> Melody<Note> {
   let tala: Vec<Duration> = vec![
@@ -149,7 +149,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 1.2;
   let cps: f32 = 3.1;
@@ -162,13 +162,13 @@ fn demonstrate() {
   let perc_melody = perc_melody();
   let kick_mel = kick_melody();
 
-  let stem_hats1 = hats::renderable(&conf, &hats_melody, &hats_arf(Presence::Staccatto));
+  let stem_hats1 = hats::renderable(&conf, &hats_melody, &hats_arf(Presence::Staccatto)).unwrap();
   let stem_hats2 = hats::renderable(&conf, &hats_melody, &hats_arf(Presence::Legato));
   let stem_hats3 = hats::renderable(&conf, &hats_melody, &hats_arf(Presence::Tenuto));
-  let stem_perc1 = perc::renderable(&conf, &perc_melody, &perc_arf(Presence::Staccatto));
+  let stem_perc1 = perc::renderable(&conf, &perc_melody, &perc_arf(Presence::Staccatto)).unwrap();
   let stem_perc2 = perc::renderable(&conf, &perc_melody, &perc_arf(Presence::Legato));
   let stem_perc3 = perc::renderable(&conf, &perc_melody, &perc_arf(Presence::Tenuto));
-  let stem_kick1 = kick::renderable(&conf, &kick_mel, &kick_arf(Presence::Staccatto));
+  let stem_kick1 = kick::renderable(&conf, &kick_mel, &kick_arf(Presence::Staccatto)).unwrap();
   let stem_kick2 = kick::renderable(&conf, &kick_mel, &kick_arf(Presence::Legato));
   let stem_kick3 = kick::renderable(&conf, &kick_mel, &kick_arf(Presence::Tenuto));
 
@@ -194,9 +194,9 @@ fn demonstrate() {
   let group_reverbs = crate::inp::arg_xform::gen_reverbs(&mut rng, cps, &Distance::Near, &Enclosure::Vast, complexity);
   let keep_stems = Some(path.as_str());
 
-  let mix = render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, keep_stems);
+  let mix = render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", path, demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 #[test]
@@ -206,7 +206,7 @@ fn test_demonstrate() {
 
 fn samp(cps: f32, root: f32) -> SampleBuffer {
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let delays: Vec<DelayParams> = vec![delay::passthrough];
 
@@ -219,9 +219,9 @@ fn samp(cps: f32, root: f32) -> SampleBuffer {
 
   let conf: Conf = Conf { cps, root };
 
-  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf(Presence::Legato));
-  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf(Presence::Staccatto));
-  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf(Presence::Tenuto));
+  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf(Presence::Legato)).unwrap();
+  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf(Presence::Staccatto)).unwrap();
+  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf(Presence::Tenuto)).unwrap();
 
   use Renderable::{Group, Instance};
   
@@ -239,7 +239,7 @@ fn samp(cps: f32, root: f32) -> SampleBuffer {
     // crate::inp::arg_xform::reverb_params(&mut rng, len_seconds, cps, &Distance::Near, &Enclosure::Spring, complexity)
   ];
   // let group_reverbs = crate::inp::arg_xform::gen_reverbs(&mut rng, cps, &Distance::Near, &Enclosure::Spring, complexity);
-  render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, None)
+  render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, None).unwrap()
 }
 
 #[test]
@@ -253,7 +253,7 @@ fn test_hypnosis() {
   let n_loops = 4;
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let mut root: f32 = rng.gen::<f32>();
   let base_cps: f32 = 1.2f32 + rng.gen::<f32>();
@@ -277,7 +277,7 @@ fn test_hypnosis() {
   }
 
   let filename = format!("{}/hypnoloop_{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &track, &filename);
+  render::engrave::samples(SR(), &track, &filename).unwrap();
 }
 
 #[test]
@@ -289,5 +289,6 @@ fn test_render_playbook() {
     "hop",
     "src/demo/playbook-demo-ambien.json",
     "test-preset-ambien",
-  )
+    None,
+  ).unwrap()
 }
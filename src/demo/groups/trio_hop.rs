@@ -157,7 +157,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 1.15;
   let root: f32 = 1.9;
@@ -167,9 +167,9 @@ fn demonstrate() {
   let bass_melody = bass_melody();
   let conf: Conf = Conf { cps, root };
 
-  let stem_lead = lead::renderable(&conf, &lead_melody, &lead_arf());
-  let stem_chords = chords::renderable(&conf, &chords_melody, &chords_arf());
-  let stem_bass = bass::renderable(&conf, &bass_melody, &bass_arf());
+  let stem_lead = lead::renderable(&conf, &lead_melody, &lead_arf()).unwrap();
+  let stem_chords = chords::renderable(&conf, &chords_melody, &chords_arf()).unwrap();
+  let stem_bass = bass::renderable(&conf, &bass_melody, &bass_arf()).unwrap();
 
   let renderables: Vec<(Arf,Renderable2)> = vec![
     // (kick_arf(), stem_kick), 
@@ -186,9 +186,9 @@ fn demonstrate() {
   let complexity: f32 = rng.gen::<f32>().min(0.01);
   let group_reverbs = vec![];
   let keep_stems = Some(path.as_str());
-  let mix = render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, keep_stems);
+  let mix = render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 #[test]
@@ -33,7 +33,8 @@ use crate::{
   presets::{self, Preset},
   render,
 };
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use crate::seed::thread_rng;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 pub fn location(name: &str) -> String {
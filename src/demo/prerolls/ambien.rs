@@ -292,7 +292,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 1.15;
   let root: f32 = 1.9;
@@ -323,9 +323,9 @@ fn demonstrate() {
   let group_reverbs = crate::inp::arg_xform::gen_reverbs(&mut rng, cps, &Distance::Near, &Enclosure::Room, complexity);
   let keep_stems = Some(path.as_str());
   let group_reverbs = vec![];
-  let mix = render::combiner(cps, root, &renderables, &group_reverbs, keep_stems);
+  let mix = render::combiner(cps, root, &renderables, &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 #[test]
@@ -342,7 +342,8 @@ fn test_render_playbook() {
     "mountain",
     "src/demo/playbook-demo-ambien.json",
     "test-preset-ambien",
-  )
+    None,
+  ).unwrap()
 }
 
 #[test]
@@ -354,5 +355,6 @@ fn test_render_deck_the_hall() {
     "mountain",
     "src/demo/playbook-demo-ambien.json",
     "test-playbook-deck-the-hall",
-  )
+    None,
+  ).unwrap()
 }
@@ -292,7 +292,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 1.15;
   let root: f32 = 1.9;
@@ -307,12 +307,12 @@ fn demonstrate() {
   let kick_mel = kick_melody();
   let conf: Conf = Conf { cps, root };
 
-  let stem_lead = lead::renderable(&conf, &lead_melody, &lead_arf());
-  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf());
-  let stem_chords = chords::renderable(&conf, &chords_melody, &chords_arf());
-  let stem_bass = bass::renderable(&conf, &bass_melody, &bass_arf());
-  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf());
-  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf());
+  let stem_lead = lead::renderable(&conf, &lead_melody, &lead_arf()).unwrap();
+  let stem_hats = hats::renderable(&conf, &hats_melody, &hats_arf()).unwrap();
+  let stem_chords = chords::renderable(&conf, &chords_melody, &chords_arf()).unwrap();
+  let stem_bass = bass::renderable(&conf, &bass_melody, &bass_arf()).unwrap();
+  let stem_perc = perc::renderable(&conf, &perc_melody, &perc_arf()).unwrap();
+  let stem_kick = kick::renderable(&conf, &kick_mel, &kick_arf()).unwrap();
 
   use Renderable::{Group, Instance};
   let renderables: Vec<(Arf,Renderable2)> = vec![
@@ -331,9 +331,9 @@ fn demonstrate() {
   let group_reverbs = crate::inp::arg_xform::gen_reverbs(&mut rng, cps, &Distance::Near, &Enclosure::Room, complexity);
   let keep_stems = Some(path.as_str());
   let group_reverbs = vec![];
-  let mix = render::combiner_with_reso2(&conf, &renderables, &vec![], &group_reverbs, keep_stems);
+  let mix = render::combiner_with_reso2(&conf, &renderables, &vec![], &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 #[test]
@@ -349,7 +349,8 @@ fn test_render_playbook() {
     "mountain",
     "src/demo/playbook-demo-ambien.json",
     "test-preset-ambien",
-  )
+    None,
+  ).unwrap()
 }
 
 #[test]
@@ -360,5 +361,6 @@ fn test_render_deck_the_hall() {
     "fum",
     "src/demo/playbooks/deck-the-hall-house-playbook.json",
     "test-fum-deck-the-hall",
-  )
+    None,
+  ).unwrap()
 }
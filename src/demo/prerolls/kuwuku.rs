@@ -292,7 +292,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 1.15;
   let root: f32 = 1.2;
@@ -324,9 +324,9 @@ fn demonstrate() {
   let group_reverbs = crate::inp::arg_xform::gen_reverbs(&mut rng, cps, &Distance::Near, &Enclosure::Vast, complexity);
   let keep_stems = Some(path.as_str());
 
-  let mix = render::combiner(cps, root, &renderables, &group_reverbs, keep_stems);
+  let mix = render::combiner(cps, root, &renderables, &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 #[test]
@@ -3,6 +3,7 @@ use render::engrave;
 /// Methods for examining a preset from any desired angle
 use super::*;
 use crate::analysis::melody::find_reach;
+use crate::error::RaudioError;
 
 /// iterations happen from first to last.
 /// so sort these in an order that matches which stems you want to read first.
@@ -20,6 +21,26 @@ pub const PRESENCES: [Presence; 3] = [Presence::Staccatto, Presence::Legato, Pre
 
 pub type LabelledArf = (String, Arf);
 
+/// A short line in the usual register of `role`, for auditioning a preset without a playbook.
+/// Percussion repeats one tone; the other roles walk the first triad above and below the root.
+pub fn test_melody(role: Role) -> Melody<Note> {
+  let (register, step): (i8, Duration) = match role {
+    Role::Kick => (5, (1, 1)),
+    Role::Perc => (7, (1, 1)),
+    Role::Hats => (12, (1, 2)),
+    Role::Bass => (5, (1, 1)),
+    Role::Chords => (7, (2, 1)),
+    Role::Lead => (9, (1, 2)),
+  };
+  let monaes: Vec<Monae> = match role {
+    Role::Kick | Role::Perc | Role::Hats => vec![(0, 0, 1); 8],
+    _ => vec![(0, 0, 1), (0, 0, 5), (0, 0, 3), (1, 0, 1), (1, 0, 5), (1, 0, 3), (-1, 0, 5), (-1, 0, 1)],
+  };
+  let amps: Vec<Ampl> = (0..monaes.len()).map(|i| if i % 2 == 0 { 1f32 } else { 0.66f32 }).collect();
+  let tones: Vec<Tone> = monaes.into_iter().map(|monae| (register, monae)).collect();
+  vec![zip_line(vec![step; tones.len()], tones, amps)]
+}
+
 /// Given a melody, role, and mode,
 /// Create all variations possible (with respect to VEP parameters)
 pub fn iter_all_vep<'render>(
@@ -91,18 +112,18 @@ pub fn iter_vep<'render>(
 /// Render each labelled arf using the preset into destination_dir.
 pub fn render_labelled_arf(
   destination_dir: &str, root: f32, cps: f32, melody: &Melody<Note>, (label, arf): &LabelledArf, preset: Preset,
-) {
+) -> Result<(), RaudioError> {
   let conf: Conf = Conf { root, cps };
 
   let group_reverbs: Vec<ReverbParams> = vec![];
   let keep_stems = Some(destination_dir);
   let stems: Vec<(Arf,Renderable2)> = vec![
-    (arf.clone(), Preset::create_stem(&conf, melody, arf, preset))
+    (arf.clone(), Preset::create_stem(&conf, melody, arf, preset).unwrap())
   ];
 
-  let samples = render::combiner_with_reso2(&conf, &stems, &vec![], &group_reverbs, keep_stems);
+  let samples = render::combiner_with_reso2(&conf, &stems, &vec![], &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", destination_dir, label);
-  engrave::samples(SR, &samples, &filename);
+  engrave::samples(SR(), &samples, &filename)
 }
 
 use std::env;
@@ -143,12 +164,12 @@ pub fn run(
 
     pool.install(|| {
       (labelled_arfs).par_iter().for_each(|arf| {
-        prism::render_labelled_arf(path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in labelled_arfs {
-      prism::render_labelled_arf(path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
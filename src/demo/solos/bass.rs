@@ -96,7 +96,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 2.12;
   let root: f32 = 1.09f32;
@@ -129,9 +129,9 @@ fn demonstrate() {
   let group_reverbs: Vec<crate::reverb::convolution::ReverbParams> = vec![];
   let keep_stems = Some(path.as_str());
   let group_reverbs = vec![];
-  let mix = render::combiner(cps, root, &renderables, &group_reverbs, keep_stems);
+  let mix = render::combiner(cps, root, &renderables, &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 #[test]
@@ -158,12 +158,12 @@ fn test_iter() {
 
     pool.install(|| {
       arfs.par_iter().for_each(|arf| {
-        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in arfs {
-      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
@@ -83,12 +83,12 @@ fn test_arf() {
 
     pool.install(|| {
       arfs.par_iter().for_each(|arf| {
-        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in arfs {
-      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
@@ -118,12 +118,12 @@ fn test_slice() {
 
     pool.install(|| {
       arfs.par_iter().for_each(|arf| {
-        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in arfs {
-      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
@@ -147,12 +147,12 @@ fn test_iter() {
 
     pool.install(|| {
       arfs.par_iter().for_each(|arf| {
-        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in arfs {
-      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
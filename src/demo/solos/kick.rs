@@ -74,12 +74,12 @@ fn test_iter() {
 
     pool.install(|| {
       arfs.par_iter().for_each(|arf| {
-        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in arfs {
-      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
@@ -89,7 +89,7 @@ fn demonstrate() {
   files::with_dir(&path);
 
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let cps: f32 = 2f32;
   let root: f32 = 1.02;
@@ -103,22 +103,22 @@ fn demonstrate() {
     &conf,
     &lead_melody,
     &lead_arf(Visibility::Visible, Energy::Low, Presence::Staccatto),
-  );
+  ).unwrap();
   let stem_lead1 = valley::lead::renderable(
     &conf,
     &lead_melody,
     &lead_arf(Visibility::Foreground, Energy::Medium, Presence::Staccatto),
-  );
+  ).unwrap();
   let stem_lead3 = valley::lead::renderable(
     &conf,
     &lead_melody,
     &lead_arf(Visibility::Background, Energy::High, Presence::Staccatto),
-  );
+  ).unwrap();
   let stem_lead4 = valley::lead::renderable(
     &conf,
     &lead_melody,
     &lead_arf(Visibility::Hidden, Energy::High, Presence::Staccatto),
-  );
+  ).unwrap();
 
   use Renderable2::{Group, Instance};
 
@@ -137,9 +137,9 @@ fn demonstrate() {
   let group_reverbs: Vec<crate::reverb::convolution::ReverbParams> = vec![];
   let keep_stems = Some(path.as_str());
   let group_reverbs = vec![];
-  let mix = render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, keep_stems);
+  let mix = render::combiner_with_reso2(&Conf { cps, root }, &renderables, &vec![], &group_reverbs, keep_stems).unwrap();
   let filename = format!("{}/{}.wav", location(demo_name), demo_name);
-  render::engrave::samples(SR, &mix, &filename);
+  render::engrave::samples(SR(), &mix, &filename).unwrap();
 }
 
 use std::env;
@@ -198,12 +198,12 @@ fn test_iter() {
 
     pool.install(|| {
       arfs.par_iter().for_each(|arf| {
-        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone());
+        prism::render_labelled_arf(&path, root, cps, &melody, arf, preset.clone()).unwrap();
       });
     });
   } else {
     for arf in arfs {
-      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone());
+      prism::render_labelled_arf(&path, root, cps, &melody, &arf, preset.clone()).unwrap();
     }
   }
 }
@@ -8,9 +8,9 @@ static demo_name: &str = "vagrant";
 fn gen_signal(start: usize, stop: usize) -> Vec<f32> {
   let mut sig: Vec<f32> = vec![];
   (start..stop).for_each(|k| {
-    let n_samples = SR / k;
+    let n_samples = SR() / k;
     for j in 0..n_samples {
-      let t = j as f32 % SRf;
+      let t = j as f32 % SRf();
       let v = (k as f32 * pi2 * t).sin();
       sig.push(v);
     }
@@ -25,9 +25,9 @@ fn gen_signal(start: usize, stop: usize) -> Vec<f32> {
 fn gen_signal_k_length(start: usize, stop: usize) -> Vec<f32> {
   let mut sig: Vec<f32> = vec![];
   (start..stop).for_each(|k| {
-    let n_samples = SR / 8;
+    let n_samples = SR() / 8;
     for j in 0..n_samples {
-      let t = j as f32 % SRf;
+      let t = j as f32 % SRf();
       let v = (k as f32 * pi2 * t).sin();
       sig.push(v);
     }
@@ -43,12 +43,12 @@ fn gen_signal_m_length(start: usize, stop: usize, m: usize) -> Vec<f32> {
 
   (start..stop).for_each(|k| {
     // Calculate the period for the current frequency in samples
-    let period = SRf / k as f32;
+    let period = SRf() / k as f32;
     let n_samples = (period * m as f32).round() as usize; // m periods
 
     // Generate samples for m periods of this frequency
     for j in 0..n_samples {
-      let t = j as f32 / SRf; // time in seconds
+      let t = j as f32 / SRf(); // time in seconds
       let v = (k as f32 * 2.0 * std::f32::consts::PI * t).sin();
       sig.push(v);
     }
@@ -69,11 +69,11 @@ fn gen_signal_log_time(start: usize, stop: usize, m: usize) -> Vec<f32> {
     // Logarithmic scaling: add a small value to avoid division by very small ln(k) values
     let ln_k = (k as f32).ln().max(MIN_LN_VALUE); // Clamp ln(k) to avoid excessively large durations
     let duration = MAX_DURATION / ln_k;
-    let n_samples = (SRf * duration).round() as usize; // number of samples based on scaled duration
+    let n_samples = (SRf() * duration).round() as usize; // number of samples based on scaled duration
 
     // Generate samples for this frequency
     for j in 0..n_samples {
-      let t = j as f32 / SRf; // time in seconds
+      let t = j as f32 / SRf(); // time in seconds
       let v = (k as f32 * 2.0 * std::f32::consts::PI * t).sin();
       sig.push(v);
     }
@@ -86,10 +86,10 @@ fn gen_signal_log_time(start: usize, stop: usize, m: usize) -> Vec<f32> {
 fn test_gen_signal() {
   let path: String = location(demo_name);
   files::with_dir(&path);
-  let signal = gen_signal(24, (SR / 2) - 1);
+  let signal = gen_signal(24, (SR() / 2) - 1);
   let filename = format!("{}/vagrant.wav", path);
 
-  render::engrave::samples(crate::synth::SR, &signal, &filename);
+  render::engrave::samples(crate::synth::SR(), &signal, &filename).unwrap();
   println!("Completed rendering test signal to {}", filename)
 }
 
@@ -97,10 +97,10 @@ fn test_gen_signal() {
 fn test_gen_signal_k_length() {
   let path: String = location(demo_name);
   files::with_dir(&path);
-  let signal = gen_signal_k_length(24, (SR / 2) - 1);
+  let signal = gen_signal_k_length(24, (SR() / 2) - 1);
   let filename = format!("{}/vagrant_k_length.wav", path);
 
-  render::engrave::samples(crate::synth::SR, &signal, &filename);
+  render::engrave::samples(crate::synth::SR(), &signal, &filename).unwrap();
   println!("Completed rendering test signal to {}", filename)
 }
 
@@ -110,10 +110,10 @@ fn test_gen_signal_m_length() {
   for m in 1..15 {
     let path: String = location(demo_name);
     files::with_dir(&path);
-    let signal = gen_signal_m_length(24, (SR / 2) - 1, m);
+    let signal = gen_signal_m_length(24, (SR() / 2) - 1, m);
     let filename = format!("{}/vagrant_m_length_m={}.wav", path, m);
 
-    render::engrave::samples(crate::synth::SR, &signal, &filename);
+    render::engrave::samples(crate::synth::SR(), &signal, &filename).unwrap();
     println!("Completed rendering test signal to {}", filename)
   }
 }
@@ -124,10 +124,10 @@ fn test_gen_signal_log_time() {
   for m in 1..15 {
     let path: String = location(demo_name);
     files::with_dir(&path);
-    let signal = gen_signal_log_time(24, (SR / 2) - 1, m);
+    let signal = gen_signal_log_time(24, (SR() / 2) - 1, m);
     let filename = format!("{}/vagrant_gen_signal_log_time={}.wav", path, m);
 
-    render::engrave::samples(crate::synth::SR, &signal, &filename);
+    render::engrave::samples(crate::synth::SR(), &signal, &filename).unwrap();
     println!("Completed rendering test signal to {}", filename)
   }
 }
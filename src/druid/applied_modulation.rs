@@ -4,7 +4,7 @@ use crate::synth::pi2;
 pub use crate::types::synthesis::{
   AmplitudeModParams, FrequencyModParams, Modifiers, ModifiersHolder, ModulationEffect, PhaseModParams,
 };
-use rand::rngs::ThreadRng;
+use crate::seed::ThreadRng;
 use rand::Rng;
 
 /// Macro to create a simple preset.
@@ -4,7 +4,7 @@ use rand;
 use rand::Rng;
 
 fn gen_float(min: f32, max: f32) -> f32 {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   rng.gen_range(min..max)
 }
 
@@ -53,7 +53,7 @@ fn transverse_modulation(k: usize, x: f32, d: f32) -> f32 {
 }
 
 pub fn multipliers(fundamental: f32, n: usize) -> Vec<f32> {
-  let max_k = NFf / fundamental;
+  let max_k = NFf() / fundamental;
   (1..=n)
     .map(|k| {
       match k {
@@ -134,7 +134,7 @@ mod test {
       phss: vec![pi2; coefficients(fund, 6).len()],
       modders: modders_bell(),
       expr: expr_none(),
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -158,6 +158,6 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 }
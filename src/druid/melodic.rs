@@ -14,55 +14,55 @@ pub fn amps_sine(freq: f32) -> Vec<f32> {
 
 /// Generates multipliers for a Fourier series sine wave starting at `freq`
 pub fn muls_max_k(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).map(|x| x as f32).collect()
 }
 
 /// Generates slightly denser multipliers for a sine wave
 pub fn muls_sine(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   vec![1f32, 2f32, 3f32]
 }
 
 /// Generates multipliers for a Fourier series sawtooth wave starting at `freq`
 pub fn muls_sawtooth(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).map(|x| x as f32).collect()
 }
 
 /// Generates amplitudes for a Fourier series sawtooth wave starting at `freq`
 pub fn amps_sawtooth(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).map(|i| 2f32 / (pi * i as f32)).collect()
 }
 
 /// Generates phases for a Fourier series sawtooth wave starting at `freq`
 pub fn phases_sawtooth(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).map(|i| if i % 2 == 0 { pi } else { 0f32 }).collect()
 }
 
 /// Generates multipliers for a Fourier series square wave starting at `freq`
 pub fn muls_square(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).filter(|&i| i % 2 != 0).map(|i| i as f32).collect()
 }
 
 /// Generates amplitudes for a Fourier series square wave starting at `freq`
 pub fn amps_square(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).filter(|&i| i % 2 != 0).map(|i| 4f32 / (pi * i as f32)).collect()
 }
 
 /// Generates multipliers for a Fourier series triangle wave starting at `freq`
 pub fn muls_triangle(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n).filter(|i| i % 2 != 0).map(|i| i as f32).collect()
 }
 
 /// Generates amplitudes for a Fourier series triangle wave starting at `freq`
 pub fn amps_triangle(freq: f32) -> Vec<f32> {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   (1..=n)
     .filter(|i| i % 2 != 0)
     .map(|i| {
@@ -162,7 +162,7 @@ mod test {
   use crate::synth::SR;
 
   fn max_mul(fund: f32, evens: bool) -> Vec<f32> {
-    let max_k = (NFf / fund) as usize;
+    let max_k = (NFf() / fund) as usize;
     if evens {
       (1..max_k).map(|x| x as f32).collect()
     } else {
@@ -179,7 +179,7 @@ mod test {
       phss,
       modders: modders_none(),
       expr: expr_none(),
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -193,7 +193,7 @@ mod test {
       phss,
       modders: modders_none(),
       expr: expr_none(),
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -207,7 +207,7 @@ mod test {
       phss,
       modders: modders_none(),
       expr: expr_none(),
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -231,7 +231,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -253,7 +253,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -275,7 +275,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -302,6 +302,6 @@ mod test {
 
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 }
@@ -112,6 +112,9 @@ pub fn inflect(
   }
 }
 
+/// Context windows for each frequency of a line. See `Frex`.
+/// Notes at either end of the line keep the glide lengths with no neighbour to glide to,
+/// so every note knows whether its neighbours glide as well. See `render::glide`.
 pub fn freq_frexer(line: &Vec<f32>, glide_from: GlideLen, glide_to: GlideLen) -> Vec<Frex> {
   let len = line.len();
   line
@@ -120,11 +123,11 @@ pub fn freq_frexer(line: &Vec<f32>, glide_from: GlideLen, glide_to: GlideLen) ->
     .map(|(i, &freq)| {
       if i == 0 && (i + 1) == len {
         // single element line
-        (GlideLen::None, None, freq, None, GlideLen::None)
+        (glide_from, None, freq, None, glide_to)
       } else if i == 0 {
-        (GlideLen::None, None, freq, Some(line[i + 1]), glide_to)
+        (glide_from, None, freq, Some(line[i + 1]), glide_to)
       } else if i == len - 1 {
-        (glide_from, Some(line[i - 1]), freq, None, GlideLen::None)
+        (glide_from, Some(line[i - 1]), freq, None, glide_to)
       } else {
         (glide_from, Some(line[i - 1]), freq, Some(line[i + 1]), glide_to)
       }
@@ -132,6 +135,7 @@ pub fn freq_frexer(line: &Vec<f32>, glide_from: GlideLen, glide_to: GlideLen) ->
     .collect()
 }
 
+/// Context windows for each note of a line, as in `freq_frexer`.
 pub fn line_frexer(line: &Vec<Note>, glide_from: GlideLen, glide_to: GlideLen) -> Vec<Frex> {
   let len = line.len();
   line
@@ -140,10 +144,10 @@ pub fn line_frexer(line: &Vec<Note>, glide_from: GlideLen, glide_to: GlideLen) -
     .map(|(i, (_, ref tone, _))| {
       if i == 0 && (i + 1) == len {
         // single element line
-        (GlideLen::None, None, tone_to_freq(tone), None, GlideLen::None)
+        (glide_from, None, tone_to_freq(tone), None, glide_to)
       } else if i == 0 {
         (
-          GlideLen::None,
+          glide_from,
           None,
           tone_to_freq(tone),
           Some(tone_to_freq(&line[i + 1].1)),
@@ -155,7 +159,7 @@ pub fn line_frexer(line: &Vec<Note>, glide_from: GlideLen, glide_to: GlideLen) -
           Some(tone_to_freq(&line[i - 1].1)),
           tone_to_freq(tone),
           None,
-          GlideLen::None,
+          glide_to,
         )
       } else {
         (
@@ -198,7 +202,7 @@ mod test {
       phss: vec![pi2, pi2, pi2],
       modders: [None, None, None],
       expr: expr_none(),
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -224,7 +228,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 }
 
@@ -31,7 +31,7 @@ use rand::seq::SliceRandom;
 use rand::Rng;
 
 fn select_random_unique(n: usize, min: usize, max: usize) -> Vec<f32> {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let range: Vec<usize> = (min..max).collect();
   let mut selected: Vec<usize> = range.choose_multiple(&mut rng, n).cloned().collect();
   selected.into_iter().map(|x| x as f32).collect()
@@ -58,25 +58,25 @@ pub fn multipliers(freq: f32, energy: &Energy) -> Vec<f32> {
     Energy::Medium => {
       // sample from all available octaves equally from the fundamental
       let max_n = 2000f32;
-      let max_freq = (NFf - fund) as usize;
+      let max_freq = (NFf() - fund) as usize;
       // note this should compute from MAX_REGISTER but it's noise and this is good enough for tired eyes
       let n = fund * 3f32;
       if n > max_n {
-        (max_n, fund, NFf)
+        (max_n, fund, NFf())
       } else {
-        (n, fund, NFf)
+        (n, fund, NFf())
       }
     }
     Energy::High => {
       // sample from all available octaves equally from the fundamental
       let max_n = 10000f32;
-      let max_freq = (NFf - fund) as usize;
+      let max_freq = (NFf() - fund) as usize;
       // note this should compute from MAX_REGISTER but it's noise and this is good enough for tired eyes
       let n = fund * 7f32;
       if n > max_n {
-        (max_n, fund, NFf)
+        (max_n, fund, NFf())
       } else {
-        (n, fund, NFf)
+        (n, fund, NFf())
       }
     }
   };
@@ -124,7 +124,7 @@ mod test {
 
   fn nearly_none_noise(fund: f32, vis: &Visibility, energy: &Energy, presence: &Presence) -> Element {
     let muls = multipliers(fund, energy);
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let phss = (0..muls.len()).map(|_| rng.gen::<f32>() * pi2).collect();
     Element {
       mode: Mode::Noise,
@@ -134,7 +134,7 @@ mod test {
       phss,
       modders: modders_none(),
       expr: expr_none(),
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -144,7 +144,7 @@ mod test {
     use crate::timbre::AmpLifespan;
 
     let muls = multipliers(fund, energy);
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let phss = (0..muls.len()).map(|_| rng.gen::<f32>() * pi2).collect();
     let contour = lifespan::mod_lifespan(10usize, 1f32, &AmpLifespan::Pluck, 1usize, 0f32);
     let expr = (contour, vec![1f32], vec![0f32]);
@@ -156,7 +156,7 @@ mod test {
       phss,
       modders: modders_none(),
       expr,
-      hplp: (vec![MFf], vec![NFf]),
+      hplp: (vec![MFf], vec![NFf()]),
       thresh: (0f32, 1f32),
     }
   }
@@ -181,7 +181,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -204,7 +204,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -227,7 +227,7 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -250,6 +250,6 @@ mod test {
     }
     files::with_dir(test_dir);
     let filename: String = format!("{}/{}.wav", test_dir, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 }
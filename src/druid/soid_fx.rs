@@ -1,6 +1,7 @@
 use super::*;
 use crate::types::synthesis::{ModifiersHolder, Soids};
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use crate::seed::thread_rng;
 
 static one: f32 = 1f32;
 
@@ -235,7 +236,7 @@ pub mod fmod {
   /// applies a short form triangle wave to each member of the soids
   /// n parameter describes how many multipliers to add of the modulation series
   pub fn triangle(soids: &Soids, n: usize) -> Soids {
-    let ref_freq = 2f32.powf(NFf.log2() - (n as f32).log2());
+    let ref_freq = 2f32.powf(NFf().log2() - (n as f32).log2());
     let samples = soids::overs_triangle(ref_freq);
     let mut ret: Soids = soids.clone();
 
@@ -253,7 +254,7 @@ pub mod fmod {
   /// applies a short form square wave to each member of the soids
   /// n parameter describes how many multipliers to add of the modulation series
   pub fn square(soids: &Soids, n: usize) -> Soids {
-    let ref_freq = 2f32.powf(NFf.log2() - (n as f32).log2());
+    let ref_freq = 2f32.powf(NFf().log2() - (n as f32).log2());
     let samples = soids::overs_square(ref_freq);
     let mut ret: Soids = soids.clone();
 
@@ -272,7 +273,7 @@ pub mod fmod {
   /// applies a short form sawtooth wave to each member of the soids
   /// n parameter describes how many multipliers to add of the modulation series
   pub fn sawtooth(soids: &Soids, n: usize) -> Soids {
-    let ref_freq = 2f32.powf(NFf.log2() - (n as f32).log2());
+    let ref_freq = 2f32.powf(NFf().log2() - (n as f32).log2());
     let samples = soids::overs_sawtooth(ref_freq);
     let mut ret: Soids = soids.clone();
 
@@ -565,7 +566,7 @@ pub mod chordlike {
   /// `freq` The reference frequency (fudamental)
   /// `range` The number of harmonic octaves to include. 0 is one octave, 1 is two octaves, etc.
   pub fn major(freq: f32, range: usize) -> Soids {
-    let mut max_mul: f32 = NFf / freq;
+    let mut max_mul: f32 = NFf() / freq;
 
     if max_mul < 1f32 {
       return empty_soids();
@@ -598,7 +599,7 @@ pub mod chordlike {
   /// `freq` The reference frequency (fudamental)
   /// `range` The number of harmonic octaves to include. 0 is one octave, 1 is two octaves, etc.
   pub fn major_seven(freq: f32, range: usize) -> Soids {
-    let mut max_mul: f32 = NFf / freq;
+    let mut max_mul: f32 = NFf() / freq;
 
     if max_mul < 1f32 {
       return empty_soids();
@@ -631,7 +632,7 @@ pub mod chordlike {
   /// `freq` The reference frequency (fudamental)
   /// `range` The number of harmonic octaves to include. 0 is one octave, 1 is two octaves, etc.
   pub fn minor_seven(freq: f32, range: usize) -> Soids {
-    let mut max_mul: f32 = NFf / freq;
+    let mut max_mul: f32 = NFf() / freq;
 
     if max_mul < 1f32 {
       return empty_soids();
@@ -666,7 +667,7 @@ pub mod chordlike {
   /// `freq` The reference frequency (fudamental).  
   /// `range` The number of harmonic octaves to include. 0 is one octave, 1 is two octaves, etc.  
   pub fn minor(freq: f32, range: usize) -> Soids {
-    let mut max_mul: f32 = NFf / freq;
+    let mut max_mul: f32 = NFf() / freq;
 
     if max_mul < 1f32 {
       return empty_soids();
@@ -701,7 +702,7 @@ pub mod chordlike {
   /// `freq` The reference frequency (fudamental).  
   /// `range` The number of harmonic octaves to include. 0 is one octave, 1 is two octaves, etc.  
   pub fn minor_offset(freq: f32, range: usize) -> Soids {
-    let mut max_mul: f32 = NFf / freq;
+    let mut max_mul: f32 = NFf() / freq;
 
     if max_mul < 1f32 {
       return empty_soids();
@@ -734,7 +735,7 @@ pub mod chordlike {
   }
 
   pub fn dimdom(freq: f32, range: usize) -> Soids {
-    let mut max_mul: f32 = NFf / freq;
+    let mut max_mul: f32 = NFf() / freq;
 
     if max_mul < 1f32 {
       return empty_soids();
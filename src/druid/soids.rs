@@ -6,10 +6,10 @@ const one: f32 = 1f32;
 
 /// Given a set of soids, create a time series representation of the signal for duration dur at cps
 pub fn sum(soids: &Soids, dur: f32, cps: f32) -> SampleBuffer {
-  let mut samples: Vec<f32> = vec![0f32; (SR as f32 * dur).ceil() as usize];
+  let mut samples: Vec<f32> = vec![0f32; (SR() as f32 * dur).ceil() as usize];
   let n = samples.len() as f32;
   for j in 0..n as usize {
-    let t = cps * j as f32 / (SR as f32 * n);
+    let t = cps * j as f32 / (SR() as f32 * n);
     for (i, amp) in soids.0.iter().enumerate() {
       let (amp, mul, offset) = (soids.0[i], soids.1[i], soids.2[i]);
       samples[j] += amp * (t * mul * pi2 + offset)
@@ -29,7 +29,7 @@ pub fn sum_norm(soids: &Soids, dur: f32, cps: f32) -> SampleBuffer {
 /// Generates sinusoids representing a stack of octaves (even harmonics) from the fundamental.
 /// Intended to be tall but focused on the low end.
 pub fn octave(freq: f32) -> Soids {
-  let n = (NFf / freq / 2f32) as usize;
+  let n = (NFf() / freq / 2f32) as usize;
   let mut muls: Vec<f32> = vec![1f32];
   muls.append(&mut (2..n).step_by(2).map(|ku| ku as f32).collect::<Vec<f32>>());
   let l = muls.len();
@@ -41,7 +41,7 @@ pub fn octave(freq: f32) -> Soids {
 
 // all integer components with inverse cubic amp decay
 pub fn integer_overs(freq: f32) -> Soids {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   let mut muls: Vec<f32> = vec![1f32];
   muls.append(&mut (1..n).map(|ku| ku as f32).collect::<Vec<f32>>());
   let l = muls.len();
@@ -70,7 +70,7 @@ pub fn id() -> Soids {
 
 // all integer components with constant amp
 pub fn unit(freq: f32) -> Soids {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   let mut muls: Vec<f32> = vec![1f32];
   muls.append(&mut (1..n).map(|ku| ku as f32).collect::<Vec<f32>>());
   let l = muls.len();
@@ -185,7 +185,7 @@ pub fn under_triangle(freq: f32) -> Soids {
 }
 
 pub fn overs_square(freq: f32) -> Soids {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
 
   let harmonics: Vec<usize> = (1..=n).filter(|&i| i % 2 != 0).collect();
   let multipliers: Vec<f32> = harmonics.iter().map(|&i| i as f32).collect();
@@ -196,7 +196,7 @@ pub fn overs_square(freq: f32) -> Soids {
 }
 
 pub fn overs_triangle(freq: f32) -> Soids {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
   let multipliers: Vec<f32> = (1..=n).filter(|i| i % 2 != 0).map(|i| i as f32).collect();
 
   let amplitudes: Vec<f32> = (1..=n)
@@ -214,7 +214,7 @@ pub fn overs_triangle(freq: f32) -> Soids {
 /// See Wolfram:  
 /// [Fourier Series Sawtooth Wave](https://mathworld.wolfram.com/FourierSeriesSawtoothWave.html)
 pub fn overs_sawtooth(freq: f32) -> Soids {
-  let n = (NFf / freq) as usize;
+  let n = (NFf() / freq) as usize;
 
   let multipliers: Vec<f32> = (1..=n).map(|x| x as f32).collect();
   let amplitudes: Vec<f32> = (1..=n).map(|n| 1f32 / (pi * n as f32)).collect();
@@ -231,8 +231,8 @@ pub enum NoiseType {
 }
 
 pub fn noise(freq: f32, noise_type: NoiseType) -> Soids {
-  let max_mul: f32 = NFf / freq;
-  let mut rng = rand::thread_rng();
+  let max_mul: f32 = NFf() / freq;
+  let mut rng = crate::seed::thread_rng();
 
   let n_stages = max_mul.log2().floor() as usize;
   let mut muls = vec![];
@@ -0,0 +1,89 @@
+/// Errors returned by the render pipeline.
+///
+/// Rendering a score touches the filesystem (samples, stems, the mixdown) and user input (playbooks, MIDI files).
+/// These failures are returned as a `RaudioError` so a caller can report them or try again instead of crashing.
+use std::fmt;
+use std::io;
+
+use crate::inp::validate::Problem;
+
+#[derive(Debug)]
+pub enum RaudioError {
+  /// (path, cause) A file or directory could not be read or created
+  Io(String, io::Error),
+  /// (path, cause) A WAV file could not be written
+  Wav(String, hound::Error),
+  /// (path, reason) An audio sample could not be loaded
+  Sample(String, String),
+  /// There is no sample to use for a part
+  MissingSample(String),
+  /// Signals could not be combined
+  Mix(String),
+  /// A compressor or expander rejected its parameters
+  Dynamics(String),
+  /// (path, reason) A MIDI file could not be imported
+  Midi(String, String),
+  /// A score could not be encoded as a MIDI file
+  MidiExport(String),
+  /// A score has problems that prevent rendering it
+  InvalidScore(Vec<Problem>),
+  /// No preset has this name
+  UnknownPreset(String),
+  /// (path, reason) A preset pack file could not be loaded
+  InvalidPreset(String, String),
+  /// (path, reason) A Scala scale or keyboard mapping could not be loaded
+  InvalidTuning(String, String),
+}
+
+impl fmt::Display for RaudioError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      RaudioError::Io(path, e) => write!(f, "Failed to access {}: {}", path, e),
+      RaudioError::Wav(path, e) => write!(f, "Failed to write audio to {}: {}", path, e),
+      RaudioError::Sample(path, reason) => write!(f, "Failed to load sample {}: {}", path, reason),
+      RaudioError::MissingSample(reason) => write!(f, "No sample available: {}", reason),
+      RaudioError::Mix(reason) => write!(f, "Failed to mix signals: {}", reason),
+      RaudioError::Dynamics(reason) => write!(f, "Failed to apply dynamics: {}", reason),
+      RaudioError::Midi(path, reason) => write!(f, "Failed to import MIDI file {}: {}", path, reason),
+      RaudioError::MidiExport(reason) => write!(f, "Failed to export MIDI: {}", reason),
+      RaudioError::UnknownPreset(name) => write!(f, "Unknown preset '{}'", name),
+      RaudioError::InvalidPreset(path, reason) => write!(f, "Invalid preset pack {}: {}", path, reason),
+      RaudioError::InvalidTuning(path, reason) => write!(f, "Invalid tuning {}: {}", path, reason),
+      RaudioError::InvalidScore(problems) => {
+        write!(f, "Score cannot be rendered:")?;
+        for problem in problems {
+          write!(f, "\n  {}", problem)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+impl std::error::Error for RaudioError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      RaudioError::Io(_, e) => Some(e),
+      RaudioError::Wav(_, e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::inp::validate::Severity;
+
+  #[test]
+  fn test_display_lists_every_problem() {
+    let problem = |path: &str| Problem {
+      severity: Severity::Error,
+      path: path.to_string(),
+      position: None,
+      message: "Line has no notes".to_string(),
+    };
+    let message = RaudioError::InvalidScore(vec![problem("$.parts[0][2][0]"), problem("$.parts[1][2][0]")]).to_string();
+    assert!(message.contains("$.parts[0][2][0]") && message.contains("$.parts[1][2][0]"));
+  }
+}
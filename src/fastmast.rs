@@ -113,7 +113,7 @@ pub fn apply_danceability(input_path: &str, output_path: &str) {
   use crate::synth::SR;
 
   // Step 1: Load and resample audio
-  let (audio, target_sample_rate) = load_and_resample_audio(input_path, SR as u32);
+  let (audio, target_sample_rate) = load_and_resample_audio(input_path, SR() as u32);
   let num_channels = audio.len();
 
   // Step 2: Process audio
@@ -145,7 +145,7 @@ fn test_apply_danceability_function() {
   // Validate the output
   let (output_audio, output_sample_rate) =
     read_audio_file(output_path).unwrap_or_else(|err| panic!("Failed to read output file '{}': {}", output_path, err));
-  assert_eq!(output_sample_rate, crate::synth::SR as u32, "Sample rate mismatch.");
+  assert_eq!(output_sample_rate, crate::synth::SR() as u32, "Sample rate mismatch.");
   assert!(!output_audio.is_empty(), "Output audio is empty.");
   assert_eq!(output_audio.len(), 2, "Expected 2 channels in output audio.");
 
@@ -9,7 +9,8 @@ pub fn exists(file_name: &str) -> Option<&str> {
   return Some(file_name);
 }
 
-pub fn with_dir(path: &str) {
+/// Create the parent directories of `path`, or `path` itself when it has no extension.
+pub fn try_with_dir(path: &str) -> std::io::Result<()> {
   let path = Path::new(path);
 
   // Determine if running in GitHub Actions
@@ -31,6 +32,12 @@ pub fn with_dir(path: &str) {
 
   // Create the directory if it doesn't exist
   if !dir.exists() {
-    fs::create_dir_all(dir).expect("Failed to create directory");
+    fs::create_dir_all(dir)?;
   }
+  Ok(())
+}
+
+/// Panicking version of `try_with_dir`.
+pub fn with_dir(path: &str) {
+  try_with_dir(path).expect("Failed to create directory");
 }
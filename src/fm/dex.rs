@@ -187,9 +187,9 @@ pub fn compute_bandwidth(operator: &Operator, offset_frequency: f32, t: f32) ->
   let mut base_mod_index = operator.modulation_index;
 
   // Apply envelopes for modulation index
-  base_mod_index += operator.mod_index_env_sum.get_at(t, SR);
+  base_mod_index += operator.mod_index_env_sum.get_at(t, SR());
 
-  base_mod_index *= operator.mod_index_env_mul.get_at(t, SR);
+  base_mod_index *= operator.mod_index_env_mul.get_at(t, SR());
 
   // Handle the case where there are no modulators
   if operator.modulators.is_empty() {
@@ -348,14 +348,14 @@ fn scale_envelope(envelope: &Envelope, gain: f32) -> Envelope {
 /// # Returns
 /// The remaining bandwidth (in Hz) available for modulation.
 pub fn get_remaining_bandwidth(operator: &Operator, max_bandwidth: f32, t: f32) -> f32 {
-  let constrained_bandwidth = max_bandwidth.min(NFf); // Ensure bandwidth does not exceed NFf
+  let constrained_bandwidth = max_bandwidth.min(NFf()); // Ensure bandwidth does not exceed NFf
   fn compute_total_bandwidth(operator: &Operator, t: f32) -> f32 {
     let f = operator.frequency;
 
     let mut base_mod_index = operator.modulation_index;
-    base_mod_index += operator.mod_index_env_sum.get_at(t, SR);
+    base_mod_index += operator.mod_index_env_sum.get_at(t, SR());
 
-    base_mod_index *= operator.mod_index_env_mul.get_at(t, SR);
+    base_mod_index *= operator.mod_index_env_mul.get_at(t, SR());
 
     let mut total_bandwidth = 2.0 * base_mod_index * f;
 
@@ -421,7 +421,7 @@ fn determine_mod_index(remaining_bandwidth: f32, mod_freq: f32) -> f32 {
 
 pub fn generate_serial_modulation_chain(operator: &Operator, lowpass_filter: f32) -> Option<Operator> {
   let mut current_operator = operator.clone();
-  let max_bandwidth = NFf.min(lowpass_filter);
+  let max_bandwidth = NFf().min(lowpass_filter);
 
   loop {
     let bandwidth_remaining = get_remaining_bandwidth(&current_operator, max_bandwidth, 0.0).max(0.0);
@@ -460,7 +460,7 @@ pub fn generate_serial_modulation_chain(operator: &Operator, lowpass_filter: f32
     // Randomly select a valid candidate and add it as a modulator
     use rand::seq::SliceRandom;
     let mut new_operator = current_operator.clone();
-    let new_modulator = candidates.choose(&mut rand::thread_rng()).unwrap().clone();
+    let new_modulator = candidates.choose(&mut crate::seed::thread_rng()).unwrap().clone();
     new_operator.modulators.push(ModulationSource::Operator(new_modulator));
     current_operator = new_operator;
   }
@@ -474,11 +474,11 @@ pub fn generate_serial_modulation_chain(operator: &Operator, lowpass_filter: f32
 }
 
 fn extend_harmonic_range(operator: &Operator, amount: f32) -> Option<Operator> {
-  let remaining_bandwidth = get_remaining_bandwidth(operator, NFf, 0.0);
+  let remaining_bandwidth = get_remaining_bandwidth(operator, NFf(), 0.0);
 
   // Determine the maximum modulation frequency for the desired extension
   let new_mod_freq = determine_mod_freq(remaining_bandwidth, 1.0) * amount;
-  if new_mod_freq > 20.0 && new_mod_freq < NFf {
+  if new_mod_freq > 20.0 && new_mod_freq < NFf() {
     let mut new_operator = operator.clone();
     let new_modulator = Operator::modulator(new_mod_freq, 1.0);
     new_operator.modulators.push(ModulationSource::Operator(new_modulator));
@@ -488,7 +488,7 @@ fn extend_harmonic_range(operator: &Operator, amount: f32) -> Option<Operator> {
 }
 
 fn thicken_harmonic_density(operator: &Operator, density_factor: usize) -> Option<Operator> {
-  let remaining_bandwidth = get_remaining_bandwidth(operator, NFf, 0.0);
+  let remaining_bandwidth = get_remaining_bandwidth(operator, NFf(), 0.0);
   let base_frequency = operator.frequency;
 
   let mut new_operator = operator.clone();
@@ -496,7 +496,7 @@ fn thicken_harmonic_density(operator: &Operator, density_factor: usize) -> Optio
 
   for i in 1..=density_factor {
     let new_mod_freq = determine_mod_freq(remaining_bandwidth / density_factor as f32, 0.5);
-    if new_mod_freq > 20.0 && new_mod_freq < NFf {
+    if new_mod_freq > 20.0 && new_mod_freq < NFf() {
       let new_modulator = Operator::modulator(base_frequency + new_mod_freq / i as f32, 0.5);
       new_operator.modulators.push(ModulationSource::Operator(new_modulator));
       added = true;
@@ -511,11 +511,11 @@ fn thicken_harmonic_density(operator: &Operator, density_factor: usize) -> Optio
 }
 
 fn subtle_enhancement(operator: &Operator) -> Option<Operator> {
-  let remaining_bandwidth = get_remaining_bandwidth(operator, NFf, 0.0);
+  let remaining_bandwidth = get_remaining_bandwidth(operator, NFf(), 0.0);
 
   // Choose a moderate frequency and modulation index
   let mod_freq = determine_mod_freq(remaining_bandwidth, 0.8);
-  if mod_freq > 20.0 && mod_freq < NFf {
+  if mod_freq > 20.0 && mod_freq < NFf() {
     let mut new_operator = operator.clone();
     let new_modulator = Operator::modulator(mod_freq, 0.8);
     new_operator.modulators.push(ModulationSource::Operator(new_modulator));
@@ -803,12 +803,12 @@ mod test_bandwidth {
   fn test_generate_serial_modulation_chain_with_filter() {
     let operator = Operator::carrier(440.0);
 
-    if let Some(chain_operator) = generate_serial_modulation_chain(&operator, NFf) {
+    if let Some(chain_operator) = generate_serial_modulation_chain(&operator, NFf()) {
       let (_center, total_bandwidth) = compute_bandwidth(&chain_operator, 0.0, 0.0);
 
       // Ensure the bandwidth is within limits (10,000 Hz)
       assert!(
-        total_bandwidth <= NFf,
+        total_bandwidth <= NFf(),
         "Total bandwidth ({}) exceeds lowpass filter limit",
         total_bandwidth
       );
@@ -1049,11 +1049,11 @@ fn animated_fm_synthesis_demo() {
     // Create carriers and render them with their modulators
     for base_freq in base_freqs {
       let carrier = random_carrier_with_modulators(base_freq, cps, n_cycles, 3, 2); // 3 modulators, depth 2
-      let rendered_signal = carrier.render(n_cycles, cps, SR);
+      let rendered_signal = carrier.render(n_cycles, cps, SR());
       final_signal.extend(rendered_signal);
     }
 
     // Save the rendered signal to a WAV file
-    engrave::samples(SR, &final_signal, &format!("animated_fm_synthesis_demo_{}_cps.wav", cps));
+    engrave::samples(SR(), &final_signal, &format!("animated_fm_synthesis_demo_{}_cps.wav", cps)).unwrap();
   }
 }
@@ -1,7 +1,8 @@
 use super::*;
 use crate::{Arf, Conf, Energy, Melody, Mode, Note, Presence, Role};
 use rand::distributions::Uniform;
-use rand::{distributions::Distribution, thread_rng, Rng};
+use rand::{distributions::Distribution, Rng};
+use crate::seed::thread_rng;
 
 /// ---------------------------------------------------------------------------
 /// 2. Abstract frequency relationships
@@ -330,7 +331,7 @@ pub fn pick_mod_frequency(carf: f32) -> (f32, bool) {
   let clamped_freq = raw_freq
         .max(0.0001)                // Avoid zero or negative frequencies
         .min(carf * 4.0)            // Ratio clamp
-        .min(NFf); // Absolute Nyquist clamp
+        .min(NFf()); // Absolute Nyquist clamp
 
   println!(
     "[DEBUG] pick_mod_frequency: carf={:.2}, raw_freq={:.2}, clamped_freq={:.2}, invert={}",
@@ -397,7 +398,7 @@ pub fn build_operator(carf: f32, depth: usize, max_depth: usize, is_first_order:
     );
 
     // 3. run bandwidth check. If it fails, skip
-    match get_remaining_range(&child_op, 0f32, 0f32, crate::synth::MFf, NFf) {
+    match get_remaining_range(&child_op, 0f32, 0f32, crate::synth::MFf, NFf()) {
       Some((_low, _high)) => {
         // all good => add
         modulators.push(ModulationSource::Operator(child_op));
@@ -440,7 +441,7 @@ pub fn generate_top_operator(carf: f32, max_depth: usize) -> Operator {
     );
 
     // optionally, do a bandwidth check on the child op
-    match get_remaining_range(&child_op, 0f32, 0f32, crate::synth::MFf, NFf) {
+    match get_remaining_range(&child_op, 0f32, 0f32, crate::synth::MFf, NFf()) {
       Some(_) => {
         top_modulators.push(ModulationSource::Operator(child_op));
       }
@@ -467,7 +468,7 @@ fn test_build_operator() {
   let op_220 = root_spec.to_operator(220.0);
 
   // Step 4: Possibly do a bandwidth check or just print
-  match get_remaining_range(&op_220, 0f32, 0f32, crate::synth::MFf, NFf) {
+  match get_remaining_range(&op_220, 0f32, 0f32, crate::synth::MFf, NFf()) {
     Some((low, high)) => {
       println!("OperatorSpec => Operator @220Hz: range [{low}..{high}] => {op_220:#?}");
     }
@@ -626,7 +627,7 @@ fn test_my_rendered_synth() {
 
   // 3) Generate a single OperatorSpec for the entire melody
   let mut root_spec = random_operator_structure(0, 2);
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   assign_lifetimes(&mut root_spec, 0, 2, &mut rng);
 
   // Convert the OperatorSpec to an Operator at an initial frequency
@@ -646,7 +647,7 @@ fn test_my_rendered_synth() {
     };
 
     // 5a) Bandwidth check and pruning loop
-    while let None = get_remaining_range(&op, 0f32, 0f32, crate::synth::MFf, NFf) {
+    while let None = get_remaining_range(&op, 0f32, 0f32, crate::synth::MFf, NFf()) {
       eprintln!("Note {} @{}Hz: Bandwidth exceeded. Pruning operator.", i, freq);
       op = prune_operator(op.clone());
     }
@@ -660,7 +661,7 @@ fn test_my_rendered_synth() {
       vec![op],
       dur_cycles,
       conf.cps, // control rate
-      SR,       // sample rate
+      SR(),       // sample rate
     );
 
     signal.extend(samples);
@@ -668,7 +669,7 @@ fn test_my_rendered_synth() {
 
   // 6) Write audio signal to a file
   if !signal.is_empty() {
-    crate::render::engrave::samples(SR, &signal, "dev-audio/test-melody-single-operator.wav");
+    crate::render::engrave::samples(SR(), &signal, "dev-audio/test-melody-single-operator.wav").unwrap();
     println!(
       "Successfully wrote {} samples to dev-audio/test-melody-single-operator.wav",
       signal.len()
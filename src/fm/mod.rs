@@ -2,7 +2,8 @@ use crate::analysis::in_range;
 use crate::phrasing::ranger::{self, Knob};
 use crate::render::engrave;
 use crate::synth::{pi, pi2, NFf, SRf, NF, SR};
-use rand::{self, thread_rng, Rng};
+use rand::{self, Rng};
+use crate::seed::thread_rng;
 
 mod dex;
 mod gen;
@@ -42,12 +43,11 @@ pub fn mul_envelopes(a: Vec<f32>, b: Vec<f32>, compress: bool) -> Vec<f32> {
 /// - `mod_index`: modulation index (dimensionless),
 /// - `n_cycles`: how many cycles of the carrier to render.
 ///
-/// Returns a `Vec<f32>` of samples at 48kHz sample rate.
+/// Returns a `Vec<f32>` of samples at the render sample rate.
 pub fn generate_signal(
   cps: f32, gain: f32, carrier_freq: f32, mod_freq: f32, mod_index: f32, n_cycles: f32,
 ) -> Vec<f32> {
-  // Hardcode sample rate (48kHz) for illustration; adapt to your needs.
-  let sample_rate = 48_000.0;
+  let sample_rate = SRf();
 
   // Number of samples for `n_cycles` of the carrier's fundamental period
   let n_samples = (n_cycles * sample_rate / cps) as usize;
@@ -77,7 +77,7 @@ pub fn generate_signal(
 /// - `mod_chain`: a vector of `(mod_index, mod_freq)` tuples defining the modulation chain,
 /// - `n_cycles`: how many cycles of the carrier to render.
 ///
-/// Returns a `Vec<f32>` of samples at 48kHz sample rate.
+/// Returns a `Vec<f32>` of samples at the render sample rate.
 pub fn generate_compound_signal(
   cps: f32,
   gain: f32,
@@ -85,7 +85,7 @@ pub fn generate_compound_signal(
   mod_chain: Vec<(f32, f32)>, // (modulation index, modulation frequency)
   n_cycles: f32,
 ) -> Vec<f32> {
-  let sample_rate = 48_000.0; // Sample rate (hardcoded at 48kHz)
+  let sample_rate = SRf();
   let dt = 1.0 / sample_rate; // Time step
   let n_samples = (n_cycles * sample_rate / cps) as usize; // Total samples for the given cycles
 
@@ -111,7 +111,7 @@ pub fn generate_compound_signal(
 pub fn compute_max_mod_freq(max_carrier_freq: f32, max_mod_index: f32) -> f32 {
   // Solve for f_m:
   //   f_m < (sample_rate/2 - max_carrier_freq) / (max_mod_index + 1)
-  let numerator = (SRf * 0.5) - max_carrier_freq;
+  let numerator = (SRf() * 0.5) - max_carrier_freq;
   let denominator = max_mod_index + 1.0;
   let raw_max_mod_freq = numerator / denominator;
 
@@ -159,7 +159,7 @@ fn nested_sine_reverse_n(n: u32, x: f32) -> f32 {
 }
 
 fn render_many(cps: f32, freq: f32, n_cycles: f32, depth: u32) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
-  let sample_rate = SRf; // Sample rate (f32)
+  let sample_rate = SRf(); // Sample rate (f32)
   let dt = 1.0 / sample_rate; // Time step
   let n_samples = (n_cycles * sample_rate / cps) as usize; // Total samples for the given duration
 
@@ -210,7 +210,7 @@ fn step_range(a: i32, b: i32) -> Vec<i32> {
 
 #[test]
 fn the_fm_song() {
-  let nf = NFf;
+  let nf = NFf();
   let car_f = 250f32;
 
   let mut song: Vec<f32> = vec![];
@@ -262,12 +262,12 @@ fn the_fm_song() {
 
       let sig = generate_compound_signal(1f32, 0.1f32, carrier, modulators.clone(), n_cycles);
       let filename = format!("dev-audio/test-mod-chain-{}-n-{}", carrier, n);
-      engrave::samples(SR, &sig, &filename);
+      engrave::samples(SR(), &sig, &filename).unwrap();
       song.extend(sig)
     }
   }
   let filename = format!("dev-audio/mod-song");
-  engrave::samples(SR, &song, &filename);
+  engrave::samples(SR(), &song, &filename).unwrap();
 }
 
 #[test]
@@ -290,6 +290,6 @@ fn test_fm() {
       carrier, mod_freq, mod_index
     );
 
-    engrave::samples(SR, &result, &filename);
+    engrave::samples(SR(), &result, &filename).unwrap();
   }
 }
@@ -193,7 +193,7 @@ impl Operator {
   pub fn eval(&self, t: f32, feedback_states: &mut [f32]) -> f32 {
     // Calculate the effective frequency considering modulation and envelopes
     let effective_frequency =
-      self.frequency * self.mod_freq_env_mul.get_at(t, SR) + self.mod_freq_env_sum.get_at(t, SR);
+      self.frequency * self.mod_freq_env_mul.get_at(t, SR()) + self.mod_freq_env_sum.get_at(t, SR());
 
     let angular_frequency = pi2 * effective_frequency;
 
@@ -209,7 +209,7 @@ impl Operator {
           feedback_offset += feedback_count;
 
           let modulation_index =
-            mod_op.modulation_index * mod_op.mod_index_env_mul.get_at(t, SR) + mod_op.mod_index_env_sum.get_at(t, SR);
+            mod_op.modulation_index * mod_op.mod_index_env_mul.get_at(t, SR()) + mod_op.mod_index_env_sum.get_at(t, SR());
 
           mod_op.eval(t, sub_feedback_states) * modulation_index
         }
@@ -217,7 +217,7 @@ impl Operator {
           // Handle feedback correctly and accumulate phase
           let feedback_signal = feedback_states[feedback_offset];
           let feedback_mod_index =
-            self.modulation_index * self.mod_index_env_mul.get_at(t, SR) + self.mod_index_env_sum.get_at(t, SR);
+            self.modulation_index * self.mod_index_env_mul.get_at(t, SR()) + self.mod_index_env_sum.get_at(t, SR());
 
           let modulated_feedback = *gain * feedback_signal * feedback_mod_index;
           feedback_states[feedback_offset] = (angular_frequency * t + feedback_signal).sin();
@@ -229,7 +229,7 @@ impl Operator {
     }
 
     // Compute the signal gain with envelopes
-    let gain = self.mod_gain_env_mul.get_at(t, SR) * self.mod_gain_env_sum.get_at(t, SR);
+    let gain = self.mod_gain_env_mul.get_at(t, SR()) * self.mod_gain_env_sum.get_at(t, SR());
 
     // if t % 1f32 < 0.0001f32 {
     //   let is_carrier = self.modulation_index  == 0f32;
@@ -586,12 +586,12 @@ mod tests {
     // Carrier at 330 Hz
     let carrier = Operator::carrier(330.0);
 
-    let signal = carrier.render(12f32, 1.5, SR);
+    let signal = carrier.render(12f32, 1.5, SR());
 
     // Ensure signal has values
     assert!(!signal.is_empty());
     let filename = format!("dev-audio/test-single-carrier-no-operator-freq-{}", carrier.frequency);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   // #[test]
@@ -759,13 +759,13 @@ mod demo {
     let mut carrier = Operator::carrier(carrier_frequency);
     carrier.modulators.extend(modulators);
     let cps = 1.5f32;
-    let signal = carrier.render(32f32 * cps, cps, SR);
+    let signal = carrier.render(32f32 * cps, cps, SR());
 
     let filename = format!(
       "dev-audio/test-single-carrier-one-operator-freq-{}-fm-song",
       carrier.frequency
     );
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
     // Now `carrier` has a modulator chain you can use for synthesis
   }
 
@@ -785,12 +785,12 @@ mod demo {
       let mut carrier = Operator::carrier(*carrier_frequency);
       carrier.modulators.extend(modulators);
       let cps = 1.5f32;
-      let signal = carrier.render(16f32 * cps, cps, SR);
+      let signal = carrier.render(16f32 * cps, cps, SR());
       melody.extend(&signal);
     }
 
     let filename = format!("dev-audio/test-melodic-fm-song");
-    engrave::samples(SR, &melody, &filename);
+    engrave::samples(SR(), &melody, &filename).unwrap();
     // Now `carrier` has a modulator chain you can use for synthesis
   }
 }
@@ -820,7 +820,7 @@ mod demo2 {
           modulators: modulators.clone(),
           ..Operator::carrier(*fund)
         };
-        let mut signal = carrier.render(n_cycles, cps, SR);
+        let mut signal = carrier.render(n_cycles, cps, SR());
         melody.extend(signal)
 
         // Now `carrier` is ready for synthesis
@@ -830,7 +830,7 @@ mod demo2 {
       let value = rng.gen::<f32>();
 
       let filename = format!("dev-audio/test-random-fm-synth-{}", value);
-      engrave::samples(SR, &melody, &filename);
+      engrave::samples(SR(), &melody, &filename).unwrap();
     }
   }
 }
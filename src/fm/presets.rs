@@ -673,7 +673,7 @@ pub fn dexed_mushstring(n_cycles: f32, cps: f32, base_frequency: f32, const_a: f
   let op4 = Operator {
     modulators: single_modulator(op5),
     mod_gain_env_mul: Envelope::SampleBased {
-      samples: gen_organic_amplitude(10, ((n_cycles * SRf) as f32 * cps) as usize, Visibility::Visible),
+      samples: gen_organic_amplitude(10, ((n_cycles * SRf()) as f32 * cps) as usize, Visibility::Visible),
     },
     ..Operator::modulator(
       op4_detune_cents + base_frequency * 2f32,
@@ -724,14 +724,14 @@ mod tests {
       let modulator_playback_rate = 1.0;
 
       let operators = dexed_mushstring(n_cycles, cps, *carrier_frequency, c);
-      let mut signal = render_operators(operators, n_cycles, cps, SR);
+      let mut signal = render_operators(operators, n_cycles, cps, SR());
 
       assert!(!signal.is_empty());
       melody.extend(signal)
     }
 
     let filename = format!("dev-audio/test-dx-strings");
-    engrave::samples(SR, &melody, &filename);
+    engrave::samples(SR(), &melody, &filename).unwrap();
   }
 
   #[test]
@@ -749,14 +749,14 @@ mod tests {
       let p = i as f32 * l / l;
       let modulator_playback_rate = 1.0;
       let operators = dexed_bassoon(p, n_cycles, cps, *carrier_frequency, 0.15f32);
-      let mut signal = render_operators(operators, n_cycles, cps, SR);
+      let mut signal = render_operators(operators, n_cycles, cps, SR());
 
       assert!(!signal.is_empty());
       melody.extend(signal)
     }
 
     let filename = format!("dev-audio/{}", label);
-    engrave::samples(SR, &melody, &filename);
+    engrave::samples(SR(), &melody, &filename).unwrap();
   }
 
   #[test]
@@ -774,14 +774,14 @@ mod tests {
       let p = i as f32 * l / l;
       let modulator_playback_rate = 1.0;
       let operators = dexed_brass(p, n_cycles, cps, *carrier_frequency, 1f32);
-      let mut signal = render_operators(operators, n_cycles, cps, SR);
+      let mut signal = render_operators(operators, n_cycles, cps, SR());
 
       assert!(!signal.is_empty());
       melody.extend(signal)
     }
 
     let filename = format!("dev-audio/{}", label);
-    engrave::samples(SR, &melody, &filename);
+    engrave::samples(SR(), &melody, &filename).unwrap();
   }
 
   #[test]
@@ -803,11 +803,11 @@ mod tests {
         let p = i as f32 * l / l;
         let modulator_playback_rate = 1.0;
         let operators = dexed_brass(p, n_cycles, cps, *carrier_frequency, mod_gain);
-        let mut chords_signal = render_operators(operators, n_cycles, cps, SR);
+        let mut chords_signal = render_operators(operators, n_cycles, cps, SR());
 
         for mul in vec![1.2f32, 1.5f32] {
           let operators = dexed_brass(p, n_cycles, cps, *carrier_frequency * mul, mod_gain);
-          let mut add_signal = render_operators(operators, n_cycles, cps, SR);
+          let mut add_signal = render_operators(operators, n_cycles, cps, SR());
           for (ii, y) in add_signal.iter().enumerate() {
             chords_signal[ii] += y;
           }
@@ -817,7 +817,7 @@ mod tests {
       }
 
       let filename = format!("dev-audio/{}-mod-gain-{}", label, mod_gain);
-      engrave::samples(SR, &melody, &filename);
+      engrave::samples(SR(), &melody, &filename).unwrap();
     }
   }
 
@@ -839,11 +839,11 @@ mod tests {
         let p = i as f32 * l / l;
         let modulator_playback_rate = 1.0;
         let operators = dexed_pad(p, n_cycles, cps, *carrier_frequency, mod_gain);
-        let mut chords_signal = render_operators(operators, n_cycles, cps, SR);
+        let mut chords_signal = render_operators(operators, n_cycles, cps, SR());
 
         for mul in vec![1.2f32, 1.5f32] {
           let operators = dexed_pad(p, n_cycles, cps, *carrier_frequency * mul, mod_gain);
-          let mut add_signal = render_operators(operators, n_cycles, cps, SR);
+          let mut add_signal = render_operators(operators, n_cycles, cps, SR());
           for (ii, y) in add_signal.iter().enumerate() {
             chords_signal[ii] += y;
           }
@@ -853,7 +853,7 @@ mod tests {
       }
 
       let filename = format!("dev-audio/{}-mod-gain-{}", label, mod_gain);
-      engrave::samples(SR, &melody, &filename);
+      engrave::samples(SR(), &melody, &filename).unwrap();
     }
   }
 
@@ -894,13 +894,13 @@ mod tests {
       let modulator_playback_rate = 1.0;
 
       let operators = dx7_simplified_5_8(*carrier_frequency);
-      let mut signal = render_operators(operators, 12f32, 1.5, SR);
+      let mut signal = render_operators(operators, 12f32, 1.5, SR());
 
       assert!(!signal.is_empty());
       melody.extend(signal)
     }
 
     let filename = format!("dev-audio/test-strings-clone");
-    engrave::samples(SR, &melody, &filename);
+    engrave::samples(SR(), &melody, &filename).unwrap();
   }
 }
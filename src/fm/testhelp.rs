@@ -3,11 +3,12 @@ pub use crate::phrasing::dynamics::gen_organic_amplitude;
 pub use crate::types::timbre::Visibility;
 
 use crate::phrasing::ranger::{self, Knob, Ranger};
-use rand::{self, thread_rng, Rng};
+use rand::{self, Rng};
+use crate::seed::thread_rng;
 
 /// Generates a random knob with parameters `a`, `b`, and `c`.
 pub fn random_knob() -> Knob {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   Knob {
     a: rng.gen_range(0.0..1.0),
     b: rng.gen_range(0.0..1.0),
@@ -17,7 +18,7 @@ pub fn random_knob() -> Knob {
 
 /// Selects a random modulation function from the available options.
 pub fn random_modulation_function() -> Ranger {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let modulation_functions: Vec<fn(&Knob, f32, f32, f32, f32, f32) -> f32> = vec![
     ranger::amod_fadeout,
     ranger::amod_cycle_fadein_1_4,
@@ -33,7 +34,7 @@ pub fn random_modulation_function() -> Ranger {
 }
 /// Selects a random modulation function from the available options.
 pub fn random_continuous_function() -> Ranger {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let modulation_functions: Vec<fn(&Knob, f32, f32, f32, f32, f32) -> f32> = vec![
     ranger::amod_fadeout,
     ranger::amod_oscillation_sine,
@@ -45,7 +46,7 @@ pub fn random_continuous_function() -> Ranger {
 
 /// Generates a random modulator with an envelope.
 pub fn random_modulator(cps: f32, n_cycles: f32, fund: f32, z_index: usize) -> Operator {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   // Generate random properties
   let knob = random_knob();
@@ -81,7 +82,7 @@ pub fn random_modulator(cps: f32, n_cycles: f32, fund: f32, z_index: usize) -> O
     ]
     .concat()
   } else {
-    gen_organic_amplitude(10, ((n_cycles * SRf) as f32 * cps) as usize, Visibility::Visible)
+    gen_organic_amplitude(10, ((n_cycles * SRf()) as f32 * cps) as usize, Visibility::Visible)
   };
   let modulators = if z_index > 0 {
     vec![ModulationSource::Operator(random_modulator(
@@ -105,7 +106,7 @@ pub fn random_modulator(cps: f32, n_cycles: f32, fund: f32, z_index: usize) -> O
 
 /// Generates a random modulator with an envelope.
 pub fn random_finite_envelope(cps: f32, n_cycles: f32, freq: f32) -> Envelope {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let mod_index_env = ranger::eval_knob_mod(random_modulation_function(), &random_knob(), cps, freq, n_cycles);
   let mod_index_env: Vec<f32> = mod_index_env.iter().map(|x| x.powi(8i32)).collect();
   Envelope::from_samples(&mod_index_env)
@@ -113,7 +114,7 @@ pub fn random_finite_envelope(cps: f32, n_cycles: f32, freq: f32) -> Envelope {
 
 /// Generates a random modulator with an envelope.
 pub fn random_continuous_envelope(cps: f32, n_cycles: f32, freq: f32) -> Envelope {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let mod_index_env = ranger::eval_knob_mod(random_continuous_function(), &random_knob(), cps, freq, n_cycles);
   let mod_index_env: Vec<f32> = mod_index_env.iter().map(|x| x.powi(8i32)).collect();
   Envelope::from_samples(&mod_index_env)
@@ -121,11 +122,11 @@ pub fn random_continuous_envelope(cps: f32, n_cycles: f32, freq: f32) -> Envelop
 
 /// Generates a random modulator with an envelope.
 pub fn random_envelope(cps: f32, n_cycles: f32, freq: f32) -> Envelope {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let mod_index_env = if rng.gen::<f32>() < 0.5f32 {
     ranger::eval_knob_mod(random_modulation_function(), &random_knob(), cps, freq, n_cycles)
   } else {
-    gen_organic_amplitude(10, ((n_cycles * SRf) as f32 * cps) as usize, Visibility::Visible)
+    gen_organic_amplitude(10, ((n_cycles * SRf()) as f32 * cps) as usize, Visibility::Visible)
   };
   let mod_index_env: Vec<f32> = mod_index_env.iter().map(|x| x.powi(8i32)).collect();
   Envelope::from_samples(&mod_index_env)
@@ -141,7 +142,7 @@ pub fn generate_envelopes(num_modulators: usize, cps: f32, n_cycles: f32, fund:
 }
 
 pub fn random_modulator_with_envelope(cps: f32, n_cycles: f32, base_freq: f32, depth: usize) -> Operator {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   // Random modulator frequency
   let mod_freq = base_freq * rng.gen_range(1..12) as f32;
@@ -5,21 +5,32 @@ use std::fmt;
 use std::fs;
 use std::io;
 
+use crate::error::RaudioError;
 use crate::types::render::*;
 use crate::types::synthesis;
 use crate::types::synthesis::*;
 use crate::types::timbre;
 use crate::types::timbre::*;
 
-pub fn load_score_from_file(filepath: &str) -> Result<DruidicScore, fmt::Error> {
-  match fs::read_to_string(&filepath) {
-    Ok(str) => {
-      let score: DruidicScore =
-        serde_json::from_str(&str).expect(&format!("Failed to parse score from file at path {}", filepath));
-      Ok(score)
-    }
-    _ => Err(fmt::Error),
+/// Load a score from a JSON playbook, or from a Standard MIDI File when the path ends in `.mid` or `.midi`.
+/// A playbook with problems that prevent rendering is rejected. See `validate::load_playbook`.
+pub fn load_score_from_file(filepath: &str) -> Result<DruidicScore, RaudioError> {
+  load_score_with_arfs(filepath, &[])
+}
+
+/// Load a score like `load_score_from_file`, giving the parts of a MIDI file the Arfs in `arfs`, in order.
+/// See `midi::read_score`.
+pub fn load_score_with_arfs(filepath: &str, arfs: &[Arf]) -> Result<DruidicScore, RaudioError> {
+  if is_midi_file(filepath) {
+    return super::midi::load_score(filepath, arfs).map_err(|msg| RaudioError::Midi(filepath.to_string(), msg));
   }
+  super::validate::load_playbook(filepath)
+}
+
+/// Whether a path names a Standard MIDI File rather than a JSON playbook.
+pub fn is_midi_file(filepath: &str) -> bool {
+  let lowercase = filepath.to_lowercase();
+  lowercase.ends_with(".mid") || lowercase.ends_with(".midi")
 }
 
 mod test_unit {
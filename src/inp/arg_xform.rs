@@ -4,9 +4,11 @@ use crate::analysis::volume::db_to_amp;
 use crate::reverb::convolution::ReverbParams;
 use crate::types::render::DruidicScoreEntry;
 use crate::types::timbre::{
-  AmpContour, Arf, Distance, Echo, Enclosure, Energy, Positioning, Presence, SpaceEffects, Visibility,
+  AmpContour, Arf, ClientPositioning, Distance, Echo, Enclosure, Energy, Positioning, Presence, Role, SpaceEffects,
+  Visibility,
 };
-use rand::{self, rngs::ThreadRng, Rng};
+use rand::{self, Rng};
+use crate::seed::ThreadRng;
 // todo
 // 1 separate stems by beat vs inst
 // 2 apply render process
@@ -26,7 +28,7 @@ pub fn positioning(
     echo,
   }: &Positioning,
 ) -> SpaceEffects {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let gain: f32 = match distance {
     Distance::Adjacent => 1f32,
     Distance::Near => db_to_amp(-6f32),
@@ -50,7 +52,7 @@ pub fn positioning(
 pub fn create_space_effects(
   cps: f32, enclosure: &Enclosure, complexity: f32, distance: &Distance, echo: &Echo,
 ) -> SpaceEffects {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let gain: f32 = match distance {
     Distance::Adjacent => 1f32,
     Distance::Near => db_to_amp(-6f32),
@@ -89,6 +91,29 @@ pub fn gen_delays(rng: &mut ThreadRng, cps: f32, echo: &Echo, complexity: f32) -
   }
 }
 
+/// Pan position in [-1, 1] (left to right) for a part.
+///
+/// An explicit `pan` on the positioning is used as given.
+/// Otherwise kick and bass stay centered, and other parts are placed on a random side,
+/// spreading wider as the part moves farther away.
+pub fn gen_pan(rng: &mut ThreadRng, arf: &Arf, positioning: &ClientPositioning) -> f32 {
+  if let Some(pan) = positioning.pan {
+    return pan.clamp(-1f32, 1f32);
+  }
+  match arf.role {
+    Role::Kick | Role::Bass => 0f32,
+    _ => {
+      let spread = match positioning.distance {
+        Distance::Adjacent => 0.15f32,
+        Distance::Near => 0.35f32,
+        Distance::Far => 0.6f32,
+      };
+      let side = if rng.gen::<bool>() { 1f32 } else { -1f32 };
+      side * spread * rng.gen_range(0.5f32..=1f32)
+    }
+  }
+}
+
 /// positioning params applied as convolution parameters to blur or distort the signal
 pub fn gen_convolution_stem(
   rng: &mut ThreadRng, arf: &Arf, len_seconds: f32, cps: f32, distance: &Distance, enclosure: &Enclosure,
@@ -245,6 +270,7 @@ fn gen_slapback(cps: f32, rng: &mut ThreadRng, complexity: f32) -> DelayParams {
   DelayParams {
     mix: 0.5f32,
     len_seconds,
+    len_cycles: Some(rate),
     n_echoes,
     gain,
     pan,
@@ -271,6 +297,7 @@ fn gen_trailing(cps: f32, rng: &mut ThreadRng, complexity: f32) -> DelayParams {
   DelayParams {
     mix,
     len_seconds,
+    len_cycles: Some(rate),
     n_echoes,
     gain,
     pan: StereoField::Mono,
@@ -279,7 +306,7 @@ fn gen_trailing(cps: f32, rng: &mut ThreadRng, complexity: f32) -> DelayParams {
 
 /// simple contour generator intended to be used in a bandpass frequency context.
 pub fn gen_bp_contour(n: usize, freq1: f32, freq2: f32, n_samples: usize) -> Vec<f32> {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   let mut checkpoints: Vec<(f32, f32, f32)> = (0..n)
     .map(|_| {
@@ -292,3 +319,37 @@ pub fn gen_bp_contour(n: usize, freq1: f32, freq2: f32, n_samples: usize) -> Vec
 
   crate::analysis::freq::render_checkpoints(&checkpoints, freq1, freq2, n_samples)
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::types::timbre::Mode;
+
+  #[test]
+  fn test_gen_pan() {
+    let mut rng = crate::seed::thread_rng();
+    let arf = |role: Role| Arf {
+      mode: Mode::Melodic,
+      role,
+      register: 7,
+      visibility: Visibility::Foreground,
+      energy: Energy::Medium,
+      presence: Presence::Legato,
+    };
+    let mut positioning = ClientPositioning {
+      echo: Echo::None,
+      enclosure: Enclosure::Room,
+      distance: Distance::Far,
+      pan: None,
+      glide: None,
+      groove: None,
+    };
+    assert_eq!(0f32, gen_pan(&mut rng, &arf(Role::Bass), &positioning), "Must keep the bass centered");
+    for _ in 0..32 {
+      let pan = gen_pan(&mut rng, &arf(Role::Lead), &positioning);
+      assert!(pan.abs() >= 0.3f32 && pan.abs() <= 0.6f32, "Far parts must spread wide, got {}", pan);
+    }
+    positioning.pan = Some(-2f32);
+    assert_eq!(-1f32, gen_pan(&mut rng, &arf(Role::Bass), &positioning), "Must use the clamped explicit pan");
+  }
+}
@@ -0,0 +1,638 @@
+/// Standard MIDI File (format 0 and 1) import.
+///
+/// Every track that holds notes becomes one part of a `DruidicScore`. Format 0 files are split by channel instead.
+/// One quarter note is one cycle, so `conf.cps` comes from the tempo at the start of the file
+/// and each later tempo change becomes a `TempoPoint` of the score's tempo map.
+/// Pitches are spelled as just intonation `Tone`s relative to the tonic of the file.
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+
+use crate::analysis::fit;
+use crate::analysis::xform_freq::{midi_to_freq, velocity_to_amplitude};
+use crate::types::render::{Conf, Dimensions, DruidicScore, Melody, TempoCurve, TempoPoint};
+use crate::types::synthesis::{Monae, Note, Tone};
+use crate::types::timbre::{
+  Arf, ClientPositioning, Distance, Echo, Enclosure, Energy, Mode, Presence, Role, Visibility,
+};
+
+/// Channel reserved for percussion in General MIDI (channel 10, zero indexed)
+pub const DRUM_CHANNEL: u8 = 9;
+
+/// Microseconds per quarter note when a file has no tempo (120 BPM)
+const DEFAULT_TEMPO: u32 = 500_000;
+
+/// Just intonation spelling of each chromatic interval above the tonic.
+/// Each entry is the inverse of `monic_theory::monae_to_chrom`.
+const JUST_INTERVALS: [Monae; 12] = [
+  (0, 0, 1),  // 1/1
+  (-1, 1, 5), // 16/15
+  (1, 0, 3),  // 9/8
+  (1, 1, 5),  // 6/5
+  (0, 0, 5),  // 5/4
+  (0, 1, 3),  // 4/3
+  (2, 0, 5),  // 45/32
+  (0, 0, 3),  // 3/2
+  (0, 1, 5),  // 8/5
+  (-1, 0, 5), // 5/3
+  (-1, 1, 3), // 16/9
+  (1, 0, 5),  // 15/8
+];
+
+/// (start tick, end tick, key, velocity)
+type TickNote = (u64, u64, u8, u8);
+
+/// (start step, end step, key, velocity) on the cycle grid
+type GridNote = (i64, i64, u8, u8);
+
+/// Notes of one part in ticks, with the channel playing most of them and the Arf embedded in the track if any.
+type TickPart = (u8, Vec<TickNote>, Option<Arf>);
+
+struct Smf {
+  division: u16,
+  parts: Vec<TickPart>,
+  /// (tick, microseconds per quarter note)
+  tempos: Vec<(u64, u32)>,
+  /// (sharps or flats, minor)
+  key_signature: Option<(i8, bool)>,
+}
+
+struct ByteReader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+  fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+    if self.pos + n > self.bytes.len() {
+      return Err(format!("Unexpected end of MIDI data at byte {}", self.pos));
+    }
+    let slice = &self.bytes[self.pos..self.pos + n];
+    self.pos += n;
+    Ok(slice)
+  }
+
+  fn u8(&mut self) -> Result<u8, String> {
+    Ok(self.take(1)?[0])
+  }
+
+  fn peek(&self) -> Result<u8, String> {
+    self.bytes.get(self.pos).copied().ok_or(format!("Unexpected end of MIDI data at byte {}", self.pos))
+  }
+
+  fn u16(&mut self) -> Result<u16, String> {
+    let b = self.take(2)?;
+    Ok(u16::from_be_bytes([b[0], b[1]]))
+  }
+
+  fn u32(&mut self) -> Result<u32, String> {
+    let b = self.take(4)?;
+    Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+  }
+
+  /// Variable length quantity, at most four bytes.
+  fn varlen(&mut self) -> Result<u32, String> {
+    let mut value: u32 = 0;
+    for _ in 0..4 {
+      let b = self.u8()?;
+      value = (value << 7) | (b & 0x7F) as u32;
+      if b & 0x80 == 0 {
+        return Ok(value);
+      }
+    }
+    Err(format!("Variable length value is too long at byte {}", self.pos))
+  }
+
+  fn chunk(&mut self) -> Result<([u8; 4], &'a [u8]), String> {
+    let id = self.take(4)?;
+    let len = self.u32()? as usize;
+    Ok(([id[0], id[1], id[2], id[3]], self.take(len)?))
+  }
+}
+
+fn parse_smf(bytes: &[u8]) -> Result<Smf, String> {
+  let mut reader = ByteReader { bytes, pos: 0 };
+  let (id, header) = reader.chunk()?;
+  if &id != b"MThd" || header.len() < 6 {
+    return Err("Not a Standard MIDI File (missing MThd header)".to_string());
+  }
+  let format = u16::from_be_bytes([header[0], header[1]]);
+  let division = u16::from_be_bytes([header[4], header[5]]);
+  if format > 1 {
+    return Err(format!("Unsupported MIDI format {}, expected 0 or 1", format));
+  }
+  if division & 0x8000 != 0 || division == 0 {
+    return Err("Unsupported MIDI time division, expected ticks per quarter note".to_string());
+  }
+
+  let mut smf = Smf {
+    division,
+    parts: Vec::new(),
+    tempos: Vec::new(),
+    key_signature: None,
+  };
+  while reader.pos < bytes.len() {
+    let (id, data) = reader.chunk()?;
+    // skip unknown chunks as the spec requires
+    if &id == b"MTrk" {
+      parse_track(data, format, &mut smf)?;
+    }
+  }
+  smf.tempos.sort_by_key(|(tick, _)| *tick);
+  Ok(smf)
+}
+
+fn parse_track(data: &[u8], format: u16, smf: &mut Smf) -> Result<(), String> {
+  let mut reader = ByteReader { bytes: data, pos: 0 };
+  let mut tick: u64 = 0;
+  let mut running_status: Option<u8> = None;
+  let mut open: HashMap<(u8, u8), VecDeque<(u64, u8)>> = HashMap::new();
+  let mut channels: BTreeMap<u8, Vec<TickNote>> = BTreeMap::new();
+  let mut embedded_arf: Option<Arf> = None;
+
+  while reader.pos < data.len() {
+    tick += reader.varlen()? as u64;
+    let status = if reader.peek()? & 0x80 != 0 {
+      reader.u8()?
+    } else {
+      running_status.ok_or("MIDI data byte without a running status")?
+    };
+
+    match status {
+      0xFF => {
+        let kind = reader.u8()?;
+        let len = reader.varlen()? as usize;
+        let meta = reader.take(len)?;
+        match kind {
+          0x51 if len == 3 => {
+            let tempo = ((meta[0] as u32) << 16) | ((meta[1] as u32) << 8) | meta[2] as u32;
+            if tempo > 0 {
+              smf.tempos.push((tick, tempo));
+            }
+          }
+          0x59 if len == 2 && smf.key_signature.is_none() => {
+            smf.key_signature = Some((meta[0] as i8, meta[1] == 1));
+          }
+          // text written by `render::midi` carries the part's Arf
+          0x01 => {
+            if let Ok(arf) = serde_json::from_slice::<Arf>(meta) {
+              embedded_arf = Some(arf);
+            }
+          }
+          0x2F => break,
+          _ => {}
+        }
+      }
+      0xF0 | 0xF7 => {
+        let len = reader.varlen()? as usize;
+        reader.take(len)?;
+        running_status = None;
+      }
+      _ => {
+        running_status = Some(status);
+        let kind = status & 0xF0;
+        let channel = status & 0x0F;
+        let key = reader.u8()?;
+        let velocity = if kind == 0xC0 || kind == 0xD0 { 0 } else { reader.u8()? };
+        match kind {
+          0x90 if velocity > 0 => open.entry((channel, key)).or_default().push_back((tick, velocity)),
+          0x80 | 0x90 => {
+            if let Some((start, velocity)) = open.get_mut(&(channel, key)).and_then(|q| q.pop_front()) {
+              channels.entry(channel).or_default().push((start, tick, key, velocity));
+            }
+          }
+          _ => {}
+        }
+      }
+    }
+  }
+
+  // close notes left hanging at the end of the track
+  for ((channel, key), starts) in open {
+    for (start, velocity) in starts {
+      channels.entry(channel).or_default().push((start, tick, key, velocity));
+    }
+  }
+  if format == 0 {
+    for (channel, mut notes) in channels {
+      notes.sort_by_key(|&(start, _, key, _)| (start, key));
+      smf.parts.push((channel, notes, embedded_arf));
+    }
+  } else if let Some(channel) = channels.iter().max_by_key(|(_, notes)| notes.len()).map(|(c, _)| *c) {
+    let mut notes: Vec<TickNote> = channels.into_values().flatten().collect();
+    notes.sort_by_key(|&(start, _, key, _)| (start, key));
+    smf.parts.push((channel, notes, embedded_arf));
+  }
+  Ok(())
+}
+
+/// Microseconds per quarter note in effect at the start of the file.
+fn initial_tempo(tempos: &[(u64, u32)]) -> u32 {
+  tempos.iter().take_while(|(tick, _)| *tick == 0).last().map_or(DEFAULT_TEMPO, |(_, tempo)| *tempo)
+}
+
+/// Tempo changes after the start of the file as a tempo map, one cycle per quarter note.
+/// Only the last change at a tick counts, and changes to the tempo already playing are dropped.
+fn tempo_points(tempos: &[(u64, u32)], division: u16) -> Vec<TempoPoint> {
+  let mut points: Vec<TempoPoint> = Vec::new();
+  let mut current = initial_tempo(tempos);
+  for (i, &(tick, tempo)) in tempos.iter().enumerate() {
+    let superseded = tempos.get(i + 1).is_some_and(|(next, _)| *next == tick);
+    if tick == 0 || superseded || tempo == current {
+      continue;
+    }
+    current = tempo;
+    points.push(TempoPoint {
+      cycle: tick as f32 / division as f32,
+      cps: (1e6f64 / tempo as f64) as f32,
+      curve: TempoCurve::Step,
+    });
+  }
+  points
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+  if b == 0 {
+    a.abs()
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+/// Reduce a count of grid steps to a `Duration` ratio of cycles.
+fn to_duration(steps: i64, steps_per_cycle: i64) -> (i32, i32) {
+  let d = gcd(steps, steps_per_cycle).max(1);
+  ((steps / d) as i32, (steps_per_cycle / d) as i32)
+}
+
+/// Pitch class of the tonic, from the key signature or else the most sounded pitch class.
+fn infer_tonic(smf: &Smf) -> u8 {
+  if let Some((sharps, minor)) = smf.key_signature {
+    let major = (7 * sharps as i32).rem_euclid(12);
+    return if minor { (major + 9) % 12 } else { major } as u8;
+  }
+  let mut weights = [0u64; 12];
+  for (channel, notes, _) in &smf.parts {
+    if *channel == DRUM_CHANNEL {
+      continue;
+    }
+    for &(start, end, key, _) in notes {
+      weights[(key % 12) as usize] += end - start;
+    }
+  }
+  // the first pitch class wins ties so C is chosen for an empty file
+  (0..12u8).rev().max_by_key(|&pc| weights[pc as usize]).unwrap_or(0)
+}
+
+/// Spell a MIDI key as a `Tone` above the score root.
+pub fn key_to_tone(key: u8, tonic: u8, root: f32) -> Tone {
+  let interval = (key as i32 - tonic as i32).rem_euclid(12);
+  let tonic_below = key as i32 - interval;
+  let register = (midi_to_freq(tonic_below) / root).log2().round() as i8;
+  (register, JUST_INTERVALS[interval as usize])
+}
+
+/// Split overlapping notes into monophonic lines, placing each note on the first line that is free.
+fn allocate_lines(notes: &[GridNote]) -> Vec<Vec<GridNote>> {
+  let mut lines: Vec<Vec<GridNote>> = Vec::new();
+  for &note in notes {
+    match lines.iter_mut().find(|line| line.last().is_none_or(|last| last.1 <= note.0)) {
+      Some(line) => line.push(note),
+      None => lines.push(vec![note]),
+    }
+  }
+  lines
+}
+
+/// Describe a part from its notes when the caller does not provide an `Arf`.
+fn infer_arf(channel: u8, notes: &[GridNote], n_lines: usize) -> Arf {
+  let mut keys: Vec<u8> = notes.iter().map(|n| n.2).collect();
+  keys.sort();
+  let median_key = keys.get(keys.len() / 2).copied().unwrap_or(60);
+
+  let (role, register) = if channel == DRUM_CHANNEL {
+    let count = |range: &[u8]| keys.iter().filter(|k| range.contains(k)).count();
+    let kicks = count(&[35, 36]);
+    let hats = count(&[42, 44, 46, 51, 53, 59]);
+    if 2 * kicks > keys.len() {
+      (Role::Kick, 5)
+    } else if 2 * hats > keys.len() {
+      (Role::Hats, 10)
+    } else {
+      (Role::Perc, 8)
+    }
+  } else {
+    let register = midi_to_freq(median_key as i32).log2().floor() as i8;
+    if median_key < 48 {
+      (Role::Bass, register)
+    } else if n_lines > 1 {
+      (Role::Chords, register)
+    } else {
+      (Role::Lead, register)
+    }
+  };
+
+  let visibility = match role {
+    Role::Lead | Role::Kick => Visibility::Visible,
+    Role::Bass | Role::Perc => Visibility::Foreground,
+    Role::Chords | Role::Hats => Visibility::Background,
+  };
+
+  let mean_velocity = notes.iter().map(|n| n.3 as f32).sum::<f32>() / notes.len().max(1) as f32;
+  let energy = if mean_velocity < 64f32 {
+    Energy::Low
+  } else if mean_velocity < 100f32 {
+    Energy::Medium
+  } else {
+    Energy::High
+  };
+
+  // how much of the time between onsets is filled by the note
+  let fill: Vec<f32> = notes
+    .windows(2)
+    .filter(|w| w[1].0 > w[0].0)
+    .map(|w| (w[0].1 - w[0].0) as f32 / (w[1].0 - w[0].0) as f32)
+    .collect();
+  let mean_fill = if fill.is_empty() {
+    1f32
+  } else {
+    fill.iter().sum::<f32>() / fill.len() as f32
+  };
+  let presence = if mean_fill < 0.5f32 {
+    Presence::Staccatto
+  } else if mean_fill < 0.9f32 {
+    Presence::Legato
+  } else {
+    Presence::Tenuto
+  };
+
+  Arf {
+    mode: Mode::Melodic,
+    register,
+    role,
+    visibility,
+    energy,
+    presence,
+  }
+}
+
+/// Convert a monophonic line of grid notes to a line of `Note`s, filling gaps with rests up to `end`.
+fn line_to_notes(
+  line: &[GridNote], end: i64, steps_per_cycle: i64, tonic: u8, root: f32, rest_tone: Tone,
+) -> Vec<Note> {
+  let mut notes: Vec<Note> = Vec::new();
+  let mut cursor = 0i64;
+  for &(start, stop, key, velocity) in line {
+    if start > cursor {
+      notes.push((to_duration(start - cursor, steps_per_cycle), rest_tone, 0f32));
+    }
+    notes.push((
+      to_duration(stop - start, steps_per_cycle),
+      key_to_tone(key, tonic, root),
+      velocity_to_amplitude(velocity as i8),
+    ));
+    cursor = stop;
+  }
+  if end > cursor {
+    notes.push((to_duration(end - cursor, steps_per_cycle), rest_tone, 0f32));
+  }
+  notes
+}
+
+/// Read a Standard MIDI File into a score that `render_score` can play.
+///
+/// ## Arguments
+///     `bytes` Contents of a format 0 or format 1 `.mid` file
+///     `arfs` Arf for each part, in order. Parts without one use the Arf embedded in their track, or else an inferred Arf.
+pub fn read_score(bytes: &[u8], arfs: &[Arf]) -> Result<DruidicScore, String> {
+  let smf = parse_smf(bytes)?;
+  let tempo = initial_tempo(&smf.tempos);
+  let cps = 1e6f64 / tempo as f64;
+  let steps_per_cycle = smf.division as i64;
+
+  let tonic = infer_tonic(&smf);
+  let root = fit(1f32, midi_to_freq(tonic as i32));
+
+  let grid_parts: Vec<(u8, Vec<GridNote>, Option<Arf>)> = smf
+    .parts
+    .iter()
+    .map(|(channel, notes, embedded_arf)| {
+      let grid_notes = notes
+        .iter()
+        .map(|&(start, end, key, velocity)| (start as i64, end as i64, key, velocity))
+        .filter(|(start, end, _, _)| end > start)
+        .collect();
+      (*channel, grid_notes, *embedded_arf)
+    })
+    .filter(|(_, notes, _): &(u8, Vec<_>, _)| !notes.is_empty())
+    .collect();
+  if grid_parts.is_empty() {
+    return Err("MIDI file has no notes".to_string());
+  }
+
+  // end every line on the first whole cycle after the last note
+  let last = grid_parts.iter().flat_map(|(_, notes, _)| notes.iter().map(|n| n.1)).max().unwrap_or(0);
+  let end = ((last + steps_per_cycle - 1) / steps_per_cycle) * steps_per_cycle;
+
+  let parts = grid_parts
+    .iter()
+    .enumerate()
+    .map(|(i, (channel, notes, embedded_arf))| {
+      let lines = allocate_lines(notes);
+      let arf = arfs.get(i).copied().or(*embedded_arf).unwrap_or_else(|| infer_arf(*channel, notes, lines.len()));
+      let rest_tone: Tone = (arf.register, (0, 0, 1));
+      let melody: Melody<Note> =
+        lines.iter().map(|line| line_to_notes(line, end, steps_per_cycle, tonic, root, rest_tone)).collect();
+      let positioning = ClientPositioning {
+        echo: Echo::None,
+        enclosure: Enclosure::Room,
+        distance: Distance::Near,
+        pan: None,
+        glide: None,
+        groove: None,
+      };
+      (positioning, arf, melody)
+    })
+    .collect();
+
+  let cpc: i16 = 4;
+  let n_cycles = end / steps_per_cycle;
+  let size = (0..16).find(|s| 2i64.pow(*s as u32) * cpc as i64 >= n_cycles).unwrap_or(16);
+
+  Ok(DruidicScore {
+    conf: Conf { cps: cps as f32, root },
+    dimensions: Dimensions { size, cpc, base: 2 },
+    parts,
+    markers: Vec::new(),
+    groupEnclosure: Enclosure::Room,
+    tempo: tempo_points(&smf.tempos, smf.division),
+    tuning: None,
+  })
+}
+
+/// Read a `.mid` file from disk. See `read_score`.
+pub fn load_score(filepath: &str, arfs: &[Arf]) -> Result<DruidicScore, String> {
+  let bytes = fs::read(filepath).map_err(|e| format!("Failed to read MIDI file {}: {}", filepath, e))?;
+  read_score(&bytes, arfs)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::monic_theory::tone_to_freq;
+  use crate::time;
+
+  fn varlen(mut v: u32) -> Vec<u8> {
+    let mut out = vec![(v & 0x7F) as u8];
+    v >>= 7;
+    while v > 0 {
+      out.insert(0, 0x80 | (v & 0x7F) as u8);
+      v >>= 7;
+    }
+    out
+  }
+
+  /// Build a format 1 file from (delta, event bytes) pairs per track.
+  fn smf(division: u16, tracks: Vec<Vec<(u32, Vec<u8>)>>) -> Vec<u8> {
+    let mut bytes = b"MThd".to_vec();
+    bytes.extend(6u32.to_be_bytes());
+    bytes.extend(1u16.to_be_bytes());
+    bytes.extend((tracks.len() as u16).to_be_bytes());
+    bytes.extend(division.to_be_bytes());
+    for events in tracks {
+      let mut data = Vec::new();
+      for (delta, event) in events {
+        data.extend(varlen(delta));
+        data.extend(event);
+      }
+      data.extend([0x00, 0xFF, 0x2F, 0x00]);
+      bytes.extend(b"MTrk");
+      bytes.extend((data.len() as u32).to_be_bytes());
+      bytes.extend(data);
+    }
+    bytes
+  }
+
+  fn cents(a: f32, b: f32) -> f32 {
+    1200f32 * (a / b).log2().abs()
+  }
+
+  #[test]
+  fn test_read_score_timing_and_pitch() {
+    let tempo_track = vec![(0, vec![0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20])]; // 500000us, 120 BPM
+    let melody_track = vec![
+      (0, vec![0x90, 60, 100]),
+      (480, vec![0x80, 60, 0]),
+      (240, vec![0x90, 64, 127]),
+      (240, vec![0x90, 67, 64]),
+      (0, vec![0x80, 64, 0]),
+      (480, vec![67, 0]), // note off using running status
+    ];
+    let bytes = smf(480, vec![tempo_track, melody_track]);
+    let score = read_score(&bytes, &[]).unwrap();
+
+    assert_eq!(2f32, score.conf.cps);
+    assert_eq!(1, score.parts.len());
+    let (_, arf, melody) = &score.parts[0];
+    assert_eq!(1, melody.len(), "Non-overlapping notes must share one line");
+    let line = &melody[0];
+    let durations: Vec<(i32, i32)> = line.iter().map(|n| n.0).collect();
+    assert_eq!(vec![(1, 1), (1, 2), (1, 2), (1, 1)], durations);
+    assert_eq!(0f32, line[1].2, "Gaps must be rests");
+    assert_eq!(3f32, time::count_cycles(line));
+    assert_eq!(velocity_to_amplitude(127), line[2].2);
+
+    for (note, key) in [(line[0], 60), (line[2], 64), (line[3], 67)] {
+      let freq = score.conf.root * tone_to_freq(&note.1);
+      assert!(
+        cents(freq, midi_to_freq(key)) < 20f32,
+        "Key {} must be spelled near its 12-ET pitch",
+        key
+      );
+    }
+    assert!(matches!(arf.role, Role::Lead));
+  }
+
+  #[test]
+  fn test_read_score_polyphony_and_drums() {
+    let chords = vec![
+      (0, vec![0x90, 60, 90]),
+      (0, vec![0x90, 64, 90]),
+      (0, vec![0x90, 67, 90]),
+      (960, vec![0x80, 60, 0]),
+      (0, vec![0x80, 64, 0]),
+      (0, vec![0x80, 67, 0]),
+    ];
+    let drums = vec![
+      (0, vec![0x99, 36, 120]),
+      (120, vec![0x89, 36, 0]),
+      (360, vec![0x99, 36, 120]),
+      (120, vec![0x89, 36, 0]),
+    ];
+    let bytes = smf(480, vec![chords, drums]);
+    let kick = Arf {
+      mode: Mode::Melodic,
+      register: 6,
+      role: Role::Kick,
+      visibility: Visibility::Visible,
+      energy: Energy::High,
+      presence: Presence::Staccatto,
+    };
+    let score = read_score(&bytes, &[]).unwrap();
+    assert_eq!(2, score.parts.len());
+    assert_eq!(3, score.parts[0].2.len(), "A triad must be split into three lines");
+    assert!(matches!(score.parts[0].1.role, Role::Chords));
+    assert!(matches!(score.parts[1].1.role, Role::Kick));
+    for (_, _, melody) in &score.parts {
+      for line in melody {
+        assert_eq!(
+          2f32,
+          time::count_cycles(line),
+          "Every line must be padded to the end of the score"
+        );
+      }
+    }
+    assert_eq!(2f32, score.conf.cps, "Must default to 120 BPM without a tempo");
+
+    let score = read_score(&bytes, &[kick]).unwrap();
+    assert_eq!(
+      6, score.parts[0].1.register,
+      "Must use the provided Arf for the first part"
+    );
+  }
+
+  #[test]
+  fn test_read_score_tempo_changes() {
+    let tempo_track = vec![
+      (0, vec![0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20]),   // 120 BPM
+      (960, vec![0xFF, 0x51, 0x03, 0x0B, 0x71, 0xB0]), // 80 BPM at cycle 2, replaced by the next change
+      (0, vec![0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40]),   // 60 BPM at cycle 2
+      (480, vec![0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40]), // 60 BPM again
+    ];
+    let melody_track = vec![(0, vec![0x90, 60, 100]), (1440, vec![0x80, 60, 0])];
+    let score = read_score(&smf(480, vec![tempo_track, melody_track]), &[]).unwrap();
+
+    assert_eq!(2f32, score.conf.cps);
+    assert_eq!(vec![TempoPoint { cycle: 2f32, cps: 1f32, curve: TempoCurve::Step }], score.tempo);
+    let line = &score.parts[0].2[0];
+    assert_eq!((3, 1), line[0].0, "Notes must keep their length in quarter notes across a tempo change");
+    let map = crate::analysis::tempo::TempoMap::of_score(score.conf.cps, &score.tempo).unwrap();
+    assert_eq!(2f64, map.seconds_at(3f32));
+  }
+
+  #[test]
+  fn test_key_to_tone_is_inverse_of_chrom() {
+    for key in 48u8..72 {
+      let (register, monae) = key_to_tone(key, 0, fit(1f32, midi_to_freq(0)));
+      assert_eq!(key as i8, crate::monic_theory::tone_to_chrom((register, monae)));
+    }
+  }
+
+  #[test]
+  fn test_read_score_rejects_bad_input() {
+    assert!(read_score(b"RIFF", &[]).is_err());
+    assert!(
+      read_score(&smf(480, vec![vec![]]), &[]).is_err(),
+      "Must reject a file without notes"
+    );
+  }
+}
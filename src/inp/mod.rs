@@ -1,3 +1,7 @@
 /// Methods for taking external input and turning it into sensible application data.
 pub mod arg_parse;
 pub mod arg_xform;
+pub mod midi;
+pub mod scala;
+pub mod syn_midi;
+pub mod validate;
@@ -0,0 +1,176 @@
+/// Scala scale (`.scl`) and keyboard mapping (`.kbm`) files, for rendering in other tunings.
+///
+/// See <https://www.huygens-fokker.org/scala/scl_format.html>. Lines starting with `!` are comments.
+/// A scale is a description line, the number of degrees, then one pitch per degree: cents when it has a period
+/// (`701.955`), else a ratio (`3/2` or `2`). A keyboard mapping lists its size, first and last key, middle key,
+/// reference key and frequency, octave degree, then the degree of each key in the map (`x` for unmapped keys).
+use std::fs;
+
+use crate::analysis::tuning::{Keyboard, Scale};
+use crate::error::RaudioError;
+
+/// The lines of a Scala file that are not comments.
+fn content_lines(text: &str) -> impl Iterator<Item = &str> {
+  text.lines().filter(|line| !line.starts_with('!'))
+}
+
+/// The first word of a line, ignoring any description after it.
+fn first_word(line: &str) -> &str {
+  line.split_whitespace().next().unwrap_or("")
+}
+
+/// Parse a pitch in cents or as a ratio, returning the ratio.
+fn parse_pitch(word: &str) -> Result<f64, String> {
+  let ratio = if word.contains('.') {
+    let cents: f64 = word.parse().map_err(|_| format!("Invalid cents '{}'", word))?;
+    2f64.powf(cents / 1200f64)
+  } else {
+    let (numerator, denominator) = word.split_once('/').unwrap_or((word, "1"));
+    let parse = |s: &str| s.parse::<u64>().map_err(|_| format!("Invalid ratio '{}'", word));
+    let (numerator, denominator) = (parse(numerator)?, parse(denominator)?);
+    if denominator == 0 {
+      return Err(format!("Invalid ratio '{}'", word));
+    }
+    numerator as f64 / denominator as f64
+  };
+  match ratio.is_finite() && ratio > 0f64 {
+    true => Ok(ratio),
+    false => Err(format!("Pitch '{}' must be above zero", word)),
+  }
+}
+
+/// Read the text of a `.scl` file.
+pub fn read_scale(text: &str) -> Result<Scale, String> {
+  let mut lines = content_lines(text);
+  let description = lines.next().ok_or("Missing description")?.trim().to_string();
+  let count = lines.next().ok_or("Missing number of notes")?;
+  let count: usize = first_word(count).parse().map_err(|_| format!("Invalid number of notes '{}'", count.trim()))?;
+  if count == 0 {
+    return Err("A scale needs at least one note".to_string());
+  }
+  let ratios = lines
+    .filter(|line| !line.trim().is_empty())
+    .take(count)
+    .map(|line| parse_pitch(first_word(line)))
+    .collect::<Result<Vec<f64>, String>>()?;
+  if ratios.len() != count {
+    return Err(format!("Expected {} notes, found {}", count, ratios.len()));
+  }
+  if ratios[count - 1] <= 1f64 {
+    return Err(format!(
+      "The last note is the period and must be above 1/1, got {}",
+      ratios[count - 1]
+    ));
+  }
+  Ok(Scale { description, ratios })
+}
+
+/// Read the text of a `.kbm` file.
+pub fn read_keyboard(text: &str) -> Result<Keyboard, String> {
+  let words: Vec<&str> = content_lines(text).map(first_word).filter(|word| !word.is_empty()).collect();
+  const FIELDS: [&str; 7] = [
+    "map size",
+    "first key",
+    "last key",
+    "middle key",
+    "reference key",
+    "reference frequency",
+    "octave degree",
+  ];
+  if words.len() < FIELDS.len() {
+    return Err(format!("Missing {}", FIELDS[words.len()]));
+  }
+  let int = |i: usize| words[i].parse::<i32>().map_err(|_| format!("Invalid {} '{}'", FIELDS[i], words[i]));
+  let size = int(0)?;
+  if size < 0 {
+    return Err(format!("Invalid map size {}", size));
+  }
+  let octave_degree = int(6)?;
+  let mut mapping = words[FIELDS.len()..]
+    .iter()
+    .take(size as usize)
+    .map(|&word| match word {
+      "x" | "X" => Ok(None),
+      _ => word.parse::<i32>().map(Some).map_err(|_| format!("Invalid degree '{}'", word)),
+    })
+    .collect::<Result<Vec<Option<i32>>, String>>()?;
+  // keys left out at the end of the map are unmapped
+  mapping.resize(size as usize, None);
+  if mapping.iter().all(Option::is_none) && size > 0 {
+    return Err("The map has no mapped keys".to_string());
+  }
+  Ok(Keyboard { mapping, octave_degree })
+}
+
+pub fn load_scale(path: &str) -> Result<Scale, RaudioError> {
+  let text = fs::read_to_string(path).map_err(|e| RaudioError::Io(path.to_string(), e))?;
+  read_scale(&text).map_err(|reason| RaudioError::InvalidTuning(path.to_string(), reason))
+}
+
+pub fn load_keyboard(path: &str) -> Result<Keyboard, RaudioError> {
+  let text = fs::read_to_string(path).map_err(|e| RaudioError::Io(path.to_string(), e))?;
+  read_keyboard(&text).map_err(|reason| RaudioError::InvalidTuning(path.to_string(), reason))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  const MEANTONE_SCL: &str = "! meanquar.scl
+!
+1/4-comma meantone scale. Pietro Aaron's temperament (1523)
+ 12
+!
+ 76.04900
+ 193.15686
+ 310.26471
+ 5/4
+ 503.42157
+ 579.47057
+ 696.57843
+ 25/16
+ 889.73529
+ 1006.84314
+ 1082.89214
+ 2/1
+";
+
+  #[test]
+  fn test_read_scale() {
+    let scale = read_scale(MEANTONE_SCL).unwrap();
+    assert_eq!(
+      "1/4-comma meantone scale. Pietro Aaron's temperament (1523)",
+      scale.description
+    );
+    assert_eq!(12, scale.ratios.len());
+    assert_eq!(1.25f64, scale.ratios[3]);
+    assert!((scale.ratios[6] - 5f64.powf(0.25)).abs() < 1e-6);
+    let builtin = Scale::meantone();
+    assert!(scale.ratios.iter().zip(&builtin.ratios).all(|(a, b)| (a - b).abs() < 1e-6));
+  }
+
+  #[test]
+  fn test_read_keyboard() {
+    let kbm = "! white keys\n7\n0\n127\n60\n69\n440.0\n5\n! mapping\n0\n1\n2\nx\n3\n4\n";
+    let keyboard = read_keyboard(kbm).unwrap();
+    assert_eq!(5, keyboard.octave_degree);
+    assert_eq!(
+      vec![Some(0), Some(1), Some(2), None, Some(3), Some(4), None],
+      keyboard.mapping
+    );
+    assert_eq!(
+      Vec::<Option<i32>>::new(),
+      read_keyboard("0\n0\n127\n60\n69\n440.0\n0\n").unwrap().mapping
+    );
+  }
+
+  #[test]
+  fn test_invalid_files_name_the_problem() {
+    assert!(read_scale("only a description").unwrap_err().contains("number of notes"));
+    assert!(read_scale("short\n3\n100.0\n3/2\n").unwrap_err().contains("Expected 3 notes"));
+    assert!(read_scale("bad\n1\n3/0\n").unwrap_err().contains("Invalid ratio"));
+    assert!(read_scale("down\n1\n-1200.0\n").unwrap_err().contains("period"));
+    assert!(read_keyboard("12\n0\n127\n").unwrap_err().contains("middle key"));
+    assert!(read_keyboard("2\n0\n127\n60\n69\n440.0\n5\nq\n").unwrap_err().contains("Invalid degree"));
+  }
+}
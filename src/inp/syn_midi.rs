@@ -0,0 +1,305 @@
+/// Syn-midi playbooks, a lightweight MIDI alternative written as JSON.
+///
+/// A syn-midi playbook is a `PlayerTrack<Midi>`: its parts are `[arf, melody]` and each note is
+/// `[duration, key, velocity]`, with the duration in cycles, the key a MIDI note number and the velocity from 0 to 127.
+/// A note with velocity 0 is a rest.
+///
+/// Tracks are converted to a `DruidicScore` so they render through the same presets as any other playbook.
+/// Keys are spelled as just intonation `Tone`s over the pitch class nearest to `conf.root` (see `midi::key_to_tone`),
+/// and the root is moved onto that pitch class. The score is played in 12-ET, where every note sounds at
+/// `midi_to_freq` of its key.
+use crate::analysis::fit;
+use crate::analysis::xform_freq::{midi_to_freq, velocity_to_amplitude};
+use crate::inp::midi::key_to_tone;
+use crate::inp::validate::Problem;
+use crate::types::render::{Conf, DruidicScore, Melody, Midi, PlayerTrack};
+use crate::types::synthesis::{Duration, Note, Tone};
+use crate::types::timbre::{ClientPositioning, Distance, Echo, Enclosure};
+
+/// Largest denominator tried when writing a duration as a ratio of cycles
+const MAX_DENOMINATOR: i32 = 960;
+
+/// Tuning of converted tracks, so every key sounds at `midi_to_freq`
+const TUNING: &str = "12-et";
+
+/// Difference in cycles below which a ratio is taken to be the duration
+const TOLERANCE: f32 = 1e-4;
+
+/// The smallest ratio of cycles equal to `cycles`, or else the nearest step of a `MAX_DENOMINATOR` grid.
+fn to_duration(cycles: f32) -> Duration {
+  (1..=MAX_DENOMINATOR)
+    .map(|d| ((cycles * d as f32).round() as i32, d))
+    .find(|&(n, d)| (n as f32 / d as f32 - cycles).abs() < TOLERANCE)
+    .unwrap_or(((cycles * MAX_DENOMINATOR as f32).round() as i32, MAX_DENOMINATOR))
+}
+
+/// Pitch class of the equal tempered pitch nearest to `root`.
+fn nearest_tonic(root: f32) -> u8 {
+  (69 + (12f32 * (root / 440f32).log2()).round() as i32).rem_euclid(12) as u8
+}
+
+/// Check the notes of a track for values with no MIDI meaning.
+/// Paths follow the syn-midi layout, where each part is `[arf, melody]`.
+fn check_track(track: &PlayerTrack<Midi>) -> Vec<Problem> {
+  let mut problems = Vec::new();
+  if !(track.conf.root.is_finite() && track.conf.root > 0f32) {
+    problems.push(Problem::error(
+      "$.conf.root".to_string(),
+      format!("Root must be a positive frequency, got {}", track.conf.root),
+    ));
+  }
+  for (i, (_, melody)) in track.parts.iter().enumerate() {
+    for (l, line) in melody.iter().enumerate() {
+      for (n, &(duration, key, velocity)) in line.iter().enumerate() {
+        let note_path = format!("$.parts[{}][1][{}][{}]", i, l, n);
+        if !(duration.is_finite() && duration > 0f32) {
+          problems.push(Problem::error(
+            format!("{}[0]", note_path),
+            format!("Duration {} must be a positive number of cycles", duration),
+          ));
+        } else if to_duration(duration).0 == 0 {
+          problems.push(Problem::error(
+            format!("{}[0]", note_path),
+            format!("Duration {} is shorter than 1/{} cycle", duration, MAX_DENOMINATOR),
+          ));
+        }
+        if !(0..=127).contains(&key) {
+          problems.push(Problem::error(
+            format!("{}[1]", note_path),
+            format!("Key {} is not a MIDI note number from 0 to 127", key),
+          ));
+        }
+        if velocity < 0 {
+          problems.push(Problem::error(
+            format!("{}[2]", note_path),
+            format!("Velocity {} must be from 0 to 127", velocity),
+          ));
+        }
+      }
+    }
+  }
+  problems
+}
+
+/// Convert a syn-midi track to a score, or list the notes that cannot be converted.
+pub fn to_score(track: PlayerTrack<Midi>) -> Result<DruidicScore, Vec<Problem>> {
+  let problems = check_track(&track);
+  if !problems.is_empty() {
+    return Err(problems);
+  }
+  let tonic = nearest_tonic(track.conf.root);
+  let root = fit(1f32, midi_to_freq(tonic as i32));
+
+  let parts = track
+    .parts
+    .into_iter()
+    .map(|(arf, melody)| {
+      let rest_tone: Tone = (arf.register, (0, 0, 1));
+      let melody: Melody<Note> = melody
+        .iter()
+        .map(|line| {
+          line
+            .iter()
+            .map(|&(duration, key, velocity)| match velocity {
+              0 => (to_duration(duration), rest_tone, 0f32),
+              _ => (
+                to_duration(duration),
+                key_to_tone(key as u8, tonic, root),
+                velocity_to_amplitude(velocity),
+              ),
+            })
+            .collect()
+        })
+        .collect();
+      let positioning = ClientPositioning {
+        echo: Echo::None,
+        enclosure: Enclosure::Room,
+        distance: Distance::Near,
+        pan: None,
+        glide: None,
+        groove: None,
+      };
+      (positioning, arf, melody)
+    })
+    .collect();
+
+  Ok(DruidicScore {
+    conf: Conf {
+      cps: track.conf.cps,
+      root,
+    },
+    dimensions: track.dimensions,
+    parts,
+    markers: Vec::new(),
+    groupEnclosure: Enclosure::Room,
+    tempo: Vec::new(),
+    tuning: Some(TUNING.to_string()),
+  })
+}
+
+/// Where a problem found in the converted score sits in the syn-midi playbook.
+/// The positioning of each part is dropped and a note's tone is its key.
+pub fn score_path_to_track_path(path: &str) -> String {
+  let Some(rest) = path.strip_prefix("$.parts") else {
+    return path.to_string();
+  };
+  let mut indices: Vec<usize> = Vec::new();
+  let mut tail = rest;
+  while let Some((index, after)) = tail.strip_prefix('[').and_then(|t| t.split_once(']')) {
+    let Ok(index) = index.parse::<usize>() else {
+      break;
+    };
+    indices.push(index);
+    tail = after;
+  }
+  match indices.get(1) {
+    Some(1) => indices[1] = 0,
+    Some(2) => {
+      indices[1] = 1;
+      if indices.get(4) == Some(&1) {
+        indices.truncate(5);
+        tail = "";
+      }
+    }
+    _ => {}
+  }
+  let indices: String = indices.iter().map(|i| format!("[{}]", i)).collect();
+  format!("$.parts{}{}", indices, tail)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::monic_theory::tone_to_freq;
+  use crate::types::timbre::{Arf, Energy, Mode, Presence, Role, Visibility};
+
+  fn cents(a: f32, b: f32) -> f32 {
+    1200f32 * (a / b).log2().abs()
+  }
+
+  #[test]
+  fn test_x_files_converts() {
+    let track = crate::music::lib::x_files::get_track();
+    let lines: Vec<Vec<Midi>> = track.parts.iter().flat_map(|(_, melody)| melody.clone()).collect();
+    let score = to_score(track).unwrap();
+    assert!(
+      (score.conf.root - 1.3f32).abs() < 0.05,
+      "Must keep the root near the track's"
+    );
+    let converted = score.parts.iter().flat_map(|(_, _, melody)| melody.iter());
+    for (line, notes) in lines.iter().zip(converted) {
+      for (&(duration, key, velocity), &((n, d), tone, amp)) in line.iter().zip(notes) {
+        assert!((n as f32 / d as f32 - duration).abs() < TOLERANCE);
+        assert_eq!(velocity_to_amplitude(velocity), amp);
+        if velocity > 0 {
+          let freq = score.conf.root * tone_to_freq(&tone);
+          assert!(
+            cents(freq, midi_to_freq(key)) < 20f32,
+            "Key {} sounds at {}Hz",
+            key,
+            freq
+          );
+        }
+      }
+    }
+    assert_eq!(
+      (1, 3),
+      score.parts[0].2[0][0].0,
+      "Must write thirds of a cycle as ratios"
+    );
+  }
+
+  #[test]
+  fn test_keys_render_at_their_equal_tempered_pitch() {
+    let track = crate::music::lib::x_files::get_track();
+    let lines: Vec<Vec<Midi>> = track.parts.iter().flat_map(|(_, melody)| melody.clone()).collect();
+    let score = to_score(track).unwrap();
+    let intonation = crate::intonation(&score, &crate::RenderOptions::default());
+    let converted = score.parts.iter().flat_map(|(_, _, melody)| melody.iter());
+    for (line, notes) in lines.iter().zip(converted) {
+      for (&(_, key, velocity), &(_, tone, _)) in line.iter().zip(notes) {
+        let freq = score.conf.root * intonation.tone_to_freq(&tone);
+        assert!(
+          velocity == 0 || cents(freq, midi_to_freq(key)) < 0.01,
+          "Key {} sounds at {}Hz instead of {}Hz",
+          key,
+          freq,
+          midi_to_freq(key)
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_invalid_notes_are_reported() {
+    let arf = Arf {
+      mode: Mode::Melodic,
+      role: Role::Lead,
+      register: 7,
+      visibility: Visibility::Visible,
+      energy: Energy::Medium,
+      presence: Presence::Legato,
+    };
+    let track: PlayerTrack<Midi> = PlayerTrack {
+      conf: Conf {
+        cps: 1f32,
+        root: 1.2f32,
+      },
+      duration: 2f32,
+      dimensions: crate::types::render::Dimensions {
+        size: 0,
+        cpc: 2,
+        base: 2,
+      },
+      parts: vec![(arf, vec![vec![(1f32, 60, 100), (0f32, 128, -1)]])],
+    };
+    let paths: Vec<String> = to_score(track).unwrap_err().into_iter().map(|p| p.path).collect();
+    assert_eq!(
+      vec![
+        "$.parts[0][1][0][1][0]",
+        "$.parts[0][1][0][1][1]",
+        "$.parts[0][1][0][1][2]"
+      ],
+      paths
+    );
+  }
+
+  #[test]
+  fn test_render_syn_midi_playbook() {
+    let dir = "dev-audio/syn_midi";
+    std::fs::create_dir_all(dir).unwrap();
+    let mut track = crate::music::lib::x_files::get_track();
+    for (_, melody) in track.parts.iter_mut() {
+      for line in melody.iter_mut() {
+        line.truncate(6);
+      }
+    }
+    let path = format!("{}/x_files.json", dir);
+    std::fs::write(&path, serde_json::to_string(&track).unwrap()).unwrap();
+    let score = crate::inp::arg_parse::load_score_from_file(&path).unwrap();
+    assert_eq!(2, score.parts.len());
+    let options = crate::RenderOptions {
+      seed: Some(1),
+      ..Default::default()
+    };
+    let output = crate::render_score(score, crate::presets::Preset::Hop, dir, "x_files", &options).unwrap();
+    assert!(std::path::Path::new(&output).exists());
+  }
+
+  #[test]
+  fn test_score_path_to_track_path() {
+    assert_eq!(
+      "$.parts[2][0].register",
+      score_path_to_track_path("$.parts[2][1].register")
+    );
+    assert_eq!(
+      "$.parts[0][1][1][3][1]",
+      score_path_to_track_path("$.parts[0][2][1][3][1][0]")
+    );
+    assert_eq!(
+      "$.parts[0][1][1][3][0]",
+      score_path_to_track_path("$.parts[0][2][1][3][0]")
+    );
+    assert_eq!("$.conf.cps", score_path_to_track_path("$.conf.cps"));
+  }
+}
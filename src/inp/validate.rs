@@ -0,0 +1,545 @@
+/// Validation of playbooks before rendering.
+///
+/// Problems are collected into a list instead of stopping at the first one.
+/// Each problem names the JSON path of the offending value, and its line and column when the source text is known.
+/// Errors make a score unrenderable; warnings describe scores that render but probably not as intended.
+use std::fmt;
+use std::fs;
+
+use crate::analysis::tuning::{self, Tuning};
+use crate::error::RaudioError;
+use crate::synth::{MAX_REGISTER, MIN_REGISTER};
+use crate::time;
+use crate::inp::syn_midi;
+use crate::types::render::{DruidicScore, Midi, PlayerTrack};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+  Error,
+  Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+  pub severity: Severity,
+  /// JSON path of the value, like `$.parts[0][2][1][3]`
+  pub path: String,
+  /// (line, column), both starting from 1
+  pub position: Option<(usize, usize)>,
+  pub message: String,
+}
+
+impl Problem {
+  pub(crate) fn error(path: String, message: String) -> Self {
+    Problem {
+      severity: Severity::Error,
+      path,
+      position: None,
+      message,
+    }
+  }
+
+  pub(crate) fn warning(path: String, message: String) -> Self {
+    Problem {
+      severity: Severity::Warning,
+      path,
+      position: None,
+      message,
+    }
+  }
+
+  pub fn is_error(&self) -> bool {
+    self.severity == Severity::Error
+  }
+}
+
+impl fmt::Display for Problem {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let severity = match self.severity {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+    };
+    match self.position {
+      Some((line, column)) => write!(
+        f,
+        "{} at {} (line {}, column {}): {}",
+        severity, self.path, line, column, self.message
+      ),
+      None => write!(f, "{} at {}: {}", severity, self.path, self.message),
+    }
+  }
+}
+
+/// An open object or array while scanning JSON text.
+struct Frame {
+  is_object: bool,
+  key: Option<String>,
+  index: usize,
+  in_value: bool,
+}
+
+fn frames_to_path(frames: &[Frame]) -> String {
+  let mut path = String::from("$");
+  for frame in frames {
+    if frame.is_object {
+      if let Some(key) = &frame.key {
+        path.push('.');
+        path.push_str(key);
+      }
+    } else if frame.in_value {
+      path.push_str(&format!("[{}]", frame.index));
+    }
+  }
+  path
+}
+
+/// Walk the tokens of a JSON document, calling `visit` with the byte offset of each token,
+/// the containers open around it, and whether the token starts a value.
+/// Stops early when `visit` returns true. Malformed text is scanned as far as possible.
+fn scan_json<F>(json: &str, mut visit: F)
+where
+  F: FnMut(usize, &[Frame], bool) -> bool,
+{
+  let bytes = json.as_bytes();
+  let mut frames: Vec<Frame> = Vec::new();
+  let mut i = 0;
+  while i < bytes.len() {
+    let c = bytes[i];
+    if c.is_ascii_whitespace() || c == b':' {
+      i += 1;
+      continue;
+    }
+    let is_key = c == b'"' && frames.last().is_some_and(|f| f.is_object && f.key.is_none());
+    let starts_value = !is_key && !matches!(c, b',' | b'}' | b']');
+    if starts_value {
+      if let Some(top) = frames.last_mut() {
+        top.in_value = true;
+      }
+    }
+    if visit(i, &frames, starts_value) {
+      return;
+    }
+    match c {
+      b'"' => {
+        let mut end = i + 1;
+        while end < bytes.len() && bytes[end] != b'"' {
+          end += if bytes[end] == b'\\' { 2 } else { 1 };
+        }
+        if is_key {
+          if let Some(top) = frames.last_mut() {
+            top.key = Some(json[i + 1..end.min(bytes.len())].to_string());
+          }
+        }
+        i = end + 1;
+      }
+      b'{' | b'[' => {
+        frames.push(Frame {
+          is_object: c == b'{',
+          key: None,
+          index: 0,
+          in_value: false,
+        });
+        i += 1;
+      }
+      b'}' | b']' => {
+        frames.pop();
+        i += 1;
+      }
+      b',' => {
+        if let Some(top) = frames.last_mut() {
+          if top.is_object {
+            top.key = None;
+          } else {
+            top.index += 1;
+          }
+          top.in_value = false;
+        }
+        i += 1;
+      }
+      _ => {
+        // numbers, booleans and null run until the next delimiter
+        while i < bytes.len() && !matches!(bytes[i], b',' | b'}' | b']') && !bytes[i].is_ascii_whitespace() {
+          i += 1;
+        }
+      }
+    }
+  }
+}
+
+/// Byte offset of a 1-based line and column.
+fn offset_of_position(json: &str, line: usize, column: usize) -> usize {
+  let line_start: usize = json.split_inclusive('\n').take(line.saturating_sub(1)).map(|l| l.len()).sum();
+  (line_start + column.saturating_sub(1)).min(json.len())
+}
+
+/// 1-based line and column of a byte offset.
+fn position_of_offset(json: &str, offset: usize) -> (usize, usize) {
+  let before = &json[..offset];
+  let line = before.matches('\n').count() + 1;
+  let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+  (line, column)
+}
+
+/// JSON path of the value around a byte offset.
+fn path_at_offset(json: &str, offset: usize) -> String {
+  let mut path = String::from("$");
+  scan_json(json, |at, frames, _| {
+    if at > offset {
+      return true;
+    }
+    // a closing bracket belongs to the container, not its last member
+    let closes = matches!(json.as_bytes()[at], b'}' | b']');
+    let frames = if closes && !frames.is_empty() {
+      &frames[..frames.len() - 1]
+    } else {
+      frames
+    };
+    path = frames_to_path(frames);
+    false
+  });
+  path
+}
+
+/// Line and column where the value at `path` starts.
+fn position_of_path(json: &str, path: &str) -> Option<(usize, usize)> {
+  let mut found = None;
+  scan_json(json, |at, frames, starts_value| {
+    if starts_value && frames_to_path(frames) == path {
+      found = Some(position_of_offset(json, at));
+    }
+    found.is_some()
+  });
+  found
+}
+
+/// How a playbook file is written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+  /// A `DruidicScore`
+  Druidic,
+  /// A `PlayerTrack<Midi>`, see `syn_midi`
+  SynMidi,
+}
+
+impl Layout {
+  /// Path in a playbook of this layout for a path of its converted score.
+  fn path(self, path: String) -> String {
+    match self {
+      Layout::Druidic => path,
+      Layout::SynMidi => syn_midi::score_path_to_track_path(&path),
+    }
+  }
+}
+
+/// Describe a serde error with its path, line and column.
+fn json_problem(json: &str, e: serde_json::Error) -> Problem {
+  let message = e.to_string();
+  // the position is reported separately
+  let message = match message.rsplit_once(" at line ") {
+    Some((message, _)) => message.to_string(),
+    None => message,
+  };
+  let path = path_at_offset(json, offset_of_position(json, e.line(), e.column()));
+  Problem {
+    position: Some((e.line(), e.column())),
+    ..Problem::error(path, message)
+  }
+}
+
+/// Parse either layout of playbook. Syn-midi playbooks are told apart by their top level `duration`.
+fn read_playbook(json: &str) -> Result<(DruidicScore, Layout), Vec<Problem>> {
+  let druidic_error = match serde_json::from_str::<DruidicScore>(json) {
+    Ok(score) => return Ok((score, Layout::Druidic)),
+    Err(e) => e,
+  };
+  let is_syn_midi =
+    serde_json::from_str::<serde_json::Value>(json).is_ok_and(|value| value.get("duration").is_some());
+  if !is_syn_midi {
+    return Err(vec![json_problem(json, druidic_error)]);
+  }
+  let track = serde_json::from_str::<PlayerTrack<Midi>>(json).map_err(|e| vec![json_problem(json, e)])?;
+  let score = syn_midi::to_score(track).map_err(|problems| with_positions(json, problems))?;
+  Ok((score, Layout::SynMidi))
+}
+
+/// Locate each problem in the playbook text.
+fn with_positions(json: &str, problems: Vec<Problem>) -> Vec<Problem> {
+  problems
+    .into_iter()
+    .map(|problem| Problem {
+      position: position_of_path(json, &problem.path),
+      ..problem
+    })
+    .collect()
+}
+
+/// Parse playbook JSON, describing a syntax or type error with its path, line and column.
+/// Syn-midi playbooks are converted to a score; of their problems, only the first is returned.
+pub fn parse_playbook(json: &str) -> Result<DruidicScore, Problem> {
+  read_playbook(json).map(|(score, _)| score).map_err(|mut problems| problems.remove(0))
+}
+
+/// Check a score for values that cannot be rendered or are likely mistakes.
+/// Paths follow the playbook layout, where each part is `[positioning, arf, melody]`.
+pub fn validate_score(score: &DruidicScore) -> Vec<Problem> {
+  let mut problems = Vec::new();
+  let registers = MIN_REGISTER..MAX_REGISTER;
+
+  if !(score.conf.cps.is_finite() && score.conf.cps > 0f32) {
+    problems.push(Problem::error(
+      "$.conf.cps".to_string(),
+      format!("Cycles per second must be positive, got {}", score.conf.cps),
+    ));
+  }
+  if !(score.conf.root.is_finite() && score.conf.root > 0f32) {
+    problems.push(Problem::error(
+      "$.conf.root".to_string(),
+      format!("Root must be a positive frequency, got {}", score.conf.root),
+    ));
+  }
+  let mut previous = 0f32;
+  for (k, point) in score.tempo.iter().enumerate() {
+    if !(point.cycle.is_finite() && point.cycle > previous) {
+      problems.push(Problem::error(
+        format!("$.tempo[{}].cycle", k),
+        format!(
+          "Tempo changes must come after cycle {}, got {}",
+          previous, point.cycle
+        ),
+      ));
+    } else {
+      previous = point.cycle;
+    }
+    if !(point.cps.is_finite() && point.cps > 0f32) {
+      problems.push(Problem::error(
+        format!("$.tempo[{}].cps", k),
+        format!("Cycles per second must be positive, got {}", point.cps),
+      ));
+    }
+  }
+  if let Some(name) = score.tuning.as_ref().filter(|name| Tuning::from_name(name).is_none()) {
+    problems.push(Problem::error(
+      "$.tuning".to_string(),
+      format!("Unknown tuning '{}'. Choose one of: {}", name, tuning::NAMES.join(", ")),
+    ));
+  }
+  let dims = &score.dimensions;
+  if dims.cpc <= 0 || dims.base <= 0 || dims.size < 0 {
+    problems.push(Problem::error(
+      "$.dimensions".to_string(),
+      format!(
+        "Dimensions must be positive, got size {} cpc {} base {}",
+        dims.size, dims.cpc, dims.base
+      ),
+    ));
+  }
+  if score.parts.is_empty() {
+    problems.push(Problem::error("$.parts".to_string(), "Score has no parts".to_string()));
+  }
+  let expected_cycles = crate::dimensions_to_cycles(dims);
+
+  for (i, (positioning, arf, melody)) in score.parts.iter().enumerate() {
+    if let Some(Err(reason)) = positioning.glide.as_ref().map(crate::render::glide::check) {
+      problems.push(Problem::error(format!("$.parts[{}][0].glide", i), reason));
+    }
+    if let Some(Err(reason)) = positioning.groove.as_ref().map(crate::render::groove::check) {
+      problems.push(Problem::error(format!("$.parts[{}][0].groove", i), reason));
+    }
+    if !registers.contains(&(arf.register as i32)) {
+      problems.push(Problem::warning(
+        format!("$.parts[{}][1].register", i),
+        format!(
+          "Register {} is outside the renderable range {}..{}",
+          arf.register, MIN_REGISTER, MAX_REGISTER
+        ),
+      ));
+    }
+    if melody.is_empty() {
+      problems.push(Problem::error(
+        format!("$.parts[{}][2]", i),
+        "Melody has no lines".to_string(),
+      ));
+    }
+    for (l, line) in melody.iter().enumerate() {
+      let line_path = format!("$.parts[{}][2][{}]", i, l);
+      if line.is_empty() {
+        problems.push(Problem::error(line_path.clone(), "Line has no notes".to_string()));
+        continue;
+      }
+      let mut line_is_timed = true;
+      for (n, ((numerator, denominator), (register, (_, _, monic)), _)) in line.iter().enumerate() {
+        let note_path = format!("{}[{}]", line_path, n);
+        if *denominator == 0 {
+          line_is_timed = false;
+          problems.push(Problem::error(
+            format!("{}[0]", note_path),
+            format!("Duration ({}, {}) has a zero denominator", numerator, denominator),
+          ));
+        } else if *numerator as f32 / *denominator as f32 <= 0f32 {
+          line_is_timed = false;
+          problems.push(Problem::error(
+            format!("{}[0]", note_path),
+            format!("Duration ({}, {}) must be positive", numerator, denominator),
+          ));
+        }
+        if !registers.contains(&(*register as i32)) {
+          problems.push(Problem::warning(
+            format!("{}[1][0]", note_path),
+            format!(
+              "Register {} is outside the renderable range {}..{}",
+              register, MIN_REGISTER, MAX_REGISTER
+            ),
+          ));
+        }
+        if *monic < 1 {
+          problems.push(Problem::error(
+            format!("{}[1][1][2]", note_path),
+            format!("Monic {} must be 1 or more", monic),
+          ));
+        }
+      }
+      if line_is_timed {
+        let cycles = time::count_cycles(line);
+        if (cycles - expected_cycles).abs() > 1e-3 {
+          problems.push(Problem::warning(
+            line_path,
+            format!(
+              "Line lasts {} cycles but the dimensions describe {} cycles",
+              cycles, expected_cycles
+            ),
+          ));
+        }
+      }
+    }
+  }
+  problems
+}
+
+/// Parse and validate playbook JSON. Every problem carries its line and column.
+pub fn validate_playbook(json: &str) -> Vec<Problem> {
+  match read_playbook(json) {
+    Err(problems) => problems,
+    Ok((score, layout)) => with_positions(json, locate(layout, validate_score(&score))),
+  }
+}
+
+/// Move the problems of a converted score to their paths in the playbook.
+fn locate(layout: Layout, problems: Vec<Problem>) -> Vec<Problem> {
+  problems
+    .into_iter()
+    .map(|problem| Problem {
+      path: layout.path(problem.path),
+      ..problem
+    })
+    .collect()
+}
+
+/// Read a playbook, returning the score when it has no errors.
+/// Warnings do not prevent loading; use `validate_playbook` to see them.
+pub fn load_playbook(filepath: &str) -> Result<DruidicScore, RaudioError> {
+  let json = fs::read_to_string(filepath).map_err(|e| RaudioError::Io(filepath.to_string(), e))?;
+  let (score, layout) = read_playbook(&json).map_err(RaudioError::InvalidScore)?;
+  let errors: Vec<Problem> = validate_score(&score).into_iter().filter(Problem::is_error).collect();
+  if errors.is_empty() {
+    Ok(score)
+  } else {
+    Err(RaudioError::InvalidScore(with_positions(&json, locate(layout, errors))))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn playbook() -> String {
+    fs::read_to_string("src/demo/playbooks/house_short_2.json").unwrap()
+  }
+
+  #[test]
+  fn test_demo_playbooks_have_no_errors() {
+    for path in [
+      "src/demo/playbooks/house_short_1.json",
+      "src/demo/playbooks/house_short_2.json",
+      "src/demo/playbooks/playbook.json",
+      "src/demo/test-druidic-score.json",
+    ] {
+      let errors: Vec<Problem> =
+        validate_playbook(&fs::read_to_string(path).unwrap()).into_iter().filter(Problem::is_error).collect();
+      assert!(errors.is_empty(), "{} must be renderable: {:?}", path, errors);
+    }
+  }
+
+  #[test]
+  fn test_parse_error_has_path_and_position() {
+    let json = "{\n  \"conf\": {\"cps\": \"fast\", \"root\": 1.0}\n}";
+    let problem = parse_playbook(json).unwrap_err();
+    assert_eq!("$.conf.cps", problem.path);
+    assert_eq!(Some(2), problem.position.map(|(line, _)| line));
+    assert!(!problem.message.contains("at line"), "Position must not repeat in the message");
+
+    let problem = parse_playbook("{\"conf\": {\"cps\": 1.0}}").unwrap_err();
+    assert_eq!("$.conf", problem.path, "A missing field belongs to its object");
+  }
+
+  #[test]
+  fn test_semantic_problems() {
+    let mut json: serde_json::Value = serde_json::from_str(&playbook()).unwrap();
+    json["parts"][0][2][0][0][0] = serde_json::json!([1, 0]);
+    json["parts"][0][2][1][2][1][1][2] = serde_json::json!(0);
+    json["parts"][1][1]["register"] = serde_json::json!(20);
+    json["parts"][2][2] = serde_json::json!([]);
+    json["parts"][1][0]["glide"] = serde_json::json!({"from": {"cycles": 0}});
+    json["parts"][2][0]["groove"] = serde_json::json!({"grid": "eighth", "swing": 120});
+    json["tempo"] = serde_json::json!([{"cycle": 8, "cps": 2}, {"cycle": 4, "cps": 0, "curve": "linear"}]);
+    json["tuning"] = serde_json::json!("pythagorean");
+    let text = serde_json::to_string_pretty(&json).unwrap();
+    let problems = validate_playbook(&text);
+
+    let find = |path: &str| problems.iter().find(|p| p.path == path);
+    let zero = find("$.parts[0][2][0][0][0]").expect("Must report a zero denominator");
+    assert!(zero.is_error());
+    let (line, _) = zero.position.expect("Must locate semantic problems in the text");
+    assert!(text.lines().nth(line - 1).unwrap().contains('['));
+    assert!(
+      find("$.parts[0][2][1][2][1][1][2]").is_some_and(Problem::is_error),
+      "Must report a monic below 1"
+    );
+    assert!(find("$.parts[1][1].register").is_some(), "Must report an out of range register");
+    assert!(find("$.parts[2][2]").is_some_and(Problem::is_error), "Must report an empty melody");
+    assert!(find("$.parts[1][0].glide").is_some_and(Problem::is_error), "Must report an empty glide");
+    assert!(find("$.parts[2][0].groove").is_some_and(Problem::is_error), "Must report a swing past the next step");
+    assert!(find("$.tempo[0].cycle").is_none());
+    assert!(
+      find("$.tempo[1].cycle").is_some_and(Problem::is_error),
+      "Must report a tempo change out of order"
+    );
+    assert!(find("$.tempo[1].cps").is_some_and(Problem::is_error), "Must report a tempo of 0");
+    assert!(find("$.tuning").is_some_and(Problem::is_error), "Must report an unknown tuning");
+  }
+
+  #[test]
+  fn test_syn_midi_problems_are_located() {
+    let mut json = serde_json::to_value(crate::music::lib::x_files::get_track()).unwrap();
+    json["parts"][1][1][0][2][1] = serde_json::json!(200);
+    json["parts"][0][0]["register"] = serde_json::json!(20);
+    let text = serde_json::to_string_pretty(&json).unwrap();
+    let problems = validate_playbook(&text);
+    let key = problems.iter().find(|p| p.path == "$.parts[1][1][0][2][1]").expect("Must report a bad key");
+    assert!(key.is_error() && key.position.is_some());
+
+    json["parts"][1][1][0][2][1] = serde_json::json!(60);
+    let problems = validate_playbook(&serde_json::to_string_pretty(&json).unwrap());
+    let register = problems.iter().find(|p| p.path == "$.parts[0][0].register");
+    assert!(register.is_some_and(|p| p.position.is_some()), "Must report the arf register at its track path");
+    assert!(problems.iter().all(|p| !p.is_error()), "{:?}", problems);
+  }
+
+  #[test]
+  fn test_line_length_disagrees_with_dimensions() {
+    let mut score = parse_playbook(&playbook()).unwrap();
+    score.dimensions.size += 1;
+    let problems = validate_score(&score);
+    assert!(!problems.is_empty());
+    assert!(problems.iter().all(|p| !p.is_error() && p.message.contains("cycles")));
+  }
+}
@@ -14,15 +14,20 @@ use crate::types::synthesis;
 use crate::types::synthesis::*;
 use crate::types::timbre;
 use crate::types::timbre::*;
-use rand::{self, rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
+use rand::{self, seq::SliceRandom, Rng};
+use crate::seed::{ThreadRng, thread_rng};
 use reverb::convolution::ReverbParams;
 use std::env;
 use std::process;
 
 mod analysis;
 pub use analysis::monic_theory;
+mod audition;
+mod batch;
+mod cli;
 mod demo;
 mod druid;
+mod error;
 mod fastmast;
 mod files;
 mod fm;
@@ -32,46 +37,28 @@ mod phrasing;
 mod presets;
 mod render;
 mod reverb;
+mod seed;
+mod serve;
 mod synth;
 pub use analysis::time;
 mod types;
 
+use error::RaudioError;
 use presets::Preset;
+use render::progress::{Event, Progress, Stage};
 
 fn main() {
-  let args: Vec<String> = env::args().collect();
-
-  if args.len() < 3 {
-    eprintln!(
-      r#"Usage:
-  raudio playbook.json preset-name asset-dir asset-name
-  raudio apply-danceability input.wav output.wav"#
-    );
-    process::exit(1);
-  }
-
-  match args[1].as_str() {
-    "apply-danceability" => {
-      if args.len() != 4 {
-        eprintln!(r#"Usage: raudio apply-danceability input.wav output.wav"#);
-        process::exit(1);
-      }
-      let input_path = &args[2];
-      let output_path = &args[3];
-      fastmast::apply_danceability(input_path, output_path);
-    }
-    _ => {
-      // Default behavior: render_playbook
-      if args.len() < 5 {
-        eprintln!(r#"Usage: raudio playbook.json preset-name asset-dir asset-name"#);
-        process::exit(1);
-      }
-      let file_path = &args[1];
-      let preset_pack = &args[2];
-      let out_dir = &args[3];
-      let mixdown_name = &args[4];
-      render_playbook(out_dir, preset_pack, file_path, mixdown_name);
+  let args: Vec<String> = env::args().skip(1).collect();
+  let command = match cli::parse(&args) {
+    Ok(command) => command,
+    Err(msg) => {
+      eprintln!("{}\n\n{}", msg, cli::USAGE);
+      process::exit(cli::EXIT_USAGE);
     }
+  };
+  if let Err(msg) = cli::run(command) {
+    eprintln!("{}", msg);
+    process::exit(cli::EXIT_FAILURE);
   }
 }
 
@@ -119,16 +106,7 @@ fn main() {
 // }
 
 fn parse_preset(s: &str) -> Option<Preset> {
-  let src = s.to_lowercase();
-  match src.as_str() {
-    "hop" => Some(Preset::Hop),
-    "valley" => Some(Preset::Valley),
-    "mountain" => Some(Preset::Mountain),
-    "bland" => Some(Preset::Bland),
-    "bright" => Some(Preset::Bright),
-    "fum" => Some(Preset::Fum),
-    _ => None,
-  }
+  Preset::from_name(s)
 }
 
 #[cfg(test)]
@@ -142,28 +120,21 @@ mod test_parse {
   }
 }
 
-fn render_playbook(out_dir: &str, preset_pack: &str, playbook_path: &str, asset_name: &str) {
-  use std::fs;
-  use std::path::Path;
-  let keep_stems = true;
-
-  let preset = parse_preset(preset_pack);
-  if preset.is_none() {
-    panic!(
-      "Must provide a valid preset pack. here are the options: hop, valley, mountain, bland, bright. You gave me '{}'.",
-      preset_pack
-    )
-  }
+fn render_playbook(
+  out_dir: &str, preset_pack: &str, playbook_path: &str, asset_name: &str, seed: Option<u64>,
+) -> Result<(), RaudioError> {
+  let options = RenderOptions {
+    keep_stems: true,
+    seed,
+    ..Default::default()
+  };
 
-  match inp::arg_parse::load_score_from_file(&playbook_path) {
-    Ok(score) => {
-      let out_path = render_score(score, preset.unwrap(), out_dir, asset_name, keep_stems);
-      println!("{}", out_path)
-    }
-    Err(msg) => {
-      panic!("Failed to open score: {}", msg)
-    }
-  }
+  let preset = parse_preset(preset_pack).ok_or_else(|| RaudioError::UnknownPreset(preset_pack.to_string()))?;
+
+  let score = inp::arg_parse::load_score_from_file(playbook_path)?;
+  let out_path = render_score(score, preset, out_dir, asset_name, &options)?;
+  println!("{}", out_path);
+  Ok(())
 }
 
 pub fn complexity(v: &Visibility, e: &Energy, p: &Presence) -> f32 {
@@ -193,9 +164,10 @@ fn dimensions_to_cycles(dims: &Dimensions) -> f32 {
   ((dims.base as i32).pow(dims.size as u32) * dims.cpc as i32) as f32
 }
 
+/// Seconds the dimensions of a score last, following the tempo map of the current thread.
 fn score_duration_seconds(score: &DruidicScore) -> f32 {
   let len_cycles: f32 = dimensions_to_cycles(&score.dimensions);
-  len_cycles / score.conf.cps
+  time::seconds_at(score.conf.cps, len_cycles)
 }
 
 // /// Given a melody, Labelled Arfs, and a preset to splay,
@@ -216,15 +188,97 @@ fn score_duration_seconds(score: &DruidicScore) -> f32 {
 //     render::combiner_with_reso(&Conf {cps, root}, &stems, &group_reverbs, keep_stems);
 // }
 
-pub fn render_score(score: DruidicScore, preset: Preset, out_dir: &str, asset_name: &str, keep_stems: bool) -> String {
+/// Settings for `render_score` that do not change the score itself.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+  /// Also write each part and a manifest describing them into the output directory. See `render::stems`.
+  pub keep_stems: bool,
+  /// When provided, the same score, preset and seed always produce the same samples
+  pub seed: Option<u64>,
+  /// (start, end) in cycles. Only this part of the score is written. See `render::window`.
+  /// Only the notes heard in the window are synthesized, and only when `seed` and `cache_dir` are set and the cache
+  /// holds the levels of a render of this unchanged score. Otherwise, as on the first render after any edit, every
+  /// note is rendered (reusing the cached parts that did not change) and the window is trimmed from the result,
+  /// whose levels are then kept for the next window.
+  pub window: Option<(f32, f32)>,
+  /// Sample encoding of the mixdown and stems
+  pub wav_format: render::engrave::WavFormat,
+  /// Samples per second to render at. Defaults to `synth::DEFAULT_SR`.
+  pub sample_rate: Option<usize>,
+  /// Also write the score markers to a sidecar file. They are always written to the mixdown as cue points.
+  pub marker_sidecar: Option<render::markers::SidecarFormat>,
+  /// Render in blocks with bounded memory. See `render::stream`.
+  pub streaming: bool,
+  /// Directory of rendered parts to reuse between seeded renders. See `render::cache`.
+  pub cache_dir: Option<String>,
+  /// Where to write progress and timing events as JSON lines. See `render::progress`.
+  pub progress: Option<render::progress::ProgressSink>,
+  /// Frequencies of the tones of the score. Defaults to just intonation, or the tuning the score names.
+  /// See `analysis::tuning`.
+  pub tuning: analysis::tuning::Tuning,
+  /// The ratio each rotation of a tone moves by. Defaults to 3/2. See `monic_theory::Lattice`.
+  pub lattice: analysis::monic_theory::Lattice,
+}
+
+/// The lattice and tuning `render_score` plays the tones of `score` in.
+/// A score naming its tuning is played in it unless the options ask for a tuning other than just intonation.
+pub(crate) fn intonation(score: &DruidicScore, options: &RenderOptions) -> analysis::tuning::Intonation {
+  let tuning = match (&options.tuning, score.tuning.as_deref().and_then(analysis::tuning::Tuning::from_name)) {
+    (analysis::tuning::Tuning::Just, Some(tuning)) => tuning,
+    _ => options.tuning.clone(),
+  };
+  analysis::tuning::Intonation {
+    lattice: options.lattice,
+    tuning,
+  }
+}
+
+/// Render a score to a stereo `out_dir/asset_name.wav` and return the path.
+/// Fails without writing anything when the score has errors. See `inp::validate::validate_score`.
+pub fn render_score(
+  score: DruidicScore, preset: Preset, out_dir: &str, asset_name: &str, options: &RenderOptions,
+) -> Result<String, RaudioError> {
+  let errors: Vec<_> = inp::validate::validate_score(&score).into_iter().filter(|p| p.is_error()).collect();
+  if !errors.is_empty() {
+    return Err(RaudioError::InvalidScore(errors));
+  }
+  let intonation = intonation(&score, options);
+  let progress = options.progress.as_ref().map(Progress::create).transpose()?;
+  // the settings of this render apply to the current thread until it returns
+  let _context = render::context::RenderContext {
+    seed: options.seed,
+    sample_rate: options.sample_rate.unwrap_or(synth::DEFAULT_SR),
+    intonation: std::sync::Arc::new(intonation.clone()),
+    tempo: analysis::tempo::TempoMap::of_score(score.conf.cps, &score.tempo).map(std::sync::Arc::new),
+    progress: progress.clone(),
+    ..Default::default()
+  }
+  .enter();
+  render::progress::emit(|| Event::RenderStart {
+    parts: score.parts.len(),
+    notes: score.parts.iter().flat_map(|(_, _, melody)| melody.iter()).map(Vec::len).sum(),
+    sample_rate: SR(),
+  });
+  let stage_finish = |stage: Stage, duration: std::time::Duration| {
+    render::progress::emit(|| Event::StageFinish {
+      stage,
+      seconds: duration.as_secs_f64(),
+    })
+  };
+  let seed = options.seed;
+  let keep_stems = options.keep_stems;
   let mixdown_name = format!("{}/{}.wav", out_dir, asset_name);
-  files::with_dir(&mixdown_name);
+  files::try_with_dir(&mixdown_name).map_err(|e| RaudioError::Io(mixdown_name.clone(), e))?;
   let mut pre_mix_buffs: Vec<synth::SampleBuffer> = Vec::new();
-  let mut rng: ThreadRng = rand::thread_rng();
   let mut stems: Vec<(Arf, Renderable2)> = Vec::with_capacity(score.parts.len());
   let mut stem_reverbs: Vec<ReverbParams> = Vec::with_capacity(score.parts.len());
+  let mut pans: Vec<f32> = Vec::with_capacity(score.parts.len());
+  let mut playing: Vec<render::Playing> = Vec::with_capacity(score.parts.len());
 
-  for (client_positioning, arf, melody) in &score.parts {
+  for (j, (client_positioning, arf, melody)) in score.parts.iter().enumerate() {
+    // each part draws from its own stream so editing one part leaves the others unchanged
+    seed::reseed_for(seed, &[j as u64]);
+    let mut rng: ThreadRng = crate::seed::thread_rng();
     let delays = inp::arg_xform::gen_delays(
       &mut rng,
       score.conf.cps,
@@ -232,7 +286,7 @@ pub fn render_score(score: DruidicScore, preset: Preset, out_dir: &str, asset_na
       complexity(&arf.visibility, &arf.energy, &arf.presence),
     );
     let len_cycles = time::count_cycles(&melody[0]);
-    let len_seconds = len_cycles / score.conf.cps;
+    let len_seconds = time::seconds_at(score.conf.cps, len_cycles);
     let convolution_layer = inp::arg_xform::gen_convolution_stem(
       &mut rng,
       arf,
@@ -241,11 +295,18 @@ pub fn render_score(score: DruidicScore, preset: Preset, out_dir: &str, asset_na
       &client_positioning.distance,
       &client_positioning.enclosure,
     );
-    let stem = Preset::create_stem(&score.conf, melody, arf, preset);
+    let stem = Preset::create_stem(&score.conf, melody, arf, preset)?;
+    pans.push(inp::arg_xform::gen_pan(&mut rng, arf, client_positioning));
+    playing.push(render::Playing {
+      glide: client_positioning.glide.or(preset.glide(arf.role)),
+      groove: client_positioning.groove.clone().or(preset.groove(arf.role)),
+    });
     stem_reverbs.push(convolution_layer);
     stems.push((arf.clone(), stem))
   }
 
+  seed::set_seed(seed);
+  let mut rng: ThreadRng = crate::seed::thread_rng();
   let len_seconds: f32 = score_duration_seconds(&score);
 
   // a single small convolution layer varying only by groupEnclosure
@@ -257,11 +318,185 @@ pub fn render_score(score: DruidicScore, preset: Preset, out_dir: &str, asset_na
     &score.groupEnclosure,
     0f32,
   )];
-  let keeps = if keep_stems { Some(out_dir) } else { None };
-  let keeps = None;
-  let signal = render::combiner_with_reso2(&score.conf, &stems, &stem_reverbs, &group_reverb, keeps);
-  render::engrave::samples(crate::synth::SR, &signal, &mixdown_name);
-  mixdown_name
+  // notes sounding into the window through the stem and group reverbs are rendered too
+  let stem_tail = stem_reverbs.iter().map(|r| r.dur).fold(0f32, f32::max);
+  let lead_seconds = stem_tail + render::window::tail_seconds(&[], &group_reverb);
+  let part_key = |j: usize, seed: u64, audible: Option<(f32, f32)>| {
+    render::cache::part_key(&score, j, preset, seed, SR(), &intonation, audible)
+  };
+  // a window replays the levels of the full render from the cache, so it matches the same region of it.
+  // Without them every note is rendered and the window is trimmed from the full render. See `render::levels`
+  let levels_path = match (&options.cache_dir, seed) {
+    (Some(dir), Some(seed)) => {
+      let full: Vec<String> = (0..score.parts.len()).map(|j| part_key(j, seed, None)).collect();
+      Some(render::cache::levels_path(dir, &render::cache::render_key(&full)))
+    }
+    _ => None,
+  };
+  let replayed = options.window.and(levels_path.as_deref()).and_then(render::cache::load_levels);
+  let audible = match replayed {
+    Some(_) => options.window.map(|(start, end)| (time::cycles_before(score.conf.cps, start, lead_seconds), end)),
+    None => None,
+  };
+  let recorded = std::sync::Arc::new(std::sync::Mutex::new(render::levels::Levels::default()));
+  let metering = match replayed {
+    Some(levels) => render::levels::Metering::Replay(std::sync::Arc::new(levels)),
+    None => render::levels::Metering::Record(recorded.clone()),
+  };
+  let recording = audible.is_none();
+  render::window::set_audible(audible);
+  render::levels::set_current(Some(metering), vec![]);
+  // parts of seeded renders are reused from the cache when unchanged, with the levels they were rendered at
+  let cache_keys: Vec<Option<(&String, String)>> = (0..score.parts.len())
+    .map(|j| match (&options.cache_dir, seed) {
+      (Some(dir), Some(seed)) => Some((dir, part_key(j, seed, audible))),
+      _ => None,
+    })
+    .collect();
+  let cache_paths: Vec<Option<String>> =
+    cache_keys.iter().map(|key| key.as_ref().map(|(dir, key)| render::cache::part_path(dir, key))).collect();
+  let part_levels: Vec<Option<render::levels::Levels>> = cache_keys
+    .iter()
+    .map(|key| match key {
+      Some((dir, key)) if recording => render::cache::load_levels(&render::cache::levels_path(dir, key)),
+      _ => None,
+    })
+    .collect();
+  let reusable = |j: usize| !recording || part_levels[j].is_some();
+  // keep the levels of each part with it, or take those of a cached part
+  let record_part = |j: usize, cached: bool| -> Result<(), RaudioError> {
+    let (Some((dir, key)), Ok(mut levels), true) = (&cache_keys[j], recorded.lock(), recording) else {
+      return Ok(());
+    };
+    match &part_levels[j] {
+      Some(part) if cached => levels.extend(part.clone()),
+      _ => render::cache::store_levels(&render::cache::levels_path(dir, key), &levels.of_part(j))?,
+    }
+    Ok(())
+  };
+  let part_cached = |j: usize| render::progress::emit(|| Event::PartCached { part: j });
+  let (n_samples, writing) = if options.streaming {
+    let cached: Vec<Option<render::stream::Spill>> = cache_paths
+      .iter()
+      .enumerate()
+      .map(|(j, path)| path.as_deref().filter(|_| reusable(j)).and_then(render::cache::load_spill))
+      .collect();
+    let skip: Vec<bool> = cached.iter().map(Option::is_some).collect();
+    skip.iter().enumerate().filter(|(_, &hit)| hit).for_each(|(j, _)| part_cached(j));
+    let (parts, synthesis) =
+      time::measure(|| render::stream::render_parts(&score.conf, &stems, &pans, &playing, &stem_reverbs, &skip));
+    render::window::set_audible(None);
+    stage_finish(Stage::Synthesis, synthesis);
+    let mut parts = parts?;
+    for (j, part) in cached.into_iter().enumerate() {
+      record_part(j, part.is_some())?;
+      match (part, &cache_paths[j]) {
+        (Some(part), _) => parts[j] = part,
+        (None, Some(path)) => render::cache::store_spill(path, &parts[j])?,
+        (None, None) => {}
+      }
+    }
+    let cps = score.conf.cps;
+    let range = options.window.map(|(start, end)| (time::samples_at(cps, start), time::samples_at(cps, end)));
+    let (mixed, group_reverb_time) = time::measure(|| render::stream::mix_parts(&parts, &group_reverb));
+    let (mixdown, mix_gain) = mixed?;
+    stage_finish(Stage::GroupReverb, group_reverb_time);
+    let (stems_written, stem_writing) = time::measure(|| match keep_stems {
+      true => render::stems::write_stem_spills(
+        &score,
+        parts,
+        mix_gain,
+        range,
+        SR(),
+        options.wav_format,
+        out_dir,
+        asset_name,
+      ),
+      false => Ok(String::new()),
+    });
+    stems_written?;
+    let (written, mixdown_writing) =
+      time::measure(|| render::stream::write(&mixdown, range, SR(), &mixdown_name, options.wav_format));
+    (written?.frames, stem_writing + mixdown_writing)
+  } else {
+    let cached: Vec<Option<synth::StereoBuffer>> = cache_paths
+      .iter()
+      .enumerate()
+      .map(|(j, path)| path.as_deref().filter(|_| reusable(j)).and_then(render::cache::load))
+      .collect();
+    let skip: Vec<bool> = cached.iter().map(Option::is_some).collect();
+    skip.iter().enumerate().filter(|(_, &hit)| hit).for_each(|(j, _)| part_cached(j));
+    let (parts, synthesis) =
+      time::measure(|| render::render_parts_stereo(&score.conf, &stems, &pans, &playing, &stem_reverbs, &skip, None));
+    render::window::set_audible(None);
+    stage_finish(Stage::Synthesis, synthesis);
+    let mut parts = parts?;
+    for (j, part) in cached.into_iter().enumerate() {
+      record_part(j, part.is_some())?;
+      match (part, &cache_paths[j]) {
+        (Some(part), _) => parts[j] = part,
+        (None, Some(path)) => render::cache::store(path, &parts[j])?,
+        (None, None) => {}
+      }
+    }
+    let (stems_written, stem_writing) = time::measure(|| -> Result<(), RaudioError> {
+      if keep_stems {
+        let mix_gain = render::stereo::mix_gain(&parts, render::levels::Stage::Parts);
+        // stems line up with the mixdown
+        let write = |parts: &[synth::StereoBuffer]| {
+          render::stems::write_stems(&score, parts, mix_gain, SR(), options.wav_format, out_dir, asset_name)
+        };
+        if let Some(window) = options.window {
+          let mut kept = parts.clone();
+          kept.iter_mut().for_each(|part| render::window::trim(score.conf.cps, window, part));
+          write(&kept)?;
+        } else {
+          write(&parts)?;
+        }
+      }
+      Ok(())
+    });
+    stems_written?;
+    let (mixdown, group_reverb_time) = time::measure(|| render::mix_parts_stereo(parts, &group_reverb));
+    let mut mixdown = mixdown?;
+    stage_finish(Stage::GroupReverb, group_reverb_time);
+    if let Some(window) = options.window {
+      render::window::trim(score.conf.cps, window, &mut mixdown);
+    }
+    let (left, right) = mixdown;
+    let n_samples = left.len().min(right.len());
+    let (written, mixdown_writing) = time::measure(|| {
+      render::engrave::try_write_audio_as(crate::synth::SR(), vec![left, right], &mixdown_name, options.wav_format)
+    });
+    written?;
+    (n_samples, stem_writing + mixdown_writing)
+  };
+  if let (Some(path), Ok(levels), true) = (&levels_path, recorded.lock(), recording) {
+    render::cache::store_levels(path, &levels)?;
+  }
+
+  let (markers_written, marker_writing) = time::measure(|| -> Result<(), RaudioError> {
+    let offset_cycles = options.window.map_or(0f32, |(start, _)| start);
+    let cues = render::markers::cues(&score.markers, score.conf.cps, offset_cycles, n_samples);
+    render::markers::write_cues(&mixdown_name, &cues)?;
+    if let Some(format) = options.marker_sidecar {
+      let path = render::markers::sidecar_path(out_dir, asset_name, format);
+      render::markers::write_sidecar(&path, &cues, format)?;
+    }
+    Ok(())
+  });
+  markers_written?;
+  stage_finish(Stage::Write, writing + marker_writing);
+  if let Some(progress) = &progress {
+    let seconds = progress.elapsed().as_secs_f64();
+    let audio_seconds = n_samples as f64 / SR() as f64;
+    progress.emit(Event::RenderFinish {
+      seconds,
+      audio_seconds,
+      realtime_factor: if audio_seconds > 0f64 { seconds / audio_seconds } else { 0f64 },
+    });
+  }
+  Ok(mixdown_name)
 }
 
 #[test]
@@ -272,5 +507,314 @@ fn test_render_playbook() {
     "fum",
     "src/demo/playbooks/house_short_1.json",
     "house_short_1_fum",
+    None,
   )
+  .unwrap()
+}
+
+/// Number of parts in a render cache, which also keeps their levels. See `render::cache`.
+#[cfg(test)]
+fn cached_parts(cache_dir: &str) -> usize {
+  let paths = std::fs::read_dir(cache_dir).unwrap().map(|entry| entry.unwrap().path());
+  paths.filter(|path| path.extension() == Some("wav".as_ref())).count()
+}
+
+/// A demo playbook cut to the first few cycles of each line so tests render quickly.
+#[cfg(test)]
+fn short_score() -> DruidicScore {
+  let mut score = inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+  for (_, _, melody) in score.parts.iter_mut() {
+    for line in melody.iter_mut() {
+      let mut onset = 0f32;
+      line.retain(|note| {
+        let keep = onset < 4f32;
+        onset += time::note_to_cycles(note);
+        keep
+      });
+    }
+  }
+  score.dimensions.size = 1;
+  score
+}
+
+#[test]
+fn test_render_score_seeded_is_reproducible() {
+  let out_dir: &str = "dev-audio/render_score_seeded";
+  let seeded = |seed: u64| RenderOptions {
+    seed: Some(seed),
+    ..Default::default()
+  };
+  let a = render_score(short_score(), Preset::Fum, out_dir, "seed-a", &seeded(1234)).unwrap();
+  let b = render_score(short_score(), Preset::Fum, out_dir, "seed-b", &seeded(1234)).unwrap();
+  let c = render_score(short_score(), Preset::Fum, out_dir, "seed-c", &seeded(4321)).unwrap();
+  let read = |path: &str| std::fs::read(path).unwrap();
+  assert_eq!(read(&a), read(&b), "Must render identical files for the same seed");
+  assert_ne!(read(&a), read(&c), "Must render a different variant for a different seed");
+  assert_eq!(2, hound::WavReader::open(&a).unwrap().spec().channels, "Must write a stereo mixdown");
+}
+
+#[test]
+fn test_render_score_in_other_tunings() {
+  let out_dir: &str = "dev-audio/render_score_tuning";
+  let tuned = |tuning: analysis::tuning::Tuning| RenderOptions {
+    seed: Some(5),
+    tuning,
+    ..Default::default()
+  };
+  let edo19 = analysis::tuning::Tuning::load("src/demo/tunings/19-edo.scl", Some("src/demo/tunings/19-edo.kbm")).unwrap();
+  let just = render_score(short_score(), Preset::Fum, out_dir, "just", &tuned(Default::default())).unwrap();
+  let tuned = render_score(short_score(), Preset::Fum, out_dir, "19-edo", &tuned(edo19)).unwrap();
+  let read = |path: &str| std::fs::read(path).unwrap();
+  assert_ne!(read(&just), read(&tuned), "Must render a different tuning differently");
+}
+
+#[test]
+fn test_render_score_follows_the_tempo_map() {
+  let out_dir: &str = "dev-audio/render_score_tempo";
+  let options = RenderOptions {
+    seed: Some(3),
+    window: Some((0f32, 4f32)),
+    ..Default::default()
+  };
+  let score = || {
+    let mut score = short_score();
+    score.parts.truncate(1);
+    score
+  };
+  let cps = score().conf.cps;
+  let mut faster = score();
+  faster.tempo = vec![TempoPoint {
+    cycle: 2f32,
+    cps: 2f32 * cps,
+    curve: TempoCurve::Step,
+  }];
+  let frames = |path: &str| hound::WavReader::open(path).unwrap().duration() as usize;
+  let steady = render_score(score(), Preset::Fum, out_dir, "steady", &options).unwrap();
+  let faster = render_score(faster, Preset::Fum, out_dir, "faster", &options).unwrap();
+  assert_eq!(time::samples_of_cycles(cps, 4f32), frames(&steady));
+  let expected = (SRf() * (2f32 / cps + 2f32 / (2f32 * cps))) as usize;
+  assert!(
+    frames(&faster).abs_diff(expected) <= 1,
+    "Must play the last two cycles twice as fast, got {} frames",
+    frames(&faster)
+  );
+}
+
+#[test]
+fn test_render_score_window_matches_full_render() {
+  let out_dir: &str = "dev-audio/render_score_window";
+  let cache_dir = "dev-audio/render_score_window/cache";
+  let _ = std::fs::remove_dir_all(cache_dir);
+  let full = RenderOptions {
+    seed: Some(99),
+    cache_dir: Some(cache_dir.to_string()),
+    ..Default::default()
+  };
+  let windowed = RenderOptions {
+    window: Some((1f32, 2f32)),
+    ..full.clone()
+  };
+  let read = |path: &str| -> Vec<f32> {
+    hound::WavReader::open(path).unwrap().samples::<f32>().map(|s| s.unwrap()).collect()
+  };
+  let score = short_score;
+  let cps = score().conf.cps;
+  let from = 2 * time::samples_of_cycles(cps, 1f32);
+  let region = |full: &[f32], part: &[f32]| full[from..from + part.len()].to_vec();
+  // without the levels of a full render the window renders every note
+  let trimmed = read(&render_score(score(), Preset::Fum, out_dir, "trimmed", &windowed).unwrap());
+  let full = read(&render_score(score(), Preset::Fum, out_dir, "full", &full).unwrap());
+  assert_eq!(2 * time::samples_of_cycles(cps, 1f32), trimmed.len(), "Must write only the window");
+  assert_eq!(region(&full, &trimmed), trimmed, "Must trim the full render");
+  // with the levels in the cache, the window synthesizes only the notes heard in it
+  let n_parts = cached_parts(cache_dir);
+  let part = read(&render_score(score(), Preset::Fum, out_dir, "window", &windowed).unwrap());
+  assert_eq!(2 * n_parts, cached_parts(cache_dir), "Must render the parts of the window");
+  // reverbs convolve by FFT, which rounds each sample by the whole signal, so only the last bits may differ
+  let max_error = region(&full, &part).iter().zip(&part).fold(0f32, |max, (a, b)| max.max((a - b).abs()));
+  assert!(
+    max_error < 1e-6f32,
+    "Must match the same region of the full render, got error {}",
+    max_error
+  );
+}
+
+#[test]
+fn test_render_score_streaming_matches_batch() {
+  let out_dir: &str = "dev-audio/render_score_stream";
+  let batch = RenderOptions {
+    seed: Some(5),
+    keep_stems: true,
+    ..Default::default()
+  };
+  let streaming = RenderOptions {
+    streaming: true,
+    ..batch.clone()
+  };
+  let read = |path: &str| -> Vec<f32> {
+    hound::WavReader::open(path).unwrap().samples::<f32>().map(|s| s.unwrap()).collect()
+  };
+  let a = read(&render_score(short_score(), Preset::Fum, out_dir, "batch", &batch).unwrap());
+  let b = read(&render_score(short_score(), Preset::Fum, out_dir, "stream", &streaming).unwrap());
+  assert_eq!(a.len(), b.len(), "Must render the same length");
+  let max_error = a.iter().zip(b.iter()).fold(0f32, |max, (x, y)| max.max((x - y).abs()));
+  assert!(max_error < 1e-3f32, "Must sound the same as a batch render, got error {}", max_error);
+  let stem = render::stems::stem_name("stream", 0, &short_score().parts[0].1.role);
+  assert!(std::path::Path::new(&format!("{}/{}", out_dir, stem)).exists());
+}
+
+#[test]
+fn test_render_score_reports_progress() {
+  let out_dir: &str = "dev-audio/render_score_progress";
+  let events_path = format!("{}/events.jsonl", out_dir);
+  let options = RenderOptions {
+    seed: Some(3),
+    progress: Some(render::progress::ProgressSink::File(events_path.clone())),
+    ..Default::default()
+  };
+  let score = short_score();
+  let n_parts = score.parts.len();
+  let n_notes: usize = score.parts.iter().flat_map(|(_, _, melody)| melody.iter()).map(Vec::len).sum();
+  render_score(score, Preset::Fum, out_dir, "progress", &options).unwrap();
+
+  let events: Vec<serde_json::Value> = std::fs::read_to_string(&events_path)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect();
+  let named = |name: &str| events.iter().filter(|e| e["event"] == name).collect::<Vec<_>>();
+  assert_eq!("render_start", events[0]["event"]);
+  assert_eq!("render_finish", events[events.len() - 1]["event"]);
+  assert_eq!(n_parts, named("part_start").len());
+  let finished = named("part_finish");
+  assert_eq!(n_parts, finished.len());
+  let notes: u64 = finished.iter().map(|e| e["notes"].as_u64().unwrap()).sum();
+  // presets may layer a part, rendering each note more than once
+  assert!(notes >= n_notes as u64, "Must count every note of a full render, got {}", notes);
+  let stages: Vec<&str> = named("stage_finish").iter().map(|e| e["stage"].as_str().unwrap()).collect();
+  assert_eq!(vec!["synthesis", "group_reverb", "write"], stages);
+  assert!(events[events.len() - 1]["realtime_factor"].as_f64().unwrap() > 0f64);
+}
+
+#[test]
+fn test_render_score_reuses_cached_parts() {
+  let out_dir: &str = "dev-audio/render_score_cache";
+  let cache_dir = format!("{}/cache", out_dir);
+  let _ = std::fs::remove_dir_all(&cache_dir);
+  let options = RenderOptions {
+    seed: Some(21),
+    cache_dir: Some(cache_dir.clone()),
+    ..Default::default()
+  };
+  let cached_parts = || cached_parts(&cache_dir);
+  let read = |path: &str| std::fs::read(path).unwrap();
+  let n_parts = short_score().parts.len();
+
+  let first = read(&render_score(short_score(), Preset::Fum, out_dir, "first", &options).unwrap());
+  assert_eq!(n_parts, cached_parts(), "Must cache every part");
+  let again = read(&render_score(short_score(), Preset::Fum, out_dir, "again", &options).unwrap());
+  assert_eq!(first, again, "Must render the same file from cached parts");
+
+  let mut edited = short_score();
+  edited.parts[0].2[0][0].2 *= 0.5f32;
+  render_score(edited, Preset::Fum, out_dir, "edited", &options).unwrap();
+  assert_eq!(n_parts + 1, cached_parts(), "Must render only the edited part");
+}
+
+#[test]
+fn test_render_score_returns_errors() {
+  let out_dir: &str = "dev-audio/render_score_errors";
+  let mut score = inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+  score.parts[0].2[0].clear();
+  match render_score(score, Preset::Fum, out_dir, "empty-line", &RenderOptions::default()) {
+    Err(RaudioError::InvalidScore(problems)) => assert_eq!("$.parts[0][2][0]", problems[0].path),
+    other => panic!("Must reject a score with an empty line, got {:?}", other),
+  }
+  assert!(!std::path::Path::new(out_dir).exists(), "Must not write anything for an invalid score");
+  assert!(matches!(
+    inp::arg_parse::load_score_from_file("does/not/exist.json"),
+    Err(RaudioError::Io(_, _))
+  ));
+  assert!(matches!(
+    render_playbook(out_dir, "nope", "src/demo/playbooks/house_short_1.json", "x", None),
+    Err(RaudioError::UnknownPreset(_))
+  ));
+}
+
+#[test]
+fn test_render_score_writes_stems() {
+  let out_dir: &str = "dev-audio/render_score_stems";
+  let options = RenderOptions {
+    keep_stems: true,
+    seed: Some(7),
+    ..Default::default()
+  };
+  let score = short_score();
+  let n_parts = score.parts.len();
+  render_score(score, Preset::Fum, out_dir, "stems", &options).unwrap();
+
+  let manifest: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(render::stems::manifest_path(out_dir, "stems")).unwrap()).unwrap();
+  let stems = manifest["stems"].as_array().unwrap();
+  assert_eq!(n_parts, stems.len(), "Must write one stem per part");
+  let mix_gain = manifest["mix_gain"].as_f64().unwrap();
+  assert!(mix_gain > 0f64 && mix_gain <= 1f64, "Must write stems at their level in the mix");
+  for stem in stems {
+    let path = format!("{}/{}", out_dir, stem["file"].as_str().unwrap());
+    let reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(2, reader.spec().channels);
+    assert_eq!(stem["samples"].as_u64().unwrap() as u32, reader.duration());
+    assert_eq!(stem["role"], stem["arf"]["role"]);
+    assert!(stem["positioning"]["distance"].is_string());
+    assert!(stem["peak"].as_f64().unwrap() >= stem["rms"].as_f64().unwrap());
+  }
+}
+
+#[test]
+fn test_render_score_at_sample_rate() {
+  let out_dir: &str = "dev-audio/render_score_sample_rate";
+  let at = |sample_rate: usize| RenderOptions {
+    seed: Some(3),
+    sample_rate: Some(sample_rate),
+    ..Default::default()
+  };
+  let seconds = |path: &str| {
+    let reader = hound::WavReader::open(path).unwrap();
+    (reader.spec().sample_rate, reader.duration() as f32 / reader.spec().sample_rate as f32)
+  };
+  let (rate_default, len_default) =
+    seconds(&render_score(short_score(), Preset::Fum, out_dir, "default", &at(synth::DEFAULT_SR)).unwrap());
+  let (rate_cd, len_cd) = seconds(&render_score(short_score(), Preset::Fum, out_dir, "cd", &at(44100)).unwrap());
+  assert_eq!((44100, 48000), (rate_cd, rate_default));
+  assert!(
+    (len_cd - len_default).abs() < 0.1f32 * len_default,
+    "Must render the same duration at any sample rate, got {}s and {}s",
+    len_cd,
+    len_default
+  );
+  assert_eq!(synth::DEFAULT_SR, synth::SR(), "Must restore the sample rate of the calling thread");
+  assert_eq!(None, seed::current_seed(), "Must restore the seed of the calling thread");
+}
+
+#[test]
+fn test_render_score_writes_markers() {
+  let out_dir: &str = "dev-audio/render_score_markers";
+  let options = RenderOptions {
+    seed: Some(5),
+    marker_sidecar: Some(render::markers::SidecarFormat::Csv),
+    ..Default::default()
+  };
+  let score = short_score();
+  let tag = score.markers[0].tag.clone();
+  let path = render_score(score, Preset::Fum, out_dir, "markers", &options).unwrap();
+  let bytes = std::fs::read(&path).unwrap();
+  assert!(bytes.windows(4).any(|w| w == b"cue "), "Must write cue points into the mixdown");
+  assert!(hound::WavReader::open(&path).is_ok());
+  let csv = std::fs::read_to_string(render::markers::sidecar_path(
+    out_dir,
+    "markers",
+    render::markers::SidecarFormat::Csv,
+  ))
+  .unwrap();
+  assert!(csv.lines().nth(1).unwrap().contains(&tag));
 }
@@ -140,7 +140,7 @@
 // #[test]
 // fn many() {
 
-//     let mut rng = rand::thread_rng();
+//     let mut rng = crate::seed::thread_rng();
 //     let mut prev_fundamental = 1f32;
 
 //     let harmonic_basis = 1.5f32;
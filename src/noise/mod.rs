@@ -11,12 +11,12 @@ fn violet(length: usize) -> SampleBuffer {
 }
 
 fn white(length: usize) -> SampleBuffer {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     (0..length).map(|_| rng.gen_range(-1.0..1.0)).collect()
 }
 
 fn pink(length: usize) -> SampleBuffer {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let num_rows = 16;
     let mut rows = vec![0.0; num_rows];
     let mut pink = vec![0.0; length];
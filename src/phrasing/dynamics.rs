@@ -26,7 +26,7 @@ fn generate_offset_triangle_wave(
 /// where each layer is wider than the last
 fn generate_layers(n_layers: usize, n_samples: usize, greatest_reduction: f32, least_reduction: f32) -> Vec<Vec<f32>> {
   let mut layers = Vec::new();
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   for i in 1..=n_layers {
     let layer_width = n_samples as f32 * ((i as f32) / n_layers as f32);
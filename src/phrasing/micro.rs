@@ -11,7 +11,7 @@ use crate::phrasing::AmpModulation;
 use crate::synth::{e, epi, pi, pi2, MFf, NFf, SR};
 use crate::types::synthesis::SampleBuffer;
 use crate::types::timbre::{Arf, Energy, MicroLifespan, Mode, Presence, Visibility};
-use rand::rngs::ThreadRng;
+use crate::seed::ThreadRng;
 use rand::Rng;
 use std::cell::RefCell;
 use std::thread_local;
@@ -19,7 +19,7 @@ use std::thread_local;
 const MAX_MICRO_HEIGHT: usize = 21;
 
 thread_local! {
-    static RNG: RefCell<ThreadRng> = RefCell::new(rand::thread_rng());
+    static RNG: RefCell<ThreadRng> = RefCell::new(crate::seed::thread_rng());
 }
 
 fn gen_rng() -> f32 {
@@ -110,17 +110,17 @@ fn micro_height(energy: &Energy) -> usize {
 }
 
 pub fn muls_micro(freq: f32, energy: &Energy) -> Vec<f32> {
-  let n = ((NFf / freq) as usize).min(micro_height(energy));
+  let n = ((NFf() / freq) as usize).min(micro_height(energy));
   (1..=n).map(|x| x as f32).collect()
 }
 
 pub fn amps_micro(freq: f32, energy: &Energy) -> Vec<f32> {
-  let n = ((NFf / freq) as usize).min(micro_height(energy));
+  let n = ((NFf() / freq) as usize).min(micro_height(energy));
   vec![1f32; n]
 }
 
 pub fn phases_micro(freq: f32, energy: &Energy) -> Vec<f32> {
-  let n = ((NFf / freq) as usize).min(micro_height(energy));
+  let n = ((NFf() / freq) as usize).min(micro_height(energy));
   vec![0f32; n]
 }
 
@@ -154,7 +154,7 @@ pub fn modders_click() -> crate::phrasing::older_ranger::Modders {
 }
 
 pub fn modders_pop() -> crate::phrasing::older_ranger::Modders {
-  let mut s = rand::thread_rng();
+  let mut s = crate::seed::thread_rng();
 
   [None, Some(vec![(1f32, amp_pop)]), None]
 }
@@ -262,7 +262,7 @@ mod test {
         phss,
         modders: modders_chiff(),
         expr: expr_none(),
-        hplp: (vec![MFf], vec![NFf]),
+        hplp: (vec![MFf], vec![NFf()]),
         thresh: (0f32, 1f32),
       }
     }
@@ -276,7 +276,7 @@ mod test {
         phss,
         modders: modders_click(),
         expr: expr_none(),
-        hplp: (vec![MFf], vec![NFf]),
+        hplp: (vec![MFf], vec![NFf()]),
         thresh: (0f32, 1f32),
       }
     }
@@ -290,7 +290,7 @@ mod test {
         phss,
         modders: modders_pop(),
         expr: expr_none(),
-        hplp: (vec![MFf], vec![NFf]),
+        hplp: (vec![MFf], vec![NFf()]),
         thresh: (0f32, 1f32),
       }
     }
@@ -315,7 +315,7 @@ mod test {
       assert_microtransient(&MicroLifespan::Chiff, &signal);
       files::with_dir(test_dir);
       let filename: String = format!("{}/{}.wav", test_dir, test_name);
-      engrave::samples(SR, &signal, &filename);
+      engrave::samples(SR(), &signal, &filename).unwrap();
     }
 
     #[test]
@@ -338,7 +338,7 @@ mod test {
       assert_microtransient(&MicroLifespan::Click, &signal);
       files::with_dir(test_dir);
       let filename: String = format!("{}/{}.wav", test_dir, test_name);
-      engrave::samples(SR, &signal, &filename);
+      engrave::samples(SR(), &signal, &filename).unwrap();
     }
 
     #[test]
@@ -362,7 +362,7 @@ mod test {
       files::with_dir(test_dir);
       assert_microtransient(&MicroLifespan::Pop, &signal);
       let filename: String = format!("{}/{}.wav", test_dir, test_name);
-      engrave::samples(SR, &signal, &filename);
+      engrave::samples(SR(), &signal, &filename).unwrap();
     }
   }
 }
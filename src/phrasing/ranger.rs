@@ -142,7 +142,7 @@ pub fn fmod_sweepdown(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32,
     return one;
   }
 
-  let max_mul: f32 = (NFf / 4f32) / (mul * fund);
+  let max_mul: f32 = (NFf() / 4f32) / (mul * fund);
   if max_mul < 1f32 || mul.log2() > 5f32 {
     return 1f32;
   }
@@ -302,7 +302,7 @@ pub fn pmod_weird(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos
 /// `b`: resonant factor  
 /// `c`: damping intensity  
 pub fn amod_peak(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
-  let max_mul: f32 = NFf / (mul * fund);
+  let max_mul: f32 = NFf() / (mul * fund);
   let max_freq: f32 = max_mul.max(4f32);
   let target_freq: f32 = fund * 2f32.powf(knob.a * max_freq);
   let df = (target_freq - fund * mul).abs();
@@ -361,11 +361,11 @@ pub fn amod_microtransient_4_20(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cy
   let playback_scaling: f32 = if knob.c.floor() == 0.0 { 1.0 } else { 1.0 / cps };
   let length_ms = (min_ms + (max_ms - min_ms) * knob.a) * playback_scaling;
   let length_seconds = length_ms / 1000f32;
-  let seconds_per_sample = 1.0f32 / SRf;
+  let seconds_per_sample = 1.0f32 / SRf();
 
-  let n_samples = (length_seconds * SRf).floor() as usize;
+  let n_samples = (length_seconds * SRf()).floor() as usize;
   let t: f32 = pos_cycles / n_cycles;
-  let curr_sample = (t * n_cycles * SRf / cps).floor() as usize;
+  let curr_sample = (t * n_cycles * SRf() / cps).floor() as usize;
   let curr_sample = curr_sample.min(n_samples);
 
   if curr_sample >= n_samples {
@@ -399,11 +399,11 @@ pub fn amod_microtransient_20_100(knob: &Knob, cps: f32, fund: f32, mul: f32, n_
   let playback_scaling: f32 = if knob.c.floor() == 0.0 { 1.0 } else { 1.0 / cps };
   let length_ms = (min_ms + (max_ms - min_ms) * knob.a) * playback_scaling;
   let length_seconds = length_ms / 1000f32;
-  let seconds_per_sample = 1.0f32 / SRf;
+  let seconds_per_sample = 1.0f32 / SRf();
 
-  let n_samples = (length_seconds * SRf).floor() as usize;
+  let n_samples = (length_seconds * SRf()).floor() as usize;
   let t: f32 = pos_cycles / n_cycles;
-  let curr_sample = (t * n_cycles * SRf / cps).floor() as usize;
+  let curr_sample = (t * n_cycles * SRf() / cps).floor() as usize;
   let curr_sample = curr_sample.min(n_samples);
 
   if curr_sample >= n_samples {
@@ -437,11 +437,11 @@ pub fn amod_microbreath_4_20(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycle
   let playback_scaling: f32 = if knob.c.floor() == 0.0 { 1.0 } else { 1.0 / cps };
   let length_ms = (min_ms + (max_ms - min_ms) * knob.a) * playback_scaling;
   let length_seconds = length_ms / 1000f32;
-  let seconds_per_sample = 1.0f32 / SRf;
+  let seconds_per_sample = 1.0f32 / SRf();
 
-  let n_samples = (length_seconds * SRf).floor() as usize;
+  let n_samples = (length_seconds * SRf()).floor() as usize;
   let t: f32 = pos_cycles / n_cycles;
-  let curr_sample = (t * n_cycles * SRf / cps).floor() as usize;
+  let curr_sample = (t * n_cycles * SRf() / cps).floor() as usize;
   let curr_sample = curr_sample.min(n_samples);
 
   if curr_sample >= n_samples {
@@ -475,11 +475,11 @@ pub fn amod_microbreath_20_100(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cyc
   let playback_scaling: f32 = if knob.c.floor() == 0.0 { 1.0 } else { 1.0 / cps };
   let length_ms = (min_ms + (max_ms - min_ms) * knob.a) * playback_scaling;
   let length_seconds = length_ms / 1000f32;
-  let seconds_per_sample = 1.0f32 / SRf;
+  let seconds_per_sample = 1.0f32 / SRf();
 
-  let n_samples = (length_seconds * SRf).floor() as usize;
+  let n_samples = (length_seconds * SRf()).floor() as usize;
   let t: f32 = pos_cycles / n_cycles;
-  let curr_sample = (t * n_cycles * SRf / cps).floor() as usize;
+  let curr_sample = (t * n_cycles * SRf() / cps).floor() as usize;
   let curr_sample = curr_sample.min(n_samples);
 
   if curr_sample >= n_samples {
@@ -672,7 +672,7 @@ pub fn amod_impulse(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, p
 ///
 /// ## Returns
 pub fn amod_pluck(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
-  let max_mul: f32 = NFf / (mul * fund);
+  let max_mul: f32 = NFf() / (mul * fund);
   let t: f32 = pos_cycles / n_cycles;
   let base_decay_rate: f32 = 5f32 + 20f32 * (1f32 - knob.a);
   let decay_mod_add: f32 = 120f32 * (knob.b.max(0.08)).powi(2i32) * fund;
@@ -810,7 +810,7 @@ pub fn amod_cycle_fadein_0p031_0p125(
 ///
 /// ## Returns
 pub fn amod_pluck3(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
-  let max_mul: f32 = NFf / (mul * fund);
+  let max_mul: f32 = NFf() / (mul * fund);
   let t: f32 = pos_cycles / n_cycles;
   let base_decay_rate: f32 = 5f32 + 5f32 * (1f32 - knob.a);
   let decay_mod_add: f32 = 20f32 * (knob.b.max(0.08)).powi(2i32) * fund;
@@ -838,7 +838,7 @@ pub fn amod_pluck3(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, po
 ///
 /// ## Returns
 pub fn amod_pluck2(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
-  let max_mul: f32 = NFf / (mul * fund);
+  let max_mul: f32 = NFf() / (mul * fund);
   let t: f32 = pos_cycles / n_cycles;
   let base_decay_rate: f32 = 5f32 + 5f32 * (1f32 - knob.a);
   let decay_mod_add: f32 = 2f32 * (knob.b).powi(2i32) * mul.sqrt();
@@ -866,7 +866,7 @@ pub fn amod_pluck2(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, po
 ///
 /// ## Returns
 pub fn amod_stab(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
-  let max_mul: f32 = NFf / (mul * fund);
+  let max_mul: f32 = NFf() / (mul * fund);
   let t: f32 = pos_cycles / n_cycles;
 
   let sus_target = MIN_DB * (1f32 - knob.c);
@@ -955,7 +955,7 @@ pub fn amod_unit(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_
 ///
 /// ## Returns
 pub fn amod_burp(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
-  let max_mul: f32 = NFf / (mul * fund);
+  let max_mul: f32 = NFf() / (mul * fund);
   let t: f32 = pos_cycles / n_cycles;
   let base_decay_rate: f32 = 2f32 + (8f32 * knob.a);
   let decay_mod_add: f32 = 2f32 * knob.b * mul / max_mul;
@@ -1011,7 +1011,9 @@ pub fn amod_oscillation_tri(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles
 pub fn amod_oscillation_sin_mul(knob: &Knob, cps: f32, fund: f32, mul: f32, n_cycles: f32, pos_cycles: f32) -> f32 {
   // let t: f32 = (mul * knob.b).sin().powi(2i32) * (pos_cycles / n_cycles) % 1f32;
   let t: f32 = (pos_cycles / n_cycles) % 1f32;
-  let osc_rate: f32 = 2f32.powf(-3f32 + 3f32 * knob.a) * (t.powf(2f32 * knob.b - one)).sin().powi(2i32);
+  // t^(2b - 1) diverges at the onset when b < 0.5; start the tremelo from silence there instead of NaN
+  let warped: f32 = Some(t.powf(2f32 * knob.b - one)).filter(|w| w.is_finite()).unwrap_or(0f32);
+  let osc_rate: f32 = 2f32.powf(-3f32 + 3f32 * knob.a) * warped.sin().powi(2i32);
 
   let y: f32 = (pi2 * osc_rate).sin();
   y.powi(2i32)
@@ -1217,6 +1219,20 @@ mod test {
   static muls: [f32; 4] = [1f32, 10f32, 20f32, 200f32];
   static n_cycles: f32 = 1.5f32;
 
+  #[test]
+  fn test_amod_oscillation_sin_mul_is_finite_at_onset() {
+    let knob: Knob = Knob {
+      a: 0.5f32,
+      b: 0.2f32,
+      c: 0f32,
+    };
+    for i in 0..n_samples {
+      let pos_cycles = n_cycles * (i as f32 / n_samples as f32);
+      let y = amod_oscillation_sin_mul(&knob, cps, fund, 1f32, n_cycles, pos_cycles);
+      assert!(y.is_finite(), "Must be finite at position {}, got {}", pos_cycles, y);
+    }
+  }
+
   #[test]
   fn test_fmod_sweepdown_a1_b0() {
     for &mul in &muls {
@@ -1257,8 +1273,8 @@ fn test_amod_fadein_monotonic_increasing() {
   let n_cycles: f32 = 1.0;
   let mut last_value = 0.0; // Start with minimum amplitude
 
-  for sample in 0..SR {
-    let pos_cycles = sample as f32 / SRf;
+  for sample in 0..SR() {
+    let pos_cycles = sample as f32 / SRf();
     let result = amod_fadein(&knob, cps, fund, mul, n_cycles, pos_cycles);
     assert!(
       result >= last_value,
@@ -1282,8 +1298,8 @@ fn test_amod_microtransient_monotonic_decreasing() {
   let n_cycles: f32 = 1.0;
   let mut last_value = 1.0;
 
-  for sample in 0..SR {
-    let pos_cycles = sample as f32 / SRf;
+  for sample in 0..SR() {
+    let pos_cycles = sample as f32 / SRf();
     let result = amod_microtransient_4_20(&knob, cps, fund, mul, n_cycles, pos_cycles);
     assert!(
       result <= last_value,
@@ -77,7 +77,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let soids = druidic_soids::id();
 
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf / 64f32]),
+    bp: (vec![MFf], vec![NFf() / 64f32]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
@@ -47,7 +47,7 @@ fn amp_knob() -> (Knob, fn(&Knob, f32, f32, f32, f32, f32) -> f32) {
 /// noise component
 pub fn stem_visible<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem<'render> {
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
@@ -100,7 +100,7 @@ pub fn stem_visible<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem<'
 /// tonal component
 pub fn stem_foreground<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem<'render> {
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
@@ -181,7 +181,7 @@ mod a2 {
 
   /// noise component
   pub fn stem_visible<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem2<'render> {
-    let bp2: Bp2 = (vec![MFf], vec![NFf], vec![]);
+    let bp2: Bp2 = (vec![MFf], vec![NFf()], vec![]);
 
     let soids = soid_fx::concat(&vec![
       soid_fx::noise::rank(0, NoiseColor::Pink, 1f32 / 3f32),
@@ -14,7 +14,7 @@ fn stem_hidden<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem<'rende
   );
 
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf / 64f32]),
+    bp: (vec![MFf], vec![NFf() / 64f32]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
@@ -75,7 +75,7 @@ fn stem_foreground<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem<'r
     soid_fx::ratio::constant(&soids, *k, *gain)
   });
   let feelsoid_fx: Feel = Feel {
-    bp: (vec![MFf], vec![NFf / 12f32]),
+    bp: (vec![MFf], vec![NFf() / 12f32]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
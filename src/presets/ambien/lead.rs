@@ -106,7 +106,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   knob_mods.0.push(amp_knob(arf.visibility, arf.energy, arf.presence));
 
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
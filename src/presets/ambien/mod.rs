@@ -8,8 +8,21 @@ pub mod perc;
 
 /// ease in and out
 pub fn amp_expr(n_seconds: f32) -> SampleBuffer {
-  let n_samples: usize = (crate::synth::SRf * n_seconds) as usize;
+  let n_samples: usize = (crate::synth::SRf() * n_seconds) as usize;
   let r = 1f32 / 2f32;
 
   (0..n_samples).map(|x| (pi * (x as f32 / n_samples as f32) / n_seconds).sin().powf(r)).collect()
 }
+
+/// The roles of this pack, written for the original `Renderable` pipeline. See `renderable2`.
+pub fn map_role_preset<'render>() -> RolePreset<'render> {
+  RolePreset {
+    label: "ambien",
+    kick: |_, melody, arf| Ok(renderable2(kick::renderable(melody, arf))),
+    perc: |_, melody, arf| Ok(renderable2(perc::renderable(melody, arf))),
+    hats: |_, melody, arf| Ok(renderable2(hats::renderable(melody, arf))),
+    chords: |_, melody, arf| Ok(renderable2(chords::renderable(melody, arf))),
+    lead: |_, melody, arf| Ok(renderable2(lead::renderable(melody, arf))),
+    bass: |_, melody, arf| Ok(renderable2(bass::renderable(melody, arf))),
+  }
+}
@@ -28,7 +28,7 @@ pub fn stem_visible<'render>(arf: &Arf, melody: &'render Melody<Note>) -> Stem<'
     vec![0f32],
   );
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: (vec![], vec![], vec![], vec![]),
     clippers: (0f32, 1f32),
   };
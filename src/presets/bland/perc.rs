@@ -1,6 +1,8 @@
 use super::*;
 
 /// mix of three different percs.
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   simple_stem(conf, melody, arf)
 }
@@ -5,7 +5,9 @@ use crate::phrasing::{contour::Expr, ranger::KnobMods};
 use crate::types::synthesis::{ModifiersHolder, Soids};
 use std::os::unix::thread;
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mut rng = thread_rng();
   let mullet = get_mullet(arf.register, arf.energy);
 
@@ -56,5 +58,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Instance(stem)
+  Ok(Renderable2::Instance(stem))
 }
@@ -1,11 +1,13 @@
 use super::*;
 
 /// mix of three different percs.
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
-  Renderable2::Mix(vec![
-    (0.5, contour_stem(conf, melody, arf)),
-    (0.5, contour_stem(conf, melody, arf)),
-  ])
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
+  Ok(Renderable2::Mix(vec![
+    (0.5, contour_stem(conf, melody, arf)?),
+    (0.5, contour_stem(conf, melody, arf)?),
+  ]))
 }
 
 mod test_functional_compressor {
@@ -107,7 +109,7 @@ use super::*;
     let attack_time = 0.05f32;
     let release_time = 0.1f32;
     let ratio = 8f32;
-    render::engrave::samples(SR, &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick)));
+    render::engrave::samples(SR(), &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick))).unwrap();
 
     for thresh in thresholds.iter() {
       let compressor_params = CompressorParams {
@@ -121,7 +123,7 @@ use super::*;
       match compressor(&samples, compressor_params, None) {
         Ok(result) => {
           let label = format!("kick-compressor-threshold-{}-attack-time-{}-release-time-{}-ratio-{}.wav", *thresh, attack_time, release_time, ratio);
-          render::engrave::samples(SR, &result, &dev_audio_asset(&label))
+          render::engrave::samples(SR(), &result, &dev_audio_asset(&label)).unwrap()
         }
         Err(msg) => {
           panic!("Failure while running test: {}", msg)
@@ -139,7 +141,7 @@ use super::*;
     let attack_time = 0.05f32;
     let release_time = 0.1f32;
     let ratio = 8f32;
-    render::engrave::samples(SR, &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick)));
+    render::engrave::samples(SR(), &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick))).unwrap();
     
     for thresh in thresholds.iter() {
       let expander_params = ExpanderParams {
@@ -155,7 +157,7 @@ use super::*;
 
           let label = format!("kick-expander-threshold-{}-attack-time-{}-release-time-{}-ratio-{}.wav", *thresh, attack_time, release_time, ratio);
           println!("Samples at 48000..48200: {:?}", &samples[48000..48400]);
-          render::engrave::samples(SR, &result, &dev_audio_asset(&label));
+          render::engrave::samples(SR(), &result, &dev_audio_asset(&label)).unwrap();
 
 
           // render::engrave::samples(SR, &result, &dev_audio_asset(&label));
@@ -192,19 +194,19 @@ use super::*;
         ratio,
         ..Default::default()
       };
-      render::engrave::samples(SR, &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick)));
+      render::engrave::samples(SR(), &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick))).unwrap();
 
       let stage_1 = expander(&samples, expander_params, None).unwrap();
       let result = compressor(&stage_1, compressor_params, None).unwrap();
       let label = format!("kick-expander-then-compressor-threshold-{}.wav", 24);
-      render::engrave::samples(SR, &result, &dev_audio_asset(&label));
+      render::engrave::samples(SR(), &result, &dev_audio_asset(&label)).unwrap();
 
 
 
       let stage_1 = compressor(&samples, compressor_params, None).unwrap();
       let result = expander(&stage_1, expander_params, None).unwrap();
       let label = format!("kick-compressor-then-expander-threshold-{}.wav", 24);
-      render::engrave::samples(SR, &result, &dev_audio_asset(&label))
+      render::engrave::samples(SR(), &result, &dev_audio_asset(&label)).unwrap()
     }
 
     fn find_indexes<F>(vec: &[f32], callback: F) -> Vec<usize>
@@ -281,7 +283,7 @@ where
       // let samples = samples[0..kill_index].to_vec();
 
 
-      render::engrave::samples(SR, &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick)));
+      render::engrave::samples(SR(), &samples, &dev_audio_asset(&format!("original-{}.wav", Role::Kick))).unwrap();
       println!("Energy of original is {}", count_energy(&samples));
       let stage_1 = expander(&samples, expander_params, None).unwrap();
       println!("Energy of stage_1 is {}", count_energy(&stage_1));
@@ -158,7 +158,7 @@ impl<'render> Conventions<'render> for BrightCon<'render> {
           vec![],
         )
       }
-      _ => (vec![MFf], vec![NFf], vec![]),
+      _ => (vec![MFf], vec![NFf()], vec![]),
     }
   }
 }
@@ -3,8 +3,10 @@ use crate::analysis::melody::{eval_odr_level, LevelMacro, Levels, ODRMacro, ODR}
 use crate::fm::*;
 
 /// Creates a renderable stem using FM synthesis
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
-  Renderable2::FMOp((
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
+  Ok(Renderable2::FMOp((
     melody,
     arf.clone(),
     dexed_bass,
@@ -12,7 +14,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     vec![], // Delay2
     vec![], // Reverb1
     vec![], // Reverb2
-  ))
+  )))
 }
 
 /// A rich bass synth evoking distorted bassoon.  
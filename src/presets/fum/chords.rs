@@ -3,8 +3,10 @@ use crate::analysis::melody::{eval_odr_level, LevelMacro, Levels, ODRMacro, ODR}
 use crate::fm::*;
 
 /// Creates a renderable stem using FM synthesis
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
-  Renderable2::FMOp((
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
+  Ok(Renderable2::FMOp((
     melody,
     arf.clone(),
     dexed_pad,
@@ -12,7 +14,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     vec![], // Delay2
     vec![], // Reverb1
     vec![], // Reverb2
-  ))
+  )))
 }
 
 #[test]
@@ -50,7 +52,7 @@ fn test_dexed_pad_bandwidth() {
     let freq = note_to_freq(note);
 
     for op in &ops {
-      match get_remaining_range(&op, 0f32, 0f32, MFf, NFf) {
+      match get_remaining_range(&op, 0f32, 0f32, MFf, NFf()) {
         Some((lower_range, upper_range)) => {
           // println!("Has op {:?}", op);
           // println!("Got bandwidth lower {} upper {}", lower_range, upper_range);
@@ -58,10 +60,10 @@ fn test_dexed_pad_bandwidth() {
         None => assert!(false, "Must not design a synth that exceeds its bandwidth"),
       }
     }
-    let s = render_operators_with_envelopes(ops, time::note_to_cycles(note), conf.cps, SR);
+    let s = render_operators_with_envelopes(ops, time::note_to_cycles(note), conf.cps, SR());
     signal.extend(s);
   }
-  crate::render::engrave::samples(SR, &signal, &format!("dev-audio/test-fm-bandwidth.wav"));
+  crate::render::engrave::samples(SR(), &signal, &format!("dev-audio/test-fm-bandwidth.wav")).unwrap();
 }
 
 /// Represents Algorithm 9 in DX-7 operator configuration for a synth pad
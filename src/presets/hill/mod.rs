@@ -0,0 +1,11 @@
+use super::*;
+pub mod kick;
+
+/// A kick of its own. The other roles are those of `hop`.
+pub fn map_role_preset<'render>() -> RolePreset<'render> {
+  RolePreset {
+    label: "hill",
+    kick: |conf, melody, arf| Ok(kick::renderable(conf, melody, arf)),
+    ..hop::map_role_preset()
+  }
+}
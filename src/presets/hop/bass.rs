@@ -26,7 +26,7 @@ fn bp<'render>(melody: &'render Melody<Note>, arf: &Arf, len_cycles: f32) -> (Sa
       lowest_register = (*register).min(lowest_register);
     }
   }
-  let n_samples: usize = (len_cycles / 2f32) as usize * SR;
+  let n_samples: usize = (len_cycles / 2f32) as usize * SR();
 
   let (highpass, lowpass): (Vec<f32>, Vec<f32>) = if let Visibility::Visible = arf.visibility {
     match arf.energy {
@@ -37,7 +37,7 @@ fn bp<'render>(melody: &'render Melody<Note>, arf: &Arf, len_cycles: f32) -> (Sa
           n_samples,
           size * rate_per_size,
         ),
-        vec![NFf],
+        vec![NFf()],
       ),
       _ => (
         vec![MFf],
@@ -45,7 +45,7 @@ fn bp<'render>(melody: &'render Melody<Note>, arf: &Arf, len_cycles: f32) -> (Sa
       ),
     }
   } else {
-    (vec![MFf], vec![NFf / 8f32])
+    (vec![MFf], vec![NFf() / 8f32])
   };
 
   (highpass, lowpass)
@@ -101,10 +101,12 @@ fn amp_knob_detune(rng: &mut ThreadRng, arf: &Arf) -> KnobPair {
   )
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mut rng = thread_rng();
   let len_cycles: f32 = time::count_cycles(&melody[0]);
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut knob_mods: KnobMods2 = KnobMods2::unit();
   if let Visibility::Visible = arf.visibility {
@@ -139,5 +141,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Instance(stem)
+  Ok(Renderable2::Instance(stem))
 }
@@ -239,7 +239,9 @@ fn dynamics(arf: &Arf, n_samples: usize, k: f32) -> SampleBuffer {
   dynamp_contour
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mullet = match arf.visibility {
     Visibility::Hidden => 2f32.powi(12i32),
     Visibility::Background => 2f32.powi(10i32),
@@ -259,7 +261,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   }
   knob_mods.0.push(amp_knob_presence(arf.visibility, arf.energy, arf.presence));
   knob_mods.2.push(pmod_chorus(arf.visibility, arf.energy, arf.presence));
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut dynamics = dynamics::gen_organic_amplitude(4, n_samples, arf.visibility);
   amp_scale(&mut dynamics, visibility_gain(arf.visibility));
@@ -290,5 +292,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Instance(stem)
+  Ok(Renderable2::Instance(stem))
 }
@@ -248,28 +248,30 @@ fn bp<'render>(melody: &'render Melody<Note>, arf: &Arf, len_cycles: f32) -> (Sa
       lowest_register = (*register).min(lowest_register);
     }
   }
-  let n_samples: usize = (len_cycles / 2f32) as usize * SR;
+  let n_samples: usize = (len_cycles / 2f32) as usize * SR();
 
   let (highpass, lowpass) = if let Visibility::Visible = arf.visibility {
     match arf.energy {
       Energy::Low => (
         filter_contour_triangle_shape_highpass(lowest_register, highest_register, n_samples, size * rate_per_size),
-        vec![NFf],
+        vec![NFf()],
       ),
       Energy::Medium => (
         vec![MFf],
         filter_contour_triangle_shape_lowpass(lowest_register + 2, n_samples, size * rate_per_size),
       ),
-      Energy::High => (vec![MFf], vec![NFf]),
+      Energy::High => (vec![MFf], vec![NFf()]),
     }
   } else {
-    (vec![MFf * 4f32], vec![NFf / 3f32])
+    (vec![MFf * 4f32], vec![NFf() / 3f32])
   };
 
   (highpass, lowpass)
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mullet = get_mullet(arf);
 
   let soids = match arf.presence {
@@ -290,7 +292,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   knob_mods.2.push(pmod_chorus(arf.visibility, arf.energy, arf.presence));
 
   let len_cycles: f32 = time::count_cycles(&melody[0]);
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut dynamics = dynamics::gen_organic_amplitude(10, n_samples, arf.visibility);
   amp_scale(&mut dynamics, visibility_gain(arf.visibility));
@@ -315,5 +317,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Instance(stem)
+  Ok(Renderable2::Instance(stem))
 }
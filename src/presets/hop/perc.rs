@@ -9,12 +9,14 @@ use super::*;
 ///
 /// # Returns
 /// A `DrumSample` with configured sample buffers, amplitude expressions, and effect parameters.
-pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn stemmy<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   // Dynamically retrieve a percussion sample file path
-  let sample_path = get_sample_path(arf);
+  let sample_path = get_sample_path(arf)?;
 
   // Read the audio sample from the retrieved path
-  let (ref_samples, sample_rate) = read_audio_file(&sample_path).expect("Failed to read percussion sample");
+  let (ref_samples, sample_rate) = read_sample(&sample_path)?;
   let gain = visibility_gain_sample(arf.visibility);
   let amp_expr = dynamics::gen_organic_amplitude(10, 2000, arf.visibility).iter().map(|v| v * gain).collect();
 
@@ -26,7 +28,7 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
   if let Visibility::Foreground = arf.visibility {
     // Generate delay macros for the percussion stem
     let delay_macros = generate_delay_macros(arf.visibility, arf.energy, arf.presence);
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     delays_note = delay_macros.iter().map(|mac| mac.gen(&mut rng, conf.cps)).collect();
 
     // Manually define reverb parameters for the percussion stem
@@ -34,11 +36,11 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
   }
 
   // Set lowpass cutoff frequency based on energy level
-  let lowpass_cutoff = NFf;
+  let lowpass_cutoff = NFf();
   let ref_sample = ref_samples[0].to_owned();
 
   // Return the renderable sample
-  Renderable2::Sample((
+  Ok(Renderable2::Sample((
     melody,
     ref_sample,
     amp_expr,
@@ -47,11 +49,13 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
     vec![],
     vec![],
     reverbs_room,
-  ))
+  )))
 }
 
 /// mix of three different percs.
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   stemmy(conf, melody, arf)
 }
 
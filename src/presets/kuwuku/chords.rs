@@ -91,7 +91,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let soids_tonal = druidic_soids::under_square(2f32.powi(10i32));
   let modifiers_tonal: ModifiersHolder = (vec![], vec![], vec![], vec![]);
   let feel_tonal: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: modifiers_tonal,
     clippers: (0f32, 1f32),
   };
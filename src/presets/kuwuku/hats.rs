@@ -52,7 +52,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let soids_noise = druidic_soids::noise(1024f32, druidic_soids::NoiseType::Pink);
   let modifiers_noise: ModifiersHolder = (vec![], vec![], vec![], vec![]);
   let feel_noise: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: modifiers_noise,
     clippers: (0f32, 1f32),
   };
@@ -73,7 +73,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let soids_tonal = druidic_soids::under_square(2f32.powi(10i32));
   let modifiers_tonal: ModifiersHolder = (vec![], vec![], vec![], vec![]);
   let feel_tonal: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: modifiers_tonal,
     clippers: (0f32, 1f32),
   };
@@ -149,7 +149,7 @@ mod a2 {
     // Noise component
     let soids_noise = druidic_soids::noise(1024f32, druidic_soids::NoiseType::Pink);
     let modifiers_noise: ModifiersHolder = (vec![], vec![], vec![], vec![]);
-    let bp2 = (vec![MFf], vec![NFf], vec![]);
+    let bp2 = (vec![MFf], vec![NFf()], vec![]);
 
     let mut knob_mods_noise: KnobMods2 = KnobMods2::unit();
     knob_mods_noise.0.push(amp_knob_noise());
@@ -168,7 +168,7 @@ mod a2 {
     // Tonal component
     let soids_tonal = druidic_soids::under_square(2f32.powi(10i32));
     let modifiers_tonal: ModifiersHolder = (vec![], vec![], vec![], vec![]);
-    let bp2 = (vec![MFf], vec![NFf], vec![]);
+    let bp2 = (vec![MFf], vec![NFf()], vec![]);
 
     let mut knob_mods_tonal: KnobMods2 = KnobMods2::unit();
     knob_mods_tonal.0.push(amp_knob_tonal());
@@ -113,7 +113,7 @@ pub fn driad(arf: &Arf) -> Ely {
 pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderable<'render> {
   let ely = driad(arf);
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: ely.modders,
     clippers: (0f32, 1f32),
   };
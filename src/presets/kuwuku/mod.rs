@@ -5,3 +5,16 @@ pub mod hats;
 pub mod kick;
 pub mod lead;
 pub mod perc;
+
+/// The roles of this pack, written for the original `Renderable` pipeline. See `renderable2`.
+pub fn map_role_preset<'render>() -> RolePreset<'render> {
+  RolePreset {
+    label: "kuwuku",
+    kick: |_, melody, arf| Ok(renderable2(kick::renderable(melody, arf))),
+    perc: |_, melody, arf| Ok(renderable2(perc::renderable(melody, arf))),
+    hats: |_, melody, arf| Ok(renderable2(hats::renderable(melody, arf))),
+    chords: |_, melody, arf| Ok(renderable2(chords::renderable(melody, arf))),
+    lead: |_, melody, arf| Ok(renderable2(lead::renderable(melody, arf))),
+    bass: |_, melody, arf| Ok(renderable2(bass::renderable(melody, arf))),
+  }
+}
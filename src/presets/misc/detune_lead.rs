@@ -27,7 +27,7 @@ pub fn renderable<'render>(melody:&'render Melody<Note>, arf:&Arf) -> Renderable
     );
 
     let feel_id:Feel = Feel {
-        bp: (vec![MFf], vec![NFf]),
+        bp: (vec![MFf], vec![NFf()]),
         modifiers: modifiers_id,
         clippers: (0f32, 1f32)
     };
@@ -35,7 +35,7 @@ pub fn renderable<'render>(melody:&'render Melody<Note>, arf:&Arf) -> Renderable
     let mut knob_mods:KnobMods = KnobMods::unit();
     // knob_mods.0.push(knob_amp());
 
-    let soids = soid_fx::detune::reece(&druidic_soids::id(), 12, 0.25f32);
+    let soids = soid_fx::fmod::reece(&druidic_soids::id(), 0.25f32, 12);
     let soids = soid_fx::map(&soids, 3, vec![
         (soid_fx::fmod::triangle, 0.11f32),
     ]);
@@ -0,0 +1,16 @@
+use super::*;
+pub mod detune_lead;
+pub mod pluck_bass;
+pub mod rave_house;
+
+/// Single instruments written for the original `Renderable` pipeline, see `renderable2`.
+/// The roles without one are those of `hop`.
+pub fn map_role_preset<'render>() -> RolePreset<'render> {
+  RolePreset {
+    label: "misc",
+    kick: |_, melody, arf| Ok(renderable2(rave_house::renderable(melody, arf))),
+    lead: |_, melody, arf| Ok(renderable2(detune_lead::renderable(melody, arf))),
+    bass: |_, melody, arf| Ok(renderable2(pluck_bass::renderable(melody, arf))),
+    ..hop::map_role_preset()
+  }
+}
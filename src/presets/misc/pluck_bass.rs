@@ -23,7 +23,7 @@ pub fn renderable<'render>(melody:&'render Melody<Note>, arf:&Arf) -> Renderable
     );
 
     let feel_id:Feel = Feel {
-        bp: (vec![MFf], vec![NFf]),
+        bp: (vec![MFf], vec![NFf()]),
         modifiers: modifiers_id,
         clippers: (0f32, 1f32)
     };
@@ -31,7 +31,7 @@ pub fn stem_visible<'render>(arf:&Arf, melody:&'render Melody<Note>) -> Stem<'re
     
     let expr = (vec![visibility_gain(Visibility::Visible)], vec![1f32], vec![0f32]);
     let feel:Feel = Feel {
-        bp: (vec![MFf], vec![NFf]),
+        bp: (vec![MFf], vec![NFf()]),
         modifiers: (
             vec![],
             vec![],
@@ -62,7 +62,7 @@ pub fn stem_foreground<'render>(arf:&Arf, melody:&'render Melody<Note>) -> Stem<
     ]);
     let expr = (vec![visibility_gain(Visibility::Background)], vec![1f32], vec![0f32]);
     let feel:Feel = Feel {
-        bp: (vec![MFf], vec![NFf]),
+        bp: (vec![MFf], vec![NFf()]),
         modifiers: (
             vec![],
             vec![],
@@ -95,7 +95,7 @@ pub fn stem_hidden<'render>(arf:&Arf, melody:&'render Melody<Note>) -> Stem<'ren
     let soids = druidic_soids::octave(500f32);
     let expr = (vec![visibility_gain(Visibility::Hidden)], vec![1f32], vec![0f32]);
     let feel:Feel = Feel {
-        bp: (vec![MFf], vec![NFf]),
+        bp: (vec![MFf], vec![NFf()]),
         modifiers: (
             vec![],
             vec![],
@@ -13,13 +13,15 @@ use crate::synth::{pi, pi2, MFf, NFf, SRf, SampleBuffer, MAX_REGISTER, MIN_REGIS
 use crate::types::render::{ArfFM, StemFM};
 use crate::{render, AmpLifespan};
 use rand;
-use rand::{prelude::SliceRandom, rngs::ThreadRng, Rng};
+use rand::{prelude::SliceRandom, Rng};
+use crate::seed::ThreadRng;
 use std::{marker::PhantomData, os::unix::thread};
 
 use crate::analysis::tools::{compressor, compute_rms, expander, CompressorParams, ExpanderParams};
 
 use crate::analysis::delay::{self, DelayParams, DelayParamsMacro, StereoField};
-use crate::analysis::sampler::read_audio_file;
+use crate::analysis::sampler::{read_audio_file, resample_linear};
+use crate::error::RaudioError;
 use crate::druid::{self, noise::NoiseColor, soid_fx, soids as druidic_soids};
 use crate::druid::{bell, melodic, noise, Element, Elementor};
 use crate::phrasing::contour::expr_none;
@@ -33,19 +35,22 @@ use crate::types::render::{Conf, Feel, Melody, Stem, Stem2, DrumSample};
 use crate::types::synthesis::{
   bp2_unit, BoostGroup, Bp2, Direction, Ely, Freq, ModulationEffect, Note, PhaseModParams,
 };
-use crate::types::synthesis::{BoostGroupMacro, MacroMotion, ModifiersHolder, Soids};
+use crate::types::synthesis::{BoostGroupMacro, Glide, Groove, MacroMotion, ModifiersHolder, Soids};
 use crate::types::timbre::{Arf, Energy, Mode, Phrasing, Presence, Role, Sound, Sound2, Visibility};
 use crate::types::{Radian, Range};
-use rand::thread_rng;
+use crate::seed::thread_rng;
 use std::fs::read_dir;
 
 pub mod ambien;
 pub mod bland;
 pub mod bright;
 pub mod fum;
+pub mod hill;
 pub mod hop;
 pub mod kuwuku;
+pub mod misc;
 pub mod mountain;
+pub mod pack;
 pub mod urbuntu;
 pub mod valley;
 
@@ -55,7 +60,9 @@ use std::collections::HashMap;
 /// Base directory for audio samples.
 const SAMPLE_SOURCE_DIR: &str = "audio-samples";
 /// Cache for sample paths to avoid repeated directory scans.
-static SAMPLE_CACHE: Lazy<RwLock<HashMap<String, Vec<String>>>> = Lazy::new(|| RwLock::new(initialize_sample_cache()));
+/// A category whose directory could not be read keeps the reason, reported when a part asks for it.
+static SAMPLE_CACHE: Lazy<RwLock<HashMap<String, Result<Vec<String>, String>>>> =
+  Lazy::new(|| RwLock::new(initialize_sample_cache()));
 
 // user configurable headroom value. defaults to -15Db
 pub const DB_HEADROOM: f32 = -3f32;
@@ -88,20 +95,22 @@ pub fn simple_tacet<'render>(melody: &'render Melody<Note>) -> Renderable2<'rend
 ///
 /// # Returns
 /// A `DrumSample` with configured sample buffers, amplitude expressions, and effect parameters.
-pub fn simple_stem<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
-  let sample_path = get_sample_path(arf);
+pub fn simple_stem<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
+  let sample_path = get_sample_path(arf)?;
 
-  let (ref_samples, sample_rate) = read_audio_file(&sample_path).expect("Failed to read percussion sample");
+  let (ref_samples, sample_rate) = read_sample(&sample_path)?;
   let gain = visibility_gain_sample(arf.visibility);
   let amp_expr = vec![1f32];
 
   let mut delays_note = vec![];
   let mut reverbs_room = vec![];
 
-  let lowpass_cutoff = NFf;
+  let lowpass_cutoff = NFf();
   let ref_sample = ref_samples[0].to_owned();
 
-  Renderable2::Sample((
+  Ok(Renderable2::Sample((
     melody,
     ref_sample,
     amp_expr,
@@ -110,7 +119,7 @@ pub fn simple_stem<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Ar
     vec![],
     vec![],
     reverbs_room,
-  ))
+  )))
 }
 
 /// Returns a `DrumSample` for the percussion preset.
@@ -122,16 +131,18 @@ pub fn simple_stem<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Ar
 ///
 /// # Returns
 /// A `DrumSample` with configured sample buffers, amplitude expressions, and effect parameters.
-pub fn contour_stem<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
-  let sample_path = get_sample_path(arf);
+pub fn contour_stem<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
+  let sample_path = get_sample_path(arf)?;
 
-  let (ref_samples, sample_rate) = read_audio_file(&sample_path).expect("Failed to read percussion sample");
+  let (ref_samples, sample_rate) = read_sample(&sample_path)?;
   let amp_expr = vec![1f32];
 
   let mut delays_note = vec![];
   let mut reverbs_room = vec![];
 
-  let lowpass_cutoff = NFf;
+  let lowpass_cutoff = NFf();
   let mut rng = thread_rng();
 
   let stem = ref_samples[0].to_owned();
@@ -140,14 +151,10 @@ pub fn contour_stem<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &A
   let compressor_params = gen_beat_compressor(&mut rng, arf);
 
   let normalized = rescale_amplitude(0.5f32, &stem);
-  let stem = compressor(
-    &expander(&normalized, expander_params, None).unwrap(),
-    compressor_params,
-    None,
-  )
-  .unwrap();
+  let expanded = expander(&normalized, expander_params, None).map_err(RaudioError::Dynamics)?;
+  let stem = compressor(&expanded, compressor_params, None).map_err(RaudioError::Dynamics)?;
 
-  Renderable2::Sample((
+  Ok(Renderable2::Sample((
     melody,
     rescale_amplitude(target_rms, &stem),
     amp_expr,
@@ -156,7 +163,7 @@ pub fn contour_stem<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &A
     vec![],
     vec![],
     reverbs_room,
-  ))
+  )))
 }
 
 pub fn gen_beat_compressor(rng: &mut ThreadRng, arf: &Arf) -> CompressorParams {
@@ -226,31 +233,28 @@ pub fn gen_beat_expander(rng: &mut ThreadRng, arf: &Arf) -> ExpanderParams {
 /// A `DrumSample` with configured sample buffers, amplitude expressions, and effect parameters.
 pub fn simple_stem_at<'render>(
   conf: &Conf, melody: &'render Melody<Note>, arf: &Arf, index: usize,
-) -> Renderable2<'render> {
-  if let Ok(sample_path) = get_sample_path_by_index(arf, index, true) {
-    let (ref_samples, sample_rate) = read_audio_file(&sample_path).expect("Failed to read percussion sample");
-    let gain = visibility_gain_sample(arf.visibility);
-    let amp_expr = vec![1f32];
-
-    let mut delays_note = vec![];
-    let mut reverbs_room = vec![];
-
-    let lowpass_cutoff = NFf;
-    let ref_sample = ref_samples[0].to_owned();
-
-    Renderable2::Sample((
-      melody,
-      ref_sample,
-      amp_expr,
-      lowpass_cutoff,
-      delays_note,
-      vec![],
-      vec![],
-      reverbs_room,
-    ))
-  } else {
-    panic!("Unable to get the stem for arf {:?} at index {}", arf, index)
-  }
+) -> Result<Renderable2<'render>, RaudioError> {
+  let sample_path = get_sample_path_by_index(arf, index, true)?;
+  let (ref_samples, sample_rate) = read_sample(&sample_path)?;
+  let gain = visibility_gain_sample(arf.visibility);
+  let amp_expr = vec![1f32];
+
+  let mut delays_note = vec![];
+  let mut reverbs_room = vec![];
+
+  let lowpass_cutoff = NFf();
+  let ref_sample = ref_samples[0].to_owned();
+
+  Ok(Renderable2::Sample((
+    melody,
+    ref_sample,
+    amp_expr,
+    lowpass_cutoff,
+    delays_note,
+    vec![],
+    vec![],
+    reverbs_room,
+  )))
 }
 
 pub trait Conventions<'render> {
@@ -325,13 +329,13 @@ fn bp_cresc<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
     Energy::High => 2f32,
   };
   let ((lowest_register, low_index), (highest_register, high_index)) = find_reach(mel);
-  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR;
+  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR();
 
   let (highpass, lowpass): (Vec<f32>, Vec<f32>) = if let Visibility::Visible = arf.visibility {
     match arf.energy {
       Energy::Low => (
         filter_contour_triangle_shape_highpass(lowest_register, highest_register, n_samples, size * rate_per_size),
-        vec![NFf],
+        vec![NFf()],
       ),
       _ => (
         vec![MFf],
@@ -339,7 +343,7 @@ fn bp_cresc<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
       ),
     }
   } else {
-    (vec![MFf], vec![NFf])
+    (vec![MFf], vec![NFf()])
   };
 
   let levels = Levels::new(0.7f32, 4f32, 0.5f32);
@@ -362,7 +366,7 @@ fn bp_wah<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
     Energy::High => 2f32,
   };
   let ((lowest_register, low_index), (highest_register, high_index)) = find_reach(mel);
-  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR;
+  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR();
 
   let level_macro: LevelMacro = LevelMacro {
     stable: match arf.energy {
@@ -426,7 +430,7 @@ fn bp_sighpad<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
     Energy::High => 2f32,
   };
   let ((lowest_register, low_index), (highest_register, high_index)) = find_reach(mel);
-  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR;
+  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR();
   let levels = Levels::new(0.7f32, 4f32, 0.5f32);
   let level_macro: LevelMacro = LevelMacro {
     stable: [1f32, 1f32],
@@ -564,8 +568,57 @@ use std::fmt;
 
 /// Composition and orchestration details required to generate audio samples.
 /// Returns a Renderable: A collection of Stems to pass into the render engine.
-type StemSpec<'render> =
-  fn(&Conf, &'render Vec<Vec<((i32, i32), (i8, (i8, i8, i8)), f32)>>, &'render Arf) -> Renderable2<'render>;
+type StemSpec<'render> = fn(
+  &Conf,
+  &'render Vec<Vec<((i32, i32), (i8, (i8, i8, i8)), f32)>>,
+  &'render Arf,
+) -> Result<Renderable2<'render>, RaudioError>;
+
+/// A renderable of the original `Renderable` pipeline, as `Preset::create_stem` renders it.
+///
+/// Each knob keeps the value it was drawn with, the bandpass gets no boosts and the delays still echo each note.
+/// The legacy presets leave the modifiers of their `Feel` empty and clip at full scale, as `Stem2` does.
+pub fn renderable2(renderable: Renderable) -> Renderable2 {
+  match renderable {
+    Renderable::Instance(stem) => Renderable2::Instance(stem2(stem)),
+    Renderable::Group(stems) => Renderable2::Group(stems.into_iter().map(stem2).collect()),
+    Renderable::Tacet(stem) => Renderable2::Tacet(stem2(stem)),
+  }
+}
+
+fn stem2((melody, soids, expr, feel, knob_mods, delays): Stem) -> Stem2 {
+  let constant = |knobs: Vec<ranger::KnobbedRanger>| -> Vec<KnobPair> {
+    knobs
+      .into_iter()
+      .map(|(knob, ranger)| {
+        (
+          KnobMacro {
+            a: [knob.a, knob.a],
+            b: [knob.b, knob.b],
+            c: [knob.c, knob.c],
+            ma: MacroMotion::Constant,
+            mb: MacroMotion::Constant,
+            mc: MacroMotion::Constant,
+          },
+          ranger,
+        )
+      })
+      .collect()
+  };
+  let KnobMods(amp, freq, phase) = knob_mods;
+  let (highpass, lowpass) = feel.bp;
+  (
+    melody,
+    soids,
+    expr,
+    (highpass, lowpass, vec![]),
+    KnobMods2(constant(amp), constant(freq), constant(phase)),
+    delays,
+    vec![],
+    vec![],
+    vec![],
+  )
+}
 
 /// Struct that defines render methods for each role.
 pub struct RolePreset<'render> {
@@ -587,36 +640,107 @@ pub enum Preset {
   Bland,
   Bright,
   Fum,
+  Ambien,
+  Kuwuku,
+  Urbuntu,
+  Hill,
+  Misc,
+  /// A pack loaded from a file. See `pack`.
+  Pack(&'static pack::Pack),
 }
 
 impl fmt::Display for Preset {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "{}", format!("{:?}", self).to_lowercase())
+    match self {
+      Preset::Pack(pack) => write!(f, "{}", pack.name),
+      _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
+    }
   }
 }
 
 impl<'render> Preset {
+  /// Every preset that can render a score.
+  pub const ALL: [Preset; 11] = [
+    Preset::Valley,
+    Preset::Mountain,
+    Preset::Hop,
+    Preset::Bland,
+    Preset::Bright,
+    Preset::Fum,
+    Preset::Ambien,
+    Preset::Kuwuku,
+    Preset::Urbuntu,
+    Preset::Hill,
+    Preset::Misc,
+  ];
+
+  /// Find a preset or a loaded pack by its case-insensitive name.
+  pub fn from_name(name: &str) -> Option<Preset> {
+    let name = name.to_lowercase();
+    Self::ALL.iter().copied().find(|preset| preset.to_string() == name).or_else(|| pack::find(&name).map(Preset::Pack))
+  }
+
+  /// Identifies what a preset sounds like, for caches of rendered parts.
+  /// A pack changes its key when its file is edited.
+  pub fn key(&self) -> String {
+    match self {
+      Preset::Pack(pack) => format!("{}@{:016x}", pack.name, pack.fingerprint),
+      _ => self.to_string(),
+    }
+  }
+
+  /// Portamento this preset asks for in parts of `role`, unless a playbook part sets its own. See `render::glide`.
+  /// The built-in presets play every note at its own pitch. Packs may glide any role they synthesize.
+  pub fn glide(&self, role: Role) -> Option<Glide> {
+    match self {
+      Preset::Pack(pack) => pack.glide(role),
+      _ => None,
+    }
+  }
+
+  /// Swing and timing this preset asks for in parts of `role`, unless a playbook part sets its own.
+  /// See `render::groove`. The built-in presets play on the grid. Packs may groove any role.
+  pub fn groove(&self, role: Role) -> Option<Groove> {
+    match self {
+      Preset::Pack(pack) => pack.groove(role),
+      _ => None,
+    }
+  }
+
   /// Returns the `RolePreset` associated with the given `Preset`.
+  /// A pack returns its base, which renders the roles it leaves out.
   pub fn get(preset: Preset) -> RolePreset<'render> {
     match preset {
+      Preset::Pack(pack) => Self::get(pack.base),
       Preset::Valley => valley::map_role_preset(),
       Preset::Mountain => mountain::map_role_preset(),
       Preset::Hop => hop::map_role_preset(),
       Preset::Bland => bland::map_role_preset(),
       Preset::Bright => bright::map_role_preset(),
       Preset::Fum => fum::map_role_preset(),
+      Preset::Ambien => ambien::map_role_preset(),
+      Preset::Kuwuku => kuwuku::map_role_preset(),
+      Preset::Urbuntu => urbuntu::map_role_preset(),
+      Preset::Hill => hill::map_role_preset(),
+      Preset::Misc => misc::map_role_preset(),
     }
   }
 
   /// Returns the label of the `Preset`.
   pub fn get_preset_label(preset: Preset) -> &'render str {
-    Self::get(preset).label
+    match preset {
+      Preset::Pack(pack) => &pack.name,
+      _ => Self::get(preset).label,
+    }
   }
 
   /// Renders a melody using the specified `Preset` and `Role`.
   pub fn create_stem(
     conf: &Conf, melody: &'render Melody<Note>, arf: &'render Arf, preset: Preset,
-  ) -> Renderable2<'render> {
+  ) -> Result<Renderable2<'render>, RaudioError> {
+    if let Preset::Pack(pack) = preset {
+      return pack.create_stem(conf, melody, arf);
+    }
     let preset = Self::get(preset);
     let render_fn = match arf.role {
       Role::Kick => preset.kick,
@@ -693,7 +817,7 @@ fn select_expr(arf: &Arf) -> Expr {
     },
   };
 
-  let amp_contour: Vec<f32> = crate::phrasing::lifespan::sample_lifespan(crate::synth::SR, lifespan, 1, 1f32);
+  let amp_contour: Vec<f32> = crate::phrasing::lifespan::sample_lifespan(crate::synth::SR(), lifespan, 1, 1f32);
   (amp_contour, vec![1f32], vec![0f32])
 }
 
@@ -710,7 +834,7 @@ pub fn microtransient_chiff(fund: f32, vis: &Visibility, energy: &Energy, presen
     phss,
     modders: micro::modders_chiff(),
     expr: expr_none(),
-    hplp: (vec![MFf], vec![NFf]),
+    hplp: (vec![MFf], vec![NFf()]),
     thresh: (0f32, 1f32),
   }
 }
@@ -724,7 +848,7 @@ pub fn microtransient_click(fund: f32, vis: &Visibility, energy: &Energy, presen
     phss,
     modders: micro::modders_chiff(),
     expr: expr_none(),
-    hplp: (vec![MFf], vec![NFf]),
+    hplp: (vec![MFf], vec![NFf()]),
     thresh: (0f32, 1f32),
   }
 }
@@ -738,7 +862,7 @@ pub fn microtransient_pop(fund: f32, vis: &Visibility, energy: &Energy, presence
     phss,
     modders: micro::modders_chiff(),
     expr: expr_none(),
-    hplp: (vec![MFf], vec![NFf]),
+    hplp: (vec![MFf], vec![NFf()]),
     thresh: (0f32, 1f32),
   }
 }
@@ -795,6 +919,30 @@ pub fn amp_scale(cont: &mut Vec<f32>, gain: f32) {
 
 use once_cell::sync::Lazy;
 use std::sync::RwLock;
+/// Directory of the sample category used for a part.
+fn sample_category(arf: &Arf) -> Result<String, RaudioError> {
+  match arf.role {
+    Role::Hats => match arf.presence {
+      Presence::Staccatto | Presence::Legato => Ok(format!("{}/hats/short", SAMPLE_SOURCE_DIR)),
+      Presence::Tenuto => Ok(format!("{}/hats/long", SAMPLE_SOURCE_DIR)),
+    },
+    Role::Kick => Ok(format!("{}/kick", SAMPLE_SOURCE_DIR)),
+    Role::Perc => Ok(format!("{}/perc", SAMPLE_SOURCE_DIR)),
+    _ => Err(RaudioError::MissingSample(format!("no samples are provided for role {}", arf.role))),
+  }
+}
+
+/// Sample paths available for a part.
+fn sample_paths(arf: &Arf) -> Result<Vec<String>, RaudioError> {
+  let key = sample_category(arf)?;
+  let cache = SAMPLE_CACHE.read().map_err(|_| RaudioError::MissingSample("the sample cache is unavailable".to_string()))?;
+  match cache.get(&key) {
+    Some(Ok(paths)) if !paths.is_empty() => Ok(paths.clone()),
+    Some(Err(reason)) => Err(RaudioError::MissingSample(format!("cannot read {}: {}", key, reason))),
+    _ => Err(RaudioError::MissingSample(format!("no samples found in {}", key))),
+  }
+}
+
 /// Retrieves a sample file path based on the given `Arf` configuration.
 ///
 /// # Parameters
@@ -802,69 +950,50 @@ use std::sync::RwLock;
 ///
 /// # Returns
 /// A randomly selected file path from the appropriate category.
-pub fn get_sample_path(arf: &Arf) -> String {
-  let key = match arf.role {
-    Role::Hats => match arf.presence {
-      Presence::Staccatto | Presence::Legato => format!("{}/hats/short", SAMPLE_SOURCE_DIR),
-      Presence::Tenuto => format!("{}/hats/long", SAMPLE_SOURCE_DIR),
-    },
-    Role::Kick => format!("{}/kick", SAMPLE_SOURCE_DIR),
-    Role::Perc => format!("{}/perc", SAMPLE_SOURCE_DIR),
-    _ => panic!("No samples provided for role: {}", arf.role),
-  };
-
-  // Access the cache
-  let cache = SAMPLE_CACHE.read().expect("Failed to read SAMPLE_CACHE");
-
-  // Retrieve the list of paths for the category
-  if let Some(paths) = cache.get(&key) {
-    paths.choose(&mut rand::thread_rng()).expect("No samples available in category").clone()
-  } else {
-    panic!("Role not found in cache: {}", arf.role);
-  }
+pub fn get_sample_path(arf: &Arf) -> Result<String, RaudioError> {
+  let paths = sample_paths(arf)?;
+  Ok(paths.choose(&mut crate::seed::thread_rng()).unwrap().clone())
 }
 
 /// Retrieves a sample file path based on the given `Arf` configuration.
 ///
 /// # Parameters
 /// - `arf`: The amplitude and visibility configuration.
+/// - `index`: Position of the sample in its category.
+/// - `wraparound`: When true, an index past the end of the category starts again from the first sample.
 ///
 /// # Returns
-/// A randomly selected file path from the appropriate category.
-pub fn get_sample_path_by_index(arf: &Arf, index: usize, wraparound: bool) -> Result<String, String> {
-  let key = match arf.role {
-    Role::Hats => match arf.presence {
-      Presence::Staccatto | Presence::Legato => format!("{}/hats/short", SAMPLE_SOURCE_DIR),
-      Presence::Tenuto => format!("{}/hats/long", SAMPLE_SOURCE_DIR),
-    },
-    Role::Kick => format!("{}/kick", SAMPLE_SOURCE_DIR),
-    Role::Perc => format!("{}/perc", SAMPLE_SOURCE_DIR),
-    _ => panic!("No samples provided for role: {}", arf.role),
-  };
-
-  // Access the cache
-  let cache = SAMPLE_CACHE.read().expect("Failed to read SAMPLE_CACHE");
-
-  // Retrieve the list of paths for the category
-  if let Some(paths) = cache.get(&key) {
-    if !wraparound && index > paths.len() {
-      return Err(format!(
-        "Attempted to access {} sample at unavailable index {}",
-        key, index
-      ));
-    }
+/// The file path at `index` in the appropriate category.
+pub fn get_sample_path_by_index(arf: &Arf, index: usize, wraparound: bool) -> Result<String, RaudioError> {
+  let paths = sample_paths(arf)?;
+  if !wraparound && index >= paths.len() {
+    return Err(RaudioError::MissingSample(format!(
+      "{} has no sample at index {}",
+      sample_category(arf)?,
+      index
+    )));
+  }
+  Ok(paths[index % paths.len()].clone())
+}
 
-    Ok(paths[index].clone())
-  } else {
-    panic!("Role not found in cache: {}", arf.role);
+/// Read a sample file with `read_audio_file`, describing a failure with the sample path.
+/// The sample is converted to the render sample rate.
+pub fn read_sample(path: &str) -> Result<(Vec<Vec<f32>>, u32), RaudioError> {
+  let (channels, sample_rate) =
+    read_audio_file(path).map_err(|e| RaudioError::Sample(path.to_string(), e.to_string()))?;
+  if sample_rate as usize == SR() {
+    return Ok((channels, sample_rate));
   }
+  let channels = channels.iter().map(|ch| resample_linear(ch, sample_rate as usize, SR())).collect();
+  Ok((channels, SR() as u32))
 }
 
 /// Initializes the sample cache by scanning the audio-sample directories.
 ///
 /// # Returns
-/// A `HashMap` where keys are categories (e.g., "kick", "hats-short") and values are vectors of file paths.
-fn initialize_sample_cache() -> HashMap<String, Vec<String>> {
+/// A `HashMap` where keys are categories (e.g., "kick", "hats-short") and values are vectors of file paths,
+/// or why the directory of the category could not be read.
+fn initialize_sample_cache() -> HashMap<String, Result<Vec<String>, String>> {
   let mut cache = HashMap::new();
 
   let categories = vec![
@@ -875,17 +1004,25 @@ fn initialize_sample_cache() -> HashMap<String, Vec<String>> {
   ];
 
   for category in categories {
-    let paths = read_dir(&category)
-      .expect(&format!("Failed to read directory: {}", category))
-      .filter_map(|entry| entry.ok())
-      .filter_map(|entry| entry.path().to_str().map(String::from))
-      .collect();
+    let paths = list_samples(&category);
     cache.insert(category, paths);
   }
 
   cache
 }
 
+/// The sorted paths of the files in `dir`, or why it could not be read.
+fn list_samples(dir: &str) -> Result<Vec<String>, String> {
+  let mut paths: Vec<String> = read_dir(dir)
+    .map_err(|e| e.to_string())?
+    .filter_map(|entry| entry.ok())
+    .filter_map(|entry| entry.path().to_str().map(String::from))
+    .collect();
+  // directory listing order is filesystem dependent; sort it so seeded renders pick the same samples
+  paths.sort();
+  Ok(paths)
+}
+
 /// Create bandpass automations with respect to Arf and Melody
 /// animation_duration_basis determines how long (in cycles) the effect lasts per-note.
 fn bp_bark<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf, animation_duration_basis: f32) -> Bp2 {
@@ -962,3 +1099,41 @@ pub fn get_rescale_target(rng: &mut ThreadRng, visibility: Visibility) -> f32 {
     Visibility::Hidden => in_range(rng, db_to_amp(-60.0), db_to_amp(-52.0)),
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_unreadable_sample_directory_is_an_error() {
+    assert!(list_samples(&format!("{}/no-such-category", SAMPLE_SOURCE_DIR)).is_err());
+  }
+
+  #[test]
+  fn test_legacy_presets_render_every_role() {
+    let score = crate::inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+    for preset in [Preset::Ambien, Preset::Kuwuku, Preset::Urbuntu, Preset::Hill, Preset::Misc] {
+      for (_, arf, melody) in &score.parts {
+        assert!(
+          Preset::create_stem(&score.conf, melody, arf, preset).is_ok(),
+          "{} must render {}",
+          preset,
+          arf.role
+        );
+      }
+    }
+
+    let (_, arf, melody) = score.parts.iter().find(|(_, arf, _)| matches!(arf.role, Role::Perc)).unwrap();
+    let legacy = kuwuku::perc::renderable(melody, arf);
+    let (Renderable::Instance(stem), Renderable2::Instance(ported)) = (legacy.clone(), renderable2(legacy)) else {
+      panic!("Must port an instance to an instance")
+    };
+    assert_eq!(stem.4 .0.len(), ported.4 .0.len());
+    for ((knob, _), (knob_macro, _)) in stem.4 .0.iter().zip(&ported.4 .0) {
+      assert_eq!([knob.a, knob.a], knob_macro.a, "Must keep the knob values of the legacy stem");
+      assert_eq!([knob.b, knob.b], knob_macro.b);
+    }
+    assert_eq!(stem.3.bp, (ported.3 .0, ported.3 .1));
+    assert_eq!(stem.5.len(), ported.5.len(), "Must echo each note with the legacy delays");
+  }
+}
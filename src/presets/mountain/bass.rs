@@ -115,10 +115,12 @@ fn amp_knob_principal(rng: &mut ThreadRng, arf: &Arf) -> KnobPair {
   )
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mut rng = thread_rng();
   let len_cycles: f32 = time::count_cycles(&melody[0]);
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut knob_mods: KnobMods2 = KnobMods2::unit();
 
@@ -166,5 +168,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Group(vec![stem])
+  Ok(Renderable2::Group(vec![stem]))
 }
@@ -221,7 +221,9 @@ fn dynamics(arf: &Arf, n_samples: usize, k: f32) -> SampleBuffer {
   dynamp_contour
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   // 8 is the optimal value for high energy because using 7 often has the same appearance but costs 2x more
   // 10 is clearly different than 8
   // 12 is clearly different than 10
@@ -264,7 +266,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   knob_mods.0.push(amp_onset(arf.visibility, arf.energy, arf.presence));
   knob_mods.0.push(amp_knob_presence(arf.visibility, arf.energy, arf.presence));
   knob_mods.2.push(pmod_chorus(arf.visibility, arf.energy, arf.presence));
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut dynamics = dynamics::gen_organic_amplitude(10, n_samples, arf.visibility);
   amp_scale(&mut dynamics, visibility_gain(arf.visibility));
@@ -293,5 +295,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Instance(stem)
+  Ok(Renderable2::Instance(stem))
 }
@@ -9,10 +9,12 @@ use super::*;
 ///
 /// # Returns
 /// A `DrumSample` with configured sample buffers, amplitude expressions, and effect parameters.
-pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
-  let sample_path = get_sample_path(arf);
+pub fn stemmy<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
+  let sample_path = get_sample_path(arf)?;
 
-  let (ref_samples, sample_rate) = read_audio_file(&sample_path).expect("Failed to read hats sample");
+  let (ref_samples, sample_rate) = read_sample(&sample_path)?;
   let gain = visibility_gain_sample(arf.visibility);
   let amp_expr = dynamics::gen_organic_amplitude(10, 2000, arf.visibility).iter().map(|v| v * gain).collect();
 
@@ -21,17 +23,17 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
 
   if let Visibility::Foreground = arf.visibility {
     let delay_macros = generate_delay_macros(arf.visibility, arf.energy, arf.presence);
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     delays_note = delay_macros.iter().map(|mac| mac.gen(&mut rng, conf.cps)).collect();
 
     reverbs_note = vec![];
   }
   let mut rng = thread_rng();
 
-  let lowpass_cutoff = NFf;
+  let lowpass_cutoff = NFf();
   let ref_sample = ref_samples[0].to_owned();
 
-  Renderable2::Sample((
+  Ok(Renderable2::Sample((
     melody,
     ref_sample,
     amp_expr,
@@ -40,16 +42,18 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
     vec![],
     reverbs_note,
     vec![],
-  ))
+  )))
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   // Return the renderable sample
-  Renderable2::Mix(vec![
-    (0.3, stemmy(conf, melody, arf)),
-    (0.3, crate::presets::valley::hats::renderable(conf, melody, arf)),
-    (0.2, stemmy(conf, melody, arf)),
-  ])
+  Ok(Renderable2::Mix(vec![
+    (0.3, stemmy(conf, melody, arf)?),
+    (0.3, crate::presets::valley::hats::renderable(conf, melody, arf)?),
+    (0.2, stemmy(conf, melody, arf)?),
+  ]))
 }
 
 /// Generates a set of delay macros for hats in house music.
@@ -2,7 +2,9 @@ use std::os::unix::thread;
 
 use super::*;
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let soids = druidic_soids::upto(2);
 
   let expr = (
@@ -101,7 +103,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   let reverbs_note: Vec<ReverbParams> = vec![];
   let reverbs_room: Vec<ReverbParams> = vec![];
 
-  Renderable2::Instance((
+  Ok(Renderable2::Instance((
     melody,
     soids,
     expr,
@@ -111,5 +113,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     delays_room,
     reverbs_note,
     reverbs_room,
-  ))
+  )))
 }
@@ -316,7 +316,9 @@ fn gen_delays(visibility: Visibility, energy: Energy, presence: Presence, cps: f
   delays
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mullet = match arf.energy {
     Energy::Low => 2f32.powi(11i32),
     Energy::Medium => 2f32.powi(9i32),
@@ -339,7 +341,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   knob_mods.2.push(pmod_chorus(arf.visibility, arf.energy, arf.presence));
 
   let len_cycles: f32 = time::count_cycles(&melody[0]);
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut dynamics = dynamics::gen_organic_amplitude(10, n_samples, arf.visibility);
   amp_scale(&mut dynamics, visibility_gain(arf.visibility));
@@ -363,5 +365,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
     reverbs_room,
   );
 
-  Renderable2::Instance(stem)
+  Ok(Renderable2::Instance(stem))
 }
@@ -52,6 +52,7 @@ fn gen_slapback(cps: f32, rng: &mut ThreadRng, complexity: f32) -> DelayParams {
   DelayParams {
     mix: 0.5f32,
     len_seconds,
+    len_cycles: Some(rate),
     n_echoes,
     gain,
     pan,
@@ -78,6 +79,7 @@ fn gen_trailing(cps: f32, rng: &mut ThreadRng, complexity: f32) -> DelayParams {
   DelayParams {
     mix,
     len_seconds,
+    len_cycles: Some(rate),
     n_echoes,
     gain,
     pan: StereoField::Mono,
@@ -94,13 +96,13 @@ fn bp_cresc<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
     Energy::High => 2f32,
   };
   let ((lowest_register, low_index), (highest_register, high_index)) = find_reach(mel);
-  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR;
+  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR();
 
   let (highpass, lowpass): (Vec<f32>, Vec<f32>) = if let Visibility::Visible = arf.visibility {
     match arf.energy {
       Energy::Low => (
         filter_contour_triangle_shape_highpass(lowest_register, highest_register, n_samples, size * rate_per_size),
-        vec![NFf],
+        vec![NFf()],
       ),
       _ => (
         vec![MFf],
@@ -108,7 +110,7 @@ fn bp_cresc<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
       ),
     }
   } else {
-    (vec![MFf], vec![NFf])
+    (vec![MFf], vec![NFf()])
   };
 
   let levels = Levels::new(0.7f32, 4f32, 0.5f32);
@@ -131,7 +133,7 @@ fn bp_wah<'render>(cps: f32, mel: &'render Melody<Note>, arf: &Arf) -> Bp2 {
     Energy::High => 2f32,
   };
   let ((lowest_register, low_index), (highest_register, high_index)) = find_reach(mel);
-  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR;
+  let n_samples: usize = ((len_cycles / 2f32) as usize).max(1) * SR();
 
   let levels = Levels::new(0.7f32, 4f32, 0.5f32);
 
@@ -0,0 +1,693 @@
+/// Preset packs defined in data files, loaded at runtime.
+///
+/// A pack describes each role as one or more layers of synthesized soids, the way the presets in this module
+/// build a `Stem2` in code. Packs are TOML or JSON files:
+///
+/// ```text
+/// name = "glass"
+/// base = "hop"              # renders the roles this pack leaves out, hop by default
+///
+/// [kick]
+/// sample = true             # drum samples from audio-samples
+///
+/// [bass]
+/// glide = { from = "sixteenth", curve = "exponential" }   # portamento, see `render::glide`
+/// groove = { grid = "sixteenth", swing = 58 }              # swing, see `render::groove`
+///
+/// [[bass.layers]]
+/// soids = "overs_sawtooth"
+/// height = { visible = 1024, foreground = 512, background = 256, hidden = 128 }
+/// fx = [{ fx = "fmod.reece", args = [1.5, 7] }]
+/// lifespan = ["pluck", "snap"]
+/// gain = 0.5
+/// bandpass = [40, 4000]
+/// amp = [{ ranger = "amod_pluck2", a = [0.2, 0.5], b = [0.6, 1], ma = ["forward", "constant"] }]
+/// ```
+///
+/// Any value of a layer may instead be given for each level of one of the VEP parameters of the part's arf:
+/// `{ visible, foreground, background, hidden }`, `{ low, medium, high }` or `{ staccatto, legato, tenuto }`.
+/// Lists of motions and lifespans are choices, one is drawn for each render.
+///
+/// Names of soids, soid effects and rangers refer to `druid::soids`, `druid::soid_fx` and `phrasing::ranger`.
+/// Boost groups, delays and reverbs take the fields of `BoostGroupMacro`, `DelayParamsMacro` and `ReverbParams`.
+/// Every name is checked when the pack is loaded, so a typo fails before rendering.
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::fs;
+use std::sync::RwLock;
+
+use super::*;
+use crate::druid::soid_fx;
+use crate::phrasing::ranger::Ranger;
+
+type Generator = fn(f32) -> Soids;
+
+/// Soid generators, taking the highest multiplier of the timbre.
+const SOIDS: [(&str, Generator); 12] = [
+  ("octave", druidic_soids::octave),
+  ("integer_overs", druidic_soids::integer_overs),
+  ("integer_unders", druidic_soids::integer_unders),
+  ("unit", druidic_soids::unit),
+  ("under_octave", druidic_soids::under_octave),
+  ("under_experiement", druidic_soids::under_experiement),
+  ("under_square", druidic_soids::under_square),
+  ("under_sawtooth", druidic_soids::under_sawtooth),
+  ("under_triangle", druidic_soids::under_triangle),
+  ("overs_square", druidic_soids::overs_square),
+  ("overs_triangle", druidic_soids::overs_triangle),
+  ("overs_sawtooth", druidic_soids::overs_sawtooth),
+];
+
+/// Soid effects with the number of arguments they take.
+const FX: [(&str, usize); 15] = [
+  ("ratio.constant", 2),
+  ("ratio.fifth_up", 2),
+  ("ratio.quince_up", 2),
+  ("ratio.dquince_up", 2),
+  ("amod.reece", 1),
+  ("amod.attenuate_bin_k", 1),
+  ("amod.gain", 1),
+  ("fmod.triangle", 1),
+  ("fmod.square", 1),
+  ("fmod.sawtooth", 1),
+  ("fmod.reece", 2),
+  ("fmod.reece2", 1),
+  ("ffilter.greater_than", 1),
+  ("ffilter.less_than", 1),
+  ("pmod.reece_chorus", 2),
+];
+
+const RANGERS: [(&str, Ranger); 40] = [
+  ("amod_noop", ranger::amod_noop),
+  ("fmod_noop", ranger::fmod_noop),
+  ("pmod_noop", ranger::pmod_noop),
+  ("fmod_sweepdown", ranger::fmod_sweepdown),
+  ("fmod_vibrato", ranger::fmod_vibrato),
+  ("fmod_warble", ranger::fmod_warble),
+  ("pmod_chorus", ranger::pmod_chorus),
+  ("pmod_chorus2", ranger::pmod_chorus2),
+  ("pmod_weird", ranger::pmod_weird),
+  ("amod_peak", ranger::amod_peak),
+  ("amod_breath", ranger::amod_breath),
+  ("amod_microtransient_4_20", ranger::amod_microtransient_4_20),
+  ("amod_microtransient_20_100", ranger::amod_microtransient_20_100),
+  ("amod_microbreath_4_20", ranger::amod_microbreath_4_20),
+  ("amod_microbreath_20_100", ranger::amod_microbreath_20_100),
+  ("amod_fadein", ranger::amod_fadein),
+  ("amod_fadeout", ranger::amod_fadeout),
+  ("amod_impulse", ranger::amod_impulse),
+  ("amod_pluck", ranger::amod_pluck),
+  ("amod_cycle_fadein_4_16", ranger::amod_cycle_fadein_4_16),
+  ("amod_cycle_fadein_1_4", ranger::amod_cycle_fadein_1_4),
+  ("amod_cycle_fadein_0p125_1", ranger::amod_cycle_fadein_0p125_1),
+  ("amod_cycle_fadein_0p031_0p125", ranger::amod_cycle_fadein_0p031_0p125),
+  ("amod_pluck3", ranger::amod_pluck3),
+  ("amod_pluck2", ranger::amod_pluck2),
+  ("amod_stab", ranger::amod_stab),
+  ("amod_fall", ranger::amod_fall),
+  ("amod_unit", ranger::amod_unit),
+  ("amod_burp", ranger::amod_burp),
+  ("amod_oscillation_tri", ranger::amod_oscillation_tri),
+  ("amod_oscillation_sin_mul", ranger::amod_oscillation_sin_mul),
+  ("amod_oscillation_sine", ranger::amod_oscillation_sine),
+  ("amod_slowest", ranger::amod_slowest),
+  ("amod_lfo_sine", ranger::amod_lfo_sine),
+  ("amod_wavelet_morphing", ranger::amod_wavelet_morphing),
+  ("amod_stick", ranger::amod_stick),
+  ("amod_seesaw", ranger::amod_seesaw),
+  ("amod_detune", ranger::amod_detune),
+  ("fmod_geo", ranger::fmod_geo),
+  ("amod_collage", ranger::amod_collage),
+];
+
+/// A value that is the same for every part, or depends on one VEP parameter of the part's arf.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Vep<T> {
+  ByVisibility {
+    visible: T,
+    foreground: T,
+    background: T,
+    hidden: T,
+  },
+  ByEnergy {
+    low: T,
+    medium: T,
+    high: T,
+  },
+  ByPresence {
+    staccatto: T,
+    legato: T,
+    tenuto: T,
+  },
+  Fixed(T),
+}
+
+impl<T> Vep<T> {
+  fn get(&self, arf: &Arf) -> &T {
+    match self {
+      Vep::ByVisibility {
+        visible,
+        foreground,
+        background,
+        hidden,
+      } => match arf.visibility {
+        Visibility::Visible => visible,
+        Visibility::Foreground => foreground,
+        Visibility::Background => background,
+        Visibility::Hidden => hidden,
+      },
+      Vep::ByEnergy { low, medium, high } => match arf.energy {
+        Energy::Low => low,
+        Energy::Medium => medium,
+        Energy::High => high,
+      },
+      Vep::ByPresence {
+        staccatto,
+        legato,
+        tenuto,
+      } => match arf.presence {
+        Presence::Staccatto => staccatto,
+        Presence::Legato => legato,
+        Presence::Tenuto => tenuto,
+      },
+      Vep::Fixed(value) => value,
+    }
+  }
+
+  fn all(&self) -> Vec<&T> {
+    match self {
+      Vep::ByVisibility {
+        visible,
+        foreground,
+        background,
+        hidden,
+      } => vec![visible, foreground, background, hidden],
+      Vep::ByEnergy { low, medium, high } => vec![low, medium, high],
+      Vep::ByPresence {
+        staccatto,
+        legato,
+        tenuto,
+      } => vec![staccatto, legato, tenuto],
+      Vep::Fixed(value) => vec![value],
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FxSpec {
+  fx: String,
+  #[serde(default)]
+  args: Vec<f32>,
+}
+
+/// A `KnobMacro` with its `Ranger`. Empty motions are `Constant`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KnobSpec {
+  ranger: String,
+  a: Vep<[f32; 2]>,
+  b: Option<Vep<[f32; 2]>>,
+  c: Option<Vep<[f32; 2]>>,
+  #[serde(default)]
+  ma: Vec<MacroMotion>,
+  #[serde(default)]
+  mb: Vec<MacroMotion>,
+  #[serde(default)]
+  mc: Vec<MacroMotion>,
+}
+
+/// One `Stem2` of a role.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayerSpec {
+  soids: String,
+  height: Vep<f32>,
+  #[serde(default)]
+  fx: Vec<FxSpec>,
+  /// Choices of the amplitude envelope of each note. Without one, notes hold their level.
+  lifespan: Option<Vep<Vec<AmpLifespan>>>,
+  gain: Option<Vep<f32>>,
+  /// (highpass, lowpass) in Hz
+  bandpass: Option<Vep<[f32; 2]>>,
+  #[serde(default)]
+  boost: Vec<BoostGroupMacro>,
+  #[serde(default)]
+  amp: Vec<KnobSpec>,
+  #[serde(default)]
+  freq: Vec<KnobSpec>,
+  #[serde(default)]
+  phase: Vec<KnobSpec>,
+  /// Applied to each note
+  delays: Option<Vep<Vec<DelayParamsMacro>>>,
+  /// Applied to the whole line
+  room_delays: Option<Vep<Vec<DelayParamsMacro>>>,
+  reverbs: Option<Vep<Vec<ReverbParams>>>,
+  room_reverbs: Option<Vep<Vec<ReverbParams>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RoleSpec {
+  /// Play drum samples instead of synthesizing layers
+  #[serde(default)]
+  sample: bool,
+  #[serde(default)]
+  layers: Vec<LayerSpec>,
+  /// Portamento between the notes of the role, see `render::glide`
+  glide: Option<Glide>,
+  /// Swing and timing of the notes of the role, see `render::groove`
+  groove: Option<Groove>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackSpec {
+  name: String,
+  base: Option<String>,
+  kick: Option<RoleSpec>,
+  perc: Option<RoleSpec>,
+  hats: Option<RoleSpec>,
+  chords: Option<RoleSpec>,
+  lead: Option<RoleSpec>,
+  bass: Option<RoleSpec>,
+}
+
+/// A loaded preset pack. See `Preset::Pack`.
+#[derive(Debug)]
+pub struct Pack {
+  pub name: String,
+  /// Hash of the file, so caches notice an edited pack
+  pub fingerprint: u64,
+  /// Renders the roles the pack leaves out
+  pub base: Preset,
+  spec: PackSpec,
+}
+
+/// Packs loaded by this process. They live until it exits, as a `Preset` refers to them.
+static PACKS: Lazy<RwLock<Vec<&'static Pack>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// The most recently loaded pack named `name`.
+pub fn find(name: &str) -> Option<&'static Pack> {
+  let packs = PACKS.read().unwrap_or_else(|e| e.into_inner());
+  packs.iter().rev().find(|pack| pack.name.eq_ignore_ascii_case(name)).copied()
+}
+
+/// Names of the packs loaded by this process.
+pub fn names() -> Vec<String> {
+  let packs = PACKS.read().unwrap_or_else(|e| e.into_inner());
+  let mut names: Vec<String> = packs.iter().map(|pack| pack.name.clone()).collect();
+  names.sort();
+  names.dedup();
+  names
+}
+
+/// Whether a preset name refers to a pack file.
+pub fn is_pack_path(name: &str) -> bool {
+  let lowercase = name.to_lowercase();
+  lowercase.ends_with(".toml") || lowercase.ends_with(".json")
+}
+
+/// Load a pack from a TOML or JSON file. Loading an unchanged file again returns the same preset.
+pub fn load(path: &str) -> Result<Preset, RaudioError> {
+  let invalid = |reason: String| RaudioError::InvalidPreset(path.to_string(), reason);
+  let text = fs::read_to_string(path).map_err(|e| RaudioError::Io(path.to_string(), e))?;
+  let spec: PackSpec = if path.to_lowercase().ends_with(".toml") {
+    toml::from_str(&text).map_err(|e| invalid(e.to_string()))?
+  } else {
+    serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?
+  };
+  let pack = Pack::new(spec, crate::render::cache::fnv1a(text.as_bytes())).map_err(invalid)?;
+
+  let mut packs = PACKS.write().unwrap_or_else(|e| e.into_inner());
+  if let Some(loaded) = packs.iter().find(|p| p.name == pack.name && p.fingerprint == pack.fingerprint) {
+    return Ok(Preset::Pack(loaded));
+  }
+  let pack: &'static Pack = Box::leak(Box::new(pack));
+  packs.push(pack);
+  Ok(Preset::Pack(pack))
+}
+
+fn check_knob(knob: &KnobSpec) -> Result<(), String> {
+  match RANGERS.iter().any(|(name, _)| *name == knob.ranger) {
+    true => Ok(()),
+    false => Err(format!("Unknown ranger '{}'", knob.ranger)),
+  }
+}
+
+fn check_layer(layer: &LayerSpec) -> Result<(), String> {
+  if !SOIDS.iter().any(|(name, _)| *name == layer.soids) {
+    let names: Vec<&str> = SOIDS.iter().map(|(name, _)| *name).collect();
+    return Err(format!(
+      "Unknown soids '{}'. Choose one of: {}",
+      layer.soids,
+      names.join(", ")
+    ));
+  }
+  if layer.height.all().iter().any(|&&height| height.is_nan() || height < 1f32) {
+    return Err("height must be at least 1".to_string());
+  }
+  for fx in &layer.fx {
+    match FX.iter().find(|(name, _)| *name == fx.fx) {
+      None => return Err(format!("Unknown soid effect '{}'", fx.fx)),
+      Some((_, arity)) if *arity != fx.args.len() => {
+        return Err(format!(
+          "Soid effect '{}' takes {} arguments, got {}",
+          fx.fx,
+          arity,
+          fx.args.len()
+        ))
+      }
+      _ => {}
+    }
+  }
+  layer.amp.iter().chain(&layer.freq).chain(&layer.phase).try_for_each(check_knob)
+}
+
+impl Pack {
+  fn new(spec: PackSpec, fingerprint: u64) -> Result<Pack, String> {
+    let builtin = |name: &str| Preset::ALL.iter().any(|p| p.to_string() == name.to_lowercase());
+    if spec.name.is_empty() || builtin(&spec.name) {
+      return Err(format!("Pack name '{}' must be new and not empty", spec.name));
+    }
+    let base = match &spec.base {
+      None => Preset::Hop,
+      Some(name) => Preset::ALL
+        .iter()
+        .copied()
+        .find(|p| p.to_string() == name.to_lowercase())
+        .ok_or(format!("Unknown base preset '{}'", name))?,
+    };
+    let roles = [
+      ("kick", &spec.kick),
+      ("perc", &spec.perc),
+      ("hats", &spec.hats),
+      ("chords", &spec.chords),
+      ("lead", &spec.lead),
+      ("bass", &spec.bass),
+    ];
+    for (role, spec) in roles {
+      if let Some(spec) = spec {
+        if spec.sample != spec.layers.is_empty() {
+          return Err(format!("{}: give either sample = true or layers", role));
+        }
+        spec.layers.iter().try_for_each(check_layer).map_err(|e| format!("{}: {}", role, e))?;
+        if let Some(glide) = &spec.glide {
+          if spec.sample {
+            return Err(format!("{}: drum samples cannot glide", role));
+          }
+          crate::render::glide::check(glide).map_err(|e| format!("{}: {}", role, e))?;
+        }
+        if let Some(groove) = &spec.groove {
+          crate::render::groove::check(groove).map_err(|e| format!("{}: {}", role, e))?;
+        }
+      }
+    }
+    Ok(Pack {
+      name: spec.name.clone(),
+      fingerprint,
+      base,
+      spec,
+    })
+  }
+
+  fn role(&self, role: Role) -> Option<&RoleSpec> {
+    match role {
+      Role::Kick => self.spec.kick.as_ref(),
+      Role::Perc => self.spec.perc.as_ref(),
+      Role::Hats => self.spec.hats.as_ref(),
+      Role::Chords => self.spec.chords.as_ref(),
+      Role::Lead => self.spec.lead.as_ref(),
+      Role::Bass => self.spec.bass.as_ref(),
+    }
+  }
+
+  /// The glide of a role, or the base preset's for the roles the pack leaves out.
+  pub fn glide(&self, role: Role) -> Option<Glide> {
+    match self.role(role) {
+      Some(spec) => spec.glide,
+      None => self.base.glide(role),
+    }
+  }
+
+  /// The groove of a role, or the base preset's for the roles the pack leaves out.
+  pub fn groove(&self, role: Role) -> Option<Groove> {
+    match self.role(role) {
+      Some(spec) => spec.groove.clone(),
+      None => self.base.groove(role),
+    }
+  }
+
+  /// Renders a melody with the layers of its role, or with the base preset.
+  pub fn create_stem<'render>(
+    &self, conf: &Conf, melody: &'render Melody<Note>, arf: &'render Arf,
+  ) -> Result<Renderable2<'render>, RaudioError> {
+    let Some(spec) = self.role(arf.role) else {
+      return Preset::create_stem(conf, melody, arf, self.base);
+    };
+    if spec.sample {
+      return simple_stem(conf, melody, arf);
+    }
+    let mut stems: Vec<Stem2<'render>> = spec.layers.iter().map(|layer| layer.stem(conf, melody, arf)).collect();
+    Ok(if stems.len() == 1 {
+      Renderable2::Instance(stems.remove(0))
+    } else {
+      Renderable2::Group(stems)
+    })
+  }
+}
+
+fn apply_fx(soids: &Soids, fx: &FxSpec) -> Soids {
+  let a = |i: usize| fx.args[i];
+  let n = |i: usize| fx.args[i].max(0f32).round() as usize;
+  match fx.fx.as_str() {
+    "ratio.constant" => soid_fx::ratio::constant(soids, a(0), a(1)),
+    "ratio.fifth_up" => soid_fx::ratio::fifth_up(soids, n(0), a(1)),
+    "ratio.quince_up" => soid_fx::ratio::quince_up(soids, n(0), a(1)),
+    "ratio.dquince_up" => soid_fx::ratio::dquince_up(soids, n(0), a(1)),
+    "amod.reece" => soid_fx::amod::reece(soids, n(0)),
+    "amod.attenuate_bin_k" => soid_fx::amod::attenuate_bin_k(soids, a(0)),
+    "amod.gain" => soid_fx::amod::gain(soids, a(0)),
+    "fmod.triangle" => soid_fx::fmod::triangle(soids, n(0)),
+    "fmod.square" => soid_fx::fmod::square(soids, n(0)),
+    "fmod.sawtooth" => soid_fx::fmod::sawtooth(soids, n(0)),
+    "fmod.reece" => soid_fx::fmod::reece(soids, a(0), n(1)),
+    "fmod.reece2" => soid_fx::fmod::reece2(soids, a(0)),
+    "ffilter.greater_than" => soid_fx::ffilter::greater_than(soids, a(0)),
+    "ffilter.less_than" => soid_fx::ffilter::less_than(soids, a(0)),
+    "pmod.reece_chorus" => soid_fx::pmod::reece_chorus(soids, n(0), a(1)),
+    // names are checked when the pack is loaded
+    _ => soids.clone(),
+  }
+}
+
+fn motion(rng: &mut ThreadRng, choices: &[MacroMotion]) -> MacroMotion {
+  choices.choose(rng).copied().unwrap_or(MacroMotion::Constant)
+}
+
+impl KnobSpec {
+  fn knob_pair(&self, rng: &mut ThreadRng, arf: &Arf) -> (KnobMacro, Ranger) {
+    let range = |v: &Option<Vep<[f32; 2]>>| v.as_ref().map(|v| *v.get(arf)).unwrap_or([0f32, 0f32]);
+    let ranger = RANGERS.iter().find(|(name, _)| *name == self.ranger).map(|(_, f)| *f);
+    (
+      KnobMacro {
+        a: *self.a.get(arf),
+        b: range(&self.b),
+        c: range(&self.c),
+        ma: motion(rng, &self.ma),
+        mb: motion(rng, &self.mb),
+        mc: motion(rng, &self.mc),
+      },
+      ranger.unwrap_or(ranger::amod_noop),
+    )
+  }
+}
+
+impl LayerSpec {
+  fn stem<'render>(&self, conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Stem2<'render> {
+    let mut rng = thread_rng();
+    let generator = SOIDS.iter().find(|(name, _)| *name == self.soids).map(|(_, f)| *f);
+    let soids = generator.unwrap_or(druidic_soids::unit)(*self.height.get(arf));
+    let soids = self.fx.iter().fold(soids, |soids, fx| apply_fx(&soids, fx));
+
+    let mut amp = match self.lifespan.as_ref().and_then(|l| l.get(arf).choose(&mut rng)) {
+      Some(lifespan) => lifespan::sample_lifespan(SR(), lifespan, 1, 1f32),
+      None => vec![1f32],
+    };
+    amp_scale(&mut amp, self.gain.as_ref().map(|g| *g.get(arf)).unwrap_or(1f32));
+
+    let [highpass, lowpass] = self.bandpass.as_ref().map(|b| *b.get(arf)).unwrap_or([MFf, NFf()]);
+    let bp = (vec![highpass], vec![lowpass], self.boost.clone());
+
+    let knobs = |specs: &Vec<KnobSpec>, rng: &mut ThreadRng| specs.iter().map(|k| k.knob_pair(rng, arf)).collect();
+    let knob_mods = KnobMods2(
+      knobs(&self.amp, &mut rng),
+      knobs(&self.freq, &mut rng),
+      knobs(&self.phase, &mut rng),
+    );
+
+    let mut delays = |macros: &Option<Vep<Vec<DelayParamsMacro>>>| -> Vec<DelayParams> {
+      macros
+        .as_ref()
+        .map(|m| m.get(arf).iter().map(|m| m.gen(&mut rng, conf.cps)).collect())
+        .unwrap_or_default()
+    };
+    let delays_note = delays(&self.delays);
+    let delays_room = delays(&self.room_delays);
+    let reverbs =
+      |params: &Option<Vep<Vec<ReverbParams>>>| params.as_ref().map(|p| p.get(arf).clone()).unwrap_or_default();
+
+    (
+      melody,
+      soids,
+      (amp, vec![1f32], vec![0f32]),
+      bp,
+      knob_mods,
+      delays_note,
+      delays_room,
+      reverbs(&self.reverbs),
+      reverbs(&self.room_reverbs),
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::types::synthesis::GlideCurve;
+
+  const GLASS: &str = r#"
+name = "glass-test"
+base = "fum"
+
+[kick]
+sample = true
+
+[[bass.layers]]
+soids = "overs_sawtooth"
+height = { visible = 512, foreground = 256, background = 128, hidden = 64 }
+fx = [{ fx = "fmod.reece", args = [1.5, 3] }]
+lifespan = ["pluck", "snap"]
+gain = { low = 0.3, medium = 0.5, high = 0.7 }
+bandpass = [40, 4000]
+amp = [{ ranger = "amod_pluck2", a = [0.2, 0.5], b = [0.6, 1.0], ma = ["forward", "constant"] }]
+delays = [{ gain = [0.3, 0.4], dtimes_cycles = [0.5], n_echoes = [2, 3], mix = [0.2, 0.3], pan = ["mono", { left-right = [0.5, 0.5] }], mecho = ["forward"], mgain = ["constant"], mpan = ["constant"], mmix = ["constant"] }]
+
+[[bass.layers]]
+soids = "overs_triangle"
+height = 64
+reverbs = [{ mix = 0.2, amp = 0.5, dur = 0.5, rate = 0.1 }]
+"#;
+
+  fn write(name: &str, contents: &str) -> String {
+    let path = format!("dev-audio/packs/{}", name);
+    crate::files::with_dir(&path);
+    fs::write(&path, contents).unwrap();
+    path
+  }
+
+  #[test]
+  fn test_load_pack_and_render_roles() {
+    let preset = load(&write("glass.toml", GLASS)).unwrap();
+    assert_eq!("glass-test", preset.to_string());
+    assert!(matches!(Preset::from_name("Glass-Test"), Some(Preset::Pack(_))));
+    assert!(
+      matches!(load(&write("glass.toml", GLASS)).unwrap(), Preset::Pack(p) if std::ptr::eq(p, find("glass-test").unwrap()))
+    );
+
+    let score = crate::inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+    for (_, arf, melody) in &score.parts {
+      let stem = Preset::create_stem(&score.conf, melody, arf, preset).unwrap();
+      match (arf.role, stem) {
+        (Role::Bass, Renderable2::Group(stems)) => {
+          assert_eq!(2, stems.len());
+          assert_eq!(1, stems[0].5.len(), "Must generate the note delays of the first layer");
+          assert_eq!(1, stems[1].7.len(), "Must apply the reverbs of the second layer");
+        }
+        (Role::Bass, _) => panic!("Must render both bass layers"),
+        (Role::Kick, stem) => assert!(matches!(stem, Renderable2::Sample(_))),
+        _ => {}
+      }
+    }
+  }
+
+  #[test]
+  fn test_demo_pack_loads() {
+    let preset = load("src/demo/packs/glass.toml").unwrap();
+    assert_eq!("glass", preset.to_string());
+    assert_ne!(Preset::Hop.key(), preset.key());
+  }
+
+  #[test]
+  fn test_pack_glides_its_bass() {
+    let preset = load("src/demo/packs/glass.toml").unwrap();
+    assert_eq!(Some(GlideCurve::Exponential), preset.glide(Role::Bass).map(|glide| glide.curve));
+    assert_eq!(None, preset.glide(Role::Lead));
+    assert_eq!(None, preset.glide(Role::Perc), "Must take the glide of the base preset");
+
+    let dir = "dev-audio/packs/glide";
+    let bass = |glide: Option<Glide>| {
+      let mut score = crate::inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+      score.parts.retain(|(_, arf, _)| matches!(arf.role, Role::Bass));
+      score.parts[0].2.iter_mut().for_each(|line| line.truncate(3));
+      score.parts[0].0.glide = glide;
+      score
+    };
+    let options = crate::RenderOptions {
+      seed: Some(2),
+      ..Default::default()
+    };
+    let glided = crate::render_score(bass(None), preset, dir, "glided", &options).unwrap();
+    let held = crate::render_score(bass(Some(Glide::default())), preset, dir, "held", &options).unwrap();
+    let read = |path: &str| fs::read(path).unwrap();
+    assert_ne!(read(&glided), read(&held), "Must glide between the notes of the bass");
+  }
+
+  #[test]
+  fn test_pack_swings_its_hats() {
+    let swung = "name = \"swung-test\"\n\n[hats]\nsample = true\ngroove = { grid = \"eighth\", swing = 66 }\n";
+    let preset = load(&write("swung.toml", swung)).unwrap();
+    assert_eq!(Some(66f32), preset.groove(Role::Hats).map(|groove| groove.swing));
+    assert_eq!(None, preset.groove(Role::Kick), "Must take the groove of the base preset");
+
+    let dir = "dev-audio/packs/groove";
+    let hats = |groove: Option<Groove>| {
+      let mut score = crate::inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+      score.parts.retain(|(_, arf, _)| matches!(arf.role, Role::Hats));
+      score.parts.truncate(1);
+      score.parts[0].2.iter_mut().for_each(|line| line.truncate(4));
+      score.parts[0].0.groove = groove;
+      score
+    };
+    let options = crate::RenderOptions {
+      seed: Some(3),
+      ..Default::default()
+    };
+    let swung = crate::render_score(hats(None), preset, dir, "swung", &options).unwrap();
+    let straight = crate::render_score(hats(Some(Groove::default())), preset, dir, "straight", &options).unwrap();
+    let read = |path: &str| fs::read(path).unwrap();
+    assert_ne!(read(&swung), read(&straight), "Must swing the offbeats of the hats");
+  }
+
+  #[test]
+  fn test_invalid_packs_name_the_problem() {
+    let bad = |contents: &str| load(&write("bad.json", contents)).unwrap_err().to_string();
+    let layer = |fields: &str| {
+      format!(
+        "{{\"name\": \"bad-test\", \"lead\": {{\"layers\": [{{\"soids\": \"overs_square\", \"height\": 8 {}}}]}}}}",
+        fields
+      )
+    };
+    assert!(bad(&layer(", \"fx\": [{\"fx\": \"fmod.reece\", \"args\": [1]}]")).contains("takes 2 arguments"));
+    assert!(bad(&layer(", \"amp\": [{\"ranger\": \"amod_loud\", \"a\": [0, 1]}]")).contains("amod_loud"));
+    assert!(bad(&layer(", \"volume\": 3")).contains("volume"));
+    assert!(bad(&layer("").replace("overs_square", "overs_circle")).contains("overs_circle"));
+    assert!(bad("{\"name\": \"hop\"}").contains("must be new"));
+    assert!(bad("{\"name\": \"bad-test\", \"base\": \"nowhere\"}").contains("Unknown base"));
+    let glide = "{\"name\": \"bad-test\", \"kick\": {\"sample\": true, \"glide\": {\"from\": \"quarter\"}}}";
+    assert!(bad(glide).contains("cannot glide"));
+    let groove = "{\"name\": \"bad-test\", \"hats\": {\"sample\": true, \"groove\": {\"swing\": 0}}}";
+    assert!(bad(groove).contains("hats: Swing"));
+  }
+}
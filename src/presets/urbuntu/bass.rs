@@ -58,13 +58,13 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let ely_sine = driad(arf);
 
   let feel_sine: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: ely_sine.modders,
     clippers: (0f32, 1f32),
   };
 
   let feel: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: ely.modders,
     clippers: (0f32, 1f32),
   };
@@ -143,7 +143,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   };
   let modifiers_tonal: ModifiersHolder = (vec![], vec![], vec![], vec![]);
   let feel_tonal: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: modifiers_tonal,
     clippers: (0f32, 1f32),
   };
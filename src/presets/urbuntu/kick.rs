@@ -105,7 +105,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let modifiers_noise: ModifiersHolder = (vec![], vec![], vec![], vec![]);
 
   let feel_noise: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: modifiers_noise,
     clippers: (0f32, 1f32),
   };
@@ -126,7 +126,7 @@ pub fn renderable<'render>(melody: &'render Melody<Note>, arf: &Arf) -> Renderab
   let soids_subsine = druidic_soids::octave(256f32);
   let modifiers_subsine: ModifiersHolder = (vec![], vec![], vec![], vec![]);
   let feel_subsine: Feel = Feel {
-    bp: (vec![MFf], vec![NFf]),
+    bp: (vec![MFf], vec![NFf()]),
     modifiers: modifiers_subsine,
     clippers: (0f32, 1f32),
   };
@@ -234,7 +234,9 @@ fn dynamics(arf: &Arf, n_samples: usize, k: f32) -> SampleBuffer {
   dynamp_contour
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   let mullet = get_mullet(&arf);
 
   let len_cycles: f32 = time::count_cycles(&melody[0]);
@@ -262,7 +264,7 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   // knob_mods.0.push(amp_onset(arf.visibility, arf.energy, arf.presence));
   // knob_mods.0.push(amp_knob_presence(arf.visibility, arf.energy, arf.presence));
   // knob_mods.2.push(pmod_chorus(arf.visibility, arf.energy, arf.presence));
-  let n_samples = (SRf * len_cycles / 2f32) as usize;
+  let n_samples = (SRf() * len_cycles / 2f32) as usize;
 
   let mut dynamics = dynamics::gen_organic_amplitude(10, n_samples, arf.visibility);
   amp_scale(&mut dynamics, visibility_gain(arf.visibility));
@@ -302,5 +304,5 @@ pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf
   );
 
   // Renderable2::Instance(stem)
-  Renderable2::FMOp(stem_fm)
+  Ok(Renderable2::FMOp(stem_fm))
 }
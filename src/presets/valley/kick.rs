@@ -11,12 +11,14 @@ use super::*;
 ///
 /// # Returns
 /// A `DrumSample` with configured sample buffers, amplitude expressions, and effect parameters.
-pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn stemmy<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   // Dynamically retrieve a kick sample file path
-  let sample_path = get_sample_path(arf);
+  let sample_path = get_sample_path(arf)?;
 
   // Read the audio sample from the retrieved path
-  let (ref_samples, sample_rate) = read_audio_file(&sample_path).expect("Failed to read kick sample");
+  let (ref_samples, sample_rate) = read_sample(&sample_path)?;
 
   let gain = 0.5f32 * visibility_gain_sample(arf.visibility);
   let amp_expr = dynamics::gen_organic_amplitude(10, 2000, arf.visibility).iter().map(|v| v * gain).collect();
@@ -27,10 +29,10 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
   let reverbs_note = vec![];
   let reverbs_room = vec![];
 
-  let lowpass_cutoff = NFf;
+  let lowpass_cutoff = NFf();
   let ref_sample = ref_samples[0].to_owned();
 
-  Renderable2::Sample((
+  Ok(Renderable2::Sample((
     melody,
     ref_sample,
     amp_expr,
@@ -39,15 +41,17 @@ pub fn stemmy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) ->
     delays_room,
     reverbs_note,
     reverbs_room,
-  ))
+  )))
 }
 
-pub fn renderable<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+pub fn renderable<'render>(
+  conf: &Conf, melody: &'render Melody<Note>, arf: &Arf,
+) -> Result<Renderable2<'render>, RaudioError> {
   contour_stem(conf, melody, arf)
   // Renderable2::Mix(vec![(0.4, stemmy(conf, melody, arf)), (0.6, synthy(conf, melody, arf))])
 }
 
-fn synthy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Renderable2<'render> {
+fn synthy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Result<Renderable2<'render>, RaudioError> {
   let soids = druidic_soids::upto(2);
 
   let expr = (
@@ -146,7 +150,7 @@ fn synthy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Ren
   let reverbs_note: Vec<ReverbParams> = vec![];
   let reverbs_room: Vec<ReverbParams> = vec![];
 
-  Renderable2::Instance((
+  Ok(Renderable2::Instance((
     melody,
     soids,
     expr,
@@ -156,5 +160,5 @@ fn synthy<'render>(conf: &Conf, melody: &'render Melody<Note>, arf: &Arf) -> Ren
     delays_room,
     reverbs_note,
     reverbs_room,
-  ))
+  )))
 }
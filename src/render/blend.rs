@@ -14,7 +14,7 @@ use crate::types::timbre::{BandpassFilter, BaseOsc, Energy, FilterMode, Phrasing
 /// That is, Ratio Q for how wide the attenuation reaches and Mod Q for how much to attenuate.
 fn filter(p: f32, freq: f32, bandpass: &Bp) -> Range {
   let min_f = sample(&bandpass.0, p).max(MF as f32);
-  let max_f = sample(&bandpass.1, p).min(NF as f32);
+  let max_f = sample(&bandpass.1, p).min(NF() as f32);
   if freq < min_f || freq > max_f {
     return 0f32;
   } else {
@@ -60,7 +60,7 @@ pub fn blender(
 
   for j in 0..n_samples {
     let p: Range = j as f32 / n_samples as f32;
-    let t: f32 = j as f32 / SR as f32;
+    let t: f32 = j as f32 / SR() as f32;
 
     // collect instantaneous modulation factors from the expression envelopes
     let am = sample(&acont, p);
@@ -102,7 +102,7 @@ pub fn gen_cocktail(n: usize) -> Vec<Mixer> {
   use rand;
   use rand::seq::SliceRandom;
   use rand::Rng;
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
 
   if n > example_options.len() {
     panic!("Requested more OldRangerDeprecateds than are available. Repeating the same OldRangerDeprecated is the same as boosting its weight.")
@@ -150,7 +150,7 @@ mod test {
   }
 
   fn test_bp() -> Bp {
-    (vec![MFf], vec![NFf])
+    (vec![MFf], vec![NFf()])
   }
 
   fn test_thresh() -> Clippers {
@@ -160,7 +160,7 @@ mod test {
   fn write_test_asset(signal: &SampleBuffer, test_name: &str) {
     files::with_dir(TEST_DIR);
     let filename = format!("{}/{}.wav", TEST_DIR, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -214,8 +214,8 @@ mod test {
     let span = test_span();
 
     let n_samples = crate::time::samples_of_cycles(span.0, span.1);
-    let highpass_filter: Vec<f32> = (0..n_samples).map(|x| NFf * (x as f32 / n_samples as f32)).collect();
-    let lowpass_filter = vec![NFf];
+    let highpass_filter: Vec<f32> = (0..n_samples).map(|x| NFf() * (x as f32 / n_samples as f32)).collect();
+    let lowpass_filter = vec![NFf()];
     let signal = blender(
       &test_frex(),
       &test_expr(),
@@ -231,7 +231,7 @@ mod test {
 
     let test_name = "blender-overs-lowpass-filter";
     let highpass_filter = vec![MFf];
-    let lowpass_filter = (0..n_samples).map(|x| NFf * (x as f32 / n_samples as f32)).collect();
+    let lowpass_filter = (0..n_samples).map(|x| NFf() * (x as f32 / n_samples as f32)).collect();
 
     let signal = blender(
       &test_frex(),
@@ -284,7 +284,7 @@ mod test {
   fn small_p_modulator(cps: f32, n_samples: usize) -> SampleBuffer {
     use rand;
     use rand::Rng;
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let frequency = 1f32;
     (0..n_samples)
       .map(|j| {
@@ -0,0 +1,225 @@
+/// Cache of rendered parts for `render_score`.
+///
+/// A seeded render of a part depends only on its melody, `Arf` and `ClientPositioning`, its index in the score,
+/// the preset, the seed and the render settings. Each rendered part, after its stem reverb and pan position,
+/// is stored as a 32 bit float WAV file named by a hash of those inputs. Editing one part of a playbook and
+/// rendering it again synthesizes only that part and mixes the rest from disk.
+///
+/// The levels of seeded renders are kept too, each part's with the part and the whole render's under a key of its parts,
+/// so a window of the render can replay them. See `render::levels`.
+///
+/// Unseeded renders choose new values every time, so they are never cached.
+/// The crate version is part of the key, so a new version of the renderer does not reuse old parts.
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::analysis::tuning::Intonation;
+use crate::error::RaudioError;
+use crate::presets::Preset;
+use crate::render::engrave;
+use crate::render::levels::Levels;
+use crate::render::stream::{self, Spill};
+use crate::synth::StereoBuffer;
+use crate::types::render::{DruidicScore, TempoPoint};
+use crate::types::synthesis::Note;
+use crate::types::timbre::{Arf, ClientPositioning};
+
+/// Everything a rendered part depends on.
+#[derive(Serialize)]
+struct PartInputs<'a> {
+  version: &'static str,
+  part: usize,
+  melody: &'a Vec<Vec<Note>>,
+  arf: &'a Arf,
+  positioning: &'a ClientPositioning,
+  preset: String,
+  seed: u64,
+  sample_rate: usize,
+  tuning: String,
+  cps: f32,
+  #[serde(skip_serializing_if = "<[TempoPoint]>::is_empty")]
+  tempo: &'a [TempoPoint],
+  root: f32,
+  /// The cycles whose notes are synthesized, see `render::window`
+  audible: Option<(f32, f32)>,
+}
+
+/// 64 bit FNV-1a, which unlike `std::hash` is the same on every platform and Rust version.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf29ce484222325u64, |hash, &b| {
+    (hash ^ b as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+/// Cache key of part `j` of `score`.
+pub fn part_key(
+  score: &DruidicScore, j: usize, preset: Preset, seed: u64, sample_rate: usize, intonation: &Intonation,
+  audible: Option<(f32, f32)>,
+) -> String {
+  let (positioning, arf, melody) = &score.parts[j];
+  let inputs = PartInputs {
+    version: env!("CARGO_PKG_VERSION"),
+    part: j,
+    melody,
+    arf,
+    positioning,
+    preset: preset.key(),
+    seed,
+    sample_rate,
+    tuning: intonation.key(),
+    cps: score.conf.cps,
+    tempo: &score.tempo,
+    root: score.conf.root,
+    audible,
+  };
+  // plain data always serializes
+  let json = serde_json::to_vec(&inputs).unwrap_or_default();
+  format!("{:016x}{:08x}", fnv1a(&json), json.len() as u32)
+}
+
+/// Path of the cached part with `key` in `cache_dir`.
+pub fn part_path(cache_dir: &str, key: &str) -> String {
+  format!("{}/{}.wav", cache_dir, key)
+}
+
+/// Cache key of the levels of a full render whose parts have `part_keys`.
+pub fn render_key(part_keys: &[String]) -> String {
+  let joined = part_keys.join("/");
+  format!("{:016x}{:08x}", fnv1a(joined.as_bytes()), joined.len() as u32)
+}
+
+/// Path of the levels of the part or render with `key` in `cache_dir`.
+pub fn levels_path(cache_dir: &str, key: &str) -> String {
+  format!("{}/{}.levels.json", cache_dir, key)
+}
+
+/// Files are written under a temporary name and renamed, so an interrupted render never leaves a partial part.
+fn publish(path: &str, write: impl FnOnce(&str) -> Result<(), RaudioError>) -> Result<(), RaudioError> {
+  crate::files::try_with_dir(path).map_err(|e| RaudioError::Io(path.to_string(), e))?;
+  let partial = format!("{}.{}.partial", path, std::process::id());
+  write(&partial)?;
+  fs::rename(&partial, path).map_err(|e| RaudioError::Io(path.to_string(), e))
+}
+
+/// Read a cached part. Returns `None` when it is not cached.
+/// An unreadable file counts as not cached, so the part is rendered and written again.
+pub fn load(path: &str) -> Option<StereoBuffer> {
+  if !Path::new(path).exists() {
+    return None;
+  }
+  let read = || -> Result<StereoBuffer, hound::Error> {
+    let mut reader = hound::WavReader::open(path)?;
+    let samples = reader.samples::<f32>().collect::<Result<Vec<f32>, _>>()?;
+    Ok((
+      samples.iter().step_by(2).copied().collect(),
+      samples.iter().skip(1).step_by(2).copied().collect(),
+    ))
+  };
+  match read() {
+    Ok(signal) => Some(signal),
+    Err(e) => {
+      eprintln!("Warning: Ignoring unreadable cached part {}: {}", path, e);
+      None
+    }
+  }
+}
+
+/// Write a rendered part to the cache.
+pub fn store(path: &str, (left, right): &StereoBuffer) -> Result<(), RaudioError> {
+  publish(path, |partial| {
+    engrave::try_write_audio(crate::synth::SR(), vec![left.clone(), right.clone()], partial)
+  })
+}
+
+/// Read cached levels. Returns `None` when they are not cached.
+/// Unreadable levels count as not cached, so they are recorded and written again.
+pub fn load_levels(path: &str) -> Option<Levels> {
+  if !Path::new(path).exists() {
+    return None;
+  }
+  let read = || -> Result<Levels, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+  };
+  match read() {
+    Ok(levels) => Some(levels),
+    Err(e) => {
+      eprintln!("Warning: Ignoring unreadable cached levels {}: {}", path, e);
+      None
+    }
+  }
+}
+
+/// Write recorded levels to the cache.
+pub fn store_levels(path: &str, levels: &Levels) -> Result<(), RaudioError> {
+  publish(path, |partial| {
+    // plain data always serializes
+    let json = serde_json::to_string(levels).unwrap_or_default();
+    fs::write(partial, json).map_err(|e| RaudioError::Io(partial.to_string(), e))
+  })
+}
+
+/// Streaming counterpart of `load`.
+pub fn load_spill(path: &str) -> Option<Spill> {
+  if !Path::new(path).exists() {
+    return None;
+  }
+  match stream::spill_wav(path) {
+    Ok(spill) => Some(spill),
+    Err(e) => {
+      eprintln!("Warning: Ignoring unreadable cached part {}: {}", path, e);
+      None
+    }
+  }
+}
+
+/// Streaming counterpart of `store`.
+pub fn store_spill(path: &str, part: &Spill) -> Result<(), RaudioError> {
+  publish(path, |partial| {
+    stream::write(part, None, crate::synth::SR(), partial, engrave::WavFormat::default()).map(|_| ())
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::analysis::monic_theory::Lattice;
+  use crate::analysis::tuning::Tuning;
+
+  #[test]
+  fn test_part_key_changes_only_with_the_part() {
+    let mut score = crate::inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap();
+    let keys = |score: &DruidicScore| -> Vec<String> {
+      (0..score.parts.len()).map(|j| part_key(score, j, Preset::Fum, 7, 48000, &Intonation::default(), None)).collect()
+    };
+    let before = keys(&score);
+    assert_eq!(before, keys(&score), "Must give the same key for the same inputs");
+    score.parts[1].2[0][0].2 *= 0.5f32;
+    let after = keys(&score);
+    assert_ne!(before[1], after[1], "Must change the key of an edited part");
+    assert!((0..before.len()).filter(|&j| j != 1).all(|j| before[j] == after[j]));
+    assert_ne!(before[0], part_key(&score, 0, Preset::Fum, 8, 48000, &Intonation::default(), None));
+    assert_ne!(before[0], part_key(&score, 0, Preset::Hop, 7, 48000, &Intonation::default(), None));
+    assert_ne!(before[0], part_key(&score, 0, Preset::Fum, 7, 44100, &Intonation::default(), None));
+    let meantone = Intonation {
+      tuning: Tuning::from_name("meantone").unwrap(),
+      ..Default::default()
+    };
+    assert_ne!(before[0], part_key(&score, 0, Preset::Fum, 7, 48000, &meantone, None));
+    let fourths = Intonation {
+      lattice: Lattice::new(4f32 / 3f32).unwrap(),
+      ..Default::default()
+    };
+    assert_ne!(before[0], part_key(&score, 0, Preset::Fum, 7, 48000, &fourths, None));
+    score.tempo.push(TempoPoint {
+      cycle: 8f32,
+      cps: score.conf.cps * 1.5f32,
+      curve: Default::default(),
+    });
+    assert!(
+      keys(&score).iter().zip(&after).all(|(a, b)| a != b),
+      "Must change every key with the tempo"
+    );
+  }
+}
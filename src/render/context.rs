@@ -0,0 +1,142 @@
+/// The settings a render synthesizes with, as one value.
+///
+/// Synthesis reads the seed, sample rate, tuning, tempo map, audible window, levels and progress reporter of the
+/// thread it runs on (see `seed`, `synth::SR`, `analysis::tuning`, `analysis::tempo`, `render::window`,
+/// `render::levels` and `render::progress`). `render_score` enters the context of its options, and each parallel task of the renderer
+/// enters the context of the thread that spawned it, so no setting is copied into a task on its own.
+///
+/// Entering a context returns a guard that puts back the previous settings of the thread when it is dropped,
+/// so a render leaves nothing behind for the next one on the same thread.
+use std::sync::Arc;
+
+use crate::analysis::tempo::{self, TempoMap};
+use crate::analysis::tuning::{self, Intonation};
+use crate::render::levels::{self, Metering};
+use crate::render::progress::{self, Progress};
+use crate::render::window;
+use crate::seed;
+use crate::synth;
+
+#[derive(Clone)]
+pub struct RenderContext {
+  /// Seed of the render, or `None` for a new variant every time
+  pub seed: Option<u64>,
+  pub sample_rate: usize,
+  pub intonation: Arc<Intonation>,
+  /// `None` plays every cycle at the tempo of the score
+  pub tempo: Option<Arc<TempoMap>>,
+  /// The cycles whose notes are synthesized, `None` for every note
+  pub audible: Option<(f32, f32)>,
+  /// Whether the mixes record or replay their levels, `None` to only measure them
+  pub levels: Option<Metering>,
+  /// The keys of the task being rendered, see `for_task`
+  pub task: Vec<u64>,
+  pub progress: Option<Progress>,
+}
+
+impl Default for RenderContext {
+  fn default() -> Self {
+    RenderContext {
+      seed: None,
+      sample_rate: synth::DEFAULT_SR,
+      intonation: Arc::new(Intonation::default()),
+      tempo: None,
+      audible: None,
+      levels: None,
+      task: Vec::new(),
+      progress: None,
+    }
+  }
+}
+
+/// Restores the settings a thread had before `RenderContext::enter`.
+#[must_use = "the context is left as soon as the guard is dropped"]
+pub struct Entered {
+  previous: RenderContext,
+}
+
+impl Drop for Entered {
+  fn drop(&mut self) {
+    self.previous.install()
+  }
+}
+
+impl RenderContext {
+  /// The settings of the current thread.
+  pub fn current() -> Self {
+    RenderContext {
+      seed: seed::current_seed(),
+      sample_rate: synth::SR(),
+      intonation: tuning::current(),
+      tempo: tempo::current(),
+      audible: window::audible(),
+      levels: levels::current(),
+      task: levels::task(),
+      progress: progress::current(),
+    }
+  }
+
+  /// The context of a unit of work identified by `keys`, which draws from its own seed. See `seed::derive`.
+  /// Its mixes are known by the keys of this task followed by `keys`.
+  pub fn for_task(&self, keys: &[u64]) -> Self {
+    RenderContext {
+      seed: self.seed.map(|s| seed::derive(s, keys)),
+      task: self.task.iter().chain(keys).copied().collect(),
+      ..self.clone()
+    }
+  }
+
+  /// Apply these settings to the current thread until the returned guard is dropped.
+  /// The thread's generator is reseeded from `seed`.
+  pub fn enter(&self) -> Entered {
+    let previous = RenderContext::current();
+    self.install();
+    Entered { previous }
+  }
+
+  fn install(&self) {
+    seed::set_seed(self.seed);
+    synth::set_sample_rate(self.sample_rate);
+    tuning::set_current(self.intonation.clone());
+    tempo::set_current(self.tempo.clone());
+    window::set_audible(self.audible);
+    levels::set_current(self.levels.clone(), self.task.clone());
+    progress::set_current(self.progress.clone());
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_enter_restores_the_previous_settings() {
+    let before = RenderContext::current();
+    {
+      let _entered = RenderContext {
+        seed: Some(9),
+        sample_rate: 44100,
+        audible: Some((1f32, 2f32)),
+        ..Default::default()
+      }
+      .enter();
+      assert_eq!(44100, synth::SR());
+      assert_eq!(Some(9), seed::current_seed());
+      assert_eq!(Some((1f32, 2f32)), window::audible());
+    }
+    assert_eq!(before.sample_rate, synth::SR());
+    assert_eq!(before.seed, seed::current_seed());
+    assert_eq!(before.audible, window::audible());
+  }
+
+  #[test]
+  fn test_tasks_derive_their_seed() {
+    let context = RenderContext {
+      seed: Some(3),
+      ..Default::default()
+    };
+    assert_eq!(Some(seed::derive(3, &[1, 2])), context.for_task(&[1, 2]).seed);
+    assert_eq!(None, RenderContext::default().for_task(&[1]).seed);
+    assert_eq!(vec![1, 2, 0], context.for_task(&[1, 2]).for_task(&[0]).task);
+  }
+}
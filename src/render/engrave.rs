@@ -1,59 +1,301 @@
-pub fn samples(sample_rate: usize, samples: &Vec<f32>, filename: &str) {
-  use std::path::Path;
-  let p: &Path = Path::new(filename);
-  let spec = hound::WavSpec {
-    channels: 1,
-    sample_rate: sample_rate as u32,
-    bits_per_sample: 32,
-    sample_format: hound::SampleFormat::Float,
-  };
-  let mut writer = hound::WavWriter::create(p, spec).unwrap();
-  for &sample in samples {
-    writer.write_sample(sample).unwrap();
+use crate::error::RaudioError;
+use rand::Rng;
+
+/// Sample encoding of a written WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BitDepth {
+  Int16,
+  Int24,
+  Int32,
+  #[default]
+  Float32,
+}
+
+impl BitDepth {
+  /// Parse a bit depth as given on the command line: 16, 24, 32 or 32f.
+  pub fn from_name(name: &str) -> Option<BitDepth> {
+    match name.to_lowercase().as_str() {
+      "16" => Some(BitDepth::Int16),
+      "24" => Some(BitDepth::Int24),
+      "32" => Some(BitDepth::Int32),
+      "32f" | "float" => Some(BitDepth::Float32),
+      _ => None,
+    }
+  }
+
+  fn bits(&self) -> u16 {
+    match self {
+      BitDepth::Int16 => 16,
+      BitDepth::Int24 => 24,
+      BitDepth::Int32 | BitDepth::Float32 => 32,
+    }
   }
-  writer.finalize().unwrap();
 }
 
-pub fn samples2(sample_rate: usize, samples: &Vec<f32>, filename: &str) {
-  use std::path::Path;
-  let p: &Path = Path::new(filename);
-  let spec = hound::WavSpec {
-    channels: 2, // Stereo
-    sample_rate: sample_rate as u32,
-    bits_per_sample: 32,
-    sample_format: hound::SampleFormat::Float,
-  };
-  let mut writer = hound::WavWriter::create(p, spec).unwrap();
-  for chunk in samples.chunks(2) {
-    if chunk.len() == 2 {
-      // Write left and right channel samples
-      writer.write_sample(chunk[0]).unwrap(); // Left
-      writer.write_sample(chunk[1]).unwrap(); // Right
-    } else {
-      // Handle the case where the input has an odd number of samples
-      writer.write_sample(chunk[0]).unwrap(); // Write the remaining single sample as mono
-      writer.write_sample(0.0).unwrap(); // Pad with silence for the other channel
+/// Noise added before reducing the bit depth, to decorrelate the quantization error from the signal.
+/// Only applies to 16 and 24 bit output.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Dither {
+  None,
+  /// Triangular noise of one LSB peak
+  #[default]
+  Tpdf,
+  /// TPDF dither with first order error feedback, moving the noise floor toward high frequencies
+  Shaped,
+}
+
+impl Dither {
+  /// Parse a dither mode as given on the command line: none, tpdf or shaped.
+  pub fn from_name(name: &str) -> Option<Dither> {
+    match name.to_lowercase().as_str() {
+      "none" => Some(Dither::None),
+      "tpdf" => Some(Dither::Tpdf),
+      "shaped" => Some(Dither::Shaped),
+      _ => None,
+    }
+  }
+}
+
+/// How samples are encoded in a WAV file. The default is 32 bit float.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WavFormat {
+  pub bit_depth: BitDepth,
+  pub dither: Dither,
+}
+
+/// Converts float samples to integers of `bits` bits, one channel at a time.
+struct Quantizer {
+  scale: f32,
+  min: f32,
+  max: f32,
+  dither: Dither,
+  /// Quantization error of the previous sample of each channel, for `Dither::Shaped`
+  errors: Vec<f32>,
+}
+
+impl Quantizer {
+  fn new(bits: u16, dither: Dither, channels: u16) -> Self {
+    let scale = ((1i64 << (bits - 1)) - 1) as f32;
+    Quantizer {
+      scale,
+      min: -scale - 1f32,
+      max: scale,
+      dither: if bits > 24 { Dither::None } else { dither },
+      errors: vec![0f32; channels as usize],
+    }
+  }
+
+  fn quantize<R: Rng>(&mut self, rng: &mut R, channel: usize, sample: f32) -> i32 {
+    let target = sample * self.scale;
+    let value = match self.dither {
+      Dither::None => target,
+      Dither::Tpdf => target + rng.gen::<f32>() - rng.gen::<f32>(),
+      Dither::Shaped => target - self.errors[channel] + rng.gen::<f32>() - rng.gen::<f32>(),
+    };
+    let rounded = value.round();
+    // Feed back the rounding error only. Clipping error is unbounded and would pin the output after a clip.
+    self.errors[channel] = rounded - (target - self.errors[channel]);
+    rounded.clamp(self.min, self.max) as i32
+  }
+}
+
+/// A WAV file written a block of frames at a time.
+pub struct WavStream {
+  writer: hound::WavWriter<std::io::BufWriter<std::fs::File>>,
+  quantizer: Quantizer,
+  rng: crate::seed::ThreadRng,
+  bit_depth: BitDepth,
+  channels: u16,
+  frames: usize,
+}
+
+impl WavStream {
+  pub fn create(sample_rate: usize, channels: u16, filename: &str, format: WavFormat) -> Result<Self, hound::Error> {
+    let spec = hound::WavSpec {
+      channels,
+      sample_rate: sample_rate as u32,
+      bits_per_sample: format.bit_depth.bits(),
+      sample_format: if format.bit_depth == BitDepth::Float32 {
+        hound::SampleFormat::Float
+      } else {
+        hound::SampleFormat::Int
+      },
+    };
+    Ok(WavStream {
+      writer: hound::WavWriter::create(std::path::Path::new(filename), spec)?,
+      quantizer: Quantizer::new(spec.bits_per_sample, format.dither, channels),
+      rng: crate::seed::thread_rng(),
+      bit_depth: format.bit_depth,
+      channels,
+      frames: 0,
+    })
+  }
+
+  /// Append interleaved samples. A trailing partial frame is padded with silence.
+  pub fn write(&mut self, samples: &[f32]) -> Result<(), hound::Error> {
+    for frame in samples.chunks(self.channels as usize) {
+      for c in 0..self.channels as usize {
+        let sample = *frame.get(c).unwrap_or(&0f32);
+        match self.bit_depth {
+          BitDepth::Float32 => self.writer.write_sample(sample)?,
+          _ => self.writer.write_sample(self.quantizer.quantize(&mut self.rng, c, sample))?,
+        }
+      }
+      self.frames += 1;
+    }
+    Ok(())
+  }
+
+  /// Number of frames written so far.
+  pub fn frames(&self) -> usize {
+    self.frames
+  }
+
+  pub fn finalize(self) -> Result<(), hound::Error> {
+    self.writer.finalize()
+  }
+}
+
+/// Write interleaved samples to a WAV file.
+/// A trailing partial frame is padded with silence.
+fn write_interleaved(
+  sample_rate: usize, channels: u16, samples: &[f32], filename: &str, format: WavFormat,
+) -> Result<(), hound::Error> {
+  let mut stream = WavStream::create(sample_rate, channels, filename, format)?;
+  stream.write(samples)?;
+  stream.finalize()
+}
+
+pub fn samples(sample_rate: usize, samples: &[f32], filename: &str) -> Result<(), RaudioError> {
+  write_interleaved(sample_rate, 1, samples, filename, WavFormat::default())
+    .map_err(|e| RaudioError::Wav(filename.to_string(), e))
+}
+
+pub fn samples2(sample_rate: usize, samples: &[f32], filename: &str) -> Result<(), RaudioError> {
+  // Left and right samples alternate. An odd sample count leaves the last right sample silent.
+  write_interleaved(sample_rate, 2, samples, filename, WavFormat::default())
+    .map_err(|e| RaudioError::Wav(filename.to_string(), e))
+}
+
+/// Write a mono or stereo signal to a WAV file in the given format.
+/// Stereo channels are trimmed to the length of the shorter one.
+pub fn try_write_audio_as(
+  sample_rate: usize, signals: Vec<Vec<f32>>, filename: &str, format: WavFormat,
+) -> Result<(), RaudioError> {
+  let written = match signals.len() {
+    1 => write_interleaved(sample_rate, 1, &signals[0], filename, format),
+    2 => {
+      let left = &signals[0];
+      let right = &signals[1];
+      let min_len = left.len().min(right.len());
+      let interleaved: Vec<f32> =
+        left[..min_len].iter().zip(right[..min_len].iter()).flat_map(|(&l, &r)| [l, r]).collect();
+      write_interleaved(sample_rate, 2, &interleaved, filename, format)
     }
+    n => {
+      return Err(RaudioError::Mix(format!(
+        "Only mono and stereo signals are supported, got {} channels",
+        n
+      )))
+    }
+  };
+  written.map_err(|e| RaudioError::Wav(filename.to_string(), e))
+}
+
+/// Write a mono or stereo signal to a 32 bit float WAV file.
+pub fn try_write_audio(sample_rate: usize, signals: Vec<Vec<f32>>, filename: &str) -> Result<(), RaudioError> {
+  try_write_audio_as(sample_rate, signals, filename, WavFormat::default())
+}
+
+/// Panicking version of `try_write_audio_as`.
+pub fn write_audio_as(sample_rate: usize, signals: Vec<Vec<f32>>, filename: &str, format: WavFormat) {
+  if let Err(e) = try_write_audio_as(sample_rate, signals, filename, format) {
+    panic!("{}", e)
   }
-  writer.finalize().unwrap();
 }
 
+/// Panicking version of `try_write_audio`.
 pub fn write_audio(sample_rate: usize, signals: Vec<Vec<f32>>, filename: &str) {
-  if signals.len() == 1 {
-    // Mono signal, use samples
-    samples(sample_rate, &signals[0], filename);
-  } else if signals.len() == 2 {
-    // Stereo signal, interleave and use samples2
-    let left = &signals[0];
-    let right = &signals[1];
-
-    // Ensure both channels have the same length
-    let min_len = left.len().min(right.len());
-    let interleaved: Vec<f32> =
-      left[..min_len].iter().zip(right[..min_len].iter()).flat_map(|(&l, &r)| vec![l, r]).collect();
-
-    samples2(sample_rate, &interleaved, filename);
-  } else {
-    panic!("Only mono and stereo signals are supported!");
+  write_audio_as(sample_rate, signals, filename, WavFormat::default())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn sine(n: usize, amp: f32) -> Vec<f32> {
+    (0..n).map(|i| amp * (i as f32 * 0.05f32).sin()).collect()
+  }
+
+  #[test]
+  fn test_write_bit_depths() {
+    crate::files::with_dir("dev-audio/engrave/x.wav");
+    let signal = sine(4800, 0.5f32);
+    for (bit_depth, name) in [
+      (BitDepth::Int16, "16"),
+      (BitDepth::Int24, "24"),
+      (BitDepth::Int32, "32"),
+    ] {
+      let filename = format!("dev-audio/engrave/sine-{}.wav", name);
+      let format = WavFormat {
+        bit_depth,
+        dither: Dither::Shaped,
+      };
+      try_write_audio_as(48000, vec![signal.clone(), signal.clone()], &filename, format).unwrap();
+      let mut reader = hound::WavReader::open(&filename).unwrap();
+      let spec = reader.spec();
+      assert_eq!(
+        (hound::SampleFormat::Int, bit_depth.bits(), 2),
+        (spec.sample_format, spec.bits_per_sample, spec.channels)
+      );
+      let full_scale = ((1i64 << (spec.bits_per_sample - 1)) - 1) as f32;
+      let read: Vec<f32> = reader.samples::<i32>().step_by(2).map(|s| s.unwrap() as f32 / full_scale).collect();
+      let max_error = read.iter().zip(signal.iter()).fold(0f32, |max, (a, b)| max.max((a - b).abs()));
+      assert!(
+        max_error < 4f32 / full_scale,
+        "{} bit samples must be within a few LSB, got {}",
+        name,
+        max_error
+      );
+    }
+  }
+
+  #[test]
+  fn test_tpdf_dither_decorrelates_quiet_signals() {
+    let mut rng = crate::seed::thread_rng();
+    // a signal below half an LSB is lost without dither
+    let lsb = 1f32 / 32767f32;
+    let quiet = vec![0.4f32 * lsb; 1000];
+    let mut plain = Quantizer::new(16, Dither::None, 1);
+    let mut dithered = Quantizer::new(16, Dither::Tpdf, 1);
+    assert!(quiet.iter().all(|&s| plain.quantize(&mut rng, 0, s) == 0));
+    let mean = quiet.iter().map(|&s| dithered.quantize(&mut rng, 0, s) as f32).sum::<f32>() / quiet.len() as f32;
+    assert!(
+      (mean - 0.4f32).abs() < 0.15f32,
+      "Dithered output must keep the signal on average, got {}",
+      mean
+    );
+  }
+
+  #[test]
+  fn test_shaped_dither_recovers_after_clipping() {
+    let mut rng = crate::seed::thread_rng();
+    let mut quantizer = Quantizer::new(16, Dither::Shaped, 1);
+    for _ in 0..1000 {
+      quantizer.quantize(&mut rng, 0, 1.5f32);
+    }
+    let silence: Vec<i32> = (0..100).map(|_| quantizer.quantize(&mut rng, 0, 0f32)).collect();
+    assert!(
+      silence.iter().all(|s| s.abs() <= 2),
+      "Silence after a clip must quantize near zero, got {:?}",
+      &silence[..10]
+    );
+  }
+
+  #[test]
+  fn test_unwritable_samples_are_an_error() {
+    let filename = "Cargo.toml/samples.wav";
+    assert!(matches!(samples(48000, &sine(100, 0.5f32), filename), Err(RaudioError::Wav(..))));
+    assert!(matches!(samples2(48000, &sine(100, 0.5f32), filename), Err(RaudioError::Wav(..))));
   }
 }
@@ -0,0 +1,187 @@
+/// Glides between neighbouring notes, also called portamento.
+///
+/// Each note carries its context window as a `Frex`: the frequencies of its neighbours and how long to glide from the
+/// previous note and toward the next one. A glide into a note starts at the previous frequency and reaches the note's
+/// own over its length. A glide out of a note leaves its frequency toward the next one at the end of the note.
+/// When a line glides both into and out of its notes, each side covers half of the interval so that neighbours meet
+/// at the geometric mean of their frequencies.
+///
+/// Glides are given in cycles and never take more than the note, or half of it when the note glides both ways.
+/// Renders honour them as a per-sample multiplier of each note's frequency.
+use crate::types::synthesis::{Frex, Glide, GlideCurve, GlideLen, Note};
+
+/// The length of a glide in cycles.
+pub fn len_cycles(len: GlideLen) -> f32 {
+  match len {
+    GlideLen::None => 0f32,
+    GlideLen::Quarter => 0.25f32,
+    GlideLen::Eigth => 0.125f32,
+    GlideLen::Sixteenth => 0.0625f32,
+    GlideLen::Cycles(cycles) => cycles.max(0f32),
+  }
+}
+
+/// Reasons a glide cannot be rendered.
+pub fn check(glide: &Glide) -> Result<(), String> {
+  for (side, len) in [("from", glide.from), ("to", glide.to)] {
+    if let GlideLen::Cycles(cycles) = len {
+      if !(cycles.is_finite() && cycles > 0f32) {
+        return Err(format!(
+          "Glide {} must last a positive number of cycles, got {}",
+          side, cycles
+        ));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// The context window of each note in `line`, gliding as `glide` asks.
+/// Rests are not glided to or from, so a glide only joins notes that sound one after the other.
+pub fn line_frexes(line: &Vec<Note>, glide: &Glide) -> Vec<Frex> {
+  let mut frexes = crate::druid::line_frexer(line, glide.from, glide.to);
+  for (i, frex) in frexes.iter_mut().enumerate() {
+    if i > 0 && line[i - 1].2 <= 0f32 {
+      frex.1 = None;
+    }
+    if line.get(i + 1).is_some_and(|next| next.2 <= 0f32) {
+      frex.3 = None;
+    }
+  }
+  frexes
+}
+
+/// Frequency multipliers of a note over its samples.
+pub struct Contour {
+  /// Multiplier of the frequency at each sample
+  pub freq: Vec<f32>,
+  /// Mean of `freq` from the start of the note to each sample.
+  /// Multiplies the frequency of the phase, which accumulates over the note as `frequency * time`.
+  pub phase: Vec<f32>,
+}
+
+fn interpolate(curve: GlideCurve, a: f32, b: f32, x: f32) -> f32 {
+  match curve {
+    GlideCurve::Linear => a + (b - a) * x,
+    GlideCurve::Exponential => a * (b / a).powf(x),
+  }
+}
+
+/// The glide of a note lasting `n_cycles` in `n_samples`, or `None` when it plays at a constant frequency.
+pub fn contour(frex: &Frex, curve: GlideCurve, n_cycles: f32, n_samples: usize) -> Option<Contour> {
+  let &(glide_from, prev, freq, next, glide_to) = frex;
+  let gliding = |len: GlideLen, neighbour: Option<f32>| {
+    len != GlideLen::None && neighbour.is_some_and(|f| f > 0f32 && f != freq) && freq > 0f32
+  };
+  let (into, out_of) = (gliding(glide_from, prev), gliding(glide_to, next));
+  if !(into || out_of) || n_samples == 0 || n_cycles <= 0f32 {
+    return None;
+  }
+  // both neighbours glide toward each other when the line glides both ways
+  let both = glide_from != GlideLen::None && glide_to != GlideLen::None;
+  let share = if both { 0.5f32 } else { 1f32 };
+  let limit = n_cycles * share;
+  let len_in = if into { len_cycles(glide_from).min(limit) } else { 0f32 };
+  let len_out = if out_of { len_cycles(glide_to).min(limit) } else { 0f32 };
+  let start = prev.map(|f| (f / freq).powf(share)).unwrap_or(1f32);
+  let end = next.map(|f| (f / freq).powf(share)).unwrap_or(1f32);
+
+  let freq: Vec<f32> = (0..n_samples)
+    .map(|j| {
+      let t = n_cycles * j as f32 / n_samples as f32;
+      if t < len_in {
+        interpolate(curve, start, 1f32, t / len_in)
+      } else if len_out > 0f32 && t > n_cycles - len_out {
+        interpolate(curve, 1f32, end, (t - (n_cycles - len_out)) / len_out)
+      } else {
+        1f32
+      }
+    })
+    .collect();
+  let mut sum = 0f32;
+  let phase = freq
+    .iter()
+    .enumerate()
+    .map(|(j, &m)| {
+      sum += m;
+      sum / (j + 1) as f32
+    })
+    .collect();
+  Some(Contour { freq, phase })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn cents(ratio: f32) -> f32 {
+    1200f32 * ratio.log2()
+  }
+
+  #[test]
+  fn test_glide_into_a_note() {
+    let frex: Frex = (GlideLen::Quarter, Some(200f32), 300f32, Some(400f32), GlideLen::None);
+    let glide = contour(&frex, GlideCurve::Linear, 1f32, 100).unwrap();
+    assert!(
+      (glide.freq[0] - 200f32 / 300f32).abs() < 1e-6,
+      "Must start at the previous note"
+    );
+    assert!((glide.freq[12] - (200f32 + 100f32 * 0.48f32) / 300f32).abs() < 1e-5);
+    assert!(
+      glide.freq[25..].iter().all(|&m| m == 1f32),
+      "Must hold the note after the glide"
+    );
+    assert!(glide.phase[99] < 1f32 && glide.phase[99] > glide.freq[0]);
+
+    let glide = contour(&frex, GlideCurve::Exponential, 1f32, 100).unwrap();
+    let halfway = cents(glide.freq[12]) - cents(glide.freq[0]);
+    assert!(
+      (halfway - 0.48f32 * cents(1.5f32)).abs() < 0.1,
+      "Must move in equal steps of pitch"
+    );
+  }
+
+  #[test]
+  fn test_neighbours_meet_halfway() {
+    let (a, b) = (200f32, 300f32);
+    let first: Frex = (GlideLen::Eigth, None, a, Some(b), GlideLen::Eigth);
+    let second: Frex = (GlideLen::Eigth, Some(a), b, None, GlideLen::Eigth);
+    let out = contour(&first, GlideCurve::Exponential, 1f32, 1000).unwrap();
+    let into = contour(&second, GlideCurve::Exponential, 1f32, 1000).unwrap();
+    let meet = (a * b).sqrt();
+    assert!((a * out.freq[999] - meet).abs() < 1f32);
+    assert!((b * into.freq[0] - meet).abs() < 1e-3);
+    assert!(out.freq[..875].iter().all(|&m| m == 1f32));
+  }
+
+  #[test]
+  fn test_no_glide() {
+    let frex: Frex = (GlideLen::None, Some(200f32), 300f32, Some(400f32), GlideLen::None);
+    assert!(contour(&frex, GlideCurve::Linear, 1f32, 100).is_none());
+    let alone: Frex = (GlideLen::Quarter, None, 300f32, None, GlideLen::Quarter);
+    assert!(contour(&alone, GlideCurve::Linear, 1f32, 100).is_none());
+    // a glide longer than its note is cut to the note
+    let long: Frex = (GlideLen::Cycles(4f32), Some(150f32), 300f32, None, GlideLen::None);
+    let glide = contour(&long, GlideCurve::Linear, 0.5f32, 100).unwrap();
+    assert!(glide.freq[99] < 1f32 && glide.freq[99] > 0.99f32);
+    assert!(check(&Glide {
+      from: GlideLen::Cycles(-1f32),
+      ..Default::default()
+    })
+    .is_err());
+  }
+
+  #[test]
+  fn test_rests_are_not_glided() {
+    let note = |monic: i8, amp: f32| ((1, 4), (5, (0, 0, monic)), amp);
+    let line: Vec<Note> = vec![note(1, 1f32), note(3, 0f32), note(5, 1f32), note(3, 1f32)];
+    let glide = Glide {
+      from: GlideLen::Sixteenth,
+      ..Default::default()
+    };
+    let frexes = line_frexes(&line, &glide);
+    assert!(frexes[2].1.is_none(), "Must not glide from a rest");
+    assert!(frexes[3].1.is_some());
+    assert!(frexes[0].3.is_none(), "Must not glide into a rest");
+  }
+}
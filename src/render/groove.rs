@@ -0,0 +1,185 @@
+/// Grooves, playing the notes of a line off the grid they are written on.
+///
+/// A groove lays a grid of eighths or sixteenths over each line, starting at its first note, and moves each step of
+/// the grid by a fraction of a step. Swing delays the second step of each pair so that the first takes `swing`
+/// percent of the pair. A table of steps, such as one taken from a reference loop, adds its own timing to
+/// consecutive steps and scales the amplitude of the notes starting on them.
+/// Notes between the steps move in proportion, so the order of the notes is kept.
+///
+/// Only the onsets of notes move. Their durations are rendered as written, so a late note overlaps the next one
+/// and an early note leaves a gap before it.
+use crate::types::synthesis::{Groove, GrooveGrid};
+
+/// The length of a step of the grid in cycles.
+pub fn step_cycles(grid: GrooveGrid) -> f32 {
+  match grid {
+    GrooveGrid::Eighth => 0.5f32,
+    GrooveGrid::Sixteenth => 0.25f32,
+  }
+}
+
+/// Offset of step `k` in steps.
+fn timing(groove: &Groove, k: i64) -> f32 {
+  let swing = match k.rem_euclid(2) {
+    1 => 2f32 * groove.swing / 100f32 - 1f32,
+    _ => 0f32,
+  };
+  let table = match groove.steps.len() {
+    0 => 0f32,
+    n => groove.steps[k.rem_euclid(n as i64) as usize].timing,
+  };
+  swing + table
+}
+
+/// Reasons a groove cannot be rendered.
+pub fn check(groove: &Groove) -> Result<(), String> {
+  if !(groove.swing.is_finite() && groove.swing > 0f32 && groove.swing < 100f32) {
+    return Err(format!(
+      "Swing must be a percent between 0 and 100, got {}",
+      groove.swing
+    ));
+  }
+  for (k, step) in groove.steps.iter().enumerate() {
+    if !step.timing.is_finite() {
+      return Err(format!("Step {} has no timing, got {}", k, step.timing));
+    }
+    if !(step.velocity.is_finite() && step.velocity >= 0f32) {
+      return Err(format!(
+        "Step {} must have a velocity of 0 or more, got {}",
+        k, step.velocity
+      ));
+    }
+  }
+  // swing repeats every 2 steps and the table every `n`
+  let n = groove.steps.len().max(1) as i64;
+  let period = if n % 2 == 0 { n } else { 2 * n };
+  for k in 0..period {
+    if 1f32 + timing(groove, k + 1) - timing(groove, k) <= 0f32 {
+      return Err(format!("Step {} must play after step {}", k + 1, k));
+    }
+  }
+  Ok(())
+}
+
+/// Cycles from its place on the grid to where a note starting at `cycle` plays.
+pub fn shift(groove: &Groove, cycle: f32) -> f32 {
+  let step = step_cycles(groove.grid);
+  let x = cycle / step;
+  let k = x.floor();
+  let (a, b) = (timing(groove, k as i64), timing(groove, k as i64 + 1));
+  (a + (b - a) * (x - k)) * step
+}
+
+/// Multiplier of the amplitude of a note starting at `cycle`, from the nearest step of the table.
+pub fn velocity(groove: &Groove, cycle: f32) -> f32 {
+  match groove.steps.len() {
+    0 => 1f32,
+    n => {
+      let k = (cycle / step_cycles(groove.grid)).round() as i64;
+      groove.steps[k.rem_euclid(n as i64) as usize].velocity
+    }
+  }
+}
+
+/// Shift in cycles and amplitude multiplier of each note of a line whose notes last `durs` cycles.
+pub fn line_feel(durs: &[f32], groove: &Groove) -> Vec<(f32, f32)> {
+  let mut onset = 0f32;
+  durs
+    .iter()
+    .map(|dur| {
+      let at = onset;
+      onset += dur;
+      (shift(groove, at), velocity(groove, at))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::types::synthesis::GrooveStep;
+
+  fn swung(grid: GrooveGrid, swing: f32) -> Groove {
+    Groove {
+      grid,
+      swing,
+      steps: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn test_swing_delays_the_offbeat() {
+    let groove = swung(GrooveGrid::Eighth, 2f32 / 3f32 * 100f32);
+    assert_eq!(0f32, shift(&groove, 0f32), "Must keep the downbeat");
+    assert!(
+      (0.5f32 + shift(&groove, 0.5f32) - 2f32 / 3f32).abs() < 1e-5,
+      "Must play the offbeat as a triplet"
+    );
+    assert_eq!(0f32, shift(&groove, 3f32));
+    // a note between the steps moves in proportion
+    assert!((shift(&groove, 0.25f32) - 0.5f32 * shift(&groove, 0.5f32)).abs() < 1e-6);
+    let sixteenths = swung(GrooveGrid::Sixteenth, 60f32);
+    assert!((shift(&sixteenths, 0.25f32) - 0.05f32).abs() < 1e-6);
+    assert_eq!(
+      0f32,
+      shift(&Groove::default(), 0.25f32),
+      "Must play straight by default"
+    );
+  }
+
+  #[test]
+  fn test_steps_repeat_their_timing_and_velocity() {
+    let step = |timing: f32, velocity: f32| GrooveStep { timing, velocity };
+    let groove = Groove {
+      grid: GrooveGrid::Sixteenth,
+      swing: 50f32,
+      steps: vec![step(0f32, 1f32), step(-0.1f32, 0.5f32), step(0.2f32, 0.8f32)],
+    };
+    assert!((shift(&groove, 0.25f32) + 0.025f32).abs() < 1e-6);
+    assert!((shift(&groove, 0.5f32) - 0.05f32).abs() < 1e-6);
+    assert!(
+      (shift(&groove, 0.75f32 + 0.5f32) - shift(&groove, 0.5f32)).abs() < 1e-6,
+      "Must repeat every 3 steps"
+    );
+    assert_eq!(0.5f32, velocity(&groove, 1f32));
+    assert_eq!(
+      0.8f32,
+      velocity(&groove, 0.45f32),
+      "Must take the velocity of the nearest step"
+    );
+    let feel = line_feel(&[0.25f32, 0.25f32, 0.5f32], &groove);
+    assert_eq!(
+      vec![0f32, -0.025f32, 0.05f32],
+      feel.iter().map(|f| (f.0 * 1e3).round() / 1e3).collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn test_check_keeps_the_steps_in_order() {
+    assert!(check(&Groove::default()).is_ok());
+    assert!(check(&swung(GrooveGrid::Eighth, 100f32)).is_err());
+    let groove = Groove {
+      swing: 70f32,
+      steps: vec![
+        GrooveStep {
+          timing: 0f32,
+          velocity: 1f32,
+        },
+        GrooveStep {
+          timing: 0.7f32,
+          velocity: 1f32,
+        },
+      ],
+      ..Default::default()
+    };
+    assert!(check(&groove).unwrap_err().contains("after step 1"));
+    let silent = Groove {
+      steps: vec![GrooveStep {
+        timing: 0f32,
+        velocity: -1f32,
+      }],
+      ..Default::default()
+    };
+    assert!(check(&silent).is_err());
+  }
+}
@@ -96,7 +96,7 @@ fn manual_sum_of_sines(frequencies: &[(f32, f32, f32)], num_samples: usize, samp
 
 /// Generates random test frequencies, amplitudes, and phases.
 fn gen_test_freqs(n: usize) -> Vec<(f32, f32, f32)> {
-  let mut rng = rand::thread_rng();
+  let mut rng = crate::seed::thread_rng();
   let mut frequencies = Vec::with_capacity(n);
   for _ in 0..n {
     let freq = rng.gen_range(20.0..20000.0);
@@ -120,14 +120,14 @@ mod test {
     files::with_dir(TEST_DIR);
     let filename = format!("{}/major-chord.wav", TEST_DIR);
 
-    let signal = ifft(&mut freqs, SR, SR * 4);
-    engrave::samples(SR as usize, &signal, &filename);
+    let signal = ifft(&mut freqs, SR(), SR() * 4);
+    engrave::samples(SR() as usize, &signal, &filename).unwrap();
 
     let mut freqs: Vec<(f32, f32, f32)> = vec![(600f32, 1f32, 0f32), (400f32, 1f32, 0f32), (240f32, 1f32, 0f32)];
     let filename = format!("{}/minor-chord.wav", TEST_DIR);
 
-    let signal = ifft(&mut freqs, SR, SR * 4);
-    engrave::samples(SR as usize, &signal, &filename);
+    let signal = ifft(&mut freqs, SR(), SR() * 4);
+    engrave::samples(SR() as usize, &signal, &filename).unwrap();
 
     let mut freqs: Vec<(f32, f32, f32)> = vec![
       (400f32, 1f32, 0f32),
@@ -138,8 +138,8 @@ mod test {
     ];
     let filename = format!("{}/monic-chord.wav", TEST_DIR);
 
-    let signal = ifft(&mut freqs, SR, SR * 4);
-    engrave::samples(SR as usize, &signal, &filename);
+    let signal = ifft(&mut freqs, SR(), SR() * 4);
+    engrave::samples(SR() as usize, &signal, &filename).unwrap();
   }
 
   #[test]
@@ -154,7 +154,7 @@ mod test {
     let filename = format!("{}/benchmark-{}.wav", TEST_DIR, spectrum.len());
 
     let (signal, ifft_duration) = time::measure(|| do_ifft(&mut spectrum.clone(), num_samples));
-    engrave::samples(sample_rate as usize, &signal, &filename);
+    engrave::samples(sample_rate as usize, &signal, &filename).unwrap();
 
     let (_, sum_duration) = time::measure(|| manual_sum_of_sines(&mut frequencies, num_samples, sample_rate));
 
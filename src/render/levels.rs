@@ -0,0 +1,155 @@
+/// The levels of a render: the peaks each of its mixes divides by to prevent clipping.
+///
+/// Mixing lines, channels and parts scales the inputs by their loudest sample and the sum by its own.
+/// A window (see `render::window`) synthesizes only the notes heard in it, so its loudest samples are not those
+/// of the full render. To render a window like the same region of the full render, the full render
+/// records its levels and the window replays them: each mix divides by the peak the full render measured
+/// instead of its own. `render_score` keeps the levels of seeded renders in its cache, see `render::cache`.
+///
+/// A mix is known by the keys of the task it runs in (see `RenderContext::for_task`) and its `Stage`, which are
+/// the same in every render of a score.
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// The mixes of a task.
+#[derive(Clone, Copy, Debug)]
+pub enum Stage {
+  /// The lines of a stem
+  Lines,
+  /// The channels of a part
+  Channels,
+  /// The parts of a render
+  Parts,
+}
+
+/// The peaks measured by each mix of a render, in the order it measured them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Levels {
+  peaks: BTreeMap<String, Vec<f32>>,
+}
+
+impl Levels {
+  /// The levels of the mixes in the tasks of part `j` and the tasks it started.
+  pub fn of_part(&self, j: usize) -> Levels {
+    let prefix = format!("{}/", j);
+    Levels {
+      peaks: self
+        .peaks
+        .iter()
+        .filter(|(key, _)| key.starts_with(&prefix))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect(),
+    }
+  }
+
+  /// Add the levels of `other`, replacing those of the same mixes.
+  pub fn extend(&mut self, other: Levels) {
+    self.peaks.extend(other.peaks)
+  }
+}
+
+/// What the mixes of a render do with their levels.
+#[derive(Clone, Debug)]
+pub enum Metering {
+  /// Measure every peak and keep it
+  Record(Arc<Mutex<Levels>>),
+  /// Divide by the peaks of these levels, measuring only those of mixes they do not have
+  Replay(Arc<Levels>),
+}
+
+thread_local! {
+  static METERING: RefCell<Option<Metering>> = const { RefCell::new(None) };
+  static TASK: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Set the metering of the current thread and the keys of the task it renders. See `RenderContext`.
+pub fn set_current(metering: Option<Metering>, task: Vec<u64>) {
+  METERING.with(|cell| *cell.borrow_mut() = metering);
+  TASK.with(|cell| *cell.borrow_mut() = task);
+}
+
+/// The metering of the current thread, if any.
+pub fn current() -> Option<Metering> {
+  METERING.with(|cell| cell.borrow().clone())
+}
+
+/// The keys of the task the current thread renders.
+pub fn task() -> Vec<u64> {
+  TASK.with(|cell| cell.borrow().clone())
+}
+
+/// The peaks of one mix. Recorded when dropped.
+pub struct Peaks {
+  key: String,
+  metering: Option<Metering>,
+  measured: Vec<f32>,
+}
+
+/// Start metering the mix of `stage` in the current task.
+pub fn peaks(stage: Stage) -> Peaks {
+  let task: String = task().iter().map(|key| format!("{}/", key)).collect();
+  Peaks {
+    key: format!("{}{:?}", task, stage).to_lowercase(),
+    metering: current(),
+    measured: Vec::new(),
+  }
+}
+
+impl Peaks {
+  /// The next peak of the mix: the one the full render measured when replaying its levels, or else `measure()`.
+  pub fn next(&mut self, measure: impl FnOnce() -> f32) -> f32 {
+    let replayed = match &self.metering {
+      Some(Metering::Replay(levels)) => {
+        levels.peaks.get(&self.key).and_then(|peaks| peaks.get(self.measured.len())).copied()
+      }
+      _ => None,
+    };
+    let peak = replayed.unwrap_or_else(measure);
+    self.measured.push(peak);
+    peak
+  }
+}
+
+impl Drop for Peaks {
+  fn drop(&mut self) {
+    if let Some(Metering::Record(levels)) = &self.metering {
+      if let Ok(mut levels) = levels.lock() {
+        levels.peaks.insert(std::mem::take(&mut self.key), std::mem::take(&mut self.measured));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_replay_divides_by_the_recorded_peaks() {
+    let recorded = Arc::new(Mutex::new(Levels::default()));
+    set_current(Some(Metering::Record(recorded.clone())), vec![2, 1]);
+    {
+      let mut peaks = peaks(Stage::Lines);
+      assert_eq!(3f32, peaks.next(|| 3f32));
+      assert_eq!(1.5f32, peaks.next(|| 1.5f32));
+    }
+    let levels = recorded.lock().unwrap().clone();
+    assert_eq!(Some(&vec![3f32, 1.5f32]), levels.peaks.get("2/1/lines"));
+    assert_eq!(levels, levels.of_part(2));
+    assert_eq!(Levels::default(), levels.of_part(1));
+
+    set_current(Some(Metering::Replay(Arc::new(levels))), vec![2, 1]);
+    let mut replayed = peaks(Stage::Lines);
+    assert_eq!(3f32, replayed.next(|| 0.5f32), "Must divide by the recorded peak");
+    assert_eq!(1.5f32, replayed.next(|| 0.5f32));
+    assert_eq!(
+      0.5f32,
+      replayed.next(|| 0.5f32),
+      "Must measure peaks that were not recorded"
+    );
+    drop(replayed);
+    set_current(None, vec![]);
+  }
+}
@@ -0,0 +1,231 @@
+/// Score markers as WAV cue points.
+///
+/// Each `Marker` of a `DruidicScore` becomes a cue point at its start, a `labl` label with its tag
+/// and an `ltxt` region spanning to its end. These are appended to a finished WAV file as the
+/// `cue ` and `LIST/adtl` chunks read by most editors.
+///
+/// The same times can be written as a sidecar JSON or CSV file.
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::error::RaudioError;
+use crate::time;
+use crate::types::render::Marker;
+
+/// File format of the marker sidecar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SidecarFormat {
+  Json,
+  Csv,
+}
+
+impl SidecarFormat {
+  pub fn from_name(name: &str) -> Option<SidecarFormat> {
+    match name.to_lowercase().as_str() {
+      "json" => Some(SidecarFormat::Json),
+      "csv" => Some(SidecarFormat::Csv),
+      _ => None,
+    }
+  }
+
+  fn extension(&self) -> &'static str {
+    match self {
+      SidecarFormat::Json => "json",
+      SidecarFormat::Csv => "csv",
+    }
+  }
+}
+
+/// A marker placed in a rendered file.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Cue {
+  /// Cue point id, counting from 1
+  pub id: u32,
+  pub label: String,
+  pub tag: String,
+  pub instance: usize,
+  pub start_seconds: f32,
+  pub end_seconds: f32,
+  /// Sample frame of the start
+  pub start_sample: usize,
+  /// Sample frame of the end
+  pub end_sample: usize,
+}
+
+/// Place markers in a render of `n_samples` frames.
+/// `offset_cycles` is the score position of the first frame, for renders of a window of the score.
+/// Markers ending before the first frame or starting after the last are dropped, the rest are clipped to the render.
+pub fn cues(markers: &[Marker], cps: f32, offset_cycles: f32, n_samples: usize) -> Vec<Cue> {
+  let offset = time::samples_at(cps, offset_cycles);
+  let to_samples = |cycles: f32| time::samples_at(cps, cycles.max(0f32)).saturating_sub(offset).min(n_samples);
+  let to_seconds = |samples: usize| samples as f32 / crate::synth::SRf();
+  markers
+    .iter()
+    .filter(|m| m.end > offset_cycles && time::samples_at(cps, m.start) < offset + n_samples)
+    .enumerate()
+    .map(|(i, m)| {
+      let start_sample = to_samples(m.start);
+      let end_sample = to_samples(m.end).max(start_sample);
+      Cue {
+        id: i as u32 + 1,
+        label: format!("{} #{}", m.tag, m.instance),
+        tag: m.tag.clone(),
+        instance: m.instance,
+        start_seconds: to_seconds(start_sample),
+        end_seconds: to_seconds(end_sample),
+        start_sample,
+        end_sample,
+      }
+    })
+    .collect()
+}
+
+fn chunk(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+  let mut bytes = Vec::with_capacity(body.len() + 9);
+  bytes.extend_from_slice(id);
+  bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+  bytes.extend_from_slice(body);
+  // chunks are word aligned
+  if body.len() % 2 == 1 {
+    bytes.push(0);
+  }
+  bytes
+}
+
+/// Encode the `cue ` chunk and the `LIST/adtl` chunk for `cues`.
+pub fn encode_chunks(cues: &[Cue]) -> Vec<u8> {
+  let mut cue_body = (cues.len() as u32).to_le_bytes().to_vec();
+  let mut adtl = b"adtl".to_vec();
+  for cue in cues {
+    cue_body.extend_from_slice(&cue.id.to_le_bytes());
+    cue_body.extend_from_slice(&(cue.start_sample as u32).to_le_bytes());
+    cue_body.extend_from_slice(b"data");
+    cue_body.extend_from_slice(&0u32.to_le_bytes());
+    cue_body.extend_from_slice(&0u32.to_le_bytes());
+    cue_body.extend_from_slice(&(cue.start_sample as u32).to_le_bytes());
+
+    let mut labl = cue.id.to_le_bytes().to_vec();
+    labl.extend_from_slice(cue.label.as_bytes());
+    labl.push(0);
+    adtl.extend(chunk(b"labl", &labl));
+
+    let mut ltxt = cue.id.to_le_bytes().to_vec();
+    ltxt.extend_from_slice(&((cue.end_sample - cue.start_sample) as u32).to_le_bytes());
+    ltxt.extend_from_slice(b"rgn ");
+    // country, language, dialect and code page
+    ltxt.extend_from_slice(&[0u8; 8]);
+    adtl.extend(chunk(b"ltxt", &ltxt));
+  }
+  let mut bytes = chunk(b"cue ", &cue_body);
+  bytes.extend(chunk(b"LIST", &adtl));
+  bytes
+}
+
+/// Append cue and label chunks to the WAV file at `path` and update its RIFF size.
+pub fn write_cues(path: &str, cues: &[Cue]) -> Result<(), RaudioError> {
+  if cues.is_empty() {
+    return Ok(());
+  }
+  let append = || -> io::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut header = [0u8; 12];
+    file.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "not a RIFF WAVE file"));
+    }
+    let end = file.seek(SeekFrom::End(0))?;
+    // the data chunk may end on an odd byte
+    if end % 2 == 1 {
+      file.write_all(&[0])?;
+    }
+    file.write_all(&encode_chunks(cues))?;
+    let riff_size = file.seek(SeekFrom::End(0))? - 8;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&(riff_size as u32).to_le_bytes())
+  };
+  append().map_err(|e| RaudioError::Io(path.to_string(), e))
+}
+
+/// Path of the marker sidecar for `asset_name` in `out_dir`.
+pub fn sidecar_path(out_dir: &str, asset_name: &str, format: SidecarFormat) -> String {
+  format!("{}/{}.markers.{}", out_dir, asset_name, format.extension())
+}
+
+/// Write `cues` as JSON or CSV to `path`.
+pub fn write_sidecar(path: &str, cues: &[Cue], format: SidecarFormat) -> Result<(), RaudioError> {
+  let contents = match format {
+    SidecarFormat::Json => {
+      serde_json::to_string_pretty(cues).map_err(|e| RaudioError::Io(path.to_string(), io::Error::from(e)))?
+    }
+    SidecarFormat::Csv => {
+      let mut csv = String::from("id,tag,instance,start_seconds,end_seconds,start_sample,end_sample\n");
+      for c in cues {
+        csv.push_str(&format!(
+          "{},\"{}\",{},{},{},{},{}\n",
+          c.id,
+          c.tag.replace('"', "\"\""),
+          c.instance,
+          c.start_seconds,
+          c.end_seconds,
+          c.start_sample,
+          c.end_sample
+        ));
+      }
+      csv
+    }
+  };
+  fs::write(path, contents).map_err(|e| RaudioError::Io(path.to_string(), e))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn marker(tag: &str, start: f32, end: f32) -> Marker {
+    Marker {
+      tag: tag.to_string(),
+      start,
+      end,
+      instance: 0,
+    }
+  }
+
+  #[test]
+  fn test_cues_are_clipped_to_the_window() {
+    let cps = 2f32;
+    let per_cycle = time::samples_per_cycle(cps);
+    let markers = vec![
+      marker("intro", 0f32, 4f32),
+      marker("verse", 4f32, 12f32),
+      marker("outro", 12f32, 16f32),
+    ];
+    let cues = cues(&markers, cps, 2f32, 8 * per_cycle);
+    assert_eq!(
+      vec!["intro", "verse"],
+      cues.iter().map(|c| c.tag.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!((0, 2 * per_cycle), (cues[0].start_sample, cues[0].end_sample));
+    assert_eq!(
+      (2 * per_cycle, 8 * per_cycle),
+      (cues[1].start_sample, cues[1].end_sample)
+    );
+    assert_eq!((1, 2), (cues[0].id, cues[1].id));
+  }
+
+  #[test]
+  fn test_write_cues_keeps_the_wav_readable() {
+    let path = "dev-audio/markers/cues.wav";
+    crate::files::with_dir(path);
+    crate::render::engrave::write_audio(48000, vec![vec![0.1f32; 4801]], path);
+    let cues = cues(&[marker("intro", 0f32, 1f32)], 1f32, 0f32, 4801);
+    write_cues(path, &cues).unwrap();
+
+    let bytes = fs::read(path).unwrap();
+    let riff_size = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+    assert_eq!(bytes.len() - 8, riff_size);
+    let find = |id: &[u8]| bytes.windows(4).position(|w| w == id);
+    assert!(find(b"cue ").is_some() && find(b"adtl").is_some() && find(b"labl").is_some());
+    assert_eq!(4801, hound::WavReader::open(path).unwrap().duration());
+  }
+}
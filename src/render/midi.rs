@@ -0,0 +1,342 @@
+/// Standard MIDI File export of a `DruidicScore`.
+///
+/// Writes a format 1 file with a tempo track followed by one track per part.
+/// One cycle is one quarter note, so the tempo comes from `conf.cps`.
+/// Each track is named after its part's role and visibility, and carries the full `Arf` as JSON text
+/// so `inp::midi::read_score` can restore it.
+///
+/// MIDI keys are 12-ET. With `pitch_bend` enabled each note is preceded by a pitch bend
+/// expressing the just intonation deviation of its `Tone`. Bent notes take channels MPE style:
+/// each note gets a channel no other note is sounding on, which is freed again when the note ends,
+/// so simultaneous notes can bend independently.
+use std::fs;
+
+use crate::analysis::fit;
+use crate::analysis::xform_freq::midi_to_freq;
+use crate::error::RaudioError;
+use crate::files;
+use crate::inp::midi::DRUM_CHANNEL;
+use crate::inp::validate::Problem;
+use crate::analysis::tuning;
+use crate::monic_theory::tone_to_freq;
+use crate::time;
+use crate::types::render::DruidicScore;
+use crate::types::synthesis::Tone;
+use crate::types::timbre::Role;
+
+/// Ticks per quarter note (one cycle)
+pub const DIVISION: u16 = 480;
+
+/// Pitch bend range in semitones, set on every channel that bends
+pub const BEND_RANGE: u8 = 2;
+
+const NOTE_OFF: u8 = 0;
+const PITCH_BEND: u8 = 1;
+const NOTE_ON: u8 = 2;
+
+/// (tick, order within the tick, event bytes)
+type TrackEvent = (u64, u8, Vec<u8>);
+
+fn varlen(mut value: u32) -> Vec<u8> {
+  let mut bytes = vec![(value & 0x7F) as u8];
+  value >>= 7;
+  while value > 0 {
+    bytes.insert(0, 0x80 | (value & 0x7F) as u8);
+    value >>= 7;
+  }
+  bytes
+}
+
+fn meta(kind: u8, data: &[u8]) -> Vec<u8> {
+  let mut bytes = vec![0xFF, kind];
+  bytes.extend(varlen(data.len() as u32));
+  bytes.extend(data);
+  bytes
+}
+
+/// Encode events as an MTrk chunk with delta times, ending with an end-of-track event.
+fn track_chunk(mut events: Vec<TrackEvent>) -> Vec<u8> {
+  events.sort_by_key(|(tick, order, _)| (*tick, *order));
+  let mut data: Vec<u8> = Vec::new();
+  let mut prev = 0u64;
+  for (tick, _, event) in events {
+    data.extend(varlen((tick - prev) as u32));
+    data.extend(event);
+    prev = tick;
+  }
+  data.extend([0x00, 0xFF, 0x2F, 0x00]);
+
+  let mut chunk = b"MTrk".to_vec();
+  chunk.extend((data.len() as u32).to_be_bytes());
+  chunk.extend(data);
+  chunk
+}
+
+/// Nearest 12-ET key for a tone and its deviation from that key in semitones.
+/// The key is spelled on the lattice of the current thread and transposed by the distance of `root` from C.
+pub fn tone_to_key(tone: &Tone, root: f32) -> (u8, f32) {
+  let exact = 69f32 + 12f32 * (root * tone_to_freq(tone) / 440f32).log2();
+  let (register, _) = *tone;
+  // spellings cover MIDI keys 0 to 127 for these registers
+  let key = if (3..=12).contains(&register) {
+    let transpose = (12f32 * (root / fit(1f32, midi_to_freq(0))).log2()).round() as i32;
+    tuning::current().lattice.tone_to_chrom(*tone) as i32 + transpose
+  } else {
+    exact.round() as i32
+  };
+  // tones spelled in another octave than they sound fall back to the nearest key
+  let key = if (exact - key as f32).abs() > 1f32 {
+    exact.round() as i32
+  } else {
+    key
+  };
+  let key = key.clamp(0, 127);
+  (key as u8, exact - key as f32)
+}
+
+/// 14 bit pitch bend value for a deviation in semitones.
+fn bend_value(semitones: f32) -> u16 {
+  let value = 8192f32 + 8192f32 * semitones / BEND_RANGE as f32;
+  value.round().clamp(0f32, 16383f32) as u16
+}
+
+/// Set the pitch bend range with RPN 0.
+fn bend_range_events(channel: u8) -> Vec<TrackEvent> {
+  [(101, 0), (100, 0), (6, BEND_RANGE), (38, 0)]
+    .iter()
+    .map(|&(cc, value)| (0u64, NOTE_OFF, vec![0xB0 | channel, cc, value]))
+    .collect()
+}
+
+/// Pitched channels handed out one note at a time, so no two sounding notes share a pitch bend.
+/// Parts are encoded one after another, so a channel keeps every span it has sounded in.
+struct ChannelPool {
+  spans: [Vec<(u64, u64)>; 16],
+  exhausted: bool,
+}
+
+impl ChannelPool {
+  fn new() -> Self {
+    ChannelPool {
+      spans: Default::default(),
+      exhausted: false,
+    }
+  }
+
+  /// Take a channel for a note from `start` to `end`, preferring the one silent for longest before `start`.
+  /// Returns the channel and whether this is its first use.
+  /// When every channel is sounding, marks the pool exhausted and takes the one with the fewest sounding notes.
+  fn take(&mut self, start: u64, end: u64) -> (u8, bool) {
+    let candidates = (0u8..16).filter(|c| *c != DRUM_CHANNEL);
+    let overlaps = |spans: &Vec<(u64, u64)>| spans.iter().filter(|(s, e)| *s < end && start < *e).count();
+    let silent_since = |spans: &Vec<(u64, u64)>| spans.iter().map(|(_, e)| *e).filter(|e| *e <= start).max();
+    let channel = candidates
+      .clone()
+      .filter(|c| overlaps(&self.spans[*c as usize]) == 0)
+      .min_by_key(|c| silent_since(&self.spans[*c as usize]))
+      .unwrap_or_else(|| {
+        self.exhausted = true;
+        candidates.min_by_key(|c| overlaps(&self.spans[*c as usize])).unwrap()
+      });
+    let spans = &mut self.spans[channel as usize];
+    let first_use = spans.is_empty();
+    spans.push((start, end));
+    (channel, first_use)
+  }
+}
+
+fn is_drum(role: &Role) -> bool {
+  matches!(role, Role::Kick | Role::Perc | Role::Hats)
+}
+
+/// Encode a score as a Standard MIDI File.
+///
+/// ## Arguments
+///     `score` The score to export
+///     `pitch_bend` When true, bend each note to its just intonation frequency
+///
+/// ## Returns
+///     The file, and warnings about parts that did not export as intended
+pub fn encode_score(score: &DruidicScore, pitch_bend: bool) -> Result<(Vec<u8>, Vec<Problem>), RaudioError> {
+  let mut bytes = b"MThd".to_vec();
+  bytes.extend(6u32.to_be_bytes());
+  bytes.extend(1u16.to_be_bytes());
+  bytes.extend((score.parts.len() as u16 + 1).to_be_bytes());
+  bytes.extend(DIVISION.to_be_bytes());
+
+  // The key signature names the root so an import spells the same tones
+  let root = score.conf.root;
+  let tonic = (12f32 * (root / fit(1f32, midi_to_freq(0))).log2()).round() as i32;
+  let sharps = ((tonic * 7).rem_euclid(12) + 5) % 12 - 5;
+  let tempo = (1e6f32 / score.conf.cps).round() as u32;
+  bytes.extend(track_chunk(vec![
+    (0, NOTE_OFF, meta(0x03, b"raudio")),
+    (0, NOTE_OFF, meta(0x51, &tempo.to_be_bytes()[1..])),
+    (0, NOTE_OFF, meta(0x59, &[sharps as i8 as u8, 0])),
+  ]));
+
+  // channels for pitched parts, skipping the drum channel
+  let mut pitched_channels = (0u8..16).filter(|c| *c != DRUM_CHANNEL).cycle();
+  let mut bend_channels = ChannelPool::new();
+  let mut problems = Vec::new();
+
+  for (i, (_, arf, melody)) in score.parts.iter().enumerate() {
+    let arf_json = serde_json::to_string(arf).map_err(|e| RaudioError::MidiExport(e.to_string()))?;
+    let mut events: Vec<TrackEvent> = vec![
+      (
+        0,
+        NOTE_OFF,
+        meta(0x03, format!("{} ({})", arf.role, arf.visibility).as_bytes()),
+      ),
+      (0, NOTE_OFF, meta(0x01, arf_json.as_bytes())),
+    ];
+    let drum = is_drum(&arf.role);
+    let bends = pitch_bend && !drum;
+    // bent notes take their channel from `bend_channels` one at a time
+    let part_channel = if drum {
+      Some(DRUM_CHANNEL)
+    } else if bends {
+      None
+    } else {
+      pitched_channels.next()
+    };
+
+    for line in melody {
+      let mut cycles = 0f32;
+      for note in line {
+        let (_, tone, amp) = note;
+        let start = (cycles * DIVISION as f32).round() as u64;
+        cycles += time::note_to_cycles(note);
+        let end = (cycles * DIVISION as f32).round() as u64;
+        if *amp <= 0f32 || end <= start {
+          continue;
+        }
+        let (key, deviation) = tone_to_key(tone, root);
+        let velocity = (amp * 127f32).round().clamp(1f32, 127f32) as u8;
+        let channel = part_channel.unwrap_or_else(|| {
+          let (channel, first_use) = bend_channels.take(start, end);
+          if first_use {
+            events.extend(bend_range_events(channel));
+          }
+          channel
+        });
+        if bends {
+          let bend = bend_value(deviation);
+          events.push((
+            start,
+            PITCH_BEND,
+            vec![0xE0 | channel, (bend & 0x7F) as u8, (bend >> 7) as u8],
+          ));
+        }
+        events.push((start, NOTE_ON, vec![0x90 | channel, key, velocity]));
+        events.push((end, NOTE_OFF, vec![0x80 | channel, key, 0]));
+      }
+    }
+    bytes.extend(track_chunk(events));
+    if bend_channels.exhausted && problems.is_empty() {
+      problems.push(Problem::warning(
+        format!("$.parts[{}]", i),
+        "More than 15 bent notes sound at once; some share a channel and their pitch bends interfere".to_string(),
+      ));
+    }
+  }
+  Ok((bytes, problems))
+}
+
+/// Write a score to `filename` as a Standard MIDI File, returning its warnings. See `encode_score`.
+pub fn score(score: &DruidicScore, pitch_bend: bool, filename: &str) -> Result<Vec<Problem>, RaudioError> {
+  let (bytes, problems) = encode_score(score, pitch_bend)?;
+  files::try_with_dir(filename)
+    .and_then(|_| fs::write(filename, bytes))
+    .map_err(|e| RaudioError::Io(filename.to_string(), e))?;
+  Ok(problems)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::inp::midi::read_score;
+
+  fn playbook() -> DruidicScore {
+    crate::inp::arg_parse::load_score_from_file("src/demo/playbooks/house_short_1.json").unwrap()
+  }
+
+  #[test]
+  fn test_tone_to_key() {
+    let c_root = fit(1f32, midi_to_freq(0));
+    assert_eq!(60, tone_to_key(&(8, (0, 0, 1)), c_root).0);
+    let (key, deviation) = tone_to_key(&(8, (0, 0, 5)), c_root);
+    assert_eq!(64, key);
+    assert!(
+      (deviation + 0.1369).abs() < 0.001,
+      "A just major third is 13.7 cents flat, got {}",
+      deviation
+    );
+    // a root a fifth above C transposes every key
+    assert_eq!(67, tone_to_key(&(8, (0, 0, 1)), fit(1f32, midi_to_freq(7))).0);
+  }
+
+  #[test]
+  fn test_export_round_trip() {
+    let score = playbook();
+    let imported = read_score(&encode_score(&score, false).unwrap().0, &[]).unwrap();
+
+    assert!((score.conf.cps - imported.conf.cps).abs() < 1e-3);
+    assert!(
+      (score.conf.root - imported.conf.root).abs() < 1e-3,
+      "Must restore the root from the key signature"
+    );
+    assert_eq!(score.parts.len(), imported.parts.len());
+    for ((_, arf, melody), (_, imported_arf, imported_melody)) in score.parts.iter().zip(imported.parts.iter()) {
+      assert_eq!(
+        arf.role.to_string(),
+        imported_arf.role.to_string(),
+        "Must embed the Arf in the track"
+      );
+      assert_eq!(arf.register, imported_arf.register);
+      let sounding = |m: &Vec<Vec<crate::types::synthesis::Note>>| m.iter().flatten().filter(|n| n.2 > 0f32).count();
+      assert_eq!(sounding(melody), sounding(imported_melody));
+    }
+  }
+
+  #[test]
+  fn test_export_pitch_bend() {
+    let (plain, _) = encode_score(&playbook(), false).unwrap();
+    let (bent, _) = encode_score(&playbook(), true).unwrap();
+    assert!(bent.len() > plain.len(), "Must add pitch bend events");
+    let dir = std::env::temp_dir().join(format!("raudio-export-midi-{}", std::process::id()));
+    let out = dir.join("bent.mid").to_string_lossy().into_owned();
+    score(&playbook(), true, &out).unwrap();
+    let written = fs::read(&out).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(read_score(&written, &[]).is_ok());
+  }
+
+  #[test]
+  fn test_export_warns_when_bent_notes_share_channels() {
+    let mut crowded = playbook();
+    let i = crowded.parts.iter().position(|(_, arf, _)| !is_drum(&arf.role)).unwrap();
+    let line = crowded.parts[i].2[0].clone();
+    crowded.parts[i].2 = vec![line; 16];
+
+    let (_, problems) = encode_score(&crowded, true).unwrap();
+    assert_eq!(1, problems.len(), "Must warn once, got {:?}", problems);
+    assert!(!problems[0].is_error());
+    assert_eq!(format!("$.parts[{}]", i), problems[0].path);
+    assert!(encode_score(&crowded, false).unwrap().1.is_empty(), "Unbent notes share their part's channel");
+  }
+
+  #[test]
+  fn test_bent_notes_get_their_own_channel() {
+    let mut pool = ChannelPool::new();
+    let taken: Vec<u8> = (0..15).map(|_| pool.take(0, 480).0).collect();
+    let mut distinct = taken.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(15, distinct.len(), "Sounding notes must not share a channel");
+    assert!(!taken.contains(&DRUM_CHANNEL));
+    let (later, first_use) = pool.take(480, 960);
+    assert!(!first_use, "A channel is free again once its note ends");
+    assert_eq!(taken[0], later, "Must reuse the channel silent for longest");
+  }
+}
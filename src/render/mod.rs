@@ -1,14 +1,27 @@
 pub mod blend;
+pub mod cache;
+pub mod context;
 pub mod engrave;
+pub mod glide;
+pub mod groove;
 pub mod ifft;
+pub mod levels;
+pub mod markers;
+pub mod midi;
 pub mod ninja;
+pub mod progress;
 pub mod realize;
+pub mod stems;
+pub mod stereo;
+pub mod stream;
+pub mod window;
 
 use crate::analysis::in_range;
 use crate::analysis::delay::{DelayParams, StereoField};
 use crate::analysis::tools::{compressor, expander, rescale_amplitude, CompressorParams, ExpanderParams};
 use crate::analysis::volume::db_to_amp;
 use crate::analysis::{delay, freq::apply_filter, freq::apply_resonance, freq::slice_signal, xform_freq};
+use crate::error::RaudioError;
 use crate::presets::get_rescale_target;
 use crate::druid::applied_modulation::{self, update_mods};
 use crate::druid::{inflect, melody_frexer, ApplyAt, Element, Elementor};
@@ -19,21 +32,27 @@ use crate::phrasing::lifespan::{self};
 use crate::phrasing::ranger::{Knob, KnobMacro, KnobMods, KnobMods2, KnobbedRanger, Ranger};
 use crate::presets::DB_HEADROOM;
 use crate::render;
+use crate::render::context::RenderContext;
+use crate::render::levels::Stage;
+use crate::render::progress::{Event, Progress};
 use crate::reverb::convolution::{self, ReverbParams};
-use crate::synth::{pi, pi2, MFf, NFf, SRf, SampleBuffer, MF, NF, SR};
+use crate::seed;
+use crate::synth::{pi, pi2, MFf, NFf, SRf, SampleBuffer, StereoBuffer, MF, NF, SR};
 use crate::time::{self, samples_per_cycle};
 use crate::types::render::{Conf, Feel, Melody, Span, Stem, Stem2, DrumSample, StemFM};
 use crate::types::synthesis::{
-  BoostGroup, BoostGroupMacro, Bp, Bp2, Clippers, GlideLen, MacroMotion, Modifiers, ModifiersHolder, Note, Range, Soids,
+  BoostGroup, BoostGroupMacro, Bp, Bp2, Clippers, Frex, Glide, GlideCurve, GlideLen, Groove, MacroMotion, Modifiers, ModifiersHolder, Note, Range, Soids,
 };
 use crate::types::timbre::{AmpContour, AmpLifespan, Arf};
 use crate::{Energy, Mode, Presence, Role, Visibility};
 use rand;
-use rand::rngs::ThreadRng;
-use rand::{thread_rng, Rng};
+use crate::seed::ThreadRng;
+use rand::Rng;
+use crate::seed::thread_rng;
 
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
+use std::time::Instant;
 
 
 #[derive(Clone, Debug)]
@@ -173,10 +192,10 @@ fn tacet(cps: f32, (melody, soids, expr, feel, knob_mods, delays): &Stem) -> Sam
   vec![0f32; signal_len]
 }
 
-fn tacet2(cps: f32, stem: &Stem2) -> SampleBuffer {
+fn tacet2(cps: f32, stem: &Stem2) -> StereoBuffer {
   let len_cycles: f32 = time::count_cycles(&stem.0[0]);
-  let signal_len = time::samples_of_cycles(cps, len_cycles);
-  vec![0f32; signal_len]
+  let signal_len = time::samples_at(cps, len_cycles);
+  stereo::silence(signal_len)
 }
 
 #[inline]
@@ -196,8 +215,10 @@ fn channel(cps: f32, root: f32, (melody, soids, expr, feel, knob_mods, delays):
       let mut p: f32 = 0f32;
       line.iter().enumerate().for_each(|(i, (_, tone, amp))| {
         let freq = root * tone_to_freq(tone);
+        let frex: Frex = (GlideLen::None, None, freq, None, GlideLen::None);
         let moment = summer(
-          p, len_cycles, cps, root, *amp, freq, durs[i], soids, &expr, feel, knob_mods, &delays,
+          p, len_cycles, cps, root, *amp, freq, &frex, GlideCurve::default(), durs[i], soids, &expr, feel, knob_mods,
+          &delays,
         );
         channel_samples.push(moment);
         p += durs[i] / len_cycles;
@@ -317,132 +338,266 @@ fn get_knob_mods(
   KnobMods(amp_knobs, freq_knobs, phase_knobs)
 }
 
-#[inline]
-fn channel_with_reso(
-  conf: &Conf,
-  arf: &Arf,
-  (melody, soids, expr, bp, knob_macros, delays1, delays2, reverbs1, reverbs2): &Stem2,
-) -> SampleBuffer {
-  let mut rng = thread_rng();
-  let Conf { cps, root } = *conf;
-  let soids = crate::analysis::trig::process_soids(soids.clone());
-
-  // Pre-compute knobs for Constant motion
-  // only evaluate moving knob macros per note-event.
-  let (constant_knobs_amp, constant_knobs_freq, constant_knobs_phase) = pre_compute_knobs(knob_macros, &mut rng);
+/// Timing of the notes of one line, shared by the batch and streaming renderers.
+pub struct LinePlan {
+  pub len_cycles: f32,
+  /// Length of each note in cycles
+  pub durs: Vec<f32>,
+  /// Tempo of each note, the mean over its length. See `analysis::tempo`.
+  pub cps: Vec<f32>,
+  /// Position of each note in the line in [0, 1]
+  pub progress: Vec<f32>,
+  /// Whether each note is heard in the audible window. See `window::sounding`.
+  pub heard: Vec<bool>,
+  /// Context window of each note when the line glides, else empty. See `glide`.
+  pub frexes: Vec<Frex>,
+  /// Shift in cycles and amplitude multiplier of each note when the line is grooved, else empty. See `groove`.
+  pub feel: Vec<(f32, f32)>,
+  /// Seconds of effects sounding after each note
+  pub tail: f32,
+}
 
-  let line_buffs: Vec<SampleBuffer> = melody
-    .iter()
-    .map(|line| {
-      let mut channel_samples: Vec<SampleBuffer> = Vec::new();
+impl LinePlan {
+  pub fn new(cps: f32, line: &Vec<Note>, tail_seconds: f32) -> Self {
+    let len_cycles = time::count_cycles(line);
+    let durs: Vec<f32> = line.iter().map(|(d, _, _)| time::duration_to_cycles(*d)).collect();
+    let mut p: f32 = 0f32;
+    let progress = durs
+      .iter()
+      .map(|dur| {
+        let at = p;
+        p += dur / len_cycles;
+        at
+      })
+      .collect();
+    let mut onset = 0f32;
+    let tempos = durs
+      .iter()
+      .map(|dur| {
+        let at = onset;
+        onset += dur;
+        time::cps_between(cps, at, onset)
+      })
+      .collect();
+    let mut plan = LinePlan {
+      len_cycles,
+      durs,
+      cps: tempos,
+      progress,
+      heard: Vec::new(),
+      frexes: Vec::new(),
+      feel: Vec::new(),
+      tail: tail_seconds,
+    };
+    plan.heard = window::sounding(cps, &plan.cues(cps), &plan.durs, tail_seconds);
+    plan
+  }
 
-      let len_cycles: f32 = time::count_cycles(line);
-      let append_delay = time::samples_of_dur(1f32, longest_delay_length(&delays1));
-      let append_reverb = time::samples_of_dur(1f32, longest_reverb_length(&reverbs1));
-
-      let pad_samples_time_effects = append_delay + append_reverb;
-      let signal_len = if pad_samples_time_effects > 0 {
-        // since usize rounding might cutoff some sample
-        // give it some room to render off the edge
-        let pad_samples_error_margin = 2 * (delays1.len() + reverbs1.len());
-        time::samples_of_cycles(cps, len_cycles) + pad_samples_time_effects + pad_samples_error_margin
-      } else {
-        time::samples_of_cycles(cps, len_cycles)
-      };
+  /// Play the notes with `feel`, see `groove::line_feel`. A grooved note is heard where it moves to.
+  pub fn set_feel(&mut self, cps: f32, feel: Vec<(f32, f32)>) {
+    self.feel = feel;
+    self.heard = window::sounding(cps, &self.cues(cps), &self.durs, self.tail);
+  }
 
-      let durs: Vec<f32> = line.iter().map(|(d, _, _)| time::duration_to_cycles(*d)).collect();
-      let mut p: f32 = 0f32;
+  /// Sample offset and gain of each note in the line.
+  /// Grooved notes move from their onset by their shift, never before the start of the line or the note before.
+  pub fn cues(&self, cps: f32) -> Vec<(usize, f32)> {
+    let onsets = time::onsets(cps, &self.durs);
+    if self.feel.is_empty() {
+      return onsets.into_iter().map(|onset| (onset, 1f32)).collect();
+    }
+    let mut cycle = 0f32;
+    let mut earliest = 0usize;
+    onsets
+      .into_iter()
+      .zip(&self.durs)
+      .zip(&self.feel)
+      .map(|((onset, dur), &(shift, gain))| {
+        let moved = time::samples_at(cps, cycle + shift) as i64 - time::samples_at(cps, cycle) as i64;
+        cycle += dur;
+        earliest = earliest.max((onset as i64 + moved).max(0) as usize);
+        (earliest, gain)
+      })
+      .collect()
+  }
+}
 
-      line.iter().enumerate().for_each(|(i, (_, tone, amp))| {
-        let freq = root * tone_to_freq(tone);
-        let knob_mods = get_knob_mods(
-          knob_macros,
-          p,
-          &mut rng,
-          &constant_knobs_amp,
-          &constant_knobs_freq,
-          &constant_knobs_phase,
-        );
+/// How the notes of a part are played, beyond what its preset synthesizes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Playing {
+  /// Portamento between the notes of each line, see `glide`
+  pub glide: Option<Glide>,
+  /// Swing and timing of the notes of each line, see `groove`
+  pub groove: Option<Groove>,
+}
 
-        // delay1 is applied per note-event in summer_with_reso
-        let mut moment = summer_with_reso(
-          p, len_cycles, cps, root, *amp, freq, durs[i], &soids, &expr, bp, knob_mods, &delays1,
-        );
+/// Length of a line buffer with room for per-note effects.
+fn line_len(cps: f32, len_cycles: f32, delays: &Vec<DelayParams>, reverbs: &Vec<ReverbParams>) -> usize {
+  let append_delay = time::samples_of_dur(1f32, longest_delay_length(delays));
+  let append_reverb = time::samples_of_dur(1f32, longest_reverb_length(reverbs));
+
+  let pad_samples_time_effects = append_delay + append_reverb;
+  if pad_samples_time_effects > 0 {
+    // since usize rounding might cutoff some sample
+    // give it some room to render off the edge
+    let pad_samples_error_margin = 2 * (delays.len() + reverbs.len());
+    time::samples_at(cps, len_cycles) + pad_samples_time_effects + pad_samples_error_margin
+  } else {
+    time::samples_at(cps, len_cycles)
+  }
+}
 
-        let target_rms = get_rescale_target(&mut rng, arf.visibility);
-        let expander_params = gen_inst_expander(&mut rng, &arf);
-        let compressor_params = gen_inst_compressor(&mut rng, &arf);
+/// Renders the notes of a `Stem2` with their per-note effects.
+pub struct ResoVoice<'a, 'render> {
+  conf: &'a Conf,
+  arf: &'a Arf,
+  stem: &'a Stem2<'render>,
+  soids: Soids,
+  constant_knobs: (Vec<Option<Knob>>, Vec<Option<Knob>>, Vec<Option<Knob>>),
+  seed: Option<u64>,
+  /// Portamento and groove of each line
+  playing: Playing,
+  /// Seconds of effects sounding after each note
+  pub tail: f32,
+}
 
-        let normalized = rescale_amplitude(0.5f32, &moment);
-        moment = compressor(
-          &expander(&normalized, expander_params, None).unwrap(),
-          compressor_params,
-          None,
-        )
-        .unwrap();
+impl<'a, 'render> ResoVoice<'a, 'render> {
+  pub fn new(conf: &'a Conf, arf: &'a Arf, stem: &'a Stem2<'render>, playing: &Playing) -> Self {
+    let (_, soids, _, _, knob_macros, delays1, delays2, reverbs1, reverbs2) = stem;
+    let mut rng = thread_rng();
+    // Pre-compute knobs for Constant motion
+    // only evaluate moving knob macros per note-event.
+    let constant_knobs = pre_compute_knobs(knob_macros, &mut rng);
+    ResoVoice {
+      conf,
+      arf,
+      stem,
+      soids: crate::analysis::trig::process_soids(soids.clone()),
+      constant_knobs,
+      seed: seed::current_seed(),
+      playing: playing.clone(),
+      tail: window::tail_seconds(delays1, reverbs1) + window::tail_seconds(delays2, reverbs2),
+    }
+  }
 
-        // Apply reverbs per note-event
-        let moment_wet = if reverbs1.is_empty() {
-          moment
-        } else {
-          reverbs1.iter().fold(moment, |sig, params| {
-            let mut sig = convolution::of(&sig, params);
-            trim_zeros(&mut sig);
-            sig
-          })
-        };
+  pub fn plan(&self, line: &Vec<Note>) -> LinePlan {
+    let mut plan = LinePlan::new(self.conf.cps, line, self.tail);
+    if let Some(glide) = &self.playing.glide {
+      plan.frexes = glide::line_frexes(line, glide);
+    }
+    if let Some(groove) = &self.playing.groove {
+      plan.set_feel(self.conf.cps, groove::line_feel(&plan.durs, groove));
+    }
+    plan
+  }
 
-        channel_samples.push(moment_wet);
-        p += durs[i] / len_cycles;
-      });
+  /// Length of the buffer the notes of a line are placed in.
+  pub fn line_len(&self, plan: &LinePlan) -> usize {
+    line_len(self.conf.cps, plan.len_cycles, &self.stem.5, &self.stem.7)
+  }
 
-      overlapping(signal_len, cps, durs, &channel_samples)
-    })
-    .collect();
+  /// Render note `i` of line `l`.
+  pub fn note(&self, l: usize, line: &Vec<Note>, plan: &LinePlan, i: usize) -> Result<StereoBuffer, RaudioError> {
+    let (_, _, expr, bp, knob_macros, delays1, _, reverbs1, _) = self.stem;
+    let root = self.conf.root;
+    let cps = plan.cps[i];
+    if self.seed.is_some() {
+      // each note draws from its own stream so partial renders match the full render
+      seed::reseed_for(self.seed, &[l as u64, i as u64]);
+    }
+    if !plan.heard[i] {
+      return Ok(stereo::silence(time::samples_of_cycles(cps, plan.durs[i])));
+    }
+    let delays1: Vec<DelayParams> = delays1.iter().map(|delay| delay.at_tempo(cps)).collect();
+    progress::note_rendered();
+    let mut rng = thread_rng();
+    let (_, tone, amp) = &line[i];
+    let p = plan.progress[i];
+    let freq = root * tone_to_freq(tone);
+    let frex = plan.frexes.get(i).copied().unwrap_or((GlideLen::None, None, freq, None, GlideLen::None));
+    let (constant_knobs_amp, constant_knobs_freq, constant_knobs_phase) = &self.constant_knobs;
+    let knob_mods = get_knob_mods(
+      knob_macros,
+      p,
+      &mut rng,
+      constant_knobs_amp,
+      constant_knobs_freq,
+      constant_knobs_phase,
+    );
 
-  match pad_and_mix_buffers(line_buffs) {
-    Ok(mixed) => {
-      let chan_wet_delays = if delays2.is_empty() {
-        mixed
-      } else {
-        delays2.iter().fold(mixed, |mut sig, delay_params| {
-          let samples_per_echo: usize = time::samples_from_dur(1f32, delay_params.len_seconds);
-          for replica_n in 0..=delay_params.n_echoes.max(1) {
-            for j in 0..sig.len() {
-              let v = sig[j];
-              let offset_j = samples_per_echo * replica_n;
-              let gain = delay::gain(j, replica_n, delay_params);
-              let clip_thresh = 1f32;
-              let gate_thresh = 0f32;
-
-              // Apply gating and clipping
-              if gain * v.abs() > clip_thresh {
-                sig[j + offset_j] += gain * v.signum() * clip_thresh;
-              } else if gain * v.abs() >= gate_thresh {
-                sig[j + offset_j] += gain * v;
-              }
-            }
-          }
-          sig
-        })
-      };
+    // delay1 is applied per note-event in summer_with_reso
+    let mut moment = summer_with_reso(
+      p,
+      plan.len_cycles,
+      cps,
+      root,
+      *amp,
+      freq,
+      &frex,
+      self.playing.glide.map(|glide| glide.curve).unwrap_or_default(),
+      plan.durs[i],
+      &self.soids,
+      expr,
+      bp,
+      knob_mods,
+      &delays1,
+    );
 
-      let mut chan_wet_reverbs = if reverbs2.is_empty() {
-        // leave the wet delay channel as is
-        chan_wet_delays
-      } else {
-        reverbs2.iter().fold(chan_wet_delays, |sig, params| {
-          let mut sig = convolution::of(&sig, params);
-          trim_zeros(&mut sig);
-          sig
-        })
-      };
-      chan_wet_reverbs
+    let target_rms = get_rescale_target(&mut rng, self.arf.visibility);
+    let expander_params = gen_inst_expander(&mut rng, self.arf)?;
+    let compressor_params = gen_inst_compressor(&mut rng, self.arf)?;
+
+    let normalized = stereo::rescale_amplitude(0.5f32, &moment);
+    moment = stereo::try_map_channels(&normalized, |sig| dynamics(sig, expander_params, compressor_params))?;
+
+    // Apply reverbs per note-event
+    if reverbs1.is_empty() {
+      Ok(moment)
+    } else {
+      Ok(reverbs1.iter().fold(moment, |sig, params| {
+        let mut sig = convolution::of_stereo(&sig, params);
+        stereo::trim_zeros(&mut sig);
+        sig
+      }))
     }
-    Err(msg) => panic!("Failed to mix and render channel: {}", msg),
   }
 }
 
+#[inline]
+fn channel_with_reso(
+  conf: &Conf, arf: &Arf, stem: &Stem2, playing: &Playing,
+) -> Result<StereoBuffer, RaudioError> {
+  let voice = ResoVoice::new(conf, arf, stem, playing);
+  let (melody, _, _, _, _, _, delays2, _, reverbs2) = stem;
+
+  let line_buffs: Vec<StereoBuffer> = melody
+    .iter()
+    .enumerate()
+    .map(|(l, line)| {
+      let plan = voice.plan(line);
+      let channel_samples =
+        (0..line.len()).map(|i| voice.note(l, line, &plan, i)).collect::<Result<Vec<StereoBuffer>, _>>()?;
+      Ok(stereo::overlapping(voice.line_len(&plan), &plan.cues(conf.cps), &channel_samples))
+    })
+    .collect::<Result<_, RaudioError>>()?;
+
+  let mixed = stereo::pad_and_mix_buffers(line_buffs, Stage::Lines).map_err(|msg| RaudioError::Mix(msg.to_string()))?;
+  // delay2 echoes are positioned by their StereoField and clipped at full scale
+  let chan_wet_delays = stereo::apply_delays(mixed, delays2, Some(1f32));
+
+  let chan_wet_reverbs = if reverbs2.is_empty() {
+    // leave the wet delay channel as is
+    chan_wet_delays
+  } else {
+    reverbs2.iter().fold(chan_wet_delays, |sig, params| {
+      let mut sig = convolution::of_stereo(&sig, params);
+      stereo::trim_zeros(&mut sig);
+      sig
+    })
+  };
+  Ok(chan_wet_reverbs)
+}
+
 /// Convolution and delay effects may produce a long tail of empty signal.
 /// Remove it.
 pub fn trim_zeros(signal: &mut Vec<f32>) {
@@ -461,37 +616,21 @@ fn find_last_audible_index(vec: &Vec<f32>, thresh: f32) -> Option<usize> {
 }
 
 fn finalize_signal(
-  mut signal: SampleBuffer, delays: &[DelayParams], reverbs: &[ReverbParams], lowpass_cutoff_freq: Option<f32>,
-) -> SampleBuffer {
-  // Apply delays
-  if !delays.is_empty() {
-    signal = delays.iter().fold(signal, |mut sig, delay_params| {
-      let samples_per_echo: usize = time::samples_from_dur(1.0, delay_params.len_seconds);
-      for replica_n in 0..=delay_params.n_echoes.max(1) {
-        for j in 0..sig.len() {
-          let v = sig[j];
-          let offset_j = j + samples_per_echo * replica_n;
-          if offset_j < sig.len() {
-            let gain = delay::gain(j, replica_n, delay_params);
-            sig[offset_j] += gain * v;
-          }
-        }
-      }
-      sig
-    });
-  }
+  signal: StereoBuffer, delays: &[DelayParams], reverbs: &[ReverbParams], lowpass_cutoff_freq: Option<f32>,
+) -> StereoBuffer {
+  // Apply delays, placing each echo in its stereo field
+  let mut signal = stereo::apply_delays(signal, delays, None);
 
   // Apply reverbs
   if !reverbs.is_empty() {
-    signal = reverbs.iter().fold(signal, |mut sig, params| {
-      let mut sig = convolution::of(&sig, params);
-      sig
-    });
+    signal = reverbs.iter().fold(signal, |sig, params| convolution::of_stereo(&sig, params));
   }
 
   // Apply Butterworth filter (if cutoff frequency is specified)
   if let Some(cutoff_freq) = lowpass_cutoff_freq {
-    signal = crate::analysis::freq::butterworth_lowpass_filter(&signal, SR as u32, NFf);
+    signal = stereo::map_channels(&signal, |sig| {
+      crate::analysis::freq::butterworth_lowpass_filter(sig, SR() as u32, NFf())
+    });
   }
 
   signal
@@ -503,7 +642,7 @@ mod tests {
 
   #[test]
   fn test_finalize_signal_has_passthrough() {
-    let original_signal: SampleBuffer = vec![0.1, 0.2, 0.3, 0.4, 0.5];
+    let original_signal: StereoBuffer = (vec![0.1, 0.2, 0.3, 0.4, 0.5], vec![0.5, 0.4, 0.3, 0.2, 0.1]);
     let delays: Vec<DelayParams> = vec![];
     let reverbs: Vec<ReverbParams> = vec![];
     let lowpass_cutoff_freq: Option<f32> = None;
@@ -519,29 +658,47 @@ mod tests {
     // let result = finalize_signal(original_signal.clone(), &delays, &reverbs, lowpass_cutoff_freq);
     // assert_eq!(result, original_signal, "Must have a passthrough effect when no settings are applied (NFf)");
   }
+
+  #[test]
+  fn test_grooved_cues_keep_their_order() {
+    let note = |d: i32| ((1, d), (5, (0, 0, 1)), 1f32);
+    let line: Vec<Note> = vec![note(2), note(2), note(4), note(4)];
+    let mut plan = LinePlan::new(1f32, &line, 0f32);
+    let straight = plan.cues(1f32);
+    assert_eq!(time::onsets(1f32, &plan.durs), straight.iter().map(|cue| cue.0).collect::<Vec<_>>());
+    plan.feel = vec![(0f32, 1f32), (0.25f32, 0.5f32), (-0.5f32, 1f32), (0f32, 1f32)];
+    let cues = plan.cues(1f32);
+    assert_eq!(straight[1].0 + time::samples_of_cycles(1f32, 0.25f32), cues[1].0);
+    assert_eq!(0.5f32, cues[1].1);
+    assert_eq!(cues[1].0, cues[2].0, "Must not play a note before the one it follows");
+    assert_eq!(straight[3], cues[3]);
+  }
 }
 
 /// Given a list of signals whose tails may intend to overlap with the head of the next signal
 /// (e.g. long delay or release times)
 /// Create a sample representing their overlapped mixing.
 pub fn overlapping(base_len: usize, cps: f32, durs: Vec<f32>, samples: &Vec<SampleBuffer>) -> SampleBuffer {
+  let cues: Vec<(usize, f32)> = time::onsets(cps, &durs).into_iter().map(|onset| (onset, 1f32)).collect();
+  overlapping_at(base_len, &cues, samples)
+}
+
+/// Mix `samples` at their sample offset, each scaled by its gain. See `LinePlan::cues`.
+pub fn overlapping_at(base_len: usize, cues: &[(usize, f32)], samples: &[SampleBuffer]) -> SampleBuffer {
   let mut signal: SampleBuffer = vec![0f32; base_len];
-  durs.iter().enumerate().fold(0, |cue, (i, dur)| {
+  for (i, &(cue, gain)) in cues.iter().enumerate() {
     // Make sure there's enough room for us to add reverb/delay artifacts
     if signal.len() < cue + samples[i].len() {
-      let mut adds  = vec![0f32; samples[i].len()];
+      let mut adds  = vec![0f32; cue + samples[i].len() - signal.len()];
       eprintln!("Warning! 'overlapping' method needed to append {} samples to the input duration to meet the wet samples requirements.", adds.len());
       signal.append(&mut adds);
     }
 
     // apply the wet samples to the running buffer
     for (j, s) in samples[i].iter().enumerate() {
-      signal[cue + j] += s
+      signal[cue + j] += gain * s
     }
-
-    // advance the cue not by the wet samples length, but by the defacto note duration length
-    cue + time::samples_of_dur(cps, *dur)
-  });
+  }
   signal
 }
 
@@ -565,6 +722,8 @@ const DB_DISTANCE: f32 = 1f32; // temporal spread of energy (spread over time)
 ///     `root` The fundamental frequency of the composition  
 ///     `vel` Velocity, a constant scalar for output amplitude  
 ///     `fundamental` The fundamental frequency of the note event  
+///     `frex` The context window of the note event, for gliding from and to its neighbours. See `glide`  
+///     `glide_curve` How the frequency moves over a glide  
 ///     `n_cycles` The length in cycles of the note event  
 ///     `soids` The sinusoidal arguments for a Fourier series  
 ///     `expr` Note-length tuple of ADSR envelopes to apply to (amplitude, frequency, phase offset)  
@@ -579,6 +738,8 @@ pub fn summer<'render>(
   root: f32,
   vel: f32, // call it vel for velicty (name amp is taken)
   fundamental: f32,
+  frex: &Frex,
+  glide_curve: GlideCurve,
   n_cycles: f32,
   soids: &Soids,
   expr: &Expr,
@@ -613,6 +774,7 @@ pub fn summer<'render>(
   let resampled_aenv = slice_signal(&expr.0, curr_progress, end_p, sig_samples);
   let resampled_fenv = slice_signal(&expr.1, 0f32, 1f32, sig_samples);
   let resampled_penv = slice_signal(&expr.2, 0f32, 1f32, sig_samples);
+  let glide = glide::contour(frex, glide_curve, n_cycles, sig_samples);
 
   // render the sample with the provided effects and context
   for delay_params in delays {
@@ -627,8 +789,9 @@ pub fn summer<'render>(
       let mut pm = resampled_penv[j];
       let hp = bp_slice_highpass[j];
       let lp = bp_slice_lowpass[j];
+      let (glide_freq, glide_phase) = glide.as_ref().map(|g| (g.freq[j], g.phase[j])).unwrap_or((1f32, 1f32));
 
-      let t0: f32 = (j as f32) / SRf;
+      let t0: f32 = (j as f32) / SRf();
       let pos_cycles: f32 = modTime.iter().fold(t0, |acc, mt| mt.apply(t0, acc));
       let mut v: f32 = 0f32;
 
@@ -655,7 +818,7 @@ pub fn summer<'render>(
           continue;
         }
 
-        let f0: f32 = fm * m * fundamental;
+        let f0: f32 = fm * m * fundamental * glide_freq;
         let f1: f32 = knobsFreq.iter().fold(f0, |acc, (knob, func)| {
           acc * func(knob, cps, fundamental, m, n_cycles, pos_cycles)
         });
@@ -663,13 +826,14 @@ pub fn summer<'render>(
         let frequency = modsFreq.iter().fold(f1, |acc, mf| mf.apply(pos_cycles, acc));
 
         // pre-filter fast check. These are application-wide hard limits.
-        if frequency > NFf || frequency < MFf {
+        if frequency > NFf() || frequency < MFf {
           continue;
         }
 
         amp *= apply_filter(frequency, hp, lp, DB_PER_OCTAVE, DB_DISTANCE);
 
-        let p0 = pm + frequency * pi2 * pos_cycles;
+        // the phase follows the mean frequency of the glide so far
+        let p0 = pm + frequency * (glide_phase / glide_freq) * pi2 * pos_cycles;
         let p1: f32 = knobsPhase.iter().fold(p0, |acc, (knob, func)| {
           acc + func(knob, cps, fundamental, m, n_cycles, pos_cycles)
         });
@@ -734,6 +898,8 @@ fn apply_delay(buffer: Vec<f32>, delay_params: &DelayParams) -> Vec<f32> {
 ///     `root` The fundamental frequency of the composition  
 ///     `vel` Velocity, a constant scalar for output amplitude  
 ///     `fundamental` The fundamental frequency of the note event  
+///     `frex` The context window of the note event, for gliding from and to its neighbours. See `glide`  
+///     `glide_curve` How the frequency moves over a glide  
 ///     `n_cycles` The length in cycles of the note event  
 ///     `soids` The sinusoidal arguments for a Fourier series  
 ///     `expr` Note-length tuple of ADSR envelopes to apply to (amplitude, frequency, phase offset)  
@@ -748,13 +914,15 @@ pub fn summer_with_reso<'render>(
   root: f32,
   vel: f32, // call it vel for velicty (name amp is taken)
   fundamental: f32,
+  frex: &Frex,
+  glide_curve: GlideCurve,
   n_cycles: f32,
   soids: &Soids,
   expr: &Expr,
   bp: &'render Bp2,
   KnobMods(knobsAmp, knobsFreq, knobsPhase): KnobMods,
   line_delays: &Vec<DelayParams>,
-) -> Vec<f32> {
+) -> StereoBuffer {
   let headroom_factor: f32 = db_to_amp(DB_HEADROOM); // would be good to lazy::static this
   let rounding_offset: usize = 10;
   let rounding_offset: usize = 0;
@@ -774,7 +942,7 @@ pub fn summer_with_reso<'render>(
   let delays = &ds;
   let append_delay = rounding_offset + time::samples_of_dur(1f32, longest_delay_length(delays));
   let sig_samples = time::samples_of_cycles(cps, n_cycles);
-  let mut sig = vec![0f32; sig_samples + append_delay];
+  let mut sig = stereo::silence(sig_samples + append_delay);
 
   if n_cycles.signum() == -1f32 || vel <= 0f32 {
     // skip rests, fill an empty vec
@@ -799,6 +967,7 @@ pub fn summer_with_reso<'render>(
   let resampled_aenv = slice_signal(&expr.0, curr_progress, end_p, sig_samples);
   let resampled_fenv = slice_signal(&expr.1, 0f32, 1f32, sig_samples);
   let resampled_penv = slice_signal(&expr.2, 0f32, 1f32, sig_samples);
+  let glide = glide::contour(frex, glide_curve, n_cycles, sig_samples);
 
   // seems that we want DB_PER_OCTAVE and DB_DISTANCE to have a product of 48.
   // (for lowpass filter)
@@ -816,8 +985,9 @@ pub fn summer_with_reso<'render>(
       let mut pm = resampled_penv[j];
       let hp = bp_slice_highpass[j];
       let lp = bp_slice_lowpass[j];
+      let (glide_freq, glide_phase) = glide.as_ref().map(|g| (g.freq[j], g.phase[j])).unwrap_or((1f32, 1f32));
 
-      let t0: f32 = (j as f32) / SRf;
+      let t0: f32 = (j as f32) / SRf();
       let pos_cycles: f32 = t0;
       let mut v: f32 = 0f32;
 
@@ -838,7 +1008,7 @@ pub fn summer_with_reso<'render>(
         });
         let mut amp = vel * a1;
 
-        let f0: f32 = fm * m * fundamental;
+        let f0: f32 = fm * m * fundamental * glide_freq;
         let f1: f32 = knobsFreq.iter().fold(f0, |acc, (knob, func)| {
           acc * func(knob, cps, fundamental, m, n_cycles, pos_cycles)
         });
@@ -846,7 +1016,7 @@ pub fn summer_with_reso<'render>(
         let frequency = f1;
 
         // pre-filter fast check. These are application-wide hard limits.
-        if frequency > NFf || frequency < MFf {
+        if frequency > NFf() || frequency < MFf {
           continue;
         }
 
@@ -862,7 +1032,8 @@ pub fn summer_with_reso<'render>(
           amp *= gain
         }
 
-        let p0 = pm + frequency * pi2 * pos_cycles;
+        // the phase follows the mean frequency of the glide so far
+        let p0 = pm + frequency * (glide_phase / glide_freq) * pi2 * pos_cycles;
         let p1: f32 = knobsPhase.iter().fold(p0, |acc, (knob, func)| {
           acc + func(knob, cps, fundamental, m, n_cycles, pos_cycles)
         });
@@ -881,17 +1052,17 @@ pub fn summer_with_reso<'render>(
         if gain < 0f32 {
           continue;
         }
+        let (gain_l, gain_r) = delay_params.pan.gains(replica_n);
 
         // apply gating and clipping
-        if gain * v.abs() > 1f32 {
-          sig[j + offset_j] += gain * v.signum();
-        } else {
-          sig[j + offset_j] += gain * v;
-        }
+        let y = if gain * v.abs() > 1f32 { gain * v.signum() } else { gain * v };
+        sig.0[j + offset_j] += gain_l * y;
+        sig.1[j + offset_j] += gain_r * y;
       }
 
       // post-gen filter:
-      sig[j] *= headroom_factor;
+      sig.0[j] *= headroom_factor;
+      sig.1[j] *= headroom_factor;
       // apply global headroom scaling
     }
   }
@@ -904,9 +1075,9 @@ pub fn summer_with_reso<'render>(
 pub fn combiner<'render>(
   cps: f32, root: f32, renderables: &Vec<Renderable<'render>>, reverbs: &Vec<convolution::ReverbParams>,
   keep_stems: Option<&str>,
-) -> SampleBuffer {
+) -> Result<SampleBuffer, RaudioError> {
   // Collect channels by processing each renderable
-  let mut channels: Vec<SampleBuffer> = renderables
+  let channels: Vec<SampleBuffer> = renderables
     .iter()
     .enumerate()
     .map(|(j, renderable)| {
@@ -925,51 +1096,49 @@ pub fn combiner<'render>(
       };
       if let Some(stem_dir) = keep_stems {
         // keep the substems
-        ch.iter().enumerate().for_each(|(stem_num, channel_samples)| {
+        for (stem_num, channel_samples) in ch.iter().enumerate() {
           let filename = format!("{}/part-{}-twig-{}.wav", stem_dir, j, stem_num);
-          render::engrave::samples(SR, &channel_samples, &filename);
-        });
-      }
-      let rendered_channel = pad_and_mix_buffers(ch);
-
-      match rendered_channel {
-        Ok(signal) => signal,
-        Err(msg) => panic!("Unexpected error while mixing channels {}", msg),
+          render::engrave::samples(SR(), channel_samples, &filename)?;
+        }
       }
+      pad_and_mix_buffers(ch).map_err(|msg| RaudioError::Mix(format!("Unexpected error while mixing channels {}", msg)))
     })
-    .collect();
+    .collect::<Result<_, RaudioError>>()?;
 
   // Optionally save stems if `keep_stems` is provided
   if let Some(stem_dir) = keep_stems {
-    channels.iter().enumerate().for_each(|(stem_num, channel_samples)| {
+    for (stem_num, channel_samples) in channels.iter().enumerate() {
       let filename = format!("{}/stem-{}.wav", stem_dir, stem_num);
-      render::engrave::samples(SR, &channel_samples, &filename);
-    });
+      render::engrave::samples(SR(), channel_samples, &filename)?;
+    }
   }
 
   // Pad and mix the collected channels into a final signal
-  match pad_and_mix_buffers(channels) {
-    Ok(signal) => {
-      // Apply reverbs if provided
-      if reverbs.is_empty() {
-        signal
-      } else {
-        reverbs.iter().fold(signal, |sig, params| {
-          let mut sig = convolution::of(&sig, &params);
-          trim_zeros(&mut sig);
-          sig
-        })
-      }
-    }
-    Err(msg) => {
-      panic!("Failed to mix and render audio: {}", msg)
-    }
-  }
+  let signal =
+    pad_and_mix_buffers(channels).map_err(|msg| RaudioError::Mix(format!("Failed to mix and render audio: {}", msg)))?;
+  // Apply reverbs if provided
+  Ok(reverbs.iter().fold(signal, |sig, params| {
+    let mut sig = convolution::of(&sig, params);
+    trim_zeros(&mut sig);
+    sig
+  }))
 }
 
 
 
-pub fn gen_inst_compressor(rng: &mut ThreadRng, arf: &Arf) -> CompressorParams {
+/// Expand then compress one channel of a note, see `gen_inst_expander` and `gen_inst_compressor`.
+fn dynamics(
+  sig: &SampleBuffer, expander_params: ExpanderParams, compressor_params: CompressorParams,
+) -> Result<SampleBuffer, RaudioError> {
+  let expanded = expander(sig, expander_params, None).map_err(RaudioError::Dynamics)?;
+  compressor(&expanded, compressor_params, None).map_err(RaudioError::Dynamics)
+}
+
+/// Compressor for a note of a synthesized part. Drums are shaped like drum samples, see `gen_beat_compressor`.
+pub fn gen_inst_compressor(rng: &mut ThreadRng, arf: &Arf) -> Result<CompressorParams, RaudioError> {
+  if let Role::Kick | Role::Perc | Role::Hats = arf.role {
+    return Ok(crate::presets::gen_beat_compressor(rng, arf));
+  }
   let ratio: f32 = match arf.energy {
     Energy::High => in_range(rng, 2.0, 3.0),
     Energy::Medium => in_range(rng, 1.5, 2.0),
@@ -997,32 +1166,35 @@ pub fn gen_inst_compressor(rng: &mut ThreadRng, arf: &Arf) -> CompressorParams {
       // lower thresholds for producing a closed / muted sound
       Presence::Tenuto => in_range(rng, -24.0, -36.0),
     },
-    _ => panic!("Not implemented for melodic arfs"),
+    role => return Err(RaudioError::Dynamics(format!("No compressor for {} parts", role))),
   };
 
-  CompressorParams {
+  Ok(CompressorParams {
     ratio,
     threshold,
     attack_time: 0.1,
     release_time: 0.2,
     ..Default::default()
-  }
+  })
 }
 
-pub fn gen_inst_expander(rng: &mut ThreadRng, arf: &Arf) -> ExpanderParams {
-
+/// Expander for a note of a synthesized part. Drums are shaped like drum samples, see `gen_beat_expander`.
+pub fn gen_inst_expander(rng: &mut ThreadRng, arf: &Arf) -> Result<ExpanderParams, RaudioError> {
+  if let Role::Kick | Role::Perc | Role::Hats = arf.role {
+    return Ok(crate::presets::gen_beat_expander(rng, arf));
+  }
   let threshold: f32 = match arf.role {
     Role::Bass => in_range(rng, -39.0, -27.0),
     Role::Chords => in_range(rng, -24.0, -15.0),
     Role::Lead => in_range(rng, -42.0, -30.0),
-    _ => panic!("Not implemented for melodic arfs"),
+    role => return Err(RaudioError::Dynamics(format!("No expander for {} parts", role))),
   };
-  ExpanderParams {
+  Ok(ExpanderParams {
     threshold,
     attack_time: 0.1,
     release_time: 0.2,
     ..Default::default()
-  }
+  })
 }
 
 
@@ -1092,144 +1264,140 @@ pub fn gen_inst_expander(rng: &mut ThreadRng, arf: &Arf) -> ExpanderParams {
 //     reverberated_signal
 // }
 
-pub fn fm_combiner_with_reso<'render>(
-  conf: &Conf, stem: StemFM<'render>, reverbs: &Vec<ReverbParams>, keep_stems: Option<&str>,
-) -> SampleBuffer {
-  println!("fm combiner_with_reso");
-  let (melody, arf, fm_fn, delay1, delay2, reverb1, reverb2) = stem;
-
-  let append_delay = time::samples_of_dur(1f32, longest_delay_length(&delay1));
-  let append_reverb = time::samples_of_dur(1f32, longest_reverb_length(&reverb1));
-
-  // Precompute signal lengths
-  let line_length_cycles = time::count_cycles(&melody[0]);
-  let total_signal_samples = time::samples_of_cycles(conf.cps, line_length_cycles) + append_delay + append_reverb;
-  let polyphony_attenuation = 1f32 / melody.len() as f32;
+/// Renders the notes of a `StemFM` with their per-note effects.
+pub struct FmVoice<'a, 'render> {
+  conf: &'a Conf,
+  stem: &'a StemFM<'render>,
+  polyphony_attenuation: f32,
+  seed: Option<u64>,
+  /// Swing and timing of each line
+  groove: Option<Groove>,
+  /// Seconds of effects sounding after each note
+  pub tail: f32,
+}
 
-  // Process each note in the melody
-  let line_buffs: Vec<SampleBuffer> = melody
-    .iter()
-    .map(|line| {
-      let mut channel_samples: Vec<SampleBuffer> = Vec::new();
+impl<'a, 'render> FmVoice<'a, 'render> {
+  pub fn new(conf: &'a Conf, stem: &'a StemFM<'render>, playing: &Playing) -> Self {
+    let (melody, _, _, delay1, delay2, reverb1, reverb2) = stem;
+    FmVoice {
+      conf,
+      stem,
+      polyphony_attenuation: 1f32 / melody.len() as f32,
+      seed: seed::current_seed(),
+      groove: playing.groove.clone(),
+      tail: window::tail_seconds(delay1, reverb1) + window::tail_seconds(delay2, reverb2),
+    }
+  }
 
-      let len_cycles = time::count_cycles(line);
-      let append_delay = time::samples_of_dur(1f32, longest_delay_length(&delay1));
-      let append_reverb = time::samples_of_dur(1f32, longest_reverb_length(&reverb1));
+  pub fn plan(&self, line: &Vec<Note>) -> LinePlan {
+    let mut plan = LinePlan::new(self.conf.cps, line, self.tail);
+    if let Some(groove) = &self.groove {
+      plan.set_feel(self.conf.cps, groove::line_feel(&plan.durs, groove));
+    }
+    plan
+  }
 
-      let pad_samples_time_effects = append_delay + append_reverb;
-      let signal_len = if pad_samples_time_effects > 0 {
-        let pad_samples_error_margin = 2 * (delay1.len() + reverb1.len());
-        time::samples_of_cycles(conf.cps, len_cycles) + pad_samples_time_effects + pad_samples_error_margin
-      } else {
-        time::samples_of_cycles(conf.cps, len_cycles)
-      };
+  /// Length of the buffer the notes of a line are placed in.
+  pub fn line_len(&self, plan: &LinePlan) -> usize {
+    line_len(self.conf.cps, plan.len_cycles, &self.stem.3, &self.stem.5)
+  }
 
-      let durs: Vec<f32> = line.iter().map(time::note_to_cycles).collect();
-      let mut curr_pos_cycles = 0f32;
+  /// Render note `i` of line `l`.
+  pub fn note(&self, l: usize, line: &Vec<Note>, plan: &LinePlan, i: usize) -> Result<SampleBuffer, RaudioError> {
+    let (_, arf, fm_fn, _, _, reverb1, _) = self.stem;
+    let conf = self.conf;
+    let cps = plan.cps[i];
+    let note = &line[i];
+    let ((step_num, step_denom), _, amp) = *note;
+    let n_cycles = plan.durs[i];
+    let curr_pos_cycles = plan.durs[..i].iter().fold(0f32, |acc, dur| acc + dur);
+
+    if self.seed.is_some() {
+      seed::reseed_for(self.seed, &[l as u64, i as u64]);
+    }
+    if step_num.signum() < 0 || step_denom.signum() < 0 || amp == 0f32 || !plan.heard[i] {
+      // this is a rest
+      return Ok(vec![0f32; time::samples_of_cycles(cps, n_cycles)]);
+    }
+    progress::note_rendered();
+    let freq = note_to_freq(note);
+    let high_freq_attenuation: f32 = match freq.log2().floor() as i32 {
+      10 => 0.8f32,
+      11 => 0.5f32,
+      12 => 0.4f32,
+      13 => 0.4f32,
+      14 => 0.6f32,
+      15 => 0.6f32,
+      _ => 1f32,
+    };
 
-      line.iter().enumerate().for_each(|(idx, note)| {
-        let ((step_num, step_denom), (register, monae), amp) = *note;
-        let n_cycles = durs[idx];
+    let velocity = amp
+      * apply_filter(
+        crate::monic_theory::note_to_freq(note),
+        MFf,
+        NFf(),
+        DB_PER_OCTAVE,
+        DB_DISTANCE,
+      );
+    let gain = self.polyphony_attenuation * high_freq_attenuation;
+    let operators = fm_fn(conf, arf, note, cps, n_cycles, curr_pos_cycles, gain * velocity);
 
-        let moment: Vec<f32> = if step_num.signum() < 0 || step_denom.signum() < 0 || amp == 0f32 {
-          // this is a rest
-          vec![0f32; time::samples_of_cycles(conf.cps, n_cycles)]
-        } else {
-          let freq = note_to_freq(note);
-          let high_freq_attenuation: f32 = match freq.log2().floor() as i32 {
-            10 => 0.8f32,
-            11 => 0.5f32,
-            12 => 0.4f32,
-            13 => 0.4f32,
-            14 => 0.6f32,
-            15 => 0.6f32,
-            _ => 1f32,
-          };
-
-          let velocity = amp
-            * apply_filter(
-              crate::monic_theory::note_to_freq(note),
-              MFf,
-              NFf,
-              DB_PER_OCTAVE,
-              DB_DISTANCE,
-            );
-          let gain = polyphony_attenuation * high_freq_attenuation;
-          let operators = fm_fn(conf, &arf, note, conf.cps, n_cycles, curr_pos_cycles, gain * velocity);
-
-          let mut rng = thread_rng();
-          // Render the operators to a single channel
-          let mut moment = render_operators(operators, n_cycles, conf.cps, SR);
-
-          let target_rms = get_rescale_target(&mut rng, arf.visibility);
-          let expander_params = gen_inst_expander(&mut rng, &arf);
-          let compressor_params = gen_inst_compressor(&mut rng, &arf);
-
-          let normalized = rescale_amplitude(0.5f32, &moment);
-          moment = compressor(
-            &expander(&normalized, expander_params, None).unwrap(),
-            compressor_params,
-            None,
-          )
-          .unwrap();
-
-          // Apply per-note reverb effects
-          if !reverb1.is_empty() {
-            moment = reverb1.iter().fold(moment, |sig, params| {
-              let mut processed = convolution::of(&sig, params);
-              trim_zeros(&mut processed);
-              processed
-            });
-          }
-          moment
-        };
-        channel_samples.push(moment);
-        curr_pos_cycles += n_cycles;
+    let mut rng = thread_rng();
+    // Render the operators to a single channel
+    let mut moment = render_operators(operators, n_cycles, cps, SR());
+
+    let target_rms = get_rescale_target(&mut rng, arf.visibility);
+    let expander_params = gen_inst_expander(&mut rng, arf)?;
+    let compressor_params = gen_inst_compressor(&mut rng, arf)?;
+
+    let normalized = rescale_amplitude(0.5f32, &moment);
+    moment = dynamics(&normalized, expander_params, compressor_params)?;
+
+    // Apply per-note reverb effects
+    if !reverb1.is_empty() {
+      moment = reverb1.iter().fold(moment, |sig, params| {
+        let mut processed = convolution::of(&sig, params);
+        trim_zeros(&mut processed);
+        processed
       });
+    }
+    Ok(moment)
+  }
+}
 
-      overlapping(signal_len, conf.cps, durs, &channel_samples)
+pub fn fm_combiner_with_reso<'render>(
+  conf: &Conf, stem: StemFM<'render>, playing: &Playing, reverbs: &Vec<ReverbParams>, keep_stems: Option<&str>,
+) -> Result<StereoBuffer, RaudioError> {
+  println!("fm combiner_with_reso");
+  let voice = FmVoice::new(conf, &stem, playing);
+  let (melody, _, _, _, delay2, _, reverb2) = &stem;
+
+  // Process each note in the melody
+  let line_buffs: Vec<StereoBuffer> = melody
+    .iter()
+    .enumerate()
+    .map(|(l, line)| {
+      let plan = voice.plan(line);
+      let channel_samples =
+        (0..line.len()).map(|i| voice.note(l, line, &plan, i)).collect::<Result<Vec<SampleBuffer>, _>>()?;
+      Ok(stereo::from_mono(overlapping_at(voice.line_len(&plan), &plan.cues(conf.cps), &channel_samples)))
     })
-    .collect();
+    .collect::<Result<_, RaudioError>>()?;
 
   // Pad and mix the note-level channels
-  let mixed_signal = match pad_and_mix_buffers(line_buffs) {
-    Ok(signal) => signal,
-    Err(msg) => panic!("Failed to mix FM channels: {}", msg),
-  };
+  let mixed_signal =
+    stereo::pad_and_mix_buffers(line_buffs, Stage::Lines).map_err(|msg| RaudioError::Mix(msg.to_string()))?;
 
-  // Apply line-level delay effects
-  let chan_wet_delays = if delay2.is_empty() {
-    mixed_signal
-  } else {
-    delay2.iter().fold(mixed_signal, |mut sig, params| {
-      let samples_per_echo = time::samples_from_dur(1f32, params.len_seconds);
-      for replica_n in 0..=params.n_echoes.max(1) {
-        for j in 0..sig.len() {
-          let v = sig[j];
-          let offset_j = samples_per_echo * replica_n;
-          let gain = delay::gain(j, replica_n, params);
-          let clip_thresh = 1f32;
-          let gate_thresh = 0f32;
-
-          // Apply gating and clipping
-          if gain * v.abs() > clip_thresh {
-            sig[j + offset_j] += gain * v.signum() * clip_thresh;
-          } else if gain * v.abs() >= gate_thresh {
-            sig[j + offset_j] += gain * v;
-          }
-        }
-      }
-      sig
-    })
-  };
+  // Apply line-level delay effects, placing each echo in its stereo field and clipping it at full scale
+  let chan_wet_delays = stereo::apply_delays(mixed_signal, delay2, Some(1f32));
 
   // Apply line-level reverb effects
   let mut final_signal = if reverb2.is_empty() {
     chan_wet_delays
   } else {
     reverb2.iter().fold(chan_wet_delays, |sig, params| {
-      let mut processed = convolution::of(&sig, params);
-      trim_zeros(&mut processed);
+      let mut processed = convolution::of_stereo(&sig, params);
+      stereo::trim_zeros(&mut processed);
       processed
     })
   };
@@ -1237,19 +1405,19 @@ pub fn fm_combiner_with_reso<'render>(
   // Save the final mixed signal if stems are requested
   if let Some(stem_dir) = keep_stems {
     let filename = format!("{}/final-fm-stem.wav", stem_dir);
-    render::engrave::samples(SR, &final_signal, &filename);
+    render::engrave::try_write_audio(SR(), vec![final_signal.0.clone(), final_signal.1.clone()], &filename)?;
   }
 
   // Apply global reverbs
   if !reverbs.is_empty() {
     final_signal = reverbs.iter().fold(final_signal, |sig, params| {
-      let mut processed = convolution::of(&sig, params);
-      trim_zeros(&mut processed);
+      let mut processed = convolution::of_stereo(&sig, params);
+      stereo::trim_zeros(&mut processed);
       processed
     });
   }
 
-  final_signal
+  Ok(final_signal)
 }
 
 #[cfg(test)]
@@ -1307,172 +1475,303 @@ mod test_fm_render {
     // Add empty reverbs if not needed
     let reverbs = vec![];
 
-    let signal = fm_combiner_with_reso(&conf, stem_fm, &reverbs, None);
+    let (left, right) = fm_combiner_with_reso(&conf, stem_fm, &Playing::default(), &reverbs, None).unwrap();
 
-    assert!(!signal.is_empty(), "FM combined signal should not be empty");
+    assert!(!left.is_empty(), "FM combined signal should not be empty");
     let filename = format!("dev-audio/test-fm_render-stemfm");
-    crate::render::engrave::samples(SR, &signal, &filename);
+    crate::render::engrave::write_audio(SR(), vec![left, right], &filename);
   }
 }
 
+/// Seed path keys for the non-indexed stages of `combiner_with_reso2`.
+pub(crate) const STEM_REVERB_SEED_KEY: u64 = u64::MAX;
+pub(crate) const GROUP_REVERB_SEED_KEY: u64 = u64::MAX - 1;
+
 /// Given a list of renderables (either instances or groups) and how to represent them in space,
 /// Generate the signals and apply reverberation. Return the new signal.
 /// Accepts an optional parameter `keep_stems`. When provided, it is the directory for placing the stems.
+///
+/// Mono counterpart of `combiner_with_reso2_stereo`, with all parts centered.
 pub fn combiner_with_reso2<'render>(
   conf: &Conf, renderables: &Vec<(Arf, Renderable2<'render>)>, stem_reverbs: &Vec<convolution::ReverbParams>,
   group_reverbs: &Vec<convolution::ReverbParams>, keep_stems: Option<&str>,
-) -> SampleBuffer {
+) -> Result<SampleBuffer, RaudioError> {
+  let mixed = combiner_with_reso2_stereo(conf, renderables, &vec![], &[], stem_reverbs, group_reverbs, keep_stems)?;
+  Ok(stereo::downmix(&mixed))
+}
+
+/// Given a list of renderables (either instances or groups) and how to represent them in space,
+/// Generate the stereo signals, position each part and apply reverberation. Return the new signal.
+/// ## Arguments
+///     `pans` Pan position of each renderable in [-1, 1] (left to right). Empty to center every part.
+///     `playing` Glide and groove of each renderable, see `Playing`. Empty to play every note as written.
+///     `stem_reverbs` Reverb applied to each renderable before it is panned. Empty to skip.
+///     `group_reverbs` Reverbs applied to the mixed signal.
+///     `keep_stems` When provided, it is the directory for placing the stems.
+pub fn combiner_with_reso2_stereo<'render>(
+  conf: &Conf, renderables: &Vec<(Arf, Renderable2<'render>)>, pans: &[f32], playing: &[Playing],
+  stem_reverbs: &Vec<convolution::ReverbParams>, group_reverbs: &Vec<convolution::ReverbParams>,
+  keep_stems: Option<&str>,
+) -> Result<StereoBuffer, RaudioError> {
+  let channels = render_parts_stereo(conf, renderables, pans, playing, stem_reverbs, &[], keep_stems)?;
+
+  // Optionally save stems if `keep_stems` is provided
+  if let Some(stem_dir) = keep_stems {
+    for (stem_num, channel_samples) in channels.iter().enumerate() {
+      let filename = format!("{}/stem-{}.wav", stem_dir, stem_num);
+      render::engrave::try_write_audio(SR(), vec![channel_samples.0.clone(), channel_samples.1.clone()], &filename)?;
+    }
+  }
+
+  mix_parts_stereo(channels, group_reverbs)
+}
+
+/// The first stage of `combiner_with_reso2_stereo`.
+/// Render each renderable with its stem reverb and pan position. Returns one signal per renderable.
+/// Leaves the current thread seeded for the group reverb stage, see `mix_parts_stereo`.
+///
+/// Renderables flagged in `skip` are not rendered and come back empty, for callers that already have their signal
+/// (see `render::cache`). Empty to render every part.
+/// Each renderable glides and grooves its notes as given in `playing`. Empty to play every note as written.
+/// When `keep_stems` is provided, the substems of each renderable are written there.
+pub fn render_parts_stereo<'render>(
+  conf: &Conf, renderables: &Vec<(Arf, Renderable2<'render>)>, pans: &[f32], playing: &[Playing],
+  stem_reverbs: &Vec<convolution::ReverbParams>, skip: &[bool], keep_stems: Option<&str>,
+) -> Result<Vec<StereoBuffer>, RaudioError> {
   // Initialize a global Rayon thread pool with a max of 4 threads
   let _ = ThreadPoolBuilder::new().num_threads(4).build_global();
 
+  // Each task enters the context of the calling thread with its own seed, so seeded renders do not depend on
+  // scheduling, and each part counts its notes for the progress events
+  let context = RenderContext::current();
+
   // Collect channels by processing each renderable in parallel
-  let mut channels: Vec<SampleBuffer> = renderables
+  let channels: Vec<StereoBuffer> = renderables
     .par_iter()
     .enumerate()
     .map(|(j, (arf, renderable))| {
+      if skip.get(j).copied().unwrap_or(false) {
+        return Ok((vec![], vec![]));
+      }
+      let part = context.progress.as_ref().map(Progress::for_part);
+      let _context = RenderContext {
+        progress: part.clone(),
+        ..context.for_task(&[j as u64])
+      }
+      .enter();
+      if let Some(part) = &part {
+        part.emit(Event::PartStart { part: j, role: arf.role });
+      }
+      let synthesis = Instant::now();
+      let played = playing.get(j).cloned().unwrap_or_default();
       let ch = match renderable {
         Renderable2::Instance(stem) => {
           // Process a single stem
-          vec![channel_with_reso(conf, arf, stem)]
+          vec![channel_with_reso(conf, arf, stem, &played)?]
         }
         Renderable2::Group(stems) => {
           // Process each stem in the group
-          stems.par_iter().map(|stem| channel_with_reso(conf, arf, stem)).collect::<Vec<_>>()
+          stems
+            .par_iter()
+            .enumerate()
+            .map(|(k, stem)| {
+              let _context = RenderContext {
+                progress: part.clone(),
+                ..context.for_task(&[j as u64, k as u64])
+              }
+              .enter();
+              channel_with_reso(conf, arf, stem, &played)
+            })
+            .collect::<Result<Vec<_>, RaudioError>>()?
         }
         Renderable2::Sample(stem) => {
-          vec![channel_with_samples(conf, stem)]
+          vec![channel_with_samples(conf, stem, &played)?]
         }
 
         Renderable2::Mix(weighted_stems) => weighted_stems
           .iter()
-          .map(|(gain, renderable2)| {
-            combiner_with_reso2(&conf, &vec![(*arf, renderable2.to_owned())], &vec![], &vec![], keep_stems)
-              .iter()
-              .map(|v| gain * v)
-              .collect()
+          .enumerate()
+          .map(|(k, (gain, renderable2))| {
+            let _context = RenderContext {
+              progress: part.as_ref().map(Progress::nested),
+              ..context.for_task(&[j as u64, k as u64])
+            }
+            .enter();
+            let (left, right) = combiner_with_reso2_stereo(
+              &conf,
+              &vec![(*arf, renderable2.to_owned())],
+              &vec![],
+              std::slice::from_ref(&played),
+              &vec![],
+              &vec![],
+              keep_stems,
+            )?;
+            Ok((left.iter().map(|v| gain * v).collect(), right.iter().map(|v| gain * v).collect()))
           })
-          .collect(),
+          .collect::<Result<Vec<_>, RaudioError>>()?,
         Renderable2::Tacet(stem) => {
           vec![tacet2(conf.cps, stem)]
         }
 
         Renderable2::FMOp(fm_stem) => {
-          vec![fm_combiner_with_reso(conf, fm_stem.clone(), &vec![], keep_stems)]
+          vec![fm_combiner_with_reso(conf, fm_stem.clone(), &played, &vec![], keep_stems)?]
         }
       };
 
       if let Some(stem_dir) = keep_stems {
         // Keep the substems
-        ch.iter().enumerate().for_each(|(stem_num, channel_samples)| {
+        for (stem_num, channel_samples) in ch.iter().enumerate() {
           let filename = format!("{}/part-{}-twig-{}.wav", stem_dir, j, stem_num);
-          if channel_samples.is_empty() {
-            eprintln!("Warning: Channel samples are empty for stem {}-{}", j, stem_num);
+          if channel_samples.0.is_empty() {
+            if let Some(part) = &part {
+              part.emit(Event::EmptyStem { part: j, stem: stem_num });
+            }
           }
-          render::engrave::samples(SR, &channel_samples, &filename);
-        });
+          render::engrave::try_write_audio(
+            SR(),
+            vec![channel_samples.0.clone(), channel_samples.1.clone()],
+            &filename,
+          )?;
+        }
       }
 
-      let rendered_channel = pad_and_mix_buffers(ch);
-
-      match rendered_channel {
-        Ok(signal) => {
-          if stem_reverbs.is_empty() {
-            signal
-          } else {
-            convolution::of(&signal, &stem_reverbs[j])
-          }
+      let signal = stereo::pad_and_mix_buffers(ch, Stage::Channels).map_err(|msg| RaudioError::Mix(msg.to_string()))?;
+      let synthesis = synthesis.elapsed();
+      let (wet, stem_reverb) = time::measure(|| {
+        if stem_reverbs.is_empty() {
+          signal
+        } else {
+          // nested tasks may have run on this thread while it waited
+          seed::reseed_for(context.seed, &[j as u64, STEM_REVERB_SEED_KEY]);
+          convolution::of_stereo(&signal, &stem_reverbs[j])
         }
-        Err(msg) => panic!("Unexpected error while mixing channels: {}", msg),
+      });
+      if let Some(part) = &part {
+        part.emit(Event::PartFinish {
+          part: j,
+          notes: part.notes(),
+          synthesis_seconds: synthesis.as_secs_f64(),
+          stem_reverb_seconds: stem_reverb.as_secs_f64(),
+        });
       }
+      Ok(stereo::pan(wet, pans.get(j).copied().unwrap_or(0f32)))
     })
-    .collect();
-
-  // Optionally save stems if `keep_stems` is provided
-  if let Some(stem_dir) = keep_stems {
-    channels.iter().enumerate().for_each(|(stem_num, channel_samples)| {
-      let filename = format!("{}/stem-{}.wav", stem_dir, stem_num);
-      render::engrave::samples(SR, &channel_samples, &filename);
-    });
-  }
+    .collect::<Result<Vec<_>, RaudioError>>()?;
+  seed::reseed_for(context.seed, &[GROUP_REVERB_SEED_KEY]);
+  Ok(channels)
+}
 
+/// The second stage of `combiner_with_reso2_stereo`.
+/// Mix the parts from `render_parts_stereo` and apply the group reverbs.
+pub fn mix_parts_stereo(
+  channels: Vec<StereoBuffer>, group_reverbs: &Vec<convolution::ReverbParams>,
+) -> Result<StereoBuffer, RaudioError> {
   // Pad and mix the collected channels into a final signal
-  match pad_and_mix_buffers(channels) {
-    Ok(signal) => {
-      // Apply reverbs if provided
-      if group_reverbs.is_empty() {
-        signal
-      } else {
-        group_reverbs.iter().fold(signal, |sig, params| {
-          let mut sig = convolution::of(&sig, &params);
-          trim_zeros(&mut sig);
-          sig
-        })
-      }
-    }
-    Err(msg) => panic!("Failed to mix and render audio: {}", msg),
+  let signal = stereo::pad_and_mix_buffers(channels, Stage::Parts).map_err(|msg| RaudioError::Mix(msg.to_string()))?;
+  // Apply reverbs if provided
+  if group_reverbs.is_empty() {
+    Ok(signal)
+  } else {
+    Ok(group_reverbs.iter().fold(signal, |sig, params| {
+      let mut sig = convolution::of_stereo(&sig, &params);
+      stereo::trim_zeros(&mut sig);
+      sig
+    }))
   }
 }
 
-/// Render a channel from sample-based input, applying the necessary effects
-#[inline]
-fn channel_with_samples(
-  conf: &Conf, (melody, ref_samples, amp_expr, lowpass_cutoff_freq, delays1, delays2, reverbs1, reverbs2): &DrumSample,
-) -> SampleBuffer {
-  let Conf { cps, root } = *conf;
-
-  let line_buffs: Vec<SampleBuffer> = melody
-    .iter()
-    .map(|line| {
-      let len_cycles = time::count_cycles(line);
-      let append_delay = time::samples_of_dur(1.0, longest_delay_length(delays1));
-      let append_reverb = time::samples_of_dur(1.0, longest_reverb_length(reverbs1));
-
-      let signal_len = time::samples_of_cycles(cps, len_cycles) + append_delay + append_reverb;
-      let durs: Vec<f32> = line.iter().map(|(d, _, _)| time::duration_to_cycles(*d)).collect();
-
-      let mut p: f32 = 0.0;
-      let mut line_signal = vec![0.0; signal_len];
-      let mut accumulated_offset: usize = 0; // Track the accumulated offset
+/// Renders the notes of a `DrumSample` with their per-note effects.
+pub struct SampleVoice<'a, 'render> {
+  conf: &'a Conf,
+  stem: &'a DrumSample<'render>,
+  seed: Option<u64>,
+  /// Swing and timing of each line
+  groove: Option<Groove>,
+  /// Seconds of effects sounding after each note
+  pub tail: f32,
+}
 
-      // Process each note in the line
-      line.iter().enumerate().for_each(|(i, (_, tone, amp))| {
-        let freq = root * tone_to_freq(tone);
+impl<'a, 'render> SampleVoice<'a, 'render> {
+  pub fn new(conf: &'a Conf, stem: &'a DrumSample<'render>, playing: &Playing) -> Self {
+    let (_, _, _, _, delays1, delays2, reverbs1, reverbs2) = stem;
+    SampleVoice {
+      conf,
+      stem,
+      seed: seed::current_seed(),
+      groove: playing.groove.clone(),
+      tail: window::tail_seconds(delays1, reverbs1) + window::tail_seconds(delays2, reverbs2),
+    }
+  }
 
-        // Render the sample for the current note
-        let moment = render_sample(
-          p,
-          len_cycles,
-          cps,
-          root,
-          *amp,
-          freq,
-          durs[i],
-          ref_samples,
-          amp_expr,
-          *lowpass_cutoff_freq,
-        );
+  pub fn plan(&self, line: &Vec<Note>) -> LinePlan {
+    let mut plan = LinePlan::new(self.conf.cps, line, self.tail);
+    if let Some(groove) = &self.groove {
+      plan.set_feel(self.conf.cps, groove::line_feel(&plan.durs, groove));
+    }
+    plan
+  }
 
-        // Apply effects (delays, reverbs) to the sample
-        let mut wet = finalize_signal(moment, delays1, reverbs1, Some(NFf));
+  /// Length of the buffer the notes of a line are placed in.
+  pub fn line_len(&self, plan: &LinePlan) -> usize {
+    let (_, _, _, _, delays1, _, reverbs1, _) = self.stem;
+    let append_delay = time::samples_of_dur(1.0, longest_delay_length(delays1));
+    let append_reverb = time::samples_of_dur(1.0, longest_reverb_length(reverbs1));
+    time::samples_at(self.conf.cps, plan.len_cycles) + append_delay + append_reverb
+  }
 
-        // Add the processed sample to the line buffer
-        add_to_buffer(&mut line_signal, wet, accumulated_offset);
+  /// Render note `i` of line `l`.
+  pub fn note(&self, l: usize, line: &Vec<Note>, plan: &LinePlan, i: usize) -> StereoBuffer {
+    let (_, ref_samples, amp_expr, lowpass_cutoff_freq, delays1, _, reverbs1, _) = self.stem;
+    let root = self.conf.root;
+    let cps = plan.cps[i];
+    if self.seed.is_some() {
+      seed::reseed_for(self.seed, &[l as u64, i as u64]);
+    }
+    if !plan.heard[i] {
+      return (vec![], vec![]);
+    }
+    progress::note_rendered();
+    let (_, tone, amp) = &line[i];
+    let freq = root * tone_to_freq(tone);
+
+    // Render the sample for the current note
+    let moment = render_sample(
+      plan.progress[i],
+      plan.len_cycles,
+      cps,
+      root,
+      *amp,
+      freq,
+      plan.durs[i],
+      ref_samples,
+      amp_expr,
+      *lowpass_cutoff_freq,
+    );
 
-        // Update the accumulated offset for the next note
-        accumulated_offset += time::samples_of_dur(cps, durs[i]);
+    // Apply effects (delays, reverbs) to the sample
+    let delays1: Vec<DelayParams> = delays1.iter().map(|delay| delay.at_tempo(cps)).collect();
+    finalize_signal(stereo::from_mono(moment), &delays1, reverbs1, Some(NFf()))
+  }
+}
 
-        // Update position in the line
-        p += durs[i] / len_cycles;
-      });
+/// Render a channel from sample-based input, applying the necessary effects
+#[inline]
+fn channel_with_samples(conf: &Conf, stem: &DrumSample, playing: &Playing) -> Result<StereoBuffer, RaudioError> {
+  let voice = SampleVoice::new(conf, stem, playing);
+  let (melody, _, _, lowpass_cutoff_freq, _, delays2, _, reverbs2) = stem;
 
-      line_signal
+  let line_buffs: Vec<StereoBuffer> = melody
+    .iter()
+    .enumerate()
+    .map(|(l, line)| {
+      let plan = voice.plan(line);
+      let notes: Vec<StereoBuffer> = (0..line.len()).map(|i| voice.note(l, line, &plan, i)).collect();
+      stereo::overlapping(voice.line_len(&plan), &plan.cues(conf.cps), &notes)
     })
     .collect();
 
-  match pad_and_mix_buffers(line_buffs) {
-    Ok(mixed) => finalize_signal(mixed, delays2, reverbs2, Some(*lowpass_cutoff_freq)),
-    Err(msg) => panic!("Failed to render and mix line buffers: {}", msg),
-  }
+  let mixed = stereo::pad_and_mix_buffers(line_buffs, Stage::Lines).map_err(|msg| RaudioError::Mix(msg.to_string()))?;
+  Ok(finalize_signal(mixed, delays2, reverbs2, Some(*lowpass_cutoff_freq)))
 }
 
 /// Render a single sample using the given parameters and reference samples
@@ -1521,21 +1820,11 @@ fn render_sample(
   }
 
   // Apply a lowpass filter to the signal
-  crate::analysis::freq::butterworth_lowpass_filter(&mut signal, SR as u32, lowpass_cutoff_freq);
+  crate::analysis::freq::butterworth_lowpass_filter(&mut signal, SR() as u32, lowpass_cutoff_freq);
 
   signal
 }
 
-/// Add a buffer into another, starting at a specified offset
-#[inline]
-fn add_to_buffer(target: &mut SampleBuffer, source: SampleBuffer, offset: usize) {
-  for (i, &sample) in source.iter().enumerate() {
-    if i + offset < target.len() {
-      target[i + offset] += sample;
-    }
-  }
-}
-
 #[cfg(test)]
 mod test {
   use convolution::ReverbParams;
@@ -1549,11 +1838,11 @@ mod test {
   fn write_test_asset(signal: &SampleBuffer, test_name: &str) {
     files::with_dir(TEST_DIR);
     let filename = format!("{}/{}.wav", TEST_DIR, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   fn modifiers_lead() -> ModifiersHolder {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let vibrato = applied_modulation::gen_vibrato(2f32, 13f32, 0f32, 1f32, 0f32, 0f32, &mut rng);
     (
       vec![],
@@ -1565,7 +1854,7 @@ mod test {
   }
 
   fn modifiers_chords() -> ModifiersHolder {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let tremelo = applied_modulation::gen_tremelo(2f32, 13f32, 0f32, 1f32, 0f32, 0f32, &mut rng);
     (
       // vec![tremelo],
@@ -1579,7 +1868,7 @@ mod test {
   fn feeling_lead() -> Feel {
     Feel {
       // expr: (lifespan::mod_lifespan(SR, 1f32, &AmpLifespan::Snap, 1, 1f32),vec![1f32],vec![0f32]),
-      bp: (vec![MFf], vec![NFf]),
+      bp: (vec![MFf], vec![NFf()]),
       modifiers: modifiers_lead(),
       clippers: (0f32, 1f32),
     }
@@ -1588,7 +1877,7 @@ mod test {
   fn feeling_chords() -> Feel {
     Feel {
       // expr: (vec![1f32],vec![1f32],vec![0f32]),
-      bp: (vec![MFf], vec![NFf]),
+      bp: (vec![MFf], vec![NFf()]),
       modifiers: modifiers_chords(),
       clippers: (0f32, 1f32),
     }
@@ -1603,6 +1892,7 @@ mod test {
       mix: 0.25f32,
       gain: 0.66,
       len_seconds: 0.33f32,
+      len_cycles: None,
       n_echoes: 5,
       pan: StereoField::Mono,
     }];
@@ -1611,6 +1901,7 @@ mod test {
       mix: 0f32,
       gain: 0f32,
       len_seconds: 0.15f32,
+      len_cycles: None,
       n_echoes: 5,
       pan: StereoField::Mono,
     }];
@@ -1642,12 +1933,12 @@ mod test {
       rate: 0.1,
     }];
 
-    let result = combiner(happy_birthday::cps, happy_birthday::root, &stems, &reverbs, None);
+    let result = combiner(happy_birthday::cps, happy_birthday::root, &stems, &reverbs, None).unwrap();
     write_test_asset(&result, "combine");
   }
   use crate::types::timbre::{Distance, Echo, Enclosure, Positioning, SpaceEffects};
   use rand::seq::SliceRandom;
-  use rand::thread_rng;
+  use crate::seed::thread_rng;
 
   fn gen_enclosure() -> Enclosure {
     let mut rng = thread_rng();
@@ -1696,7 +1987,7 @@ mod test {
         &stems,
         &se_lead.reverbs,
         None,
-      );
+      ).unwrap();
       write_test_asset(&result, &format!("combine_with_space_{}", i));
     }
   }
@@ -1735,7 +2026,7 @@ mod test {
           &stems_raw,
           &se_lead.reverbs,
           None,
-      );
+      ).unwrap();
       let duration_raw = start_raw.elapsed();
       write_test_asset(&result_raw, &format!("test_mixing_soids_raw_soids"));
   
@@ -1747,7 +2038,7 @@ mod test {
           &stems_processed,
           &se_lead.reverbs,
           None,
-      );
+      ).unwrap();
       let duration_processed = start_processed.elapsed();
       write_test_asset(&result_processed, &format!("test_mixing_soids_processed_soids"));
   
@@ -1762,6 +2053,7 @@ mod test {
     let params: Vec<DelayParams> = vec![
       DelayParams {
         len_seconds: 1f32,
+        len_cycles: None,
         n_echoes: 5,
         gain: 1f32,
         mix: 1f32,
@@ -1769,6 +2061,7 @@ mod test {
       },
       DelayParams {
         len_seconds: 3f32,
+        len_cycles: None,
         n_echoes: 5,
         gain: 1f32,
         mix: 1f32,
@@ -1780,4 +2073,36 @@ mod test {
     let actual = longest_delay_length(&params);
     assert_eq!(expected, actual, "Invalid longest delay time")
   }
+
+  #[test]
+  fn test_synthesized_drums_take_the_dynamics_of_drum_samples() {
+    let arf = |role: Role| Arf {
+      mode: Mode::Melodic,
+      role,
+      register: 5,
+      visibility: Visibility::Visible,
+      energy: Energy::High,
+      presence: Presence::Legato,
+    };
+    for role in [Role::Kick, Role::Perc, Role::Hats] {
+      crate::seed::set_seed(Some(4));
+      let expander = gen_inst_expander(&mut thread_rng(), &arf(role)).unwrap();
+      let compressor = gen_inst_compressor(&mut thread_rng(), &arf(role)).unwrap();
+      crate::seed::set_seed(Some(4));
+      let sample_expander = crate::presets::gen_beat_expander(&mut thread_rng(), &arf(role));
+      let sample_compressor = crate::presets::gen_beat_compressor(&mut thread_rng(), &arf(role));
+      assert_eq!(sample_expander.threshold, expander.threshold, "{} must expand like a drum sample", role);
+      assert_eq!(
+        (sample_compressor.ratio, sample_compressor.threshold),
+        (compressor.ratio, compressor.threshold),
+        "{} must compress like a drum sample",
+        role
+      );
+    }
+    crate::seed::set_seed(None);
+    for role in [Role::Bass, Role::Chords, Role::Lead] {
+      assert!(gen_inst_expander(&mut thread_rng(), &arf(role)).is_ok());
+      assert!(gen_inst_compressor(&mut thread_rng(), &arf(role)).is_ok());
+    }
+  }
 }
@@ -21,7 +21,7 @@ use crate::types::synthesis::{
   Bp, Direction, Duration, FilterPoint, Freq, Frex, GlideLen, Monae, Mote, Note, Radian, Range, Tone,
 };
 use crate::types::timbre::{BandpassFilter, BaseOsc, Energy, FilterMode, Phrasing, Presence, Sound, Timeframe};
-use rand::rngs::ThreadRng;
+use crate::seed::ThreadRng;
 use rand::Rng;
 use serde::de;
 
@@ -35,7 +35,7 @@ use super::{overlapping, pad_and_mix_buffers};
 fn filter(progress: f32, freq: f32, bandpass: &Bp) -> Range {
   let p = progress.max(0f32).min(1f32);
   let min_f = sample(&bandpass.0, p).max(MF as f32);
-  let max_f = sample(&bandpass.1, p).min(NF as f32);
+  let max_f = sample(&bandpass.1, p).min(NF() as f32);
   if freq < min_f || freq > max_f {
     return 0f32;
   } else {
@@ -108,7 +108,7 @@ pub fn ninj<'render>(
         let offset_j = replica_n * samples_per_echo;
 
         let p: f32 = (offset_j + j) as f32 / n_samples as f32;
-        let t0: f32 = (offset_j + j) as f32 / SRf;
+        let t0: f32 = (offset_j + j) as f32 / SRf();
         let t: f32 = modTime.iter().fold(t0, |acc, mt| mt.apply(t0, acc));
         let mut v: f32 = 0f32;
 
@@ -174,7 +174,7 @@ pub fn ninja<'render>(
 
   for j in 0..n_samples {
     let p: f32 = j as f32 / n_samples as f32;
-    let t0: f32 = j as f32 / SRf;
+    let t0: f32 = j as f32 / SRf();
     let t: f32 = modTime.iter().fold(t0, |acc, mt| mt.apply(t0, acc));
     let mut v: f32 = 0f32;
 
@@ -236,7 +236,7 @@ mod test {
   fn write_test_asset(signal: &SampleBuffer, test_name: &str) {
     files::with_dir(TEST_DIR);
     let filename = format!("{}/{}.wav", TEST_DIR, test_name);
-    engrave::samples(SR, &signal, &filename);
+    engrave::samples(SR(), &signal, &filename).unwrap();
   }
 
   #[test]
@@ -247,7 +247,7 @@ mod test {
     let thresh: (f32, f32) = (0f32, 1f32);
     let freq = 222f32;
     let expr = (vec![1f32], vec![1f32], vec![0f32]);
-    let bp = (vec![MFf], vec![NFf]);
+    let bp = (vec![MFf], vec![NFf()]);
     let dressing = Dressing::new(
       melodic::amps_sawtooth(freq),
       melodic::muls_sawtooth(freq),
@@ -267,7 +267,7 @@ mod test {
     let thresh: (f32, f32) = (0f32, 1f32);
     let freq = 222f32;
     let expr = (vec![1f32], vec![1f32], vec![0f32]);
-    let bp = (vec![MFf], vec![NFf]);
+    let bp = (vec![MFf], vec![NFf()]);
     let dressing = Dressing::new(
       melodic::amps_sawtooth(freq),
       melodic::muls_sawtooth(freq),
@@ -292,7 +292,7 @@ mod test {
     let thresh: (f32, f32) = (0f32, 1f32);
     let freq = 222f32;
     let expr = (vec![1f32], vec![1f32], vec![0f32]);
-    let bp = (vec![MFf], vec![NFf]);
+    let bp = (vec![MFf], vec![NFf()]);
     let dressing = Dressing::new(
       melodic::amps_sawtooth(freq),
       melodic::muls_sawtooth(freq),
@@ -310,13 +310,13 @@ mod test {
   }
 
   fn modifiers_lead() -> ModifiersHolder {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let vibrato = gen_vibrato(2f32, 13f32, 0f32, 1f32, 0f32, 0f32, &mut rng);
     (vec![], vec![], vec![vibrato], vec![])
   }
 
   fn modifiers_chords() -> ModifiersHolder {
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
     let tremelo = gen_tremelo(2f32, 13f32, 0f32, 1f32, 0f32, 0f32, &mut rng);
     (vec![tremelo], vec![], vec![], vec![])
   }
@@ -341,7 +341,7 @@ mod test {
 
     FeelingHolder {
       expr: (vec![amp], vec![1f32], vec![0f32]),
-      bp: (vec![MFf], vec![NFf]),
+      bp: (vec![MFf], vec![NFf()]),
       dressing: Dressing::new(amps2, muls2, offs2),
       modifiers: modifiers_chords(),
     }
@@ -365,7 +365,7 @@ mod test {
         vec![1f32],
         vec![0f32],
       );
-      let bp = (vec![MFf], vec![NFf]);
+      let bp = (vec![MFf], vec![NFf()]);
       let dressing = Dressing::new(
         melodic::amps_sawtooth(freq),
         melodic::muls_sawtooth(freq),
@@ -403,7 +403,7 @@ mod test {
         vec![1f32],
         vec![0f32],
       );
-      let bp = (vec![MFf], vec![NFf]);
+      let bp = (vec![MFf], vec![NFf()]);
       let dressing = Dressing::new(
         melodic::amps_sawtooth(freq),
         melodic::muls_sawtooth(freq),
@@ -428,7 +428,7 @@ mod test {
     let melody1 = x_files::lead_melody();
     let melody2 = x_files::piano_melody();
     let test_name = "x_files_delay";
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
 
     let rs: Vec<(Vec<Vec<(f32, i32, i8)>>, fn(f32, f32) -> FeelingHolder)> =
       vec![(melody1, feeling_lead), (melody2, feeling_chords)];
@@ -437,6 +437,7 @@ mod test {
       mix: 0.5f32,
       gain: 0.99,
       len_seconds: 0.15f32,
+      len_cycles: None,
       n_echoes: 5,
       pan: StereoField::Mono,
     }];
@@ -492,7 +493,7 @@ mod test {
     let melody1 = x_files::lead_melody();
     let melody2 = x_files::piano_melody();
     let test_name = "x_files_overlapping_delay";
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
 
     let rs: Vec<(Vec<Vec<(f32, i32, i8)>>, fn(f32, f32) -> FeelingHolder)> =
       vec![(melody1, feeling_lead), (melody2, feeling_chords)];
@@ -501,6 +502,7 @@ mod test {
       mix: 0.5f32,
       gain: 0.99,
       len_seconds: 0.15f32,
+      len_cycles: None,
       n_echoes: 5,
       pan: StereoField::Mono,
     }];
@@ -564,7 +566,7 @@ mod test {
     let melody1 = x_files::lead_melody();
     let melody2 = x_files::piano_melody();
     let test_name = "x_files_reverb";
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
 
     let stems: Vec<(Vec<Vec<(f32, i32, i8)>>, fn(f32, f32) -> FeelingHolder)> =
       vec![(melody1, feeling_lead), (melody2, feeling_chords)];
@@ -573,6 +575,7 @@ mod test {
       mix: 0f32,
       gain: 0f32,
       len_seconds: 0f32,
+      len_cycles: None,
       n_echoes: 0,
       pan: StereoField::Mono,
     }];
@@ -648,7 +651,7 @@ mod test {
     let melody1 = x_files::lead_melody();
     let melody2 = x_files::piano_melody();
     let test_name = "x_files_delay_and_reverb";
-    let mut rng = rand::thread_rng();
+    let mut rng = crate::seed::thread_rng();
 
     let stems: Vec<(Vec<Vec<(f32, i32, i8)>>, fn(f32, f32) -> FeelingHolder)> =
       vec![(melody1, feeling_lead), (melody2, feeling_chords)];
@@ -658,6 +661,7 @@ mod test {
         mix: 0.5f32,
         gain: 0.3f32,
         len_seconds: 0.66f32,
+        len_cycles: None,
         n_echoes: 3,
         pan: StereoField::Mono,
       },
@@ -665,6 +669,7 @@ mod test {
         mix: 0.5f32,
         gain: 0.5f32,
         len_seconds: 1.5f32,
+        len_cycles: None,
         n_echoes: 2,
         pan: StereoField::Mono,
       },
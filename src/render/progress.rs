@@ -0,0 +1,245 @@
+/// Progress and timing events of a render, as JSON lines.
+///
+/// `render_score` reports the start of the render, each part starting and finishing, the time spent in each stage
+/// and the real-time factor of the whole render, along with warnings about parts like stems that came out empty. Each event is one JSON object per line with an `event` name and
+/// the `elapsed` seconds since the render started, written to stderr or a file as it happens:
+///
+/// ```text
+/// {"event":"part_finish","elapsed":4.21,"part":2,"notes":96,"synthesis_seconds":3.9,"stem_reverb_seconds":0.3}
+/// ```
+///
+/// Like the seed and sample rate, the reporter of a render is a thread local that parallel tasks inherit.
+/// Parts report the notes they synthesize through `note_rendered`.
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::RaudioError;
+use crate::types::timbre::Role;
+
+/// Where progress events are written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressSink {
+  Stderr,
+  File(String),
+}
+
+impl ProgressSink {
+  /// Parse a sink as given on the command line: `stderr` or a file path.
+  pub fn from_name(name: &str) -> ProgressSink {
+    match name {
+      "stderr" => ProgressSink::Stderr,
+      path => ProgressSink::File(path.to_string()),
+    }
+  }
+}
+
+/// A stage of `render_score`.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+  /// Every part, with its stem reverb
+  Synthesis,
+  GroupReverb,
+  /// The mixdown, stems and markers
+  Write,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+  RenderStart {
+    parts: usize,
+    notes: usize,
+    sample_rate: usize,
+  },
+  PartStart {
+    part: usize,
+    role: Role,
+  },
+  /// A part was read from the render cache instead of rendered
+  PartCached {
+    part: usize,
+  },
+  PartFinish {
+    part: usize,
+    /// Notes synthesized, once for each layer of the preset and leaving out those outside of a render window
+    notes: usize,
+    synthesis_seconds: f64,
+    stem_reverb_seconds: f64,
+  },
+  /// A stem kept with `keep_stems` has no samples, so its file is empty
+  EmptyStem {
+    part: usize,
+    stem: usize,
+  },
+  StageFinish {
+    stage: Stage,
+    seconds: f64,
+  },
+  RenderFinish {
+    seconds: f64,
+    audio_seconds: f64,
+    /// Render time over audio time. Below 1 renders faster than real time.
+    realtime_factor: f64,
+  },
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+  #[serde(flatten)]
+  event: &'a Event,
+  elapsed: f64,
+}
+
+struct Reporter {
+  writer: Mutex<Box<dyn Write + Send>>,
+  start: Instant,
+}
+
+/// Handle to the events of one render.
+#[derive(Clone)]
+pub struct Progress {
+  reporter: Arc<Reporter>,
+  /// Notes rendered by the current part
+  notes: Arc<AtomicUsize>,
+  /// Set inside a part, where nested renders count notes but do not report parts of their own
+  nested: bool,
+}
+
+impl Progress {
+  pub fn create(sink: &ProgressSink) -> Result<Progress, RaudioError> {
+    let writer: Box<dyn Write + Send> = match sink {
+      ProgressSink::Stderr => Box::new(io::stderr()),
+      ProgressSink::File(path) => {
+        let open = || -> io::Result<File> {
+          crate::files::try_with_dir(path)?;
+          File::create(path)
+        };
+        Box::new(BufWriter::new(open().map_err(|e| RaudioError::Io(path.clone(), e))?))
+      }
+    };
+    Ok(Progress {
+      reporter: Arc::new(Reporter {
+        writer: Mutex::new(writer),
+        start: Instant::now(),
+      }),
+      notes: Arc::new(AtomicUsize::new(0)),
+      nested: false,
+    })
+  }
+
+  /// Write an event. Progress is best effort, so a failed write does not stop the render.
+  pub fn emit(&self, event: Event) {
+    if self.nested {
+      return;
+    }
+    let line = Line {
+      event: &event,
+      elapsed: self.reporter.start.elapsed().as_secs_f64(),
+    };
+    if let (Ok(json), Ok(mut writer)) = (serde_json::to_string(&line), self.reporter.writer.lock()) {
+      let _ = writeln!(writer, "{}", json).and_then(|_| writer.flush());
+    }
+  }
+
+  /// Handle for rendering one part, counting its notes from zero.
+  pub fn for_part(&self) -> Progress {
+    if self.nested {
+      return self.clone();
+    }
+    Progress {
+      reporter: self.reporter.clone(),
+      notes: Arc::new(AtomicUsize::new(0)),
+      nested: false,
+    }
+  }
+
+  /// Handle for a render nested inside of the current part.
+  pub fn nested(&self) -> Progress {
+    Progress {
+      nested: true,
+      ..self.clone()
+    }
+  }
+
+  /// Notes rendered since `for_part`.
+  pub fn notes(&self) -> usize {
+    self.notes.load(Ordering::Relaxed)
+  }
+
+  /// Time since the render started.
+  pub fn elapsed(&self) -> Duration {
+    self.reporter.start.elapsed()
+  }
+}
+
+thread_local! {
+  static CURRENT: RefCell<Option<Progress>> = const { RefCell::new(None) };
+}
+
+/// Report the events of renders on the current thread to `progress`. `None` reports nothing.
+pub fn set_current(progress: Option<Progress>) {
+  CURRENT.with(|cell| *cell.borrow_mut() = progress)
+}
+
+/// The reporter of the current thread, if any.
+pub fn current() -> Option<Progress> {
+  CURRENT.with(|cell| cell.borrow().clone())
+}
+
+/// Report an event to the reporter of the current thread, if any.
+pub fn emit(event: impl FnOnce() -> Event) {
+  if let Some(progress) = current() {
+    progress.emit(event())
+  }
+}
+
+/// Count a synthesized note for the current part.
+pub fn note_rendered() {
+  CURRENT.with(|cell| {
+    if let Some(progress) = cell.borrow().as_ref() {
+      progress.notes.fetch_add(1, Ordering::Relaxed);
+    }
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_events_are_json_lines() {
+    let path = "dev-audio/progress/events.jsonl";
+    let progress = Progress::create(&ProgressSink::File(path.to_string())).unwrap();
+    set_current(Some(progress.for_part()));
+    note_rendered();
+    note_rendered();
+    let part = current().unwrap();
+    part.emit(Event::PartFinish {
+      part: 3,
+      notes: part.notes(),
+      synthesis_seconds: 0.5,
+      stem_reverb_seconds: 0.25,
+    });
+    part.emit(Event::EmptyStem { part: 3, stem: 1 });
+    // nested renders count notes but report nothing
+    part.nested().emit(Event::PartCached { part: 0 });
+    set_current(None);
+    drop((progress, part));
+
+    let contents = std::fs::read_to_string(path).unwrap();
+    let lines: Vec<serde_json::Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(2, lines.len());
+    assert_eq!("part_finish", lines[0]["event"]);
+    assert_eq!(3, lines[0]["part"]);
+    assert_eq!(2, lines[0]["notes"]);
+    assert!(lines[0]["elapsed"].as_f64().unwrap() >= 0f64);
+    assert_eq!("empty_stem", lines[1]["event"]);
+    assert_eq!(1, lines[1]["stem"]);
+  }
+}
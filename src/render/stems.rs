@@ -0,0 +1,148 @@
+/// Stem export for `render_score`.
+///
+/// Each part of a score is written to its own stereo WAV file after its stem reverb and pan position are applied,
+/// but before the group reverb. Stems are written at the gain the mix gives them to prevent clipping,
+/// so summing the stems gives the mix the group reverb is applied to.
+/// A JSON manifest next to the stems describes each part so a render can be remixed elsewhere.
+use serde::Serialize;
+use std::fs;
+use std::io;
+
+use crate::analysis::volume::rms;
+use crate::error::RaudioError;
+use crate::render::engrave::{self, WavFormat};
+use crate::render::stream::{self, Spill};
+use crate::synth::StereoBuffer;
+use crate::types::render::{DruidicScore, TempoPoint};
+use crate::types::timbre::{Arf, ClientPositioning, Role};
+
+/// Description of one written stem.
+#[derive(Debug, Serialize)]
+pub struct StemEntry {
+  /// Index of the part in the score
+  pub part: usize,
+  /// File name of the stem, relative to the manifest
+  pub file: String,
+  pub role: Role,
+  pub arf: Arf,
+  pub positioning: ClientPositioning,
+  /// Length of the stem in samples per channel
+  pub samples: usize,
+  pub seconds: f32,
+  /// Largest absolute sample value of either channel
+  pub peak: f32,
+  /// RMS of both channels together
+  pub rms: f32,
+}
+
+/// Contents of the `<asset-name>.stems.json` manifest.
+#[derive(Debug, Serialize)]
+pub struct StemManifest {
+  pub asset_name: String,
+  /// File name of the mixdown, relative to the manifest
+  pub mixdown: String,
+  pub sample_rate: usize,
+  /// Gain applied to every stem, the level each part has in the mix
+  pub mix_gain: f32,
+  pub cps: f32,
+  /// Tempo changes of the score after it starts at `cps`
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub tempo: Vec<TempoPoint>,
+  pub stems: Vec<StemEntry>,
+}
+
+/// File name of the stem for part `j`.
+pub fn stem_name(asset_name: &str, j: usize, role: &Role) -> String {
+  format!("{}.stem-{}-{}.wav", asset_name, j, role)
+}
+
+/// Path of the manifest for `asset_name` in `out_dir`.
+pub fn manifest_path(out_dir: &str, asset_name: &str) -> String {
+  format!("{}/{}.stems.json", out_dir, asset_name)
+}
+
+/// Measure a stem for its manifest entry.
+fn entry(
+  j: usize, file: String, (positioning, arf): (&ClientPositioning, &Arf), signal: &StereoBuffer, sample_rate: usize,
+) -> StemEntry {
+  let (left, right) = signal;
+  let samples = left.len().max(right.len());
+  let both: Vec<f32> = left.iter().chain(right.iter()).copied().collect();
+  StemEntry {
+    part: j,
+    file,
+    role: arf.role,
+    arf: *arf,
+    positioning: positioning.clone(),
+    samples,
+    seconds: samples as f32 / sample_rate as f32,
+    peak: both.iter().fold(0f32, |max, s| max.max(s.abs())),
+    rms: rms(&both),
+  }
+}
+
+/// Write one WAV file per part of `score` and a manifest describing them into `out_dir`.
+/// `parts` holds the rendered signal of each part, in the order of `score.parts`,
+/// and `mix_gain` the gain they are mixed at. See `stereo::mix_gain`.
+/// Returns the path of the manifest.
+pub fn write_stems(
+  score: &DruidicScore, parts: &[StereoBuffer], mix_gain: f32, sample_rate: usize, format: WavFormat, out_dir: &str,
+  asset_name: &str,
+) -> Result<String, RaudioError> {
+  let mut stems: Vec<StemEntry> = Vec::with_capacity(parts.len());
+  for (j, ((positioning, arf, _), (left, right))) in score.parts.iter().zip(parts.iter()).enumerate() {
+    let file = stem_name(asset_name, j, &arf.role);
+    let scaled: StereoBuffer =
+      (left.iter().map(|s| mix_gain * s).collect(), right.iter().map(|s| mix_gain * s).collect());
+    stems.push(entry(j, file.clone(), (positioning, arf), &scaled, sample_rate));
+    let (left, right) = scaled;
+    engrave::try_write_audio_as(sample_rate, vec![left, right], &format!("{}/{}", out_dir, file), format)?;
+  }
+
+  write_manifest(score, stems, mix_gain, sample_rate, out_dir, asset_name)
+}
+
+/// Streaming counterpart of `write_stems`, for the parts from `stream::render_parts`.
+/// Only frames `[start, end)` of each part are written when a range is given.
+pub fn write_stem_spills(
+  score: &DruidicScore, parts: Vec<Spill>, mix_gain: f32, range: Option<(usize, usize)>, sample_rate: usize,
+  format: WavFormat, out_dir: &str, asset_name: &str,
+) -> Result<String, RaudioError> {
+  let mut stems: Vec<StemEntry> = Vec::with_capacity(parts.len());
+  for (j, ((positioning, arf, _), mut part)) in score.parts.iter().zip(parts).enumerate() {
+    let file = stem_name(asset_name, j, &arf.role);
+    part.scale(mix_gain);
+    let written = stream::write(&part, range, sample_rate, &format!("{}/{}", out_dir, file), format)?;
+    let samples = written.frames;
+    stems.push(StemEntry {
+      part: j,
+      file,
+      role: arf.role,
+      arf: *arf,
+      positioning: positioning.clone(),
+      samples,
+      seconds: samples as f32 / sample_rate as f32,
+      peak: written.peak,
+      rms: if samples == 0 { 0f32 } else { (written.sum_squares / (2 * samples) as f64).sqrt() as f32 },
+    });
+  }
+  write_manifest(score, stems, mix_gain, sample_rate, out_dir, asset_name)
+}
+
+fn write_manifest(
+  score: &DruidicScore, stems: Vec<StemEntry>, mix_gain: f32, sample_rate: usize, out_dir: &str, asset_name: &str,
+) -> Result<String, RaudioError> {
+  let manifest = StemManifest {
+    asset_name: asset_name.to_string(),
+    mixdown: format!("{}.wav", asset_name),
+    sample_rate,
+    mix_gain,
+    cps: score.conf.cps,
+    tempo: score.tempo.clone(),
+    stems,
+  };
+  let path = manifest_path(out_dir, asset_name);
+  let json = serde_json::to_string_pretty(&manifest).map_err(|e| RaudioError::Io(path.clone(), io::Error::from(e)))?;
+  fs::write(&path, json).map_err(|e| RaudioError::Io(path.clone(), e))?;
+  Ok(path)
+}
@@ -0,0 +1,289 @@
+/// Two-channel buffer operations for the render pipeline.
+///
+/// Synthesis happens in mono; a signal becomes stereo when a panned delay or a part pan position is applied.
+/// Buffers with identical channels are processed once and copied, so centered material costs the same as before.
+use crate::analysis::delay::{self, DelayParams, StereoField};
+use crate::render::levels::{self, Peaks, Stage};
+use crate::synth::{pi_4, SampleBuffer, StereoBuffer};
+use crate::time;
+
+/// Copy a mono signal into both channels.
+pub fn from_mono(sig: SampleBuffer) -> StereoBuffer {
+  (sig.clone(), sig)
+}
+
+/// Fold a stereo signal into a single channel.
+pub fn downmix((left, right): &StereoBuffer) -> SampleBuffer {
+  let n = left.len().max(right.len());
+  (0..n).map(|i| 0.5f32 * (left.get(i).unwrap_or(&0f32) + right.get(i).unwrap_or(&0f32))).collect()
+}
+
+/// Number of samples in the longest channel.
+pub fn len((left, right): &StereoBuffer) -> usize {
+  left.len().max(right.len())
+}
+
+/// Zero-filled stereo buffer.
+pub fn silence(n_samples: usize) -> StereoBuffer {
+  (vec![0f32; n_samples], vec![0f32; n_samples])
+}
+
+/// Apply a mono process to each channel.
+/// When both channels carry the same signal, `f` is called once.
+pub fn map_channels<F>((left, right): &StereoBuffer, f: F) -> StereoBuffer
+where
+  F: Fn(&SampleBuffer) -> SampleBuffer,
+{
+  let l = f(left);
+  let r = if left == right { l.clone() } else { f(right) };
+  (l, r)
+}
+
+/// Like `map_channels` for an `f` that can fail.
+pub fn try_map_channels<F, E>((left, right): &StereoBuffer, f: F) -> Result<StereoBuffer, E>
+where
+  F: Fn(&SampleBuffer) -> Result<SampleBuffer, E>,
+{
+  let l = f(left)?;
+  let r = if left == right { l.clone() } else { f(right)? };
+  Ok((l, r))
+}
+
+/// Scale both channels by one factor so the stereo signal has the target RMS.
+pub fn rescale_amplitude(target_rms: f32, (left, right): &StereoBuffer) -> StereoBuffer {
+  let n = (left.len() + right.len()) as f32;
+  if n == 0f32 {
+    return (left.clone(), right.clone());
+  }
+  let sum_squares: f32 = left.iter().chain(right.iter()).map(|x| x * x).sum();
+  let scale_factor = target_rms / (sum_squares / n).sqrt();
+  (
+    left.iter().map(|x| x * scale_factor).collect(),
+    right.iter().map(|x| x * scale_factor).collect(),
+  )
+}
+
+/// Given a list of stereo signals whose tails may overlap with the head of the next signal,
+/// place each one at the onset of its note and sum them, scaled by its gain. See `render::LinePlan::cues`.
+/// Stereo counterpart of `render::overlapping_at`.
+pub fn overlapping(base_len: usize, cues: &[(usize, f32)], samples: &[StereoBuffer]) -> StereoBuffer {
+  let mut signal = silence(base_len);
+  // cue each note at the onset of its duration, not after the wet samples of the one before it
+  for (i, &(cue, gain)) in cues.iter().enumerate() {
+    add_at(&mut signal, &samples[i].0, cue, (gain, 0f32));
+    add_at(&mut signal, &samples[i].1, cue, (0f32, gain));
+  }
+  signal
+}
+
+/// Gains for a pan position in [-1, 1] (left to right) using a constant power pan law.
+/// The law is scaled by √2 so a centered pan has unity gain in both channels, like an unpanned part.
+/// The power of both channels together is the same at every position, so a hard-panned part is
+/// deliberately 3 dB louder in its channel than in either channel of a centered part.
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+  let theta = (pan.clamp(-1f32, 1f32) + 1f32) * pi_4;
+  (theta.cos() * 2f32.sqrt(), theta.sin() * 2f32.sqrt())
+}
+
+/// Position a stereo signal in the mix.
+pub fn pan((mut left, mut right): StereoBuffer, pan: f32) -> StereoBuffer {
+  if pan == 0f32 {
+    return (left, right);
+  }
+  let (gl, gr) = pan_gains(pan);
+  left.iter_mut().for_each(|s| *s *= gl);
+  right.iter_mut().for_each(|s| *s *= gr);
+  (left, right)
+}
+
+/// Add a mono signal into a stereo buffer, starting at `offset`, with per-channel gains.
+pub fn add_at(target: &mut StereoBuffer, source: &SampleBuffer, offset: usize, (gl, gr): (f32, f32)) {
+  let needed = offset + source.len();
+  if target.0.len() < needed {
+    target.0.resize(needed, 0f32);
+  }
+  if target.1.len() < needed {
+    target.1.resize(needed, 0f32);
+  }
+  for (i, &s) in source.iter().enumerate() {
+    target.0[offset + i] += gl * s;
+    target.1[offset + i] += gr * s;
+  }
+}
+
+/// Apply a stack of delays to a stereo signal, positioning each echo with the delay's `StereoField`.
+///
+/// A `Mono` delay is applied to each channel with `delay::apply_mono`, echoes above `clip` clipped,
+/// exactly as unpanned delays have always sounded.
+/// Other delays leave the dry signal as is and extend the buffer to hold the echo tail.
+pub fn apply_delays(sig: StereoBuffer, delays: &[DelayParams], clip: Option<f32>) -> StereoBuffer {
+  delays.iter().fold(sig, |(mut left, mut right), params| {
+    if let StereoField::Mono = params.pan {
+      return map_channels(&(left, right), |channel| {
+        let mut channel = channel.clone();
+        delay::apply_mono(&mut channel, params, clip);
+        channel
+      });
+    }
+    if delay::is_passthrough(params) {
+      return (left, right);
+    }
+    let samples_per_echo: usize = time::samples_from_dur(1f32, params.len_seconds);
+    let n_echoes = params.n_echoes.max(1);
+    let dry = (left.clone(), right.clone());
+    left.resize(dry.0.len() + samples_per_echo * n_echoes, 0f32);
+    right.resize(dry.1.len() + samples_per_echo * n_echoes, 0f32);
+    for replica_n in 1..=n_echoes {
+      let (gl, gr) = params.pan.gains(replica_n);
+      let offset = samples_per_echo * replica_n;
+      let gain = delay::gain(offset, replica_n, params);
+      for (j, v) in dry.0.iter().enumerate() {
+        left[j + offset] += gl * gain * v;
+      }
+      for (j, v) in dry.1.iter().enumerate() {
+        right[j + offset] += gr * gain * v;
+      }
+    }
+    (left, right)
+  })
+}
+
+/// Remove the trailing silence shared by both channels.
+pub fn trim_zeros((left, right): &mut StereoBuffer) {
+  let last = |sig: &SampleBuffer| sig.iter().rposition(|v| v.abs() > 0.001);
+  match (last(left), last(right)) {
+    (None, None) => {}
+    (l, r) => {
+      let n = l.unwrap_or(0).max(r.unwrap_or(0)) + 1;
+      left.truncate(n);
+      right.truncate(n);
+    }
+  }
+}
+
+fn peak(sig: &SampleBuffer) -> f32 {
+  sig.iter().map(|s| s.abs()).fold(0f32, f32::max)
+}
+
+/// Sum of `buffers` scaled by the gain that keeps each of them from clipping.
+fn pre_scaled_sum(buffers: &[StereoBuffer], peaks: &mut Peaks) -> (StereoBuffer, f32) {
+  let n = buffers.iter().map(len).max().unwrap_or(0);
+  let global_max = peaks.next(|| buffers.iter().map(|(l, r)| peak(l).max(peak(r))).fold(0f32, f32::max));
+  let pre_scale = if global_max > 1f32 { 1f32 / global_max } else { 1f32 };
+
+  let mut mixed = silence(n);
+  for (l, r) in buffers {
+    for (i, s) in l.iter().enumerate() {
+      mixed.0[i] += pre_scale * s;
+    }
+    for (i, s) in r.iter().enumerate() {
+      mixed.1[i] += pre_scale * s;
+    }
+  }
+  (mixed, pre_scale)
+}
+
+/// Sum stereo buffers of any length into one, scaling both channels together when the sum would clip.
+/// For buffers with identical channels this produces the same result as `realize::mix_buffers` in each channel.
+/// The mix is metered as the mix of `stage` in the current task, see `render::levels`.
+pub fn pad_and_mix_buffers(buffers: Vec<StereoBuffer>, stage: Stage) -> Result<StereoBuffer, &'static str> {
+  if buffers.is_empty() {
+    return Ok((Vec::new(), Vec::new()));
+  }
+  let mut peaks = levels::peaks(stage);
+  let (mut mixed, _) = pre_scaled_sum(&buffers, &mut peaks);
+  let sum_max = peaks.next(|| peak(&mixed.0).max(peak(&mixed.1)));
+  if sum_max > 1f32 {
+    mixed.0.iter_mut().for_each(|s| *s /= sum_max);
+    mixed.1.iter_mut().for_each(|s| *s /= sum_max);
+  }
+  Ok(mixed)
+}
+
+/// The gain `pad_and_mix_buffers` gives every one of `buffers` in their mix of `stage`.
+pub fn mix_gain(buffers: &[StereoBuffer], stage: Stage) -> f32 {
+  let mut peaks = levels::peaks(stage);
+  let (mixed, pre_scale) = pre_scaled_sum(buffers, &mut peaks);
+  pre_scale / peaks.next(|| peak(&mixed.0).max(peak(&mixed.1))).max(1f32)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::render::realize;
+
+  #[test]
+  fn test_mix_matches_mono_mix_for_centered_buffers() {
+    let a = vec![0.5, -1.5, 0.25];
+    let b = vec![0.75, 0.5];
+    let mono = realize::mix_buffers(&mut vec![a.clone(), vec![0.75, 0.5, 0.0]]).unwrap();
+    let (left, right) = pad_and_mix_buffers(vec![from_mono(a), from_mono(b)], Stage::Parts).unwrap();
+    assert_eq!(mono, left);
+    assert_eq!(mono, right);
+  }
+
+  #[test]
+  fn test_mix_gain_scales_buffers_to_their_mix() {
+    let a = (vec![0.5, -1.5, 0.25], vec![0.5, 1.0, 0.0]);
+    let b = (vec![0.75, -0.5], vec![1.25, 0.5]);
+    let gain = mix_gain(&[a.clone(), b.clone()], Stage::Parts);
+    let (left, right) = pad_and_mix_buffers(vec![a.clone(), b.clone()], Stage::Parts).unwrap();
+    for i in 0..3 {
+      let sum = |c: fn(&StereoBuffer) -> &SampleBuffer| gain * (c(&a)[i] + c(&b).get(i).unwrap_or(&0f32));
+      assert!((left[i] - sum(|s| &s.0)).abs() < 1e-6);
+      assert!((right[i] - sum(|s| &s.1)).abs() < 1e-6);
+    }
+  }
+
+  #[test]
+  fn test_pan_center_is_unity() {
+    let (gl, gr) = pan_gains(0f32);
+    assert!((gl - 1f32).abs() < 1e-6 && (gr - 1f32).abs() < 1e-6);
+    let (gl, gr) = pan_gains(-1f32);
+    assert!(gl > 1f32 && gr.abs() < 1e-6, "Hard left must silence the right channel");
+    for pan in [-1f32, -0.3, 0.5, 1.0] {
+      let (gl, gr) = pan_gains(pan);
+      assert!((gl * gl + gr * gr - 2f32).abs() < 1e-5, "Power must not depend on the pan position");
+    }
+  }
+
+  #[test]
+  fn test_apply_delays_pans_echoes() {
+    let mut impulse = vec![0f32; 48000];
+    impulse[0] = 1f32;
+    let params = DelayParams {
+      len_seconds: 0.1,
+      len_cycles: None,
+      n_echoes: 2,
+      gain: 0.5,
+      mix: 1f32,
+      pan: StereoField::LeftRight(1f32, 1f32),
+    };
+    let (left, right) = apply_delays(from_mono(impulse), &[params], None);
+    let echo = time::samples_from_dur(1f32, 0.1);
+    assert_eq!(1f32, left[0]);
+    assert_eq!(1f32, right[0]);
+    assert!(left[echo] > 0f32 && right[echo] == 0f32, "First echo must be on the left");
+    assert!(left[2 * echo] == 0f32 && right[2 * echo] > 0f32, "Second echo must be on the right");
+  }
+
+  #[test]
+  fn test_apply_delays_keeps_mono_delays() {
+    let signal: SampleBuffer = (0..2000).map(|i| 1.5f32 * (i as f32 * 0.01).sin()).collect();
+    let params = DelayParams {
+      len_seconds: 0.002,
+      len_cycles: None,
+      n_echoes: 2,
+      gain: 0.6,
+      mix: 0.5,
+      pan: StereoField::Mono,
+    };
+    for clip in [None, Some(1f32)] {
+      let mut expected = signal.clone();
+      delay::apply_mono(&mut expected, &params, clip);
+      let (left, right) = apply_delays(from_mono(signal.clone()), &[params], clip);
+      assert_eq!(expected, left);
+      assert_eq!(expected, right);
+    }
+  }
+}
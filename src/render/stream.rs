@@ -0,0 +1,903 @@
+/// Streaming renders with memory independent of the length of the score.
+///
+/// The batch renderer holds every line, channel and part as a whole buffer and convolves whole signals at once.
+/// Here each stage is a `Source` pulled `BLOCK` frames at a time: notes are synthesized into a rolling buffer
+/// as their onset comes up, delays keep only their longest echo of history and reverbs use partitioned convolution.
+///
+/// The renderer scales lines, channels and parts by their loudest sample to prevent clipping, which is only known
+/// once the whole signal exists. Those signals are spilled block by block to temporary files along with their peak,
+/// and read back with the batch renderer's gain. Memory is bounded by the longest note and impulse response;
+/// disk use grows with the length of the score.
+///
+/// A streaming render sounds the same as `render_parts_stereo` and `mix_parts_stereo` up to rounding.
+use rayon::prelude::*;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use super::{FmVoice, Playing, ResoVoice, SampleVoice, GROUP_REVERB_SEED_KEY, STEM_REVERB_SEED_KEY};
+use crate::analysis::delay::{self, DelayParams};
+use crate::analysis::freq::Butterworth;
+use crate::error::RaudioError;
+use crate::render::context::RenderContext;
+use crate::render::engrave::{WavFormat, WavStream};
+use crate::render::levels::{self, Stage};
+use crate::render::progress::{Event, Progress};
+use crate::render::{stereo, Renderable2};
+use crate::reverb::convolution::{Partitioned, PartitionedState, ReverbParams};
+use crate::seed;
+use crate::synth::{NFf, StereoBuffer, SR};
+use crate::time;
+use crate::types::render::{Conf, DrumSample, Stem2, StemFM};
+use crate::types::synthesis::Note;
+use crate::types::timbre::Arf;
+
+/// Frames pulled from a source at a time.
+pub const BLOCK: usize = 4096;
+
+/// Samples below this level at the end of a signal are dropped, as by `stereo::trim_zeros`.
+const AUDIBLE: f32 = 0.001;
+
+/// A stereo signal produced a block at a time.
+pub trait Source: Send {
+  /// Write the next frames into `left` and `right`, which have the same length.
+  /// Returns the number of frames written. Fewer than requested means the signal has ended.
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError>;
+}
+
+type Boxed<'a> = Box<dyn Source + 'a>;
+
+struct Silence {
+  remaining: usize,
+}
+
+impl Source for Silence {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let n = left.len().min(self.remaining);
+    left[..n].fill(0f32);
+    right[..n].fill(0f32);
+    self.remaining -= n;
+    Ok(n)
+  }
+}
+
+/// The notes of one line, placed at their onsets like `stereo::overlapping`.
+/// A note is rendered when the block containing its onset is pulled.
+struct Notes<F> {
+  /// Onset and gain of each note, see `LinePlan::cues`
+  cues: Vec<(usize, f32)>,
+  render: F,
+  next: usize,
+  /// Frames of the line before effects extend it
+  base_len: usize,
+  /// Index of the first frame in `pending`
+  cursor: usize,
+  /// Rendered frames not yet pulled
+  pending: StereoBuffer,
+}
+
+impl<F> Source for Notes<F>
+where
+  F: FnMut(usize) -> Result<StereoBuffer, RaudioError> + Send,
+{
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let n = left.len();
+    while self.next < self.cues.len() && self.cues[self.next].0 < self.cursor + n {
+      let (l, r) = (self.render)(self.next)?;
+      let (cue, gain) = self.cues[self.next];
+      let offset = cue - self.cursor;
+      stereo::add_at(&mut self.pending, &l, offset, (gain, 0f32));
+      stereo::add_at(&mut self.pending, &r, offset, (0f32, gain));
+      self.next += 1;
+    }
+    let remaining = if self.next < self.cues.len() {
+      n
+    } else {
+      self.base_len.saturating_sub(self.cursor).max(stereo::len(&self.pending))
+    };
+    let k = n.min(remaining);
+    let available = k.min(self.pending.0.len()).min(self.pending.1.len());
+    left[..available].copy_from_slice(&self.pending.0[..available]);
+    right[..available].copy_from_slice(&self.pending.1[..available]);
+    left[available..k].fill(0f32);
+    right[available..k].fill(0f32);
+    self.pending.0.drain(..k.min(self.pending.0.len()));
+    self.pending.1.drain(..k.min(self.pending.1.len()));
+    self.cursor += k;
+    Ok(k)
+  }
+}
+
+/// Sum of sources, each scaled by a gain.
+struct Sum<'a> {
+  inputs: Vec<(Boxed<'a>, f32)>,
+  scratch: StereoBuffer,
+}
+
+impl<'a> Source for Sum<'a> {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let n = left.len();
+    left.fill(0f32);
+    right.fill(0f32);
+    self.scratch.0.resize(n, 0f32);
+    self.scratch.1.resize(n, 0f32);
+    let mut written = 0;
+    for (input, gain) in self.inputs.iter_mut() {
+      let k = input.pull(&mut self.scratch.0[..n], &mut self.scratch.1[..n])?;
+      for i in 0..k {
+        left[i] += *gain * self.scratch.0[i];
+        right[i] += *gain * self.scratch.1[i];
+      }
+      written = written.max(k);
+    }
+    Ok(written)
+  }
+}
+
+/// Streaming counterpart of one `Mono` step of `stereo::apply_delays`, see `delay::apply_mono`.
+/// Each replica is a feedback stage over the output of the stage before it, so the output is as long as the input.
+struct MonoDelay<'a> {
+  input: Boxed<'a>,
+  params: DelayParams,
+  clip: Option<f32>,
+  /// Frames between a replica and the signal it echoes, by replica
+  offsets: Vec<usize>,
+  /// The most recent output frames of each replica stage, as rings of `offsets` frames
+  history: Vec<StereoBuffer>,
+  position: usize,
+}
+
+impl<'a> MonoDelay<'a> {
+  fn new(input: Boxed<'a>, params: &DelayParams, clip: Option<f32>) -> Self {
+    let samples_per_echo: usize = time::samples_from_dur(1f32, params.len_seconds);
+    let offsets: Vec<usize> = (0..=params.n_echoes.max(1)).map(|replica_n| samples_per_echo * replica_n).collect();
+    let history = offsets.iter().map(|&offset| stereo::silence(offset)).collect();
+    MonoDelay {
+      input,
+      params: *params,
+      clip,
+      offsets,
+      history,
+      position: 0,
+    }
+  }
+}
+
+impl<'a> Source for MonoDelay<'a> {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let k = self.input.pull(left, right)?;
+    for i in 0..k {
+      let n = self.position;
+      for (replica_n, &offset) in self.offsets.iter().enumerate() {
+        let ring = &mut self.history[replica_n];
+        for (sig, past) in [(&mut left[i], &mut ring.0), (&mut right[i], &mut ring.1)] {
+          if offset == 0 {
+            *sig += delay::echo(*sig, delay::gain(n, replica_n, &self.params), self.clip);
+          } else {
+            if n >= offset {
+              let v = past[n % offset];
+              *sig += delay::echo(v, delay::gain(n - offset, replica_n, &self.params), self.clip);
+            }
+            past[n % offset] = *sig;
+          }
+        }
+      }
+      self.position += 1;
+    }
+    Ok(k)
+  }
+}
+
+/// Streaming counterpart of one step of `stereo::apply_delays` with a panned `StereoField`.
+struct Delay<'a> {
+  input: Boxed<'a>,
+  /// (frames behind, left gain, right gain) of each echo
+  taps: Vec<(usize, f32, f32)>,
+  /// The most recent input frames, as a ring
+  history: StereoBuffer,
+  position: usize,
+  /// Frames of echo left to write after the input ends
+  tail: Option<usize>,
+}
+
+impl<'a> Delay<'a> {
+  fn new(input: Boxed<'a>, params: &DelayParams) -> Self {
+    let samples_per_echo: usize = time::samples_from_dur(1f32, params.len_seconds);
+    let n_echoes = params.n_echoes.max(1);
+    let taps: Vec<(usize, f32, f32)> = (1..=n_echoes)
+      .map(|replica_n| {
+        let (gl, gr) = params.pan.gains(replica_n);
+        let offset = samples_per_echo * replica_n;
+        let gain = delay::gain(offset, replica_n, params);
+        (offset, gl * gain, gr * gain)
+      })
+      .collect();
+    let size = samples_per_echo * n_echoes + 1;
+    Delay {
+      input,
+      taps,
+      history: stereo::silence(size),
+      position: 0,
+      tail: None,
+    }
+  }
+}
+
+impl<'a> Source for Delay<'a> {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let n = left.len();
+    let k = match self.tail {
+      None => {
+        let k = self.input.pull(left, right)?;
+        if k < n {
+          let size = self.history.0.len();
+          let extra = (size - 1).min(n - k);
+          left[k..k + extra].fill(0f32);
+          right[k..k + extra].fill(0f32);
+          self.tail = Some(size - 1 - extra);
+          k + extra
+        } else {
+          k
+        }
+      }
+      Some(remaining) => {
+        let k = remaining.min(n);
+        left[..k].fill(0f32);
+        right[..k].fill(0f32);
+        self.tail = Some(remaining - k);
+        k
+      }
+    };
+    let size = self.history.0.len();
+    for i in 0..k {
+      self.history.0[self.position] = left[i];
+      self.history.1[self.position] = right[i];
+      for &(offset, gl, gr) in &self.taps {
+        let at = (self.position + size - offset) % size;
+        left[i] += gl * self.history.0[at];
+        right[i] += gr * self.history.1[at];
+      }
+      self.position = (self.position + 1) % size;
+    }
+    Ok(k)
+  }
+}
+
+/// Streaming counterpart of `convolution::of_stereo`.
+/// Like the batch reverb, the output is followed by silence as long as the impulse response.
+struct Reverb<'a> {
+  input: Boxed<'a>,
+  reverb: Partitioned,
+  states: (PartitionedState, PartitionedState),
+  dry: StereoBuffer,
+  tail: Option<usize>,
+}
+
+impl<'a> Reverb<'a> {
+  fn new(input: Boxed<'a>, params: &ReverbParams) -> Self {
+    let reverb = Partitioned::new(params, BLOCK);
+    let states = (reverb.state(), reverb.state());
+    Reverb {
+      input,
+      reverb,
+      states,
+      dry: stereo::silence(BLOCK),
+      tail: None,
+    }
+  }
+}
+
+impl<'a> Source for Reverb<'a> {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let n = left.len();
+    if let Some(remaining) = self.tail {
+      let k = remaining.min(n);
+      left[..k].fill(0f32);
+      right[..k].fill(0f32);
+      self.tail = Some(remaining - k);
+      return Ok(k);
+    }
+    self.dry.0.resize(n, 0f32);
+    self.dry.1.resize(n, 0f32);
+    let k = self.input.pull(&mut self.dry.0, &mut self.dry.1)?;
+    self.reverb.process(&mut self.states.0, &self.dry.0[..k], &mut left[..k]);
+    self.reverb.process(&mut self.states.1, &self.dry.1[..k], &mut right[..k]);
+    if k < n {
+      let silence = self.reverb.impulse_len();
+      let extra = silence.min(n - k);
+      left[k..k + extra].fill(0f32);
+      right[k..k + extra].fill(0f32);
+      self.tail = Some(silence - extra);
+      return Ok(k + extra);
+    }
+    Ok(k)
+  }
+}
+
+/// Streaming counterpart of the lowpass in `finalize_signal`.
+struct Lowpass<'a> {
+  input: Boxed<'a>,
+  filters: (Butterworth, Butterworth),
+}
+
+impl<'a> Source for Lowpass<'a> {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let k = self.input.pull(left, right)?;
+    left[..k].iter_mut().for_each(|s| *s = self.filters.0.process(*s));
+    right[..k].iter_mut().for_each(|s| *s = self.filters.1.process(*s));
+    Ok(k)
+  }
+}
+
+struct Pan<'a> {
+  input: Boxed<'a>,
+  gains: (f32, f32),
+}
+
+impl<'a> Source for Pan<'a> {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let k = self.input.pull(left, right)?;
+    left[..k].iter_mut().for_each(|s| *s *= self.gains.0);
+    right[..k].iter_mut().for_each(|s| *s *= self.gains.1);
+    Ok(k)
+  }
+}
+
+/// A signal written to a temporary file, with the measurements the batch renderer takes of whole buffers.
+/// The file is removed when the spill is dropped.
+pub struct Spill {
+  path: PathBuf,
+  /// Length in frames
+  pub frames: usize,
+  /// Largest absolute sample of either channel, before `gain`
+  peak: f32,
+  /// Index of the last frame louder than `AUDIBLE`
+  last_audible: Option<usize>,
+  /// Applied to every sample read back
+  gain: f32,
+}
+
+static SPILLS: AtomicUsize = AtomicUsize::new(0);
+
+impl Spill {
+  /// Write all of `source` to a temporary file.
+  pub fn from_source(source: &mut dyn Source) -> Result<Spill, RaudioError> {
+    let path = std::env::temp_dir().join(format!(
+      "raudio-{}-{}.spill",
+      std::process::id(),
+      SPILLS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let io_error = |e: io::Error| RaudioError::Io(path.display().to_string(), e);
+    let mut spill = Spill {
+      path: path.clone(),
+      frames: 0,
+      peak: 0f32,
+      last_audible: None,
+      gain: 1f32,
+    };
+    let mut writer = BufWriter::new(File::create(&path).map_err(io_error)?);
+    let (mut left, mut right) = stereo::silence(BLOCK);
+    loop {
+      let k = source.pull(&mut left, &mut right)?;
+      let mut bytes: Vec<u8> = Vec::with_capacity(8 * k);
+      for i in 0..k {
+        let loudest = left[i].abs().max(right[i].abs());
+        spill.peak = spill.peak.max(loudest);
+        if loudest > AUDIBLE {
+          spill.last_audible = Some(spill.frames + i);
+        }
+        bytes.extend_from_slice(&left[i].to_le_bytes());
+        bytes.extend_from_slice(&right[i].to_le_bytes());
+      }
+      writer.write_all(&bytes).map_err(io_error)?;
+      spill.frames += k;
+      if k < BLOCK {
+        break;
+      }
+    }
+    writer.flush().map_err(io_error)?;
+    Ok(spill)
+  }
+
+  /// Scale every sample read back by `gain`.
+  pub fn scale(&mut self, gain: f32) {
+    self.gain *= gain;
+  }
+
+  /// Largest absolute sample as read back.
+  pub fn peak(&self) -> f32 {
+    self.peak * self.gain
+  }
+
+  /// Length without the trailing silence, as by `stereo::trim_zeros`.
+  pub fn audible_frames(&self) -> usize {
+    self.last_audible.map_or(self.frames, |last| last + 1)
+  }
+
+  /// Read the signal back from the start.
+  pub fn reader(&self) -> Result<Box<dyn Source>, RaudioError> {
+    let file = File::open(&self.path).map_err(|e| RaudioError::Io(self.path.display().to_string(), e))?;
+    Ok(Box::new(SpillReader {
+      path: self.path.display().to_string(),
+      reader: BufReader::new(file),
+      remaining: self.frames,
+      gain: self.gain,
+      bytes: vec![],
+    }))
+  }
+}
+
+impl Drop for Spill {
+  fn drop(&mut self) {
+    let _ = fs::remove_file(&self.path);
+  }
+}
+
+struct SpillReader {
+  path: String,
+  reader: BufReader<File>,
+  remaining: usize,
+  gain: f32,
+  bytes: Vec<u8>,
+}
+
+impl Source for SpillReader {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let k = left.len().min(self.remaining);
+    self.bytes.resize(8 * k, 0);
+    self.reader.read_exact(&mut self.bytes).map_err(|e| RaudioError::Io(self.path.clone(), e))?;
+    for (i, frame) in self.bytes.chunks_exact(8).enumerate() {
+      left[i] = self.gain * f32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]);
+      right[i] = self.gain * f32::from_le_bytes([frame[4], frame[5], frame[6], frame[7]]);
+    }
+    self.remaining -= k;
+    Ok(k)
+  }
+}
+
+/// A stereo 32 bit float WAV file.
+struct WavSource {
+  path: String,
+  samples: hound::WavIntoSamples<BufReader<File>, f32>,
+}
+
+impl Source for WavSource {
+  fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+    let mut sample = || self.samples.next().transpose().map_err(|e| RaudioError::Wav(self.path.clone(), e));
+    for i in 0..left.len() {
+      match (sample()?, sample()?) {
+        (Some(l), Some(r)) => {
+          left[i] = l;
+          right[i] = r;
+        }
+        _ => return Ok(i),
+      }
+    }
+    Ok(left.len())
+  }
+}
+
+/// Spill a stereo 32 bit float WAV file, such as one written by `write` in the default format.
+pub fn spill_wav(path: &str) -> Result<Spill, RaudioError> {
+  let reader = hound::WavReader::open(path).map_err(|e| RaudioError::Wav(path.to_string(), e))?;
+  let spec = reader.spec();
+  if spec.channels != 2 || spec.sample_format != hound::SampleFormat::Float {
+    return Err(RaudioError::Mix(format!("{} is not a stereo float WAV file", path)));
+  }
+  Spill::from_source(&mut WavSource {
+    path: path.to_string(),
+    samples: reader.into_samples(),
+  })
+}
+
+/// Streaming counterpart of `stereo::pad_and_mix_buffers`.
+fn mix(inputs: &[Spill], stage: Stage) -> Result<Spill, RaudioError> {
+  mix_with_gain(inputs, stage).map(|(mixed, _)| mixed)
+}
+
+/// `mix`, also returning the gain every input has in the mix as by `stereo::mix_gain`.
+fn mix_with_gain(inputs: &[Spill], stage: Stage) -> Result<(Spill, f32), RaudioError> {
+  let mut peaks = levels::peaks(stage);
+  let global_max = peaks.next(|| inputs.iter().map(Spill::peak).fold(0f32, f32::max));
+  let pre_scale = if global_max > 1f32 { 1f32 / global_max } else { 1f32 };
+  let readers = inputs.iter().map(|s| s.reader().map(|r| (r, pre_scale))).collect::<Result<Vec<_>, _>>()?;
+  let mut sum = Sum {
+    inputs: readers,
+    scratch: stereo::silence(BLOCK),
+  };
+  let mut mixed = Spill::from_source(&mut sum)?;
+  let sum_max = peaks.next(|| mixed.peak);
+  if sum_max > 1f32 {
+    mixed.gain = 1f32 / sum_max;
+  }
+  let gain = pre_scale * mixed.gain;
+  Ok((mixed, gain))
+}
+
+fn with_delays<'a>(input: Boxed<'a>, delays: &[DelayParams], clip: Option<f32>) -> Boxed<'a> {
+  delays.iter().fold(input, |sig, params| match params.pan {
+    delay::StereoField::Mono => Box::new(MonoDelay::new(sig, params, clip)),
+    _ if delay::is_passthrough(params) => sig,
+    _ => Box::new(Delay::new(sig, params)),
+  })
+}
+
+fn with_reverbs<'a>(input: Boxed<'a>, reverbs: &[ReverbParams]) -> Boxed<'a> {
+  reverbs.iter().fold(input, |sig, params| Box::new(Reverb::new(sig, params)))
+}
+
+/// Spill `input` through `reverbs`, dropping the silence after each one as the batch renderer does.
+fn spill_trimmed_reverbs(mut input: Boxed, reverbs: &[ReverbParams]) -> Result<Spill, RaudioError> {
+  let mut trimmed: Option<Spill> = None;
+  for params in reverbs {
+    let mut wet = Reverb::new(input, params);
+    let mut spill = Spill::from_source(&mut wet)?;
+    spill.frames = spill.audible_frames();
+    input = spill.reader()?;
+    trimmed = Some(spill);
+  }
+  match trimmed {
+    Some(spill) => Ok(spill),
+    None => Spill::from_source(input.as_mut()),
+  }
+}
+
+/// Spill each line of a melody, rendering its notes with `note(l, i)`.
+fn lines<F>(
+  cps: f32, melody: &[Vec<Note>], plan: impl Fn(&Vec<Note>) -> (super::LinePlan, usize), note: F,
+) -> Result<Vec<Spill>, RaudioError>
+where
+  F: Fn(usize, &Vec<Note>, &super::LinePlan, usize) -> Result<StereoBuffer, RaudioError> + Sync,
+{
+  melody
+    .iter()
+    .enumerate()
+    .map(|(l, line)| {
+      let (plan, base_len) = plan(line);
+      let mut notes = Notes {
+        cues: plan.cues(cps),
+        render: |i| note(l, line, &plan, i),
+        next: 0,
+        base_len,
+        cursor: 0,
+        pending: (vec![], vec![]),
+      };
+      Spill::from_source(&mut notes)
+    })
+    .collect()
+}
+
+/// Streaming counterpart of `channel_with_reso`.
+fn channel_with_reso(conf: &Conf, arf: &Arf, stem: &Stem2, playing: &Playing) -> Result<Spill, RaudioError> {
+  let voice = ResoVoice::new(conf, arf, stem, playing);
+  let (melody, _, _, _, _, _, delays2, _, reverbs2) = stem;
+  let lines = lines(
+    conf.cps,
+    melody,
+    |line| {
+      let plan = voice.plan(line);
+      let base_len = voice.line_len(&plan);
+      (plan, base_len)
+    },
+    |l, line, plan, i| voice.note(l, line, plan, i),
+  )?;
+  let mixed = mix(&lines, Stage::Lines)?;
+  drop(lines);
+  spill_trimmed_reverbs(with_delays(mixed.reader()?, delays2, Some(1f32)), reverbs2)
+}
+
+/// Streaming counterpart of `channel_with_samples`.
+fn channel_with_samples(conf: &Conf, stem: &DrumSample, playing: &Playing) -> Result<Spill, RaudioError> {
+  let voice = SampleVoice::new(conf, stem, playing);
+  let (melody, _, _, _, _, delays2, _, reverbs2) = stem;
+  let lines = lines(
+    conf.cps,
+    melody,
+    |line| {
+      let plan = voice.plan(line);
+      let base_len = voice.line_len(&plan);
+      (plan, base_len)
+    },
+    |l, line, plan, i| Ok(voice.note(l, line, plan, i)),
+  )?;
+  let mixed = mix(&lines, Stage::Lines)?;
+  drop(lines);
+  let wet = with_reverbs(with_delays(mixed.reader()?, delays2, None), reverbs2);
+  let sample_rate = SR() as u32;
+  let mut filtered = Lowpass {
+    input: wet,
+    filters: (
+      Butterworth::lowpass(sample_rate, NFf()),
+      Butterworth::lowpass(sample_rate, NFf()),
+    ),
+  };
+  Spill::from_source(&mut filtered)
+}
+
+/// Streaming counterpart of `fm_combiner_with_reso`.
+fn channel_fm(conf: &Conf, stem: &StemFM, playing: &Playing) -> Result<Spill, RaudioError> {
+  let voice = FmVoice::new(conf, stem, playing);
+  let (melody, _, _, _, delay2, _, reverb2) = stem;
+  let lines = lines(
+    conf.cps,
+    melody,
+    |line| {
+      let plan = voice.plan(line);
+      let base_len = voice.line_len(&plan);
+      (plan, base_len)
+    },
+    |l, line, plan, i| voice.note(l, line, plan, i).map(stereo::from_mono),
+  )?;
+  let mixed = mix(&lines, Stage::Lines)?;
+  drop(lines);
+  spill_trimmed_reverbs(with_delays(mixed.reader()?, delay2, Some(1f32)), reverb2)
+}
+
+/// The channels of one renderable, as in `render_parts_stereo`. Their tasks derive from `context`, that of the render.
+fn channels(
+  context: &RenderContext, conf: &Conf, j: usize, arf: &Arf, renderable: &Renderable2, playing: &Playing,
+) -> Result<Vec<Spill>, RaudioError> {
+  match renderable {
+    Renderable2::Instance(stem) => Ok(vec![channel_with_reso(conf, arf, stem, playing)?]),
+    Renderable2::Group(stems) => stems
+      .par_iter()
+      .enumerate()
+      .map(|(k, stem)| {
+        let _context = context.for_task(&[j as u64, k as u64]).enter();
+        channel_with_reso(conf, arf, stem, playing)
+      })
+      .collect(),
+    Renderable2::Sample(stem) => Ok(vec![channel_with_samples(conf, stem, playing)?]),
+    Renderable2::Mix(weighted_stems) => weighted_stems
+      .iter()
+      .enumerate()
+      .map(|(k, (gain, renderable2))| {
+        let _context = RenderContext {
+          progress: context.progress.as_ref().map(Progress::nested),
+          ..context.for_task(&[j as u64, k as u64])
+        }
+        .enter();
+        let parts = render_parts(conf, &vec![(*arf, renderable2.to_owned())], &[], std::slice::from_ref(playing), &[], &[])?;
+        let mut mixed = mix(&parts, Stage::Parts)?;
+        mixed.gain *= gain;
+        Ok(mixed)
+      })
+      .collect(),
+    Renderable2::Tacet(stem) => {
+      let len_cycles: f32 = time::count_cycles(&stem.0[0]);
+      let mut silence = Silence {
+        remaining: time::samples_at(conf.cps, len_cycles),
+      };
+      Ok(vec![Spill::from_source(&mut silence)?])
+    }
+    Renderable2::FMOp(fm_stem) => Ok(vec![channel_fm(conf, fm_stem, playing)?]),
+  }
+}
+
+/// Streaming counterpart of `render_parts_stereo`.
+/// Render each renderable with its stem reverb and pan position. Returns one spilled signal per renderable.
+/// Renderables flagged in `skip` come back empty.
+pub fn render_parts(
+  conf: &Conf, renderables: &Vec<(Arf, Renderable2)>, pans: &[f32], playing: &[Playing],
+  stem_reverbs: &[ReverbParams], skip: &[bool],
+) -> Result<Vec<Spill>, RaudioError> {
+  let context = RenderContext::current();
+
+  let parts = renderables
+    .par_iter()
+    .enumerate()
+    .map(|(j, (arf, renderable))| {
+      if skip.get(j).copied().unwrap_or(false) {
+        return Spill::from_source(&mut Silence { remaining: 0 });
+      }
+      let part = context.progress.as_ref().map(Progress::for_part);
+      let part_context = RenderContext {
+        progress: part.clone(),
+        ..context.clone()
+      };
+      let _context = part_context.for_task(&[j as u64]).enter();
+      if let Some(part) = &part {
+        part.emit(Event::PartStart {
+          part: j,
+          role: arf.role,
+        });
+      }
+      let synthesis = Instant::now();
+      let played = playing.get(j).cloned().unwrap_or_default();
+      let channels = channels(&part_context, conf, j, arf, renderable, &played)?;
+      let signal = mix(&channels, Stage::Channels)?;
+      drop(channels);
+      let synthesis = synthesis.elapsed();
+      let wet = if stem_reverbs.is_empty() {
+        signal.reader()?
+      } else {
+        seed::reseed_for(context.seed, &[j as u64, STEM_REVERB_SEED_KEY]);
+        Box::new(Reverb::new(signal.reader()?, &stem_reverbs[j]))
+      };
+      let pan = pans.get(j).copied().unwrap_or(0f32);
+      let mut panned = Pan {
+        input: wet,
+        gains: if pan == 0f32 {
+          (1f32, 1f32)
+        } else {
+          stereo::pan_gains(pan)
+        },
+      };
+      // the stem reverb is applied as the part is spilled
+      let (spill, stem_reverb) = time::measure(|| Spill::from_source(&mut panned));
+      if let Some(part) = &part {
+        part.emit(Event::PartFinish {
+          part: j,
+          notes: part.notes(),
+          synthesis_seconds: synthesis.as_secs_f64(),
+          stem_reverb_seconds: stem_reverb.as_secs_f64(),
+        });
+      }
+      spill
+    })
+    .collect::<Result<Vec<_>, RaudioError>>()?;
+  seed::reseed_for(context.seed, &[GROUP_REVERB_SEED_KEY]);
+  Ok(parts)
+}
+
+/// Streaming counterpart of `mix_parts_stereo`.
+/// Mix the parts from `render_parts` and apply the group reverbs.
+/// Also returns the gain every part has in the mix, see `stereo::mix_gain`.
+pub fn mix_parts(parts: &[Spill], group_reverbs: &[ReverbParams]) -> Result<(Spill, f32), RaudioError> {
+  let (mixed, gain) = mix_with_gain(parts, Stage::Parts)?;
+  if group_reverbs.is_empty() {
+    return Ok((mixed, gain));
+  }
+  Ok((spill_trimmed_reverbs(mixed.reader()?, group_reverbs)?, gain))
+}
+
+/// Largest sample and sum of squares of a written signal, for `stems::StemEntry`.
+pub struct Written {
+  pub frames: usize,
+  pub peak: f32,
+  pub sum_squares: f64,
+}
+
+/// Write frames `[start, end)` of `spill` to a stereo WAV file, padding with silence past its end.
+/// Without a range the whole signal is written.
+pub fn write(
+  spill: &Spill, range: Option<(usize, usize)>, sample_rate: usize, filename: &str, format: WavFormat,
+) -> Result<Written, RaudioError> {
+  let wav_error = |e| RaudioError::Wav(filename.to_string(), e);
+  let (start, end) = range.unwrap_or((0, spill.frames));
+  let mut reader = spill.reader()?;
+  let mut stream = WavStream::create(sample_rate, 2, filename, format).map_err(wav_error)?;
+  let mut written = Written {
+    frames: 0,
+    peak: 0f32,
+    sum_squares: 0f64,
+  };
+  let (mut left, mut right) = stereo::silence(BLOCK);
+  let mut interleaved: Vec<f32> = Vec::with_capacity(2 * BLOCK);
+  let mut position = 0usize;
+  while position < end {
+    let k = reader.pull(&mut left, &mut right)?;
+    left[k..].fill(0f32);
+    right[k..].fill(0f32);
+    let from = start.max(position) - position;
+    let to = (end - position).min(BLOCK);
+    interleaved.clear();
+    for i in from..to.max(from) {
+      written.peak = written.peak.max(left[i].abs()).max(right[i].abs());
+      written.sum_squares += (left[i] * left[i] + right[i] * right[i]) as f64;
+      interleaved.push(left[i]);
+      interleaved.push(right[i]);
+    }
+    stream.write(&interleaved).map_err(wav_error)?;
+    position += BLOCK;
+  }
+  written.frames = stream.frames();
+  stream.finalize().map_err(wav_error)?;
+  Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn signal(n: usize) -> StereoBuffer {
+    (
+      (0..n).map(|i| (i as f32 * 0.01).sin()).collect(),
+      (0..n).map(|i| (i as f32 * 0.013).cos()).collect(),
+    )
+  }
+
+  struct Buffer {
+    signal: StereoBuffer,
+    position: usize,
+  }
+
+  impl Source for Buffer {
+    fn pull(&mut self, left: &mut [f32], right: &mut [f32]) -> Result<usize, RaudioError> {
+      let k = left.len().min(self.signal.0.len() - self.position);
+      left[..k].copy_from_slice(&self.signal.0[self.position..self.position + k]);
+      right[..k].copy_from_slice(&self.signal.1[self.position..self.position + k]);
+      self.position += k;
+      Ok(k)
+    }
+  }
+
+  fn read(source: &mut dyn Source) -> StereoBuffer {
+    let spill = Spill::from_source(source).unwrap();
+    let mut reader = spill.reader().unwrap();
+    let mut out = stereo::silence(spill.frames);
+    reader.pull(&mut out.0, &mut out.1).unwrap();
+    out
+  }
+
+  #[test]
+  fn test_delay_matches_apply_delays() {
+    let params = DelayParams {
+      len_seconds: 0.05,
+      len_cycles: None,
+      n_echoes: 3,
+      gain: 0.5,
+      mix: 0.7,
+      pan: delay::StereoField::LeftRight(0.9, 0.6),
+    };
+    let dry = signal(3 * BLOCK + 17);
+    let expected = stereo::apply_delays(dry.clone(), std::slice::from_ref(&params), None);
+    let mut delayed = Delay::new(
+      Box::new(Buffer {
+        signal: dry,
+        position: 0,
+      }),
+      &params,
+    );
+    let streamed = read(&mut delayed);
+    assert_eq!(stereo::len(&expected), stereo::len(&streamed));
+    let max_error = streamed.0.iter().zip(expected.0.iter()).fold(0f32, |max, (a, b)| max.max((a - b).abs()));
+    assert!(max_error < 1e-5, "Must match the batch delay, got error {}", max_error);
+  }
+
+  #[test]
+  fn test_mono_delay_matches_apply_delays() {
+    let params = DelayParams {
+      len_seconds: 0.002,
+      len_cycles: None,
+      n_echoes: 3,
+      gain: 0.5,
+      mix: 0.7,
+      pan: delay::StereoField::Mono,
+    };
+    let dry = signal(3 * BLOCK + 17);
+    for params in [params, delay::passthrough] {
+      let expected = stereo::apply_delays(dry.clone(), std::slice::from_ref(&params), Some(1f32));
+      let mut delayed = MonoDelay::new(
+        Box::new(Buffer {
+          signal: dry.clone(),
+          position: 0,
+        }),
+        &params,
+        Some(1f32),
+      );
+      assert_eq!(expected, read(&mut delayed));
+    }
+  }
+
+  #[test]
+  fn test_mix_matches_pad_and_mix() {
+    let a = signal(BLOCK + 5);
+    let b = (vec![1.5f32; 300], vec![-0.5f32; 300]);
+    let expected = stereo::pad_and_mix_buffers(vec![a.clone(), b.clone()], Stage::Parts).unwrap();
+    let spills: Vec<Spill> = [a, b]
+      .into_iter()
+      .map(|signal| Spill::from_source(&mut Buffer { signal, position: 0 }).unwrap())
+      .collect();
+    let mixed = mix(&spills, Stage::Parts).unwrap();
+    let streamed = read(mixed.reader().unwrap().as_mut());
+    let max_error = streamed.0.iter().zip(expected.0.iter()).fold(0f32, |max, (a, b)| max.max((a - b).abs()));
+    assert!(
+      max_error < 1e-6,
+      "Must scale like the batch mix, got error {}",
+      max_error
+    );
+  }
+}
@@ -0,0 +1,108 @@
+/// Partial renders of a score.
+///
+/// A window of cycles `[start, end)` is rendered by synthesizing only the notes that can be heard in it:
+/// notes beginning before `end` whose sound, including the tails of their delays and reverbs, reaches `start`.
+/// Every other note is rendered as silence of the same duration, so the kept notes stay at their positions
+/// in the line and the result can be trimmed to the window.
+///
+/// Seeded renders reseed per note, so a kept note draws the same values whether or not its neighbours are
+/// synthesized. Lines, channels and parts are mixed at the levels of the full render (see `render::levels`),
+/// so the window matches the same region of a full render up to the rounding of the reverb convolution, which
+/// differs in the last bits of a sample. `render_score` renders a window like this only when it has the levels of
+/// a full render of the unchanged score in its cache, and otherwise renders every note and trims.
+use std::cell::Cell;
+
+use crate::analysis::delay::DelayParams;
+use crate::reverb::convolution::ReverbParams;
+use crate::synth::{SRf, StereoBuffer};
+use crate::time;
+
+thread_local! {
+  static AUDIBLE: Cell<Option<(f32, f32)>> = const { Cell::new(None) };
+}
+
+/// Limit note synthesis on the current thread to notes heard in cycles `[start, end)`.
+/// `None` renders every note.
+pub fn set_audible(audible: Option<(f32, f32)>) {
+  AUDIBLE.with(|cell| cell.set(audible))
+}
+
+/// The cycles whose notes are synthesized on the current thread, if limited.
+pub fn audible() -> Option<(f32, f32)> {
+  AUDIBLE.with(|cell| cell.get())
+}
+
+/// Seconds an effects chain keeps sounding after its input ends.
+/// Each delay and reverb is applied to the output of the previous one, so their lengths add up.
+pub fn tail_seconds(delays: &[DelayParams], reverbs: &[ReverbParams]) -> f32 {
+  delays.iter().map(|d| d.len_seconds * d.n_echoes.max(1) as f32).sum::<f32>()
+    + reverbs.iter().map(|r| r.dur).sum::<f32>()
+}
+
+/// For each note of a line, cued at `cues` for `durs` cycles (see `LinePlan`), whether it is heard in the audible
+/// cycles of the current thread.
+/// A note is heard when it begins before the end of the window and sounds until after its start,
+/// counting `tail_seconds` of effects after the note ends. Both are compared in samples, where the note plays.
+pub fn sounding(cps: f32, cues: &[(usize, f32)], durs: &[f32], tail_seconds: f32) -> Vec<bool> {
+  let Some((start, end)) = audible() else {
+    return vec![true; cues.len()];
+  };
+  let (from, to) = (time::samples_at(cps, start), time::samples_at(cps, end));
+  let tail = (tail_seconds * SRf()) as usize;
+  // the length of each note is the distance to the onset of the next
+  let ends = time::onsets(cps, &[durs, &[0f32]].concat());
+  cues
+    .iter()
+    .enumerate()
+    .map(|(i, &(cue, _))| {
+      let len = ends[i + 1] - ends[i];
+      cue < to && cue + len + tail > from
+    })
+    .collect()
+}
+
+/// Keep only the samples from cycle `start` to cycle `end`.
+/// A partial render may end early where its trailing silence was trimmed, so it is padded to the end of the window.
+pub fn trim(cps: f32, (start, end): (f32, f32), (left, right): &mut StereoBuffer) {
+  for channel in [left, right] {
+    let a = time::samples_at(cps, start);
+    let b = time::samples_at(cps, end);
+    channel.resize(b, 0f32);
+    channel.drain(..a);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::render::LinePlan;
+  use crate::types::synthesis::Note;
+
+  #[test]
+  fn test_sounding_keeps_notes_heard_in_the_window() {
+    let quarter: Note = ((1, 4), (0, (0, 0, 1)), 1f32);
+    let whole: Note = ((1, 1), (0, (0, 0, 1)), 1f32);
+    // onsets 0, 1, 1.25, 1.5, 1.75, 2
+    let line = vec![whole, quarter, quarter, quarter, quarter, whole];
+
+    set_audible(Some((1.5f32, 2f32)));
+    assert_eq!(
+      vec![false, false, false, true, true, false],
+      LinePlan::new(1f32, &line, 0f32).heard
+    );
+    // a quarter cycle of reverb makes the second and third notes audible at 1.5
+    assert_eq!(
+      vec![false, true, true, true, true, false],
+      LinePlan::new(1f32, &line, 0.26f32).heard
+    );
+    // pushed a quarter cycle ahead, the last note is heard before 2
+    let mut pushed = LinePlan::new(1f32, &line, 0f32);
+    let mut feel = vec![(0f32, 1f32); line.len()];
+    feel[5].0 = -0.25f32;
+    pushed.set_feel(1f32, feel);
+    assert_eq!(vec![false, false, false, true, true, true], pushed.heard);
+
+    set_audible(None);
+    assert!(LinePlan::new(1f32, &line, 0f32).heard.iter().all(|&heard| heard));
+  }
+}
@@ -1,10 +1,14 @@
 use crate::druid::{soid_fx, soids as some_soids};
 use crate::phrasing::contour::gen_contour;
-use crate::synth::{pi, SampleBuffer, SR};
+use crate::synth::{pi, SampleBuffer, StereoBuffer, SR};
 use crate::time;
 use crate::types::timbre::AmpContour;
-use rand::{rngs::ThreadRng, thread_rng, Rng};
-use rustfft::{num_complex::Complex, FftPlanner};
+use rand::Rng;
+use crate::seed::{ThreadRng, thread_rng};
+use serde::Deserialize;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
 
 #[derive(Copy, Clone)]
 pub enum Cube {
@@ -18,7 +22,7 @@ pub struct ReverbProfile {
   cube: Cube,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Deserialize)]
 ///
 /// amp: The impulse amplitude coefficient
 /// dur: Length in seconds for the impulse to live
@@ -57,6 +61,24 @@ fn gen_impulse(amp: f32, rate: f32, dur: f32) -> SampleBuffer {
 /// onto a given signal. Here it genereates an impulse response to produce a reverberation effect.
 pub fn of(sig: &SampleBuffer, params: &ReverbParams) -> SampleBuffer {
   let impulse_response = gen_impulse(params.amp, params.rate, params.dur);
+  convolve(sig, &impulse_response, params.mix)
+}
+
+/// Stereo variant of `of`.
+/// Both channels share one impulse response so the stereo image is preserved.
+pub fn of_stereo((left, right): &StereoBuffer, params: &ReverbParams) -> StereoBuffer {
+  let impulse_response = gen_impulse(params.amp, params.rate, params.dur);
+  let wet_left = convolve(left, &impulse_response, params.mix);
+  let wet_right = if left == right {
+    wet_left.clone()
+  } else {
+    convolve(right, &impulse_response, params.mix)
+  };
+  (wet_left, wet_right)
+}
+
+/// Mix a signal with its convolution by `impulse_response`.
+fn convolve(sig: &SampleBuffer, impulse_response: &SampleBuffer, mix: f32) -> SampleBuffer {
   let n = sig.len() + impulse_response.len();
 
   let mut planner = FftPlanner::new();
@@ -85,12 +107,99 @@ pub fn of(sig: &SampleBuffer, params: &ReverbParams) -> SampleBuffer {
   // Mix dry and wet signals
   let mut mixed_signal: SampleBuffer = vec![0.0; n];
   for i in 0..sig.len() {
-    mixed_signal[i] = (1.0 - params.mix) * sig[i] + params.mix * wet_signal[i];
+    mixed_signal[i] = (1.0 - mix) * sig[i] + mix * wet_signal[i];
   }
 
   mixed_signal
 }
 
+/// Streaming counterpart of `of`, using uniformly partitioned convolution.
+///
+/// The impulse response is split into partitions of `block` samples whose spectra are kept,
+/// so each block of input costs FFTs of twice the block size no matter how long the signal is.
+/// Like `of`, the wet signal is cut off at the end of the input.
+pub struct Partitioned {
+  block: usize,
+  mix: f32,
+  /// Spectrum of each partition of the impulse response
+  partitions: Vec<Vec<Complex<f32>>>,
+  fft: Arc<dyn Fft<f32>>,
+  ifft: Arc<dyn Fft<f32>>,
+}
+
+/// Input history of one channel convolved by a `Partitioned` reverb.
+pub struct PartitionedState {
+  /// Spectra of the most recent input blocks, newest first
+  inputs: VecDeque<Vec<Complex<f32>>>,
+  /// Second half of the previous block's output
+  overlap: Vec<f32>,
+}
+
+impl Partitioned {
+  /// Generate the impulse response for `params`, drawing the same values as `of`.
+  pub fn new(params: &ReverbParams, block: usize) -> Self {
+    let impulse_response = gen_impulse(params.amp, params.rate, params.dur);
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(2 * block);
+    let ifft = planner.plan_fft_inverse(2 * block);
+    let partitions = impulse_response
+      .chunks(block)
+      .map(|chunk| {
+        let mut spectrum: Vec<Complex<f32>> = chunk.iter().map(|&s| Complex::new(s, 0.0)).collect();
+        spectrum.resize(2 * block, Complex::new(0.0, 0.0));
+        fft.process(&mut spectrum);
+        spectrum
+      })
+      .collect();
+    Partitioned {
+      block,
+      mix: params.mix,
+      partitions,
+      fft,
+      ifft,
+    }
+  }
+
+  /// Length of the impulse response in samples, rounded up to whole blocks.
+  pub fn impulse_len(&self) -> usize {
+    self.partitions.len() * self.block
+  }
+
+  pub fn state(&self) -> PartitionedState {
+    PartitionedState {
+      inputs: VecDeque::with_capacity(self.partitions.len()),
+      overlap: vec![0f32; self.block],
+    }
+  }
+
+  /// Convolve the next block of input into `output`.
+  /// Every block but the last must have `block` samples.
+  pub fn process(&self, state: &mut PartitionedState, input: &[f32], output: &mut [f32]) {
+    let n = 2 * self.block;
+    let mut spectrum: Vec<Complex<f32>> = input.iter().map(|&s| Complex::new(s, 0.0)).collect();
+    spectrum.resize(n, Complex::new(0.0, 0.0));
+    self.fft.process(&mut spectrum);
+    state.inputs.push_front(spectrum);
+    state.inputs.truncate(self.partitions.len());
+
+    let mut result = vec![Complex::new(0.0, 0.0); n];
+    for (x, h) in state.inputs.iter().zip(self.partitions.iter()) {
+      for i in 0..n {
+        result[i] += x[i] * h[i];
+      }
+    }
+    self.ifft.process(&mut result);
+
+    for (i, sample) in input.iter().enumerate() {
+      let wet = result[i].re / n as f32 + state.overlap[i];
+      output[i] = (1.0 - self.mix) * sample + self.mix * wet;
+    }
+    for i in 0..self.block {
+      state.overlap[i] = result[self.block + i].re / n as f32;
+    }
+  }
+}
+
 fn pad_buffers(signal: &SampleBuffer, impulse_response: &SampleBuffer) -> (SampleBuffer, SampleBuffer) {
   let mut padded_signal = signal.clone();
   let mut padded_ir = impulse_response.clone();
@@ -103,3 +212,31 @@ fn pad_buffers(signal: &SampleBuffer, impulse_response: &SampleBuffer) -> (Sampl
 
   (padded_signal, padded_ir)
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_partitioned_matches_of() {
+    let params = ReverbParams {
+      mix: 0.4,
+      amp: 0.5,
+      dur: 0.05,
+      rate: 0.3,
+    };
+    let sig: SampleBuffer = (0..5000).map(|i| (i as f32 * 0.01).sin()).collect();
+    crate::seed::set_seed(Some(3));
+    let expected = of(&sig, &params);
+
+    crate::seed::set_seed(Some(3));
+    let reverb = Partitioned::new(&params, 256);
+    let mut state = reverb.state();
+    let mut streamed = vec![0f32; sig.len()];
+    for (input, output) in sig.chunks(256).zip(streamed.chunks_mut(256)) {
+      reverb.process(&mut state, input, output);
+    }
+    let max_error = streamed.iter().zip(expected.iter()).fold(0f32, |max, (a, b)| max.max((a - b).abs()));
+    assert!(max_error < 1e-4, "Must match whole-signal convolution, got error {}", max_error);
+  }
+}
@@ -0,0 +1,560 @@
+/// Local HTTP service for rendering playbooks, for callers that would otherwise start `raudio render` per request.
+///
+/// Renders are queued as jobs and run by a fixed number of worker threads. Each job renders with `render_score`
+/// into its own directory, and the parts of every job share the rayon pool, so more workers interleave more renders
+/// without adding synthesis threads.
+///
+/// ```text
+/// POST /jobs                      {"playbook": {...}, "preset": "hop", "seed": 7, "stems": true}
+///                                 -> 202 {"id": "1", "status": "queued"}
+/// GET  /jobs/1                    -> 200 {"id": "1", "status": "done", "files": ["mix.wav", ...]}
+/// GET  /jobs/1/files/mix.wav      -> 200 the file
+/// ```
+///
+/// A job request takes the options of `raudio render`: `seed`, `stems`, `range` (as `"A:B"`), `bits`, `dither`,
+/// `sample_rate`, `markers`, `stream`, `tuning` and `basis`, and an `asset_name` that defaults to `mix`. Its `preset`
+/// names a built-in preset or a pack loaded at startup with `--pack`, and its `tuning` a built-in tuning; the server
+/// never reads a file a request names, so jobs take no Scala files or `keyboard` mapping. Progress events
+/// of each job are written to its `progress.jsonl`. Invalid requests are answered with 400 and the problems found in
+/// the playbook.
+/// The server speaks just enough HTTP/1.1 for local clients and closes every connection after one response.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+use crate::cli::{self, RenderFields};
+use crate::error::RaudioError;
+use crate::inp::validate;
+use crate::presets::{self, Preset};
+use crate::render::progress::ProgressSink;
+use crate::types::render::DruidicScore;
+use crate::RenderOptions;
+
+/// Largest request body accepted, in bytes
+const MAX_BODY: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServeOptions {
+  /// Address to listen on. Port 0 picks any free port.
+  pub addr: String,
+  /// Jobs rendered at the same time
+  pub workers: usize,
+  /// Each job writes its files to `out_dir/<id>`
+  pub out_dir: String,
+  /// Render cache shared by every seeded job. See `render::cache`.
+  pub cache_dir: Option<String>,
+  /// Preset pack files loaded at startup, which jobs may name
+  pub packs: Vec<String>,
+}
+
+impl Default for ServeOptions {
+  fn default() -> Self {
+    ServeOptions {
+      addr: "127.0.0.1:7878".to_string(),
+      workers: 1,
+      out_dir: "raudio-jobs".to_string(),
+      cache_dir: None,
+      packs: vec![],
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+  Queued,
+  Running,
+  Done,
+  Failed,
+}
+
+/// Body of `POST /jobs`.
+#[derive(Deserialize)]
+struct JobRequest {
+  playbook: serde_json::Value,
+  preset: String,
+  asset_name: Option<String>,
+  #[serde(flatten)]
+  options: RenderFields,
+}
+
+/// A job ready to render.
+struct Render {
+  score: DruidicScore,
+  preset: Preset,
+  asset_name: String,
+  options: RenderOptions,
+}
+
+struct Job {
+  status: Status,
+  error: Option<String>,
+  /// Taken by the worker that renders the job
+  render: Option<Render>,
+  dir: String,
+}
+
+#[derive(Default)]
+struct Jobs {
+  jobs: BTreeMap<u64, Job>,
+  queued: VecDeque<u64>,
+  last_id: u64,
+}
+
+/// Jobs shared by the connections and the workers.
+#[derive(Default)]
+struct Queue {
+  jobs: Mutex<Jobs>,
+  ready: Condvar,
+}
+
+impl Queue {
+  fn lock(&self) -> MutexGuard<'_, Jobs> {
+    // renders run outside of the lock, so a poisoned lock still holds consistent jobs
+    self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
+
+/// A listening server with its workers started. Connections are answered by `run`.
+pub struct Server {
+  listener: TcpListener,
+  queue: Arc<Queue>,
+  out_dir: String,
+}
+
+impl Server {
+  pub fn bind(options: &ServeOptions) -> Result<Server, RaudioError> {
+    for path in &options.packs {
+      presets::pack::load(path)?;
+    }
+    fs::create_dir_all(&options.out_dir).map_err(|e| RaudioError::Io(options.out_dir.clone(), e))?;
+    let listener = TcpListener::bind(&options.addr).map_err(|e| RaudioError::Io(options.addr.clone(), e))?;
+    let queue = Arc::new(Queue::default());
+    for _ in 0..options.workers.max(1) {
+      let queue = queue.clone();
+      let cache_dir = options.cache_dir.clone();
+      thread::spawn(move || work(&queue, cache_dir));
+    }
+    Ok(Server {
+      listener,
+      queue,
+      out_dir: options.out_dir.clone(),
+    })
+  }
+
+  pub fn local_addr(&self) -> Result<SocketAddr, RaudioError> {
+    self.listener.local_addr().map_err(|e| RaudioError::Io("server address".to_string(), e))
+  }
+
+  /// Answer connections until the process exits, each on its own thread.
+  pub fn run(self) {
+    let server = Arc::new(self);
+    for stream in server.listener.incoming() {
+      match stream {
+        Ok(stream) => {
+          let server = server.clone();
+          thread::spawn(move || {
+            if let Err(e) = server.answer(stream) {
+              eprintln!("Warning: Failed to answer a request: {}", e);
+            }
+          });
+        }
+        Err(e) => eprintln!("Warning: Failed to accept a connection: {}", e),
+      }
+    }
+  }
+
+  fn answer(&self, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+    let request = match read_request(&mut reader) {
+      Ok(request) => request,
+      Err(Reply::Json(status, body)) => return write_json(&mut stream, status, &body),
+      Err(Reply::Io(e)) => return Err(e),
+    };
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+      ("POST", ["jobs"]) => match self.submit(&request.body) {
+        Ok(id) => write_json(
+          &mut stream,
+          202,
+          &json!({"id": id.to_string(), "status": Status::Queued}),
+        ),
+        Err(body) => write_json(&mut stream, 400, &body),
+      },
+      ("GET", ["jobs", id]) => match self.status(id) {
+        Some(body) => write_json(&mut stream, 200, &body),
+        None => write_json(&mut stream, 404, &json!({"error": format!("No job {}", id)})),
+      },
+      ("GET", ["jobs", id, "files", name]) => match self.file(id, name) {
+        Some(path) => write_file(&mut stream, &path),
+        None => write_json(
+          &mut stream,
+          404,
+          &json!({"error": format!("No file {} for job {}", name, id)}),
+        ),
+      },
+      (_, ["jobs"]) | (_, ["jobs", _]) | (_, ["jobs", _, "files", _]) => write_json(
+        &mut stream,
+        405,
+        &json!({"error": format!("{} is not allowed here", request.method)}),
+      ),
+      _ => write_json(&mut stream, 404, &json!({"error": format!("No route for {}", path)})),
+    }
+  }
+
+  /// Queue a job, or describe why the request cannot render.
+  fn submit(&self, body: &[u8]) -> Result<u64, serde_json::Value> {
+    let invalid = |message: String| json!({ "error": message });
+    let request: JobRequest =
+      serde_json::from_slice(body).map_err(|e| invalid(format!("Invalid job request: {}", e)))?;
+    let json = request.playbook.to_string();
+    let errors: Vec<String> =
+      validate::validate_playbook(&json).iter().filter(|p| p.is_error()).map(|p| p.to_string()).collect();
+    if !errors.is_empty() {
+      return Err(json!({"error": "Score cannot be rendered", "problems": errors}));
+    }
+    let score = validate::parse_playbook(&json).map_err(|p| invalid(p.to_string()))?;
+    let mut render = prepare(request, score).map_err(invalid)?;
+
+    let mut jobs = self.queue.lock();
+    jobs.last_id += 1;
+    let id = jobs.last_id;
+    let dir = format!("{}/{}", self.out_dir, id);
+    render.options.progress = Some(ProgressSink::File(format!("{}/progress.jsonl", dir)));
+    jobs.jobs.insert(
+      id,
+      Job {
+        status: Status::Queued,
+        error: None,
+        render: Some(render),
+        dir,
+      },
+    );
+    jobs.queued.push_back(id);
+    self.queue.ready.notify_one();
+    Ok(id)
+  }
+
+  fn status(&self, id: &str) -> Option<serde_json::Value> {
+    let id = id.parse::<u64>().ok()?;
+    let jobs = self.queue.lock();
+    let job = jobs.jobs.get(&id)?;
+    let files = if job.status == Status::Done {
+      list_files(&job.dir)
+    } else {
+      vec![]
+    };
+    Some(json!({"id": id.to_string(), "status": job.status, "error": job.error, "files": files}))
+  }
+
+  /// Path of a file written by a finished job. Only names listed in its status are served.
+  fn file(&self, id: &str, name: &str) -> Option<String> {
+    let id = id.parse::<u64>().ok()?;
+    let jobs = self.queue.lock();
+    let job = jobs.jobs.get(&id)?;
+    if job.status != Status::Done || !list_files(&job.dir).iter().any(|f| f == name) {
+      return None;
+    }
+    Some(format!("{}/{}", job.dir, name))
+  }
+}
+
+/// Parse the options of a job request as `raudio render` parses its flags, without reading any file it names.
+fn prepare(request: JobRequest, score: DruidicScore) -> Result<Render, String> {
+  let asset_name = request.asset_name.unwrap_or("mix".to_string());
+  cli::check_asset_name(&asset_name)?;
+  if request.options.keyboard.is_some() {
+    return Err("Jobs cannot name a keyboard mapping".to_string());
+  }
+  if let Some(tuning) = &request.options.tuning {
+    cli::find_tuning(tuning)?;
+  }
+  Ok(Render {
+    score,
+    preset: cli::find_preset(&request.preset)?,
+    asset_name,
+    options: request.options.render_options()?,
+  })
+}
+
+/// Render queued jobs one at a time, forever.
+fn work(queue: &Queue, cache_dir: Option<String>) {
+  loop {
+    let (id, render, dir) = {
+      let mut jobs = queue.lock();
+      let id = loop {
+        match jobs.queued.pop_front() {
+          Some(id) => break id,
+          None => jobs = queue.ready.wait(jobs).unwrap_or_else(|e| e.into_inner()),
+        }
+      };
+      let job = jobs.jobs.get_mut(&id).expect("queued jobs are listed");
+      job.status = Status::Running;
+      (id, job.render.take().expect("jobs are rendered once"), job.dir.clone())
+    };
+    let mut options = render.options;
+    options.cache_dir = cache_dir.clone();
+    let error = cli::render_caught(render.score, render.preset, &dir, &render.asset_name, &options).err();
+    let mut jobs = queue.lock();
+    if let Some(job) = jobs.jobs.get_mut(&id) {
+      job.status = if error.is_some() { Status::Failed } else { Status::Done };
+      job.error = error;
+    }
+  }
+}
+
+/// Names of the files in a job directory, sorted.
+fn list_files(dir: &str) -> Vec<String> {
+  let mut names: Vec<String> = fs::read_dir(dir)
+    .map(|entries| {
+      entries
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect()
+    })
+    .unwrap_or_default();
+  names.sort();
+  names
+}
+
+struct Request {
+  method: String,
+  path: String,
+  body: Vec<u8>,
+}
+
+/// Why a request could not be read: the connection failed, or the client gets this reply.
+enum Reply {
+  Io(io::Error),
+  Json(u16, serde_json::Value),
+}
+
+impl From<io::Error> for Reply {
+  fn from(e: io::Error) -> Self {
+    Reply::Io(e)
+  }
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Reply> {
+  let bad_request = |message: &str| Reply::Json(400, json!({ "error": message }));
+  let mut line = String::new();
+  reader.read_line(&mut line)?;
+  let mut words = line.split_whitespace();
+  let (method, path) = match (words.next(), words.next()) {
+    (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+    _ => return Err(bad_request("Malformed request line")),
+  };
+  let mut content_length = 0usize;
+  loop {
+    line.clear();
+    if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+      break;
+    }
+    if let Some((name, value)) = line.split_once(':') {
+      if name.trim().eq_ignore_ascii_case("content-length") {
+        content_length = value.trim().parse().map_err(|_| bad_request("Invalid Content-Length"))?;
+      }
+    }
+  }
+  if content_length > MAX_BODY {
+    return Err(Reply::Json(
+      413,
+      json!({"error": format!("Bodies are limited to {} bytes", MAX_BODY)}),
+    ));
+  }
+  let mut body = vec![0u8; content_length];
+  reader.read_exact(&mut body)?;
+  Ok(Request { method, path, body })
+}
+
+fn reason(status: u16) -> &'static str {
+  match status {
+    200 => "OK",
+    202 => "Accepted",
+    400 => "Bad Request",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    413 => "Payload Too Large",
+    _ => "Internal Server Error",
+  }
+}
+
+fn write_head(stream: &mut impl Write, status: u16, content_type: &str, len: u64) -> io::Result<()> {
+  write!(
+    stream,
+    "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    status,
+    reason(status),
+    content_type,
+    len
+  )
+}
+
+fn write_json(stream: &mut impl Write, status: u16, body: &serde_json::Value) -> io::Result<()> {
+  let body = body.to_string();
+  write_head(stream, status, "application/json", body.len() as u64)?;
+  stream.write_all(body.as_bytes())?;
+  stream.flush()
+}
+
+fn write_file(stream: &mut impl Write, path: &str) -> io::Result<()> {
+  let mut file = fs::File::open(path)?;
+  let content_type = match path.rsplit_once('.').map(|(_, ext)| ext) {
+    Some("wav") => "audio/wav",
+    Some("json") | Some("jsonl") => "application/json",
+    Some("csv") => "text/csv",
+    _ => "application/octet-stream",
+  };
+  write_head(stream, 200, content_type, file.metadata()?.len())?;
+  io::copy(&mut file, stream)?;
+  stream.flush()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::time::{Duration, Instant};
+
+  /// Send one request and return the status and body of the reply.
+  fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Vec<u8>) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+      stream,
+      "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+      method,
+      path,
+      body.len(),
+      body
+    )
+    .unwrap();
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).unwrap();
+    let head_end = reply.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = String::from_utf8_lossy(&reply[..head_end]).to_string();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, reply[head_end + 4..].to_vec())
+  }
+
+  fn json_of(body: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(body).unwrap()
+  }
+
+  #[test]
+  fn test_render_job_over_http() {
+    let options = ServeOptions {
+      addr: "127.0.0.1:0".to_string(),
+      workers: 2,
+      out_dir: "dev-audio/serve".to_string(),
+      ..Default::default()
+    };
+    let server = Server::bind(&options).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+
+    let playbook = serde_json::to_value(crate::short_score()).unwrap();
+    let job = json!({"playbook": playbook, "preset": "fum", "seed": 11, "stems": true, "bits": "16"});
+    let (status, body) = request(addr, "POST", "/jobs", &job.to_string());
+    assert_eq!(202, status, "{}", String::from_utf8_lossy(&body));
+    let id = json_of(&body)["id"].as_str().unwrap().to_string();
+
+    let started = Instant::now();
+    let done = loop {
+      let (status, body) = request(addr, "GET", &format!("/jobs/{}", id), "");
+      assert_eq!(200, status);
+      let job = json_of(&body);
+      match job["status"].as_str().unwrap() {
+        "queued" | "running" => {
+          assert!(started.elapsed() < Duration::from_secs(600), "Job must finish");
+          thread::sleep(Duration::from_millis(200));
+        }
+        _ => break job,
+      }
+    };
+    assert_eq!("done", done["status"], "{}", done);
+    let files: Vec<&str> = done["files"].as_array().unwrap().iter().map(|f| f.as_str().unwrap()).collect();
+    assert!(files.contains(&"mix.wav") && files.contains(&"mix.stems.json") && files.contains(&"progress.jsonl"));
+
+    let (status, wav) = request(addr, "GET", &format!("/jobs/{}/files/mix.wav", id), "");
+    assert_eq!(200, status);
+    let reader = hound::WavReader::new(io::Cursor::new(wav)).unwrap();
+    assert_eq!((2, 16), (reader.spec().channels, reader.spec().bits_per_sample));
+    assert!(reader.len() > 0);
+
+    assert_eq!(
+      404,
+      request(addr, "GET", &format!("/jobs/{}/files/..%2Fother", id), "").0
+    );
+    assert_eq!(404, request(addr, "GET", "/jobs/999", "").0);
+    assert_eq!(405, request(addr, "DELETE", "/jobs", "").0);
+  }
+
+  #[test]
+  fn test_invalid_jobs_are_rejected() {
+    let options = ServeOptions {
+      addr: "127.0.0.1:0".to_string(),
+      out_dir: "dev-audio/serve".to_string(),
+      packs: vec!["src/demo/packs/glass.toml".to_string()],
+      ..Default::default()
+    };
+    let server = Server::bind(&options).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    assert!(cli::find_preset("glass").is_ok(), "Jobs must be able to name the packs loaded at startup");
+
+    let playbook = serde_json::to_value(crate::short_score()).unwrap();
+    let (status, body) = request(addr, "POST", "/jobs", "{\"preset\": ");
+    assert_eq!(400, status);
+    assert!(json_of(&body)["error"].as_str().unwrap().contains("Invalid job request"));
+    let (status, body) = request(
+      addr,
+      "POST",
+      "/jobs",
+      &json!({"playbook": playbook, "preset": "loud"}).to_string(),
+    );
+    assert_eq!(400, status);
+    assert!(json_of(&body)["error"].as_str().unwrap().contains("Unknown preset"));
+    let (status, body) = request(
+      addr,
+      "POST",
+      "/jobs",
+      &json!({"playbook": playbook, "preset": "src/demo/packs/glass.toml"}).to_string(),
+    );
+    assert_eq!(400, status, "Must not load a preset file a job names");
+    assert!(json_of(&body)["error"].as_str().unwrap().contains("Unknown preset"));
+    let (status, body) = request(
+      addr,
+      "POST",
+      "/jobs",
+      &json!({"playbook": playbook, "preset": "hop", "tuning": "src/demo/tunings/19-edo.scl"}).to_string(),
+    );
+    assert_eq!(400, status, "Must not load a tuning file a job names");
+    assert!(json_of(&body)["error"].as_str().unwrap().contains("Unknown tuning"));
+    let (status, body) = request(
+      addr,
+      "POST",
+      "/jobs",
+      &json!({"playbook": playbook, "preset": "hop", "tuning": "12-et", "keyboard": "src/demo/tunings/19-edo.kbm"})
+        .to_string(),
+    );
+    assert_eq!(400, status, "Must not load a keyboard mapping a job names");
+    assert!(json_of(&body)["error"].as_str().unwrap().contains("keyboard"));
+    let mut broken = playbook.clone();
+    broken["conf"]["cps"] = json!(-1);
+    let (status, body) = request(
+      addr,
+      "POST",
+      "/jobs",
+      &json!({"playbook": broken, "preset": "hop"}).to_string(),
+    );
+    assert_eq!(400, status);
+    assert!(!json_of(&body)["problems"].as_array().unwrap().is_empty());
+  }
+}
@@ -2,9 +2,13 @@
 /// It includes definitions for Sample Rate, Minimum Frequency, Minimum Decibel Value,
 /// Maximum Decibel value.
 ///
+/// The sample rate is chosen per render with `render::context::RenderContext`, so it and the values derived from it
+/// (such as the Nyquist frequency) are functions rather than constants.
+///
 /// The module also offers convenient aliases for standard constants at f32 precision.
 use crate::render;
 use crate::types::*;
+use std::cell::Cell;
 
 pub const pi: f32 = std::f32::consts::PI;
 pub const pi2: f32 = pi * 2f32;
@@ -13,18 +17,48 @@ pub const pi_4: f32 = std::f32::consts::FRAC_PI_4;
 pub const e: f32 = std::f32::consts::E;
 pub const epi: f32 = pi * std::f32::consts::E;
 
-pub use crate::types::synthesis::{RangeBuffer, SampleBuffer};
+pub use crate::types::synthesis::{RangeBuffer, SampleBuffer, StereoBuffer};
+
+/// Sample rate used when none is configured
+pub const DEFAULT_SR: usize = 48000;
 
-pub const SR: usize = 48000;
-pub const SRi: i32 = SR as i32;
-pub const SRf: f32 = SR as f32;
-pub const SRu: u32 = SR as u32;
+thread_local! {
+  static SAMPLE_RATE: Cell<usize> = const { Cell::new(DEFAULT_SR) };
+}
+
+/// Set the sample rate for everything rendered on the current thread.
+/// Renders set it through `render::context::RenderContext`, which carries it into their worker threads.
+pub fn set_sample_rate(sample_rate: usize) {
+  SAMPLE_RATE.with(|cell| cell.set(sample_rate));
+}
+
+/// Sample rate of the current thread, in samples per second.
+pub fn SR() -> usize {
+  SAMPLE_RATE.with(|cell| cell.get())
+}
+pub fn SRi() -> i32 {
+  SR() as i32
+}
+pub fn SRf() -> f32 {
+  SR() as f32
+}
+pub fn SRu() -> u32 {
+  SR() as u32
+}
 
 // Nyquist Frequency: Maximum renderable frequency
-pub const NF: usize = SR / 2;
-pub const NFi: i32 = NF as i32;
-pub const NFu: u32 = NF as u32;
-pub const NFf: f32 = SR as f32 / 2f32;
+pub fn NF() -> usize {
+  SR() / 2
+}
+pub fn NFi() -> i32 {
+  NF() as i32
+}
+pub fn NFu() -> u32 {
+  NF() as u32
+}
+pub fn NFf() -> f32 {
+  SR() as f32 / 2f32
+}
 
 // Minimum Frequency: Minimum supported application frequency
 pub const MF: usize = 24;
@@ -33,18 +67,27 @@ pub const MFu: u32 = MF as u32;
 pub const MFf: f32 = MF as f32;
 
 // Aliases for Time Domain
-pub const SECONDS_PER_SAMPLE: f32 = 1.0 / SRf; // Time duration of a single sample
-pub const SAMPLES_PER_MILLISECOND: f32 = SRf / 1000.0; // Number of samples in a millisecond
-pub const SAMPLES_PER_SECOND: f32 = SRf; // Alias for samples per second
+/// Time duration of a single sample
+pub fn SECONDS_PER_SAMPLE() -> f32 {
+  1.0 / SRf()
+}
+/// Number of samples in a millisecond
+pub fn SAMPLES_PER_MILLISECOND() -> f32 {
+  SRf() / 1000.0
+}
+/// Alias for samples per second
+pub fn SAMPLES_PER_SECOND() -> f32 {
+  SRf()
+}
 
 pub fn MAX_POW_2i() -> i32 {
-  NFf.log2() as i32
+  NFf().log2() as i32
 }
 pub fn MAX_POW_2f() -> f32 {
-  NFf.log2()
+  NFf().log2()
 }
 pub fn MAX_POW_2u() -> u32 {
-  NFf.log2() as u32
+  NFf().log2() as u32
 }
 pub const MAX_REGISTER: i32 = 13;
 pub const MIN_REGISTER: i32 = 4;
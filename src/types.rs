@@ -42,6 +42,9 @@ pub mod synthesis {
   /// Sample values in 0 to 1
   pub type RangeBuffer = Vec<f32>;
 
+  /// Pair of (left, right) sample buffers in -1 to 1
+  pub type StereoBuffer = (SampleBuffer, SampleBuffer);
+
   /// When in the melody does the filter activate
   #[derive(Debug, Clone, Copy)]
   pub enum FilterPoint {
@@ -89,7 +92,8 @@ pub mod synthesis {
     }
   }
 
-  #[derive(Copy, Clone, Debug)]
+  #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
   pub enum MacroMotion {
     
     // static values
@@ -104,7 +108,7 @@ pub mod synthesis {
     Random,  // a new value each time
   }
 
-  #[derive(Copy, Clone, Debug)]
+  #[derive(Copy, Clone, Debug, Deserialize)]
   pub struct BoostGroupMacro {
     // min/max freqs. is a static value for macro application
     pub bandpass: [Range; 2],
@@ -122,7 +126,7 @@ pub mod synthesis {
 
   impl BoostGroupMacro {
     pub fn gen(&self) -> BoostGroup {
-      let mut rng = rand::thread_rng();
+      let mut rng = crate::seed::thread_rng();
       let min_freq = in_range(&mut rng, self.bandpass[0], self.bandpass[1]);
       let width = in_range(&mut rng, self.bandwidth[0], self.bandwidth[1]);
       BoostGroup {
@@ -138,14 +142,106 @@ pub mod synthesis {
   pub type Bp2 = (Vec<f32>, Vec<f32>, Vec<BoostGroupMacro>);
 
   pub fn bp2_unit() -> Bp2 {
-    (vec![MFf], vec![NFf], vec![])
+    (vec![MFf], vec![NFf()], vec![])
   }
-  #[derive(Clone, Copy)]
+  /// Length of a glide between neighbouring notes. See `render::glide`.
+  #[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
   pub enum GlideLen {
+    #[default]
     None,
+    /// A quarter of a cycle
     Quarter,
+    /// An eighth of a cycle
     Eigth,
+    /// A sixteenth of a cycle
     Sixteenth,
+    /// Any length in cycles
+    Cycles(f32),
+  }
+
+  /// How the frequency moves over a glide.
+  #[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
+  pub enum GlideCurve {
+    /// Equal steps in Hz
+    #[default]
+    Linear,
+    /// Equal steps in pitch
+    Exponential,
+  }
+
+  /// Portamento between the notes of each line of a part, as requested by a preset or a playbook.
+  /// `from` glides into each note from the previous one and `to` glides out of each note toward the next.
+  #[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
+  pub struct Glide {
+    #[serde(default)]
+    pub from: GlideLen,
+    #[serde(default)]
+    pub to: GlideLen,
+    #[serde(default)]
+    pub curve: GlideCurve,
+  }
+
+  /// Subdivision a groove is laid on. One cycle is one quarter note.
+  #[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
+  pub enum GrooveGrid {
+    /// Half of a cycle
+    Eighth,
+    /// A quarter of a cycle
+    #[default]
+    Sixteenth,
+  }
+
+  /// Timing and velocity of the notes on one step of a groove.
+  #[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
+  pub struct GrooveStep {
+    /// Offset of the step as a fraction of a step. Positive plays late.
+    #[serde(default)]
+    pub timing: f32,
+    /// Multiplier of the amplitude of the notes on the step
+    #[serde(default = "GrooveStep::unity")]
+    pub velocity: f32,
+  }
+
+  impl GrooveStep {
+    fn unity() -> f32 {
+      1f32
+    }
+  }
+
+  /// Swing and per-step offsets moving the notes of each line of a part off the grid, as requested by a preset or
+  /// a playbook. The durations of the notes are left as written. See `render::groove`.
+  #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+  #[serde(rename_all = "kebab-case")]
+  pub struct Groove {
+    #[serde(default)]
+    pub grid: GrooveGrid,
+    /// Percent of each pair of steps taken by its first step. 50 plays straight and 66.7 plays triplets.
+    #[serde(default = "Groove::straight")]
+    pub swing: f32,
+    /// Offsets of consecutive steps, repeating from the start of each line. Empty to swing alone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<GrooveStep>,
+  }
+
+  impl Groove {
+    fn straight() -> f32 {
+      50f32
+    }
+  }
+
+  impl Default for Groove {
+    fn default() -> Self {
+      Groove {
+        grid: GrooveGrid::default(),
+        swing: Groove::straight(),
+        steps: Vec::new(),
+      }
+    }
   }
 
   /// Context window for a frequency in series of frequencies, as in a melody.
@@ -165,7 +261,7 @@ pub mod synthesis {
   /// then an analogous Frex looks like (GlideLen::None, None, C, E, GlideLen::None)
   /// and then for the second note, (GlideLen::None, C, E, G, GlideLen::None)
   ///
-  /// as of May 25 2024 the glide modulation logic is yet to be implemented in the ugen
+  /// The glide modulation is applied by `render::glide` in `summer` and `summer_with_reso`.
   pub type Frex = (GlideLen, Option<Freq>, Freq, Option<Freq>, GlideLen);
 
   /// Parameters for amplitude modulation effects.
@@ -305,12 +401,36 @@ pub mod render {
 
   pub type Midi = (Duration, MidiVal, SignedByte);
 
-  #[derive(Debug, Serialize, Deserialize)]
+  /// A named section of a score, in cycles.
+  #[derive(Debug, Clone, Serialize, Deserialize)]
   pub struct Marker {
-    tag: String,
-    start: f32,
-    end: f32,
-    instance: usize,
+    pub tag: String,
+    pub start: f32,
+    pub end: f32,
+    /// Occurrence of this section when it repeats
+    pub instance: usize,
+  }
+
+  /// How the tempo moves from the previous point of a tempo map to the next.
+  #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+  #[serde(rename_all = "kebab-case")]
+  pub enum TempoCurve {
+    /// Hold the previous tempo and change at the point
+    #[default]
+    Step,
+    /// Change by the same number of cycles per second in every cycle
+    Linear,
+    /// Change by the same ratio in every cycle
+    Exponential,
+  }
+
+  /// A change of tempo, reached at `cycle` of the score. See `analysis::tempo`.
+  #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+  pub struct TempoPoint {
+    pub cycle: f32,
+    pub cps: f32,
+    #[serde(default)]
+    pub curve: TempoCurve,
   }
 
   #[derive(Debug, Serialize, Deserialize)]
@@ -335,6 +455,13 @@ pub mod render {
     pub parts: Vec<DruidicScoreEntry<synthesis::Note>>,
     pub markers: Vec<Marker>,
     pub groupEnclosure: timbre::Enclosure,
+    /// Tempo changes after the start of the score, which plays at `conf.cps` until the first of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tempo: Vec<TempoPoint>,
+    /// Name of the tuning the score is written for (see `analysis::tuning::NAMES`), played unless a render asks for
+    /// a tuning other than just intonation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tuning: Option<String>,
   }
 
   #[derive(Debug, Serialize, Deserialize)]
@@ -366,12 +493,13 @@ pub mod render {
   use crate::fm::Operator;
   use crate::Arf;
   use rand::seq::SliceRandom;
-  use rand::{thread_rng, Rng};
+  use rand::Rng;
+  use crate::seed::thread_rng;
 
   impl Feel {
     pub fn unit() -> Self {
       Feel {
-        bp: (vec![crate::synth::MFf], vec![crate::synth::NFf]),
+        bp: (vec![crate::synth::MFf], vec![crate::synth::NFf()]),
         modifiers: ModBox::unit(),
         clippers: (0f32, 1f32),
       }
@@ -381,7 +509,7 @@ pub mod render {
       use timbre::AmpLifespan;
       use timbre::Role::*;
 
-      let mut rng = rand::thread_rng();
+      let mut rng = crate::seed::thread_rng();
 
       let bp_reg_low = arf.register as f32;
       const MAX_REGISTER: usize = 14;
@@ -400,9 +528,9 @@ pub mod render {
       let n_segments: usize = 3
         * match arf.visibility {
           timbre::Visibility::Hidden => 1,
-          timbre::Visibility::Background => rand::thread_rng().gen_range(2..=3),
-          timbre::Visibility::Visible => rand::thread_rng().gen_range(3..=5),
-          timbre::Visibility::Foreground => rand::thread_rng().gen_range(4..=7),
+          timbre::Visibility::Background => crate::seed::thread_rng().gen_range(2..=3),
+          timbre::Visibility::Visible => crate::seed::thread_rng().gen_range(3..=5),
+          timbre::Visibility::Foreground => crate::seed::thread_rng().gen_range(4..=7),
         };
       use crate::inp::arg_xform::gen_bp_contour;
       // arbitrary number of samples for the filter contour
@@ -430,7 +558,7 @@ pub mod render {
             2f32.powf(bp_reg_low),
             resolution,
           );
-          let lowpass = vec![crate::synth::NFf];
+          let lowpass = vec![crate::synth::NFf()];
           (highpass, lowpass)
         }
         timbre::Visibility::Foreground => {
@@ -678,12 +806,21 @@ pub mod timbre {
     Bouncy,
   }
 
-  #[derive(Debug, Deserialize, Serialize, Copy, Clone)]
+  #[derive(Debug, Deserialize, Serialize, Clone)]
   #[serde(rename_all = "kebab-case")]
   pub struct ClientPositioning {
     pub echo: Echo,
     pub enclosure: Enclosure,
     pub distance: Distance,
+    /// Optional pan position in [-1, 1] (left to right). When omitted, one is derived from the part's role and distance.
+    #[serde(default)]
+    pub pan: Option<f32>,
+    /// Optional portamento between the notes of the part. When omitted, the preset's glide for the role is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glide: Option<super::synthesis::Glide>,
+    /// Optional swing and timing feel of the part. When omitted, the preset's groove for the role is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groove: Option<super::synthesis::Groove>,
   }
 
   /// High level description for audio effect generation.